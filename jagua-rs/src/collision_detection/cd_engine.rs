@@ -1,20 +1,28 @@
+use std::borrow::Cow;
+
 use indexmap::IndexSet;
+use rayon::prelude::*;
 use tribool::Tribool;
 
-use crate::collision_detection::hazard::Hazard;
 use crate::collision_detection::hazard::HazardEntity;
+use crate::collision_detection::hazard::{Cutout, Hazard};
 use crate::collision_detection::hpg::grid::Grid;
 use crate::collision_detection::hpg::hazard_proximity_grid::{DirtyState, HazardProximityGrid};
 use crate::collision_detection::hpg::hpg_cell::HPGCell;
 use crate::collision_detection::quadtree::qt_node::QTNode;
 use crate::collision_detection::quadtree::qt_traits::QTQueryable;
+use crate::entities::item::Item;
+use crate::entities::placed_item::PlacedItem;
 use crate::fsize;
 use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
 use crate::geometry::geo_enums::{GeoPosition, GeoRelation};
-use crate::geometry::geo_traits::{CollidesWith, Shape, Transformable, TransformableFrom};
+use crate::geometry::geo_traits::{
+    CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
+};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::edge::Edge;
+use crate::geometry::primitives::ellipse::Ellipse;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
@@ -33,6 +41,8 @@ pub struct CDEngine {
     config: CDEConfig,
     bbox: AARectangle,
     uncommitted_deregisters: Vec<Hazard>,
+    cutouts: Vec<Cutout>,
+    exterior_ellipse: Option<Ellipse>,
 }
 
 /// Snapshot of the state of [CDEngine] at a given time.
@@ -41,6 +51,7 @@ pub struct CDEngine {
 pub struct CDESnapshot {
     dynamic_hazards: Vec<Hazard>,
     grid: Option<Grid<HPGCell>>,
+    cutouts: Vec<Cutout>,
 }
 
 impl CDEngine {
@@ -68,9 +79,18 @@ impl CDEngine {
             config,
             bbox,
             uncommitted_deregisters: vec![],
+            cutouts: vec![],
+            exterior_ellipse: None,
         }
     }
 
+    /// Confines the engine to an ellipse, everything outside of it is treated as part of the [HazardEntity::BinExterior].
+    /// Containment in the ellipse is checked analytically, the shape of the bin exterior hazard should circumscribe it.
+    pub fn with_exterior_ellipse(mut self, ellipse: Ellipse) -> Self {
+        self.exterior_ellipse = Some(ellipse);
+        self
+    }
+
     /// Registers a new hazard in the CDE.
     pub fn register_hazard(&mut self, hazard: Hazard) {
         debug_assert!(
@@ -123,6 +143,9 @@ impl CDEngine {
 
         let hazard = self.dynamic_hazards.swap_remove(haz_index);
 
+        if let Some(hpg) = self.haz_prox_grid.as_mut() {
+            hpg.deregister_hazard(&hazard, self.dynamic_hazards.iter(), commit_instant)
+        }
         match commit_instant {
             true => self.quadtree.deregister_hazard(hazard_entity),
             false => {
@@ -130,12 +153,35 @@ impl CDEngine {
                 self.uncommitted_deregisters.push(hazard);
             }
         }
-        if let Some(hpg) = self.haz_prox_grid.as_mut() {
-            hpg.deregister_hazard(hazard_entity, self.dynamic_hazards.iter(), commit_instant)
-        }
         debug_assert!(assertions::qt_contains_no_dangling_hazards(self));
     }
 
+    /// Registers a region cut out of a placed item.
+    /// Shapes entirely inside the cutout will no longer collide with the hazard of the item it was cut out of.
+    /// The hazard proximity grid is not affected, it keeps treating the cutout as occupied.
+    pub fn register_cutout(&mut self, cutout: Cutout) {
+        debug_assert!(
+            !self.cutouts.iter().any(|c| c.entity == cutout.entity),
+            "Cutout already registered"
+        );
+        self.cutouts.push(cutout);
+    }
+
+    /// Removes a cutout from the CDE.
+    pub fn deregister_cutout(&mut self, cutout_entity: HazardEntity) {
+        let index = self
+            .cutouts
+            .iter()
+            .position(|c| c.entity == cutout_entity)
+            .expect("Cutout not found");
+        self.cutouts.swap_remove(index);
+    }
+
+    /// Returns all cutouts registered in the CDE.
+    pub fn cutouts(&self) -> &[Cutout] {
+        &self.cutouts
+    }
+
     pub fn create_snapshot(&mut self) -> CDESnapshot {
         self.commit_deregisters();
         assert!(self
@@ -145,6 +191,7 @@ impl CDEngine {
         CDESnapshot {
             dynamic_hazards: self.dynamic_hazards.clone(),
             grid: self.haz_prox_grid.as_ref().map(|hpg| hpg.grid.clone()),
+            cutouts: self.cutouts.clone(),
         }
     }
 
@@ -203,6 +250,9 @@ impl CDEngine {
             hpg.restore(snapshot.grid.clone().expect("no hpg in snapshot"));
         }
 
+        //Cutouts
+        self.cutouts.clone_from(&snapshot.cutouts);
+
         debug_assert!(self.dynamic_hazards.len() == snapshot.dynamic_hazards.len());
     }
 
@@ -240,6 +290,10 @@ impl CDEngine {
         self.config
     }
 
+    pub fn exterior_ellipse(&self) -> Option<&Ellipse> {
+        self.exterior_ellipse.as_ref()
+    }
+
     /// If the grid has uncommitted deregisters, it is considered dirty and cannot be accessed.
     /// To flush all the changes, call [`Self::flush_haz_prox_grid`].
     pub fn haz_prox_grid(&self) -> Result<&HazardProximityGrid, DirtyState> {
@@ -278,6 +332,18 @@ impl CDEngine {
             .chain(self.dynamic_hazards.iter())
     }
 
+    /// Returns the distance from `point` to the closest active hazard, zero if the point lies inside a hazard.
+    /// Any hazards in `irrelevant_hazards` are ignored.
+    pub fn proximity_at(&self, point: &Point, irrelevant_hazards: &[HazardEntity]) -> fsize {
+        self.all_hazards()
+            .filter(|h| h.active && !irrelevant_hazards.contains(&h.entity))
+            .map(|h| match h.shape.distance_from_border(point) {
+                (pos, _) if pos == h.entity.position() => 0.0,
+                (_, distance) => distance,
+            })
+            .fold(fsize::MAX, fsize::min)
+    }
+
     ///Checks whether a reference simple polygon, with a transformation applies, collides with any of the hazards.
     ///The check is first done on the surrogate, then with the actual shape.
     ///A buffer shape is used as a temporary storage for the transformed shape.
@@ -314,15 +380,131 @@ impl CDEngine {
         irrelevant_hazards: &[HazardEntity],
     ) -> bool {
         match self.bbox.relation_to(&shape.bbox()) {
-            //Not fully inside bbox => definite collision
-            GeoRelation::Disjoint | GeoRelation::Enclosed | GeoRelation::Intersecting => true,
-            GeoRelation::Surrounding => {
-                self.poly_collides_by_edge_intersection(shape, irrelevant_hazards)
-                    || self.poly_collides_by_containment(shape, irrelevant_hazards)
+            //Not fully inside bbox => definite collision, unless the bin boundary is soft
+            GeoRelation::Disjoint | GeoRelation::Enclosed | GeoRelation::Intersecting
+                if !self.config.soft_bin_boundary =>
+            {
+                true
+            }
+            _ => {
+                let irrelevant_hazards = self.with_soft_boundary(irrelevant_hazards);
+                let irrelevant_hazards =
+                    self.with_cutout_parents(&irrelevant_hazards, |c| c.contains_poly(shape));
+                let margin = self.contact_margin(shape.diameter());
+                self.exits_ellipse(&irrelevant_hazards, |e| {
+                    shape.points.iter().any(|p| e.distance(p) > margin)
+                }) || self.poly_collides_by_edge_intersection(shape, &irrelevant_hazards)
+                    || self.poly_collides_by_containment(shape, &irrelevant_hazards)
+            }
+        }
+    }
+
+    ///Checks whether a circle collides with any of the (relevant) hazards.
+    ///The test is exact: instead of polygonizing the circle, the distance from its center to the edges of the hazards is compared to its radius.
+    /// # Arguments
+    /// * `circle` - The circle (already transformed) to be checked for collisions
+    /// * `irrelevant_hazards` - entities to be ignored during the check
+    pub fn circle_collides(&self, circle: &Circle, irrelevant_hazards: &[HazardEntity]) -> bool {
+        let circle = &shrink_circle(circle.clone(), self.contact_margin(2.0 * circle.radius));
+        match self.bbox.relation_to(&circle.bbox()) {
+            //Not fully inside bbox => definite collision, unless the bin boundary is soft
+            GeoRelation::Disjoint | GeoRelation::Enclosed | GeoRelation::Intersecting
+                if !self.config.soft_bin_boundary =>
+            {
+                true
+            }
+            _ => {
+                let irrelevant_hazards = self.with_soft_boundary(irrelevant_hazards);
+                let irrelevant_hazards =
+                    self.with_cutout_parents(&irrelevant_hazards, |c| c.contains_circle(circle));
+                if self.exits_ellipse(&irrelevant_hazards, |e| !e.contains_circle(circle)) {
+                    return true;
+                }
+                //an edge of a hazard within the radius of the center
+                if self
+                    .quadtree
+                    .collides(circle, &irrelevant_hazards)
+                    .is_some()
+                {
+                    return true;
+                }
+                //no edge is close, so the circle either lies fully inside or fully outside every hazard
+                self.all_hazards()
+                    .filter(|h| h.active && !irrelevant_hazards.contains(&h.entity))
+                    .any(|haz| match haz.entity.position() {
+                        GeoPosition::Interior => haz.shape.collides_with(circle),
+                        GeoPosition::Exterior => !haz.shape.collides_with(&circle.center),
+                    })
             }
         }
     }
 
+    ///Checks whether an item, with a transformation applied, collides with any of the (relevant) hazards.
+    ///Dispatched on the shape of the item: circular items are checked exactly with [`Self::circle_collides`],
+    ///all others with [`Self::surrogate_or_poly_collides`].
+    /// # Arguments
+    /// * `item` - The item to be checked for collisions
+    /// * `transform` - The transformation to be applied to the item
+    /// * `buffer_shape` - A temporary storage for the transformed shape
+    /// * `irrelevant_hazards` - entities to be ignored during the check
+    pub fn item_collides(
+        &self,
+        item: &Item,
+        transform: &Transformation,
+        buffer_shape: &mut SimplePolygon,
+        irrelevant_hazards: &[HazardEntity],
+    ) -> bool {
+        match &item.circle {
+            Some(circle) => {
+                self.circle_collides(&circle.transform_clone(transform), irrelevant_hazards)
+            }
+            None => self.surrogate_or_poly_collides(
+                &item.shape,
+                transform,
+                buffer_shape,
+                irrelevant_hazards,
+            ),
+        }
+    }
+
+    ///Checks whether a placed item collides with any of the (relevant) hazards.
+    ///Same as [`Self::item_collides`], for an item which is already transformed.
+    pub fn placed_item_collides(
+        &self,
+        pi: &PlacedItem,
+        irrelevant_hazards: &[HazardEntity],
+    ) -> bool {
+        match &pi.circle {
+            Some(circle) => self.circle_collides(circle, irrelevant_hazards),
+            None => self.poly_collides(&pi.shape, irrelevant_hazards),
+        }
+    }
+
+    /// Returns the area of a reference shape, with a transformation applied, which lies outside the bin.
+    /// Zero if the shape lies entirely within the bin, or if the engine has no bin exterior hazard.
+    /// Intended to penalize items poking outside a soft bin boundary, see [CDEConfig::soft_bin_boundary].
+    /// Non-convex bins are decomposed into convex parts on every call.
+    pub fn boundary_violation(&self, shape: &SimplePolygon, transform: &Transformation) -> fsize {
+        let Some(bin_outer) = self
+            .static_hazards
+            .iter()
+            .find(|h| h.entity == HazardEntity::BinExterior)
+            .map(|h| &h.shape)
+        else {
+            return 0.0;
+        };
+        let t_shape = shape.transform_clone(transform);
+        let inside_area = match bin_outer.is_convex {
+            true => t_shape.intersection_area_with_convex(bin_outer),
+            false => bin_outer
+                .convex_decomposition()
+                .iter()
+                .map(|part| t_shape.intersection_area_with_convex(part))
+                .sum(),
+        };
+        fsize::max(0.0, t_shape.area - inside_area)
+    }
+
     /// Checks whether a surrogate collides with any of the (relevant) hazards.
     /// # Arguments
     /// * `base_surrogate` - The (untransformed) surrogate to be checked for collisions
@@ -334,19 +516,44 @@ impl CDEngine {
         transform: &Transformation,
         irrelevant_hazards: &[HazardEntity],
     ) -> bool {
-        for pole in base_surrogate.ff_poles() {
-            let t_pole = pole.transform_clone(transform);
-            if self
-                .quadtree
-                .collides(&t_pole, irrelevant_hazards)
-                .is_some()
-            {
-                return true;
-            }
+        let irrelevant_hazards = self.with_soft_boundary(irrelevant_hazards);
+        let irrelevant_hazards = self.with_cutout_parents(&irrelevant_hazards, |c| {
+            let t_surrogate = base_surrogate.transform_clone(transform);
+            t_surrogate.ff_poles().iter().all(|p| c.contains_circle(p))
+                && t_surrogate.ff_piers().iter().all(|p| c.contains_edge(p))
+        });
+        let irrelevant_hazards = irrelevant_hazards.as_ref();
+        //all poles lie within their bounding circle, if it is clear of hazards, so are the poles
+        let check_poles = !self.config.bounding_circle_broad_phase
+            || self.bounding_circle_collides(base_surrogate, transform, irrelevant_hazards);
+        let margin = self.contact_margin(2.0 * base_surrogate.poles_bounding_circle.radius);
+        let pole_collides = |pole: &Circle| {
+            let t_pole = shrink_circle(pole.transform_clone(transform), margin);
+            self.exits_ellipse(irrelevant_hazards, |e| !e.contains_circle(&t_pole))
+                || self
+                    .quadtree
+                    .collides(&t_pole, irrelevant_hazards)
+                    .is_some()
+        };
+        let parallel = self
+            .config
+            .parallel_pole_threshold
+            .is_some_and(|threshold| base_surrogate.ff_poles().len() >= threshold);
+        let poles_collide = match (check_poles, parallel) {
+            (false, _) => false,
+            (true, true) => base_surrogate.ff_poles().par_iter().any(pole_collides),
+            (true, false) => base_surrogate.ff_poles().iter().any(pole_collides),
+        };
+        if poles_collide {
+            return true;
         }
         for pier in base_surrogate.ff_piers() {
-            let t_pier = pier.transform_clone(transform);
-            if self
+            let Some(t_pier) = shrink_pier(pier.transform_clone(transform), margin) else {
+                continue;
+            };
+            if self.exits_ellipse(irrelevant_hazards, |e| {
+                !e.collides_with(&t_pier.start) || !e.collides_with(&t_pier.end)
+            }) || self
                 .quadtree
                 .collides(&t_pier, irrelevant_hazards)
                 .is_some()
@@ -357,6 +564,25 @@ impl CDEngine {
         false
     }
 
+    /// Checks whether the bounding circle of the surrogate's poles, with a transformation applied, collides with any of the (relevant) hazards.
+    /// Serves as the broad phase of [CDEngine::surrogate_collides], see [CDEConfig::bounding_circle_broad_phase]:
+    /// if the circle does not collide, none of the poles can.
+    pub fn bounding_circle_collides(
+        &self,
+        base_surrogate: &SPSurrogate,
+        transform: &Transformation,
+        irrelevant_hazards: &[HazardEntity],
+    ) -> bool {
+        let circle = &base_surrogate.poles_bounding_circle;
+        let margin = self.contact_margin(2.0 * circle.radius);
+        let t_circle = shrink_circle(circle.transform_clone(transform), margin);
+        self.exits_ellipse(irrelevant_hazards, |e| !e.contains_circle(&t_circle))
+            || self
+                .quadtree
+                .collides(&t_circle, irrelevant_hazards)
+                .is_some()
+    }
+
     /// Checks whether a point definitely collides with any of the (relevant) hazards.
     /// Only fully hazardous nodes in the quadtree are considered.
     pub fn point_definitely_collides_with(&self, point: &Point, entity: HazardEntity) -> Tribool {
@@ -394,16 +620,85 @@ impl CDEngine {
         }
     }
 
+    /// Extends `irrelevant_hazards` with the items from which a region was cut out, if the queried entity lies entirely inside that region.
+    fn with_cutout_parents<'a>(
+        &self,
+        irrelevant_hazards: &'a [HazardEntity],
+        inside_cutout: impl Fn(&Cutout) -> bool,
+    ) -> Cow<'a, [HazardEntity]> {
+        let mut parents = self
+            .cutouts
+            .iter()
+            .filter(|c| !irrelevant_hazards.contains(&c.parent) && inside_cutout(c))
+            .map(|c| c.parent)
+            .peekable();
+        match parents.peek() {
+            None => Cow::Borrowed(irrelevant_hazards),
+            Some(_) => Cow::Owned(irrelevant_hazards.iter().cloned().chain(parents).collect()),
+        }
+    }
+
+    /// Adds the bin exterior to the irrelevant hazards if the bin boundary is soft, see [CDEConfig::soft_bin_boundary]
+    fn with_soft_boundary<'a>(
+        &self,
+        irrelevant_hazards: &'a [HazardEntity],
+    ) -> Cow<'a, [HazardEntity]> {
+        match self.config.soft_bin_boundary
+            && !irrelevant_hazards.contains(&HazardEntity::BinExterior)
+        {
+            false => Cow::Borrowed(irrelevant_hazards),
+            true => Cow::Owned(
+                irrelevant_hazards
+                    .iter()
+                    .cloned()
+                    .chain([HazardEntity::BinExterior])
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Whether a shape leaves the exterior ellipse, see [CDEngine::with_exterior_ellipse].
+    /// Always false if the engine has no exterior ellipse or the bin exterior is irrelevant.
+    fn exits_ellipse(
+        &self,
+        irrelevant_hazards: &[HazardEntity],
+        exits: impl FnOnce(&Ellipse) -> bool,
+    ) -> bool {
+        match &self.exterior_ellipse {
+            Some(ellipse) if !irrelevant_hazards.contains(&HazardEntity::BinExterior) => {
+                exits(ellipse)
+            }
+            _ => false,
+        }
+    }
+
     fn poly_collides_by_edge_intersection(
         &self,
         shape: &SimplePolygon,
         irrelevant_hazards: &[HazardEntity],
     ) -> bool {
-        shape
-            .edge_iter()
+        self.query_edges(shape)
             .any(|e| self.quadtree.collides(&e, irrelevant_hazards).is_some())
     }
 
+    /// The edges of `shape` to test for intersections with the hazards.
+    /// If touching is allowed, they are pulled inward so they no longer intersect the hazards they merely touch, see [CDEConfig::touching_allowed].
+    fn query_edges<'a>(&self, shape: &'a SimplePolygon) -> impl Iterator<Item = Edge> + 'a {
+        let margin = self.contact_margin(shape.diameter());
+        shape.edge_iter().filter_map(move |e| match margin == 0.0 {
+            true => Some(e),
+            false => e.shrink_inward(margin),
+        })
+    }
+
+    /// Distance within which contact with a shape of the given diameter is not treated as a collision, see [CDEConfig::touching_allowed]
+    fn contact_margin(&self, diameter: fsize) -> fsize {
+        match self.config.touching_allowed {
+            false => 0.0,
+            true => self.config.geo_tolerance.margin(diameter),
+        }
+    }
+
     fn poly_collides_by_containment(
         &self,
         shape: &SimplePolygon,
@@ -420,7 +715,8 @@ impl CDEngine {
         //"almost" meaning that, when edges are very close together, they are considered equal.
         //Some relations which would normally be seen as Intersecting are now being considered Enclosed/Surrounding
         let haz_shape = haz.shape.as_ref();
-        let bbox_relation = haz_shape.bbox().almost_relation_to(&shape.bbox());
+        let tol = &self.config.geo_tolerance;
+        let bbox_relation = haz_shape.bbox().relation_to_within(&shape.bbox(), tol);
 
         let (s_mu, s_omega) = match bbox_relation {
             GeoRelation::Surrounding => (shape, haz_shape), //inclusion possible
@@ -445,7 +741,7 @@ impl CDEngine {
                 return collides;
             }
         }
-        let inclusion = s_omega.collides_with(&s_mu.poi.center);
+        let inclusion = s_omega.collides_with_point_within(&s_mu.poi.center, tol);
 
         match haz.entity.position() {
             GeoPosition::Interior => inclusion,
@@ -462,6 +758,7 @@ impl CDEngine {
     ) where
         T: QTQueryable,
     {
+        let irrelevant_hazards = self.with_soft_boundary(irrelevant_hazards);
         let n_init_detected = detected.len();
         detected.extend(irrelevant_hazards.iter().cloned());
         let irrelevant_range = n_init_detected..detected.len();
@@ -491,15 +788,23 @@ impl CDEngine {
         detected: &mut Vec<HazardEntity>,
     ) {
         //temporarily add the irrelevant hazards to the buffer
+        let irrelevant_hazards = self.with_soft_boundary(irrelevant_hazards);
         let n_init_detected = detected.len();
         detected.extend(irrelevant_hazards.iter().cloned());
         let irrelevant_range = n_init_detected..detected.len();
 
         //collect all colliding entities due to edge intersection
-        shape
-            .edge_iter()
+        self.query_edges(shape)
             .for_each(|e| self.quadtree.collect_collisions(&e, detected));
 
+        //collect the bin exterior if the polygon leaves the exterior ellipse
+        let margin = self.contact_margin(shape.diameter());
+        if !detected.contains(&HazardEntity::BinExterior)
+            && self.exits_ellipse(&[], |e| shape.points.iter().any(|p| e.distance(p) > margin))
+        {
+            detected.push(HazardEntity::BinExterior);
+        }
+
         //collect all colliding entities due to containment
         //TODO: check if gathering the hazards inside the bbox using the quadtree is faster
         self.all_hazards().filter(|h| h.active).for_each(|h| {
@@ -522,20 +827,45 @@ impl CDEngine {
         detected: &mut Vec<HazardEntity>,
     ) {
         //temporarily add the irrelevant hazards to the buffer
+        let irrelevant_hazards = self.with_soft_boundary(irrelevant_hazards);
         let n_init_detected = detected.len();
         detected.extend(irrelevant_hazards.iter().cloned());
         let irrelevant_range = n_init_detected..detected.len();
 
+        let margin = self.contact_margin(2.0 * base_surrogate.poles_bounding_circle.radius);
         for pole in base_surrogate.ff_poles() {
-            let t_pole = pole.transform_clone(transform);
+            let t_pole = shrink_circle(pole.transform_clone(transform), margin);
+            if !detected.contains(&HazardEntity::BinExterior)
+                && self.exits_ellipse(&[], |e| !e.contains_circle(&t_pole))
+            {
+                detected.push(HazardEntity::BinExterior);
+            }
             self.quadtree.collect_collisions(&t_pole, detected)
         }
         for pier in base_surrogate.ff_piers() {
-            let t_pier = pier.transform_clone(transform);
-            self.quadtree.collect_collisions(&t_pier, detected);
+            if let Some(t_pier) = shrink_pier(pier.transform_clone(transform), margin) {
+                self.quadtree.collect_collisions(&t_pier, detected);
+            }
         }
 
         //drain the irrelevant hazards, leaving only the colliding entities
         detected.drain(irrelevant_range);
     }
 }
+
+/// Shrinks the radius of a circle by `margin`, see [CDEConfig::touching_allowed]
+fn shrink_circle(mut circle: Circle, margin: fsize) -> Circle {
+    circle.radius = fsize::max(0.0, circle.radius - margin);
+    circle
+}
+
+/// Shortens a pier by `margin` at both ends, see [CDEConfig::touching_allowed]
+fn shrink_pier(pier: Edge, margin: fsize) -> Option<Edge> {
+    if margin == 0.0 {
+        Some(pier)
+    } else if pier.diameter() <= 2.0 * margin {
+        None
+    } else {
+        Some(pier.extend_at_front(-margin).extend_at_back(-margin))
+    }
+}
@@ -1,6 +1,9 @@
 use crate::entities::placed_item::PlacedItem;
 use crate::geometry::d_transformation::DTransformation;
 use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_traits::{CollidesWith, DistanceFrom};
+use crate::geometry::primitives::circle::Circle;
+use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use std::borrow::Borrow;
 use std::sync::Arc;
@@ -26,8 +29,40 @@ impl Hazard {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Region removed from a placed item by a subtracting placement (see [`PlacedItem::subtract`]).
+/// Shapes which lie entirely inside the cutout are not affected by the hazard of the item it was cut out of.
+#[derive(Clone, Debug)]
+pub struct Cutout {
+    /// The entity of the subtracting placed item
+    pub entity: HazardEntity,
+    /// The entity of the placed item the region was cut out of
+    pub parent: HazardEntity,
+    /// The shape of the region
+    pub shape: Arc<SimplePolygon>,
+}
+
+impl Cutout {
+    pub fn contains_poly(&self, poly: &SimplePolygon) -> bool {
+        self.shape.encloses(poly)
+    }
+
+    pub fn contains_circle(&self, circle: &Circle) -> bool {
+        match self.shape.distance_from_border(&circle.center) {
+            (GeoPosition::Interior, distance) => distance > circle.radius,
+            (GeoPosition::Exterior, _) => false,
+        }
+    }
+
+    pub fn contains_edge(&self, edge: &Edge) -> bool {
+        self.shape.collides_with(&edge.start)
+            && self.shape.collides_with(&edge.end)
+            && !self.shape.edge_iter().any(|e| e.collides_with(edge))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Entity inducing the `Hazard`. All entities are uniquely identified.
+/// The ordering (by variant, then by id) is used to break ties between equidistant hazards deterministically.
 pub enum HazardEntity {
     /// An item placed in the layout, defined by its id and applied transformation.
     PlacedItem { id: usize, dt: DTransformation },
@@ -35,6 +70,8 @@ pub enum HazardEntity {
     BinExterior,
     /// Represents a hole in the bin.
     BinHole { id: usize },
+    /// Represents a part of the unusable border along the contour of the bin, see [`Bin::with_inset`](crate::entities::bin::Bin::with_inset).
+    BinInset { id: usize },
     /// Represents a zone in the bin with a specific quality level that is inferior to the base quality.
     InferiorQualityZone { quality: usize, id: usize },
 }
@@ -46,6 +83,7 @@ impl HazardEntity {
             HazardEntity::PlacedItem { .. } => GeoPosition::Interior,
             HazardEntity::BinExterior => GeoPosition::Exterior,
             HazardEntity::BinHole { .. } => GeoPosition::Interior,
+            HazardEntity::BinInset { .. } => GeoPosition::Interior,
             HazardEntity::InferiorQualityZone { .. } => GeoPosition::Interior,
         }
     }
@@ -56,6 +94,7 @@ impl HazardEntity {
             HazardEntity::PlacedItem { .. } => true,
             HazardEntity::BinExterior => false,
             HazardEntity::BinHole { .. } => false,
+            HazardEntity::BinInset { .. } => false,
             HazardEntity::InferiorQualityZone { .. } => false,
         }
     }
@@ -66,6 +105,7 @@ impl HazardEntity {
             HazardEntity::PlacedItem { .. } => true,
             HazardEntity::BinExterior => true,
             HazardEntity::BinHole { .. } => true,
+            HazardEntity::BinInset { .. } => true,
             HazardEntity::InferiorQualityZone { .. } => false,
         }
     }
@@ -5,8 +5,33 @@ use crate::collision_detection::hazard::HazardEntity;
 
 /// Trait that allows for ignoring out specific hazards.
 /// Enables querying the `CDEngine` only for relevant hazards.
+/// Filters can be composed using [`HazardFilter::and`], [`HazardFilter::or`] and [`HazardFilter::not`].
 pub trait HazardFilter {
     fn is_irrelevant(&self, entity: &HazardEntity) -> bool;
+
+    /// Deems hazards irrelevant only if both `self` and `other` deem them irrelevant.
+    fn and<F: HazardFilter>(self, other: F) -> AndHazardFilter<Self, F>
+    where
+        Self: Sized,
+    {
+        AndHazardFilter(self, other)
+    }
+
+    /// Deems hazards irrelevant if either `self` or `other` deems them irrelevant.
+    fn or<F: HazardFilter>(self, other: F) -> OrHazardFilter<Self, F>
+    where
+        Self: Sized,
+    {
+        OrHazardFilter(self, other)
+    }
+
+    /// Deems hazards irrelevant if `self` deems them relevant, and vice versa.
+    fn not(self) -> NotHazardFilter<Self>
+    where
+        Self: Sized,
+    {
+        NotHazardFilter(self)
+    }
 }
 
 /// Returns the entities that are deemed irrelevant by the specified `HazardFilter`.
@@ -33,6 +58,18 @@ pub struct QZHazardFilter(pub usize);
 /// Deems hazards induced by specific entities as irrelevant.
 pub struct EntityHazardFilter(pub Vec<HazardEntity>);
 
+/// Intersection of two `HazardFilter`s, see [`HazardFilter::and`].
+#[derive(Clone, Debug)]
+pub struct AndHazardFilter<A, B>(pub A, pub B);
+
+/// Union of two `HazardFilter`s, see [`HazardFilter::or`].
+#[derive(Clone, Debug)]
+pub struct OrHazardFilter<A, B>(pub A, pub B);
+
+/// Complement of a `HazardFilter`, see [`HazardFilter::not`].
+#[derive(Clone, Debug)]
+pub struct NotHazardFilter<A>(pub A);
+
 /// Combines multiple `HazardFilter`s into a single filter.
 pub struct CombinedHazardFilter<'a> {
     pub filters: Vec<Box<&'a dyn HazardFilter>>,
@@ -44,6 +81,7 @@ impl HazardFilter for BinHazardFilter {
             HazardEntity::PlacedItem { .. } => false,
             HazardEntity::BinExterior => true,
             HazardEntity::BinHole { .. } => true,
+            HazardEntity::BinInset { .. } => true,
             HazardEntity::InferiorQualityZone { .. } => true,
         }
     }
@@ -61,6 +99,30 @@ impl HazardFilter for EntityHazardFilter {
     }
 }
 
+impl<A: HazardFilter, B: HazardFilter> HazardFilter for AndHazardFilter<A, B> {
+    fn is_irrelevant(&self, entity: &HazardEntity) -> bool {
+        self.0.is_irrelevant(entity) && self.1.is_irrelevant(entity)
+    }
+}
+
+impl<A: HazardFilter, B: HazardFilter> HazardFilter for OrHazardFilter<A, B> {
+    fn is_irrelevant(&self, entity: &HazardEntity) -> bool {
+        self.0.is_irrelevant(entity) || self.1.is_irrelevant(entity)
+    }
+}
+
+impl<A: HazardFilter> HazardFilter for NotHazardFilter<A> {
+    fn is_irrelevant(&self, entity: &HazardEntity) -> bool {
+        !self.0.is_irrelevant(entity)
+    }
+}
+
+impl<T: HazardFilter + ?Sized> HazardFilter for &T {
+    fn is_irrelevant(&self, entity: &HazardEntity) -> bool {
+        (**self).is_irrelevant(entity)
+    }
+}
+
 impl HazardFilter for QZHazardFilter {
     fn is_irrelevant(&self, entity: &HazardEntity) -> bool {
         match entity {
@@ -1,5 +1,4 @@
 use std::fmt::{Display, Formatter};
-use std::iter;
 
 use itertools::Itertools;
 
@@ -11,7 +10,7 @@ use crate::collision_detection::hpg::grid_generator;
 use crate::collision_detection::hpg::hpg_cell::{HPGCell, HPGCellUpdate};
 use crate::fsize;
 use crate::geometry::geo_enums::GeoPosition;
-use crate::geometry::geo_traits::Shape;
+use crate::geometry::geo_traits::{DistanceFrom, Shape};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::util::assertions;
 
@@ -111,18 +110,95 @@ impl HazardProximityGrid {
 
     pub fn deregister_hazard<'a, I>(
         &mut self,
-        to_deregister: HazardEntity,
+        to_deregister: &Hazard,
         remaining: I,
         process_now: bool,
     ) where
         I: Iterator<Item = &'a Hazard> + Clone,
     {
         if process_now {
-            for cell in self.grid.cells.iter_mut().flatten() {
-                cell.deregister_hazards(iter::once(to_deregister), remaining.clone());
-            }
+            self.deregister_hazard_incremental(to_deregister, remaining);
         } else {
-            self.uncommitted_deregisters.push(to_deregister);
+            self.uncommitted_deregisters.push(to_deregister.entity);
+        }
+    }
+
+    /// Only the cells which had `to_deregister` as their closest hazard are re-evaluated.
+    /// These are found by propagating the removal through the grid with a boundary fill,
+    /// after which they are updated with the remaining hazards that can still be the closest to them.
+    fn deregister_hazard_incremental<'a, I>(&mut self, to_deregister: &Hazard, remaining: I)
+    where
+        I: Iterator<Item = &'a Hazard> + Clone,
+    {
+        let shape = &to_deregister.shape;
+        let seed_box = AARectangle::new(
+            shape.bbox().x_min - 2.0 * self.cell_radius,
+            shape.bbox().y_min - 2.0 * self.cell_radius,
+            shape.bbox().x_max + 2.0 * self.cell_radius,
+            shape.bbox().y_max + 2.0 * self.cell_radius,
+        );
+
+        //Reset all cells which had the deregistered hazard as closest, and collect the hazards dominating the cells around them
+        let mut b_fill = BoundaryFillHPG::new(&self.grid, &seed_box);
+        let mut affected_cells = vec![];
+        let mut neighboring_entities = vec![];
+
+        while let Some(next_cell) = b_fill.pop() {
+            let cell = self.grid.cells[next_cell].as_mut();
+            let position_in_bf = match cell {
+                Some(cell) => match cell.deregister_hazard(to_deregister) {
+                    HPGCellUpdate::Affected => {
+                        affected_cells.push(next_cell);
+                        GeoPosition::Interior
+                    }
+                    HPGCellUpdate::NotAffected => {
+                        if !neighboring_entities.contains(&cell.uni_prox.1) {
+                            neighboring_entities.push(cell.uni_prox.1);
+                        }
+                        GeoPosition::Interior
+                    }
+                    HPGCellUpdate::NeighborsNotAffected => GeoPosition::Exterior,
+                },
+                None => GeoPosition::Exterior,
+            };
+            b_fill.report_position(next_cell, position_in_bf, &self.grid);
+        }
+
+        if !affected_cells.is_empty() {
+            //First register the neighboring hazards, these are likely the closest and provide an upper bound for the proximity
+            let neighboring_hazards = remaining
+                .clone()
+                .filter(|h| neighboring_entities.contains(&h.entity))
+                .collect_vec();
+            for &i in affected_cells.iter() {
+                let cell = self.grid.cells[i].as_mut().unwrap();
+                cell.register_hazards(neighboring_hazards.iter().copied());
+            }
+
+            //Only hazards within reach of the affected cells can still be closer than the current upper bound
+            let (affected_bbox, reach) = affected_cells.iter().fold(
+                (None, 0.0),
+                |(bbox, reach): (Option<AARectangle>, fsize), &i| {
+                    let cell = self.grid.cells[i].as_ref().unwrap();
+                    let bbox = match bbox {
+                        Some(bbox) => AARectangle::bounding_rectangle(&bbox, &cell.bbox),
+                        None => cell.bbox.clone(),
+                    };
+                    (Some(bbox), reach.max(cell.uni_prox.0))
+                },
+            );
+            let affected_bbox = affected_bbox.unwrap();
+            let candidates = remaining
+                .clone()
+                .filter(|h| {
+                    let bc = &h.shape.surrogate().poles_bounding_circle;
+                    affected_bbox.distance(&bc.center) - bc.radius <= reach
+                })
+                .collect_vec();
+            for &i in affected_cells.iter() {
+                let cell = self.grid.cells[i].as_mut().unwrap();
+                cell.register_hazards(candidates.iter().copied());
+            }
         }
     }
 
@@ -45,8 +45,10 @@ impl HPGCell {
                 false => distance,
             };
             match &hazard.entity {
-                HazardEntity::BinExterior | HazardEntity::BinHole { .. } => {
-                    if prox < static_uni_prox.0 {
+                HazardEntity::BinExterior
+                | HazardEntity::BinHole { .. }
+                | HazardEntity::BinInset { .. } => {
+                    if takes_precedence((prox, hazard.entity), static_uni_prox) {
                         static_uni_prox = (prox, hazard.entity);
                     }
                 }
@@ -137,13 +139,13 @@ impl HPGCell {
             }
         };
 
-        match haz_prox.partial_cmp(&current_prox).unwrap() {
-            Ordering::Less => {
+        match takes_precedence((haz_prox, to_register.entity), self.uni_prox) {
+            true => {
                 //new hazard is closer
                 self.uni_prox = (haz_prox, to_register.entity);
                 HPGCellUpdate::Affected
             }
-            _ => {
+            false => {
                 if haz_prox > current_prox + 2.0 * self.radius {
                     HPGCellUpdate::NeighborsNotAffected
                 } else {
@@ -171,13 +173,13 @@ impl HPGCell {
             }
         };
 
-        match new_prox.partial_cmp(&current_prox).unwrap() {
-            Ordering::Less => {
+        match takes_precedence((new_prox, to_register.entity), self.uni_prox) {
+            true => {
                 //new hazard is closer
                 self.uni_prox = (new_prox, to_register.entity);
                 HPGCellUpdate::Affected
             }
-            _ => {
+            false => {
                 //The current cell is unaffected, but its neighbors might be
                 //maximum distance between neighboring cells
                 let max_neighbor_distance = 2.0 * self.radius;
@@ -215,6 +217,39 @@ impl HPGCell {
         }
     }
 
+    /// Resets the cell to its static proximity if its closest hazard is the one being deregistered.
+    /// The cell then has to be re-evaluated against the remaining hazards by the caller.
+    pub fn deregister_hazard(&mut self, to_deregister: &Hazard) -> HPGCellUpdate {
+        if self.uni_prox.1 == to_deregister.entity {
+            self.uni_prox = self.static_uni_prox;
+            HPGCellUpdate::Affected
+        } else {
+            //The current cell is unaffected, but its neighbors might have the deregistered hazard as their closest.
+            //Every affected cell is connected to the hazard through cells where it is at most one neighbor distance further than the closest.
+            let max_neighbor_distance = 2.0 * self.radius;
+            let current_prox_upper_bound = self.uni_prox.0 + max_neighbor_distance;
+
+            //The bounding circle of the poles is a cheap lower bound for the distance to the hazard
+            let surrogate = to_deregister.shape.surrogate();
+            let bounding_prox = match surrogate
+                .poles_bounding_circle
+                .distance_from_border(&self.centroid)
+            {
+                (GeoPosition::Interior, _) => 0.0,
+                (GeoPosition::Exterior, dist) => dist.abs(),
+            };
+            if bounding_prox > current_prox_upper_bound {
+                return HPGCellUpdate::NeighborsNotAffected;
+            }
+
+            let haz_prox = distance_to_surrogate_poles_border(self, &surrogate.poles);
+            match haz_prox > current_prox_upper_bound {
+                false => HPGCellUpdate::NotAffected,
+                true => HPGCellUpdate::NeighborsNotAffected,
+            }
+        }
+    }
+
     pub fn could_accommodate_item(&self, item: &Item) -> bool {
         let poi_d = item.shape.poi.radius;
         if self.radius > poi_d {
@@ -255,6 +290,20 @@ pub fn distance_to_surrogate_poles_border(hp_cell: &HPGCell, poles: &[Circle]) -
         .unwrap()
 }
 
+/// Whether hazard `(proximity, entity)` replaces the current closest hazard of a cell.
+/// Equidistant hazards are resolved in favor of the smallest [HazardEntity],
+/// so the closest hazard of a cell does not depend on the order in which hazards are registered.
+fn takes_precedence(
+    (proximity, entity): (fsize, HazardEntity),
+    (current_proximity, current_entity): (fsize, HazardEntity),
+) -> bool {
+    match proximity.partial_cmp(&current_proximity).unwrap() {
+        Ordering::Less => true,
+        Ordering::Equal => entity < current_entity,
+        Ordering::Greater => false,
+    }
+}
+
 ///All possible results of an update on a cell in the `HazardProximityGrid`
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HPGCellUpdate {
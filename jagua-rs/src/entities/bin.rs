@@ -7,12 +7,15 @@ use crate::collision_detection::hazard::Hazard;
 use crate::collision_detection::hazard::HazardEntity;
 use crate::entities::quality_zone::InferiorQualityZone;
 use crate::entities::quality_zone::N_QUALITIES;
-use crate::fsize;
 use crate::geometry::geo_traits::Shape;
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::circle::Circle;
+use crate::geometry::primitives::ellipse::Ellipse;
+use crate::geometry::primitives::point::Point;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
 use crate::util::config::CDEConfig;
+use crate::{fsize, PI};
 
 /// A container in which items can be placed.
 #[derive(Clone, Debug)]
@@ -20,6 +23,8 @@ pub struct Bin {
     pub id: usize,
     /// The contour of the bin
     pub outer: Arc<SimplePolygon>,
+    /// The exact contour of elliptical bins, `outer` is then a polygon circumscribing it, see [Bin::from_ellipse]
+    pub outer_ellipse: Option<Ellipse>,
     /// The cost of using the bin
     pub value: u64,
     /// Transformation applied to the shape with respect to the original shape in the input file (for example to center it).
@@ -31,6 +36,8 @@ pub struct Bin {
     /// The starting state of the `CDEngine` for this bin.
     pub base_cde: Arc<CDEngine>,
     pub area: fsize,
+    /// Width of the unusable border along the contour of the bin, see [Bin::with_inset]
+    pub inset: fsize,
 }
 
 impl Bin {
@@ -66,7 +73,7 @@ impl Bin {
             qz
         };
 
-        let bin_hazards = generate_bin_hazards(&outer, &holes, &quality_zones);
+        let bin_hazards = generate_bin_hazards(&outer, &holes, &quality_zones, 0.0);
 
         let base_cde = CDEngine::new(outer.bbox().inflate_to_square(), bin_hazards, cde_config);
         let base_cde = Arc::new(base_cde);
@@ -75,15 +82,40 @@ impl Bin {
         Self {
             id,
             outer,
+            outer_ellipse: None,
             value,
             pretransform,
             holes,
             quality_zones,
             base_cde,
             area,
+            inset: 0.0,
         }
     }
 
+    /// Makes a border of width `inset` along the contour of the bin unusable, enforced by the collision detection engine.
+    /// Follows the contour of irregular bins as well, holes are not affected.
+    pub fn with_inset(mut self, inset: fsize) -> Self {
+        assert!(inset >= 0.0, "inset must be non-negative");
+        if inset == self.inset {
+            return self;
+        }
+        let bin_hazards =
+            generate_bin_hazards(&self.outer, &self.holes, &self.quality_zones, inset);
+        let base_cde = CDEngine::new(
+            self.outer.bbox().inflate_to_square(),
+            bin_hazards,
+            self.base_cde.config(),
+        );
+        let base_cde = match &self.outer_ellipse {
+            Some(ellipse) => base_cde.with_exterior_ellipse(ellipse.clone()),
+            None => base_cde,
+        };
+        self.base_cde = Arc::new(base_cde);
+        self.inset = inset;
+        self
+    }
+
     /// Create a new `Bin` for a strip-packing problem. Instead of a shape, the bin is always rectangular.
     pub fn from_strip(rect: AARectangle, cde_config: CDEConfig) -> Self {
         let id = 0;
@@ -96,6 +128,36 @@ impl Bin {
         Bin::new(id, poly, value, pretransform, vec![], vec![], cde_config)
     }
 
+    /// Create a new round `Bin`, see [Bin::from_ellipse].
+    pub fn from_circle(id: usize, circle: Circle, value: u64, cde_config: CDEConfig) -> Self {
+        Bin::from_ellipse(id, Ellipse::from(circle), value, cde_config)
+    }
+
+    /// Create a new elliptical `Bin`.
+    /// The ellipse is stored as the exact contour of the bin and the collision detection engine checks containment in it analytically.
+    /// `outer` becomes a polygon circumscribing the ellipse, which the engine only uses as a conservative approximation.
+    pub fn from_ellipse(id: usize, ellipse: Ellipse, value: u64, cde_config: CDEConfig) -> Self {
+        let max_deviation = cde_config.geo_tolerance.margin(ellipse.diameter());
+        let poly = ellipse.circumscribed_polygon(max_deviation);
+
+        let bin = Bin::new(
+            id,
+            poly,
+            value,
+            Transformation::empty(),
+            vec![],
+            vec![],
+            cde_config,
+        );
+        let base_cde = bin.base_cde.as_ref().clone();
+        Bin {
+            base_cde: Arc::new(base_cde.with_exterior_ellipse(ellipse.clone())),
+            area: ellipse.area(),
+            outer_ellipse: Some(ellipse),
+            ..bin
+        }
+    }
+
     pub fn bbox(&self) -> AARectangle {
         self.outer.bbox()
     }
@@ -105,10 +167,21 @@ fn generate_bin_hazards(
     outer: &Arc<SimplePolygon>,
     holes: &[Arc<SimplePolygon>],
     quality_zones: &[Option<InferiorQualityZone>],
+    inset: fsize,
 ) -> Vec<Hazard> {
     //Hazard induced by the outside of the bin
     let mut hazards = vec![Hazard::new(HazardEntity::BinExterior, outer.clone())];
 
+    //Hazards induced by the inset along the contour of the bin
+    if inset > 0.0 {
+        hazards.extend(
+            generate_inset_shapes(outer, inset)
+                .into_iter()
+                .enumerate()
+                .map(|(id, shape)| Hazard::new(HazardEntity::BinInset { id }, Arc::new(shape))),
+        );
+    }
+
     //Hazard induced by any holes in the bin
     hazards.extend(holes.iter().enumerate().map(|(i, shape)| {
         let haz_entity = HazardEntity::BinHole { id: i };
@@ -127,3 +200,41 @@ fn generate_bin_hazards(
     }
     hazards
 }
+
+/// Covers all points inside `outer` within distance `inset` of its contour:
+/// a rectangle along the inside of every edge and a polygon enclosing a circle around every vertex.
+fn generate_inset_shapes(outer: &SimplePolygon, inset: fsize) -> Vec<SimplePolygon> {
+    //a regular polygon with this circumradius encloses the circle with radius `inset`
+    let circumradius = inset / (PI / N_INSET_VERTEX_SIDES as fsize).cos();
+
+    let edge_rects = outer.edge_iter().map(|e| {
+        let (dx, dy) = (e.end.0 - e.start.0, e.end.1 - e.start.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        //the interior of a counterclockwise polygon lies to the left of its edges
+        let (nx, ny) = (-dy / length * inset, dx / length * inset);
+        SimplePolygon::new(vec![
+            e.start,
+            e.end,
+            Point(e.end.0 + nx, e.end.1 + ny),
+            Point(e.start.0 + nx, e.start.1 + ny),
+        ])
+    });
+
+    let vertex_polygons = outer.points.iter().map(|p| {
+        let points = (0..N_INSET_VERTEX_SIDES)
+            .map(|i| {
+                let angle = 2.0 * PI * i as fsize / N_INSET_VERTEX_SIDES as fsize;
+                Point(
+                    p.0 + circumradius * angle.cos(),
+                    p.1 + circumradius * angle.sin(),
+                )
+            })
+            .collect_vec();
+        SimplePolygon::new(points)
+    });
+
+    edge_rects.chain(vertex_polygons).collect_vec()
+}
+
+/// Number of sides of the polygons used to approximate the inset around the vertices of a bin
+const N_INSET_VERTEX_SIDES: usize = 8;
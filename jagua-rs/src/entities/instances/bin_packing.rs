@@ -4,6 +4,7 @@ use crate::entities::item::Item;
 use crate::fsize;
 use crate::geometry::geo_traits::Shape;
 use crate::util::assertions;
+use itertools::Itertools;
 
 /// Bin-packing problem instance: a set of items to be packed into a set of bins.
 /// The items are to be packed in such a way that the total cost of the bins used is minimized.
@@ -15,6 +16,8 @@ pub struct BPInstance {
     pub item_area: fsize,
     /// Set of bins available to pack the items, along with their quantities
     pub bins: Vec<(Bin, usize)>,
+    /// Pairs of item ids `(a, b)`, where all copies of item `a` have to be placed before any copy of item `b`
+    pub precedence: Vec<(usize, usize)>,
 }
 
 impl BPInstance {
@@ -30,8 +33,77 @@ impl BPInstance {
             items,
             item_area,
             bins,
+            precedence: vec![],
         }
     }
+
+    /// Constrains the order in which the items have to be placed, see [`InstanceGeneric::precedence`].
+    pub fn with_precedence(mut self, precedence: Vec<(usize, usize)>) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    /// Lower bound on the number of bins required to produce all items: `ceil(item_area / bin_area)`.
+    /// If the bins differ, the area of the largest one is used.
+    pub fn area_lower_bound(&self) -> usize {
+        ceil_ratio(self.item_area, self.max_bin_area())
+    }
+
+    /// Lower bound on the number of bins required to produce all items,
+    /// obtained from the one-dimensional bin packing relaxation in which every item is reduced to its area
+    /// and every bin to the area of the largest one.
+    /// Uses the L2 bound of Martello and Toth, which dominates [`BPInstance::area_lower_bound`]
+    /// by also accounting for items which are too large to share a bin with each other.
+    pub fn lower_bound(&self) -> usize {
+        let capacity = self.max_bin_area();
+        let sizes = self
+            .items
+            .iter()
+            .map(|(item, qty)| (item.shape.area(), *qty))
+            .collect_vec();
+
+        //the bound for a threshold `alpha`: items smaller than it are ignored, the rest is split in three classes
+        let bound = |alpha: fsize| {
+            let (mut n_large, mut n_medium, mut medium_area, mut small_area) = (0, 0, 0.0, 0.0);
+            for &(size, qty) in sizes.iter() {
+                if size > capacity - alpha {
+                    //cannot share a bin with any item of at least `alpha`
+                    n_large += qty;
+                } else if size > capacity / 2.0 {
+                    //at most one per bin, the remaining space of their bins can hold small items
+                    n_medium += qty;
+                    medium_area += size * qty as fsize;
+                } else if size >= alpha {
+                    small_area += size * qty as fsize;
+                }
+            }
+            let residual_space = n_medium as fsize * capacity - medium_area;
+            n_large + n_medium + ceil_ratio(small_area - residual_space, capacity)
+        };
+
+        sizes
+            .iter()
+            .map(|(size, _)| *size)
+            .filter(|size| *size <= capacity / 2.0)
+            .chain([0.0])
+            .map(bound)
+            .fold(self.area_lower_bound(), usize::max)
+    }
+
+    fn max_bin_area(&self) -> fsize {
+        self.bins
+            .iter()
+            .map(|(bin, _)| bin.area)
+            .fold(0.0, fsize::max)
+    }
+}
+
+/// `ceil(a / b)`, with some tolerance for rounding errors. Zero if `a` is not positive.
+fn ceil_ratio(a: fsize, b: fsize) -> usize {
+    match a > 0.0 {
+        true => (a / b - 1e-4).ceil().max(0.0) as usize,
+        false => 0,
+    }
 }
 
 impl InstanceGeneric for BPInstance {
@@ -42,4 +114,8 @@ impl InstanceGeneric for BPInstance {
     fn item_area(&self) -> fsize {
         self.item_area
     }
+
+    fn precedence(&self) -> &[(usize, usize)] {
+        &self.precedence
+    }
 }
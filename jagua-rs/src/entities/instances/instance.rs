@@ -1,8 +1,22 @@
+use std::hash::Hasher;
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::entities::bin::Bin;
 use crate::entities::instances::bin_packing::BPInstance;
 use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::instances::strip_packing::SPInstance;
 use crate::entities::item::Item;
-use crate::fsize;
+use crate::geometry::convex_hull::convex_hull_from_points;
+use crate::geometry::d_transformation::DTransformation;
+use crate::geometry::geo_enums::AllowedRotation;
+use crate::geometry::geo_traits::{Shape, TransformableFrom};
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::transformation::Transformation;
+use crate::util::config::{CDEConfig, GeoTolerance};
+use crate::util::fingerprint::Fingerprinter;
+use crate::{fsize, PI};
 
 /// An `Instance` is the static (unmodifiable) representation of a problem instance.
 /// This enum contains all variants of an instance.
@@ -13,6 +27,161 @@ pub enum Instance {
     BP(BPInstance),
 }
 
+impl Instance {
+    /// Returns all pairs of item ids `(outer, inner)` where a copy of item `inner` fits in a concavity of item `outer`,
+    /// i.e. within the convex hull of `outer` without colliding with it, at one of its allowed rotations.
+    /// An item is only paired with itself if at least two copies of it are demanded.
+    /// Positions are tried at the centers of the cells of a hazard proximity grid over the convex hull of `outer`
+    /// and continuous rotations are tried in steps of [`NESTING_ROTATION_STEP`], so some fitting pairs might be missed.
+    pub fn nesting_candidates(&self) -> Vec<(usize, usize)> {
+        self.items()
+            .par_iter()
+            .filter(|(outer, _)| has_concavity(outer))
+            .flat_map(|(outer, _)| {
+                //the concavities of the outer item are the free space of a bin shaped like its convex hull, with the item as a hole
+                let hull = SimplePolygon::new(convex_hull_from_points(outer.shape.points.clone()));
+                let pocket_bin = Bin::new(
+                    0,
+                    hull,
+                    0,
+                    Transformation::empty(),
+                    vec![outer.shape.as_ref().clone()],
+                    vec![],
+                    CDEConfig::default(),
+                );
+                self.items()
+                    .iter()
+                    .filter(|(inner, qty)| inner.id != outer.id || *qty > 1)
+                    .filter(|(inner, _)| fits_in_bin(inner, &pocket_bin))
+                    .map(|(inner, _)| (outer.id, inner.id))
+                    .collect_vec()
+            })
+            .collect()
+    }
+
+    /// Returns a stable hash of everything the instance consists of: the geometry, rotations, qualities and values
+    /// of all items and bins (or the strip), their demands and the configurations their surrogates and
+    /// collision detection engines were built with.
+    /// Identical instances have the same fingerprint across runs and platforms (for the same `fsize`),
+    /// any change to one of the shapes changes it.
+    pub fn fingerprint(&self) -> u64 {
+        let mut fp = Fingerprinter::new();
+        fp.write_usize(self.items().len());
+        for (item, qty) in self.items() {
+            fingerprint_item(&mut fp, item, *qty);
+        }
+        fp.write_usize(self.precedence().len());
+        for &(a, b) in self.precedence() {
+            fp.write_usize(a);
+            fp.write_usize(b);
+        }
+        match self {
+            Instance::SP(spi) => {
+                fp.write_u8(0);
+                fp.write_fsize(spi.strip_height);
+                fp.write_serialized(&spi.packing_axis);
+            }
+            Instance::BP(bpi) => {
+                fp.write_u8(1);
+                fp.write_usize(bpi.bins.len());
+                for (bin, qty) in bpi.bins.iter() {
+                    fingerprint_bin(&mut fp, bin, *qty);
+                }
+            }
+        }
+        fp.finish()
+    }
+}
+
+fn fingerprint_item(fp: &mut Fingerprinter, item: &Item, qty: usize) {
+    fp.write_usize(item.id);
+    fp.write_usize(qty);
+    fp.write_polygon(&item.shape);
+    match &item.allowed_rotation {
+        AllowedRotation::None => fp.write_u8(0),
+        AllowedRotation::Continuous => fp.write_u8(1),
+        AllowedRotation::Discrete(rotations) => {
+            fp.write_u8(2);
+            fp.write_usize(rotations.len());
+            rotations.iter().for_each(|&r| fp.write_fsize(r));
+        }
+        AllowedRotation::Range { min, max } => {
+            fp.write_u8(3);
+            fp.write_fsize(*min);
+            fp.write_fsize(*max);
+        }
+    }
+    fp.write_serialized(&item.base_quality);
+    fp.write_u64(item.value);
+    fp.write_transformation(&item.pretransform);
+    fp.write_serialized(&item.surrogate_config);
+    fp.write_serialized(&item.position_lattice);
+    fp.write_serialized(&item.min_edge_contact);
+    fp.write_serialized(&item.meta);
+}
+
+fn fingerprint_bin(fp: &mut Fingerprinter, bin: &Bin, qty: usize) {
+    fp.write_usize(bin.id);
+    fp.write_usize(qty);
+    fp.write_polygon(&bin.outer);
+    fp.write_u64(bin.value);
+    fp.write_transformation(&bin.pretransform);
+    fp.write_usize(bin.holes.len());
+    bin.holes.iter().for_each(|hole| fp.write_polygon(hole));
+    for quality_zone in bin.quality_zones.iter().flatten() {
+        fp.write_usize(quality_zone.quality);
+        fp.write_usize(quality_zone.zones.len());
+        quality_zone
+            .zones
+            .iter()
+            .for_each(|zone| fp.write_polygon(zone));
+    }
+    fp.write_serialized(&bin.base_cde.config());
+    fp.write_fsize(bin.inset);
+}
+
+/// Step (in radians) with which continuous rotations are sampled when searching for [`Instance::nesting_candidates`]
+pub const NESTING_ROTATION_STEP: fsize = PI / 12.0;
+
+fn has_concavity(item: &Item) -> bool {
+    let hull = SimplePolygon::new(convex_hull_from_points(item.shape.points.clone()));
+    !GeoTolerance::DEFAULT.eq(hull.area(), item.shape.area())
+}
+
+/// Whether the item fits somewhere in the bin, trying every cell of the bin's hazard proximity grid which could accommodate it
+fn fits_in_bin(item: &Item, bin: &Bin) -> bool {
+    let cde = &bin.base_cde;
+    let Ok(hpg) = cde.haz_prox_grid() else {
+        return false;
+    };
+    let rotations = match &item.allowed_rotation {
+        AllowedRotation::None => vec![0.0],
+        AllowedRotation::Discrete(rotations) => rotations.clone(),
+        AllowedRotation::Continuous => (0..24)
+            .map(|i| i as fsize * NESTING_ROTATION_STEP)
+            .collect(),
+        AllowedRotation::Range { min, max } => {
+            let n_steps = ((max - min) / NESTING_ROTATION_STEP).floor() as usize;
+            (0..=n_steps)
+                .map(|i| min + i as fsize * NESTING_ROTATION_STEP)
+                .collect()
+        }
+    };
+    let mut buffer = item.shape.as_ref().clone();
+    hpg.grid
+        .cells
+        .iter()
+        .flatten()
+        .filter(|cell| cell.could_accommodate_item(item))
+        .any(|cell| {
+            rotations.iter().any(|&rotation| {
+                let d_transf = DTransformation::new(rotation, cell.centroid.into());
+                buffer.transform_from(&item.shape, &d_transf.compose());
+                !cde.poly_collides(&buffer, &[])
+            })
+        })
+}
+
 impl InstanceGeneric for Instance {
     fn items(&self) -> &[(Item, usize)] {
         match self {
@@ -27,6 +196,13 @@ impl InstanceGeneric for Instance {
             Instance::BP(instance) => instance.item_area(),
         }
     }
+
+    fn precedence(&self) -> &[(usize, usize)] {
+        match self {
+            Instance::SP(instance) => instance.precedence(),
+            Instance::BP(instance) => instance.precedence(),
+        }
+    }
 }
 
 impl From<SPInstance> for Instance {
@@ -1,6 +1,12 @@
+use std::iter::Map;
+use std::slice::Iter;
+
 use crate::entities::item::Item;
 use crate::fsize;
 
+/// Iterator over the items of an instance, see [`InstanceGeneric::item_iter`]
+pub type ItemIter<'a> = Map<Iter<'a, (Item, usize)>, fn(&(Item, usize)) -> &Item>;
+
 /// Trait for shared functionality of all instance variants.
 pub trait InstanceGeneric {
     fn items(&self) -> &[(Item, usize)];
@@ -10,8 +16,15 @@ pub trait InstanceGeneric {
     fn item(&self, id: usize) -> &Item {
         &self.items()[id].0
     }
+    /// Iterates over all items, in order of their id, without their quantities
+    fn item_iter(&self) -> ItemIter<'_> {
+        self.items().iter().map(|(item, _)| item)
+    }
     fn total_item_qty(&self) -> usize {
         self.items().iter().map(|(_, qty)| qty).sum()
     }
     fn item_area(&self) -> fsize;
+    /// Pairs of item ids `(a, b)`, where all copies of item `a` have to be placed before any copy of item `b`.
+    /// See [`crate::entities::solution::Solution::precedence_violations`].
+    fn precedence(&self) -> &[(usize, usize)];
 }
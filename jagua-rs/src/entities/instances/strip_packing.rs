@@ -1,8 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::item::Item;
-use crate::fsize;
 use crate::geometry::geo_traits::Shape;
+use crate::geometry::transformation::Transformation;
 use crate::util::assertions;
+use crate::{fsize, PI};
 
 /// Strip-packing problem instance: a set of items to be packed into a single strip.
 /// The items are to be packed in such a way that the total width of the strip used is minimized.
@@ -12,8 +15,12 @@ pub struct SPInstance {
     pub items: Vec<(Item, usize)>,
     /// The total area of the items
     pub item_area: fsize,
-    /// The (fixed) height of the strip
+    /// The (fixed) height of the strip, in the internal coordinates where the strip extends along the X axis
     pub strip_height: fsize,
+    /// The axis along which the strip extends in the coordinates of the instance
+    pub packing_axis: PackingAxis,
+    /// Pairs of item ids `(a, b)`, where all copies of item `a` have to be placed before any copy of item `b`
+    pub precedence: Vec<(usize, usize)>,
 }
 
 impl SPInstance {
@@ -29,6 +36,55 @@ impl SPInstance {
             items,
             item_area,
             strip_height,
+            packing_axis: PackingAxis::X,
+            precedence: vec![],
+        }
+    }
+
+    /// Constrains the order in which the items have to be placed, see [`InstanceGeneric::precedence`].
+    pub fn with_precedence(mut self, precedence: Vec<(usize, usize)>) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    /// Sets the axis along which the strip extends, see [`PackingAxis`].
+    /// The items are expected to already be rotated to the internal coordinates.
+    pub fn with_packing_axis(mut self, packing_axis: PackingAxis) -> Self {
+        self.packing_axis = packing_axis;
+        self
+    }
+}
+
+/// Axis along which a strip extends, which is the dimension of the strip to be minimized.
+/// Internally, the strip always extends along the X axis.
+/// Strips extending along the Y axis are rotated by 90° to the internal coordinates.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PackingAxis {
+    /// Fixed height, variable width
+    #[default]
+    X,
+    /// Fixed width, variable height
+    Y,
+}
+
+impl PackingAxis {
+    /// Rotation applied to the items and the strip to convert them to the internal coordinates
+    pub fn rotation(&self) -> fsize {
+        match self {
+            PackingAxis::X => 0.0,
+            PackingAxis::Y => -PI / 2.0,
+        }
+    }
+
+    /// Transformation from the coordinates of the instance to the internal coordinates
+    /// of a strip with fixed dimension `fixed_dim`, with the origins of both strips coinciding.
+    pub fn strip_pretransform(&self, fixed_dim: fsize) -> Transformation {
+        match self {
+            PackingAxis::X => Transformation::empty(),
+            //(x, y) -> (y, fixed_dim - x)
+            PackingAxis::Y => {
+                Transformation::from_rotation(self.rotation()).translate((0.0, fixed_dim))
+            }
         }
     }
 }
@@ -41,4 +97,8 @@ impl InstanceGeneric for SPInstance {
     fn item_area(&self) -> fsize {
         self.item_area
     }
+
+    fn precedence(&self) -> &[(usize, usize)] {
+        &self.precedence
+    }
 }
@@ -1,10 +1,19 @@
 use std::sync::Arc;
 
+use itertools::Itertools;
+
 use crate::collision_detection::hazard_filter::QZHazardFilter;
+use crate::entities::bin::Bin;
+use crate::entities::instances::strip_packing::PackingAxis;
+use crate::geometry::d_transformation::DTransformation;
+use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
 use crate::geometry::geo_enums::AllowedRotation;
+use crate::geometry::primitives::circle::Circle;
+use crate::geometry::primitives::point::Point;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
 use crate::util::config::SPSurrogateConfig;
+use crate::{fsize, PI};
 
 /// Item to be placed in a Layout
 #[derive(Clone, Debug)]
@@ -23,6 +32,24 @@ pub struct Item {
     pub hazard_filter: Option<QZHazardFilter>,
     /// Configuration for the surrogate generation
     pub surrogate_config: SPSurrogateConfig,
+    /// Order of the rotational symmetry of the shape, see [SimplePolygon::rotational_symmetry].
+    /// Rotations which differ by a multiple of `2π / rotational_symmetry` result in the same shape.
+    pub rotational_symmetry: u32,
+    /// Steps (x, y) of the lattice the item's position (in the coordinates of the input file) is restricted to, if any.
+    /// See [Item::snap_to_lattice].
+    pub position_lattice: Option<(fsize, fsize)>,
+    /// Minimum length of its boundary every placement of the item has to share with other items or the bin, if any.
+    /// See [Layout::edge_contact_violations](crate::entities::layout::Layout::edge_contact_violations).
+    pub min_edge_contact: Option<fsize>,
+    /// Metadata of the item from the input file, not interpreted but passed through to the solution
+    pub meta: Option<serde_json::Value>,
+    /// Exact shape of the item if it is circular, in the same coordinates as `shape`.
+    /// `shape` is then a polygon circumscribing the circle: placed items collide with others as that polygon,
+    /// while the circle is used to check where the item itself can be placed, see [CDEngine::item_collides](crate::collision_detection::cd_engine::CDEngine::item_collides).
+    pub circle: Option<Circle>,
+    /// Contour of the item as defined in the input file, if polygon simplification changed it.
+    /// Unlike `shape`, it is not pretransformed: the absolute transformations of a solution apply to it directly.
+    pub original_shape: Option<Arc<SimplePolygon>>,
 }
 
 impl Item {
@@ -36,6 +63,33 @@ impl Item {
         surrogate_config: SPSurrogateConfig,
     ) -> Item {
         shape.generate_surrogate(surrogate_config);
+        Item::new_with_surrogate(
+            id,
+            shape,
+            allowed_rotation,
+            base_quality,
+            value,
+            pretransform,
+            surrogate_config,
+        )
+    }
+
+    /// Same as [Item::new], but for a shape of which the surrogate was already generated with `surrogate_config`
+    /// (for example one restored from a cache), which is then not generated again.
+    pub fn new_with_surrogate(
+        id: usize,
+        shape: SimplePolygon,
+        allowed_rotation: AllowedRotation,
+        base_quality: Option<usize>,
+        value: u64,
+        pretransform: Transformation,
+        surrogate_config: SPSurrogateConfig,
+    ) -> Item {
+        assert!(
+            shape.surrogate.is_some(),
+            "surrogate of item {id} not generated"
+        );
+        let rotational_symmetry = shape.rotational_symmetry();
         let shape = Arc::new(shape);
         let hazard_filter = base_quality.map(QZHazardFilter);
         Item {
@@ -47,6 +101,151 @@ impl Item {
             pretransform,
             hazard_filter,
             surrogate_config,
+            rotational_symmetry,
+            position_lattice: None,
+            min_edge_contact: None,
+            meta: None,
+            circle: None,
+            original_shape: None,
+        }
+    }
+
+    /// Restricts the position of the item to a lattice with the given steps, see [Item::snap_to_lattice].
+    pub fn with_position_lattice(mut self, position_lattice: Option<(fsize, fsize)>) -> Self {
+        if let Some((step_x, step_y)) = position_lattice {
+            assert!(
+                step_x > 0.0 && step_y > 0.0,
+                "lattice steps of item {} should be positive",
+                self.id
+            );
         }
+        self.position_lattice = position_lattice;
+        self
+    }
+
+    /// Requires placements of the item to share at least `min_edge_contact` of their boundary, see [Item::min_edge_contact].
+    pub fn with_min_edge_contact(mut self, min_edge_contact: Option<fsize>) -> Self {
+        if let Some(min_edge_contact) = min_edge_contact {
+            assert!(
+                min_edge_contact >= 0.0,
+                "minimum edge contact of item {} should not be negative",
+                self.id
+            );
+        }
+        self.min_edge_contact = min_edge_contact;
+        self
+    }
+
+    /// Attaches metadata to the item, see [Item::meta].
+    pub fn with_meta(mut self, meta: Option<serde_json::Value>) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Marks the item as circular, see [Item::circle].
+    pub fn with_circle(mut self, circle: Option<Circle>) -> Self {
+        self.circle = circle;
+        self
+    }
+
+    /// Attaches the contour of the item before simplification, see [Item::original_shape].
+    pub fn with_original_shape(mut self, original_shape: Option<SimplePolygon>) -> Self {
+        self.original_shape = original_shape.map(Arc::new);
+        self
+    }
+
+    /// The surrogate of the item's shape, generated during its creation with [Item::surrogate_config]
+    pub fn surrogate(&self) -> &SPSurrogate {
+        self.shape.surrogate()
+    }
+
+    /// Whether the item can be placed with `rotation` (in radians), see [AllowedRotation::allows]
+    pub fn is_rotation_allowed(&self, rotation: fsize) -> bool {
+        self.allowed_rotation.allows(rotation)
+    }
+
+    /// Moves a placement of the item in `bin` to the nearest position on its lattice, keeping the rotation.
+    /// The lattice is defined in the coordinates of the input file, so both the pretransformations
+    /// of the item and the bin are taken into account.
+    /// Placements of items without a lattice are returned unchanged.
+    pub fn snap_to_lattice(&self, d_transf: &DTransformation, bin: &Bin) -> DTransformation {
+        let Some((step_x, step_y)) = self.position_lattice else {
+            return *d_transf;
+        };
+        //position of the item in the coordinates of the input file
+        let absolute = Transformation::empty()
+            .transform(&self.pretransform)
+            .transform_from_decomposed(d_transf)
+            .transform(&bin.pretransform.clone().inverse())
+            .decompose();
+        let (x, y) = absolute.translation();
+        let snapped = DTransformation::new(
+            absolute.rotation(),
+            ((x / step_x).round() * step_x, (y / step_y).round() * step_y),
+        );
+        //back to the internal coordinates
+        Transformation::empty()
+            .transform(&self.pretransform.clone().inverse())
+            .transform_from_decomposed(&snapped)
+            .transform(&bin.pretransform)
+            .decompose()
+    }
+
+    /// The allowed rotation (in radians) which minimizes the extent of the item along `axis`,
+    /// e.g. its height for [`PackingAxis::Y`]. Ties are resolved in favor of the first candidate rotation.
+    /// For continuous rotations (and ranges), the minimum is attained with an edge of the convex hull perpendicular to `axis`
+    /// or at a bound of the range, so only those rotations are evaluated.
+    pub fn min_footprint_rotation(&self, axis: PackingAxis) -> fsize {
+        let hull = self
+            .surrogate()
+            .convex_hull_indices
+            .iter()
+            .map(|&i| self.shape.points[i])
+            .collect_vec();
+
+        //extent of the rotated hull along the axis
+        let extent = |rotation: fsize| {
+            let (sin, cos) = rotation.sin_cos();
+            let (min, max) = hull
+                .iter()
+                .map(|Point(x, y)| match axis {
+                    PackingAxis::X => x * cos - y * sin,
+                    PackingAxis::Y => x * sin + y * cos,
+                })
+                .fold((fsize::INFINITY, fsize::NEG_INFINITY), |(min, max), v| {
+                    (min.min(v), max.max(v))
+                });
+            max - min
+        };
+
+        let candidates = match &self.allowed_rotation {
+            AllowedRotation::None => vec![0.0],
+            AllowedRotation::Discrete(rotations) => rotations.clone(),
+            AllowedRotation::Continuous | AllowedRotation::Range { .. } => {
+                //rotations which turn an edge of the hull perpendicular to the axis (in either direction)
+                let aligning = (0..hull.len()).flat_map(|i| {
+                    let (Point(x1, y1), Point(x2, y2)) = (hull[i], hull[(i + 1) % hull.len()]);
+                    let edge_angle = (y2 - y1).atan2(x2 - x1);
+                    let rotation = match axis {
+                        PackingAxis::X => PI / 2.0 - edge_angle,
+                        PackingAxis::Y => -edge_angle,
+                    };
+                    [rotation, rotation + PI].map(|r| r.rem_euclid(2.0 * PI))
+                });
+                let bounds = match self.allowed_rotation {
+                    AllowedRotation::Range { min, max } => vec![min, max],
+                    _ => vec![],
+                };
+                bounds
+                    .into_iter()
+                    .chain(aligning.filter(|&r| self.is_rotation_allowed(r)))
+                    .collect_vec()
+            }
+        };
+
+        candidates
+            .into_iter()
+            .min_by(|&a, &b| extent(a).total_cmp(&extent(b)))
+            .unwrap_or(0.0)
     }
 }
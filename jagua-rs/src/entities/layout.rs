@@ -1,12 +1,23 @@
+use std::hash::{Hash, Hasher};
+
 use crate::collision_detection::cd_engine::{CDESnapshot, CDEngine};
-use crate::collision_detection::hazard::{Hazard, HazardEntity};
+use crate::collision_detection::hazard::{Cutout, Hazard, HazardEntity};
+use crate::collision_detection::hazard_filter;
+use crate::collision_detection::hazard_filter::{CombinedHazardFilter, EntityHazardFilter};
+use crate::collision_detection::quadtree::qt_node::QTNode;
 use crate::entities::bin::Bin;
+use crate::entities::instances::bin_packing::BPInstance;
+use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::item::Item;
 use crate::entities::placed_item::{PItemKey, PlacedItem};
 use crate::fsize;
 use crate::geometry::d_transformation::DTransformation;
-use crate::geometry::geo_traits::Shape;
+use crate::geometry::geo_traits::{CollidesWith, DistanceFrom, Shape};
+use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::point::Point;
 use crate::util::assertions;
+use crate::PI;
+use itertools::Itertools;
 use slotmap::SlotMap;
 
 ///A Layout is made out of a [Bin] with a set of [Item]s positioned inside of it in a specific way.
@@ -47,11 +58,55 @@ impl Layout {
         // swap the bin
         self.bin = bin;
         // update the CDE
-        self.cde = self.bin.base_cde.as_ref().clone();
-        for (_, pi) in self.placed_items.iter() {
+        self.cde = self.fresh_cde();
+    }
+
+    /// Builds a collision detection engine from scratch for the bin and the currently placed items
+    fn fresh_cde(&self) -> CDEngine {
+        let mut cde = self.bin.base_cde.as_ref().clone();
+        for (_, pi) in self.placed_items.iter().filter(|(_, pi)| !pi.subtract) {
             let hazard = Hazard::new(pi.into(), pi.shape.clone());
-            self.cde.register_hazard(hazard);
+            cde.register_hazard(hazard);
         }
+        for (_, pi) in self.placed_items.iter().filter(|(_, pi)| pi.subtract) {
+            let cutout = self.create_cutout(pi);
+            cde.register_cutout(cutout);
+        }
+        cde
+    }
+
+    /// Checks whether none of the placed items collide with each other or with the bin.
+    /// Instead of relying on the incrementally maintained [CDEngine], a fresh one is built from the bin and the placed items,
+    /// so inconsistencies which accumulated in the engine's state do not go unnoticed.
+    pub fn is_collision_free(&self) -> bool {
+        let cde = self.fresh_cde();
+        self.placed_items
+            .values()
+            .filter(|pi| !pi.subtract)
+            .all(|pi| {
+                let ehf = EntityHazardFilter(vec![pi.into()]);
+                let combo_filter = match &pi.hazard_filter {
+                    None => CombinedHazardFilter {
+                        filters: vec![Box::new(&ehf)],
+                    },
+                    Some(hf) => CombinedHazardFilter {
+                        filters: vec![Box::new(&ehf), Box::new(hf)],
+                    },
+                };
+                let mut irrelevant_hazards =
+                    hazard_filter::generate_irrelevant_hazards(&combo_filter, cde.all_hazards());
+
+                //items placed inside one of the item's cutouts do not collide with it
+                for cutout in cde.cutouts().iter().filter(|c| c.parent == pi.into()) {
+                    irrelevant_hazards.extend(
+                        self.placed_items
+                            .values()
+                            .filter(|other| !other.subtract && cutout.contains_poly(&other.shape))
+                            .map(HazardEntity::from),
+                    );
+                }
+                !cde.placed_item_collides(pi, &irrelevant_hazards)
+            })
     }
 
     pub fn create_snapshot(&mut self) -> LayoutSnapshot {
@@ -80,6 +135,10 @@ impl Layout {
 
     pub fn place_item(&mut self, item: &Item, d_transformation: DTransformation) -> PItemKey {
         let pi = PlacedItem::new(item, d_transformation);
+        self.assert_rotation_allowed(item, &pi);
+        if self.cde.config().verify_placements {
+            self.verify_placement(item, &pi);
+        }
         let hazard = Hazard::new(HazardEntity::from(&pi), pi.shape.clone());
 
         let pik = self.placed_items.insert(pi);
@@ -90,6 +149,69 @@ impl Layout {
         pik
     }
 
+    /// Places an item which cuts its shape out of the placed item it lies in, see [`PlacedItem::subtract`].
+    /// Its area is subtracted from the usage and other items can be placed inside the region again.
+    /// Cutouts have to be removed before the item they were cut out of.
+    pub fn place_cutout(&mut self, item: &Item, d_transformation: DTransformation) -> PItemKey {
+        let pi = PlacedItem {
+            subtract: true,
+            ..PlacedItem::new(item, d_transformation)
+        };
+        self.assert_rotation_allowed(item, &pi);
+        let cutout = self.create_cutout(&pi);
+
+        let pik = self.placed_items.insert(pi);
+        self.cde.register_cutout(cutout);
+
+        pik
+    }
+
+    /// Panics if the placed item is rotated in a way its item does not allow
+    fn assert_rotation_allowed(&self, item: &Item, pi: &PlacedItem) {
+        assert!(
+            item.is_rotation_allowed(pi.d_transf.rotation()),
+            "placement of item {} at [{}] in layout {} has a rotation which is not allowed: {:?}",
+            item.id,
+            pi.d_transf,
+            self.id,
+            item.allowed_rotation
+        );
+    }
+
+    /// Panics if the placed item collides with any of the (relevant) hazards in the layout.
+    /// Only performed if enabled in the [CDEConfig](crate::util::config::CDEConfig), see `verify_placements`.
+    fn verify_placement(&self, item: &Item, pi: &PlacedItem) {
+        let irrel_hazards = match item.hazard_filter.as_ref() {
+            None => vec![],
+            Some(hf) => hazard_filter::generate_irrelevant_hazards(hf, self.cde.all_hazards()),
+        };
+        if self.cde.placed_item_collides(pi, &irrel_hazards) {
+            let mut colliding = vec![];
+            self.cde
+                .collect_poly_collisions(&pi.shape, &irrel_hazards, &mut colliding);
+            panic!(
+                "placement of item {} at [{}] in layout {} collides with {:?}",
+                item.id, pi.d_transf, self.id, colliding
+            );
+        }
+    }
+
+    /// Creates the [Cutout] for a subtracting placed item, cut out of the smallest placed item enclosing it.
+    fn create_cutout(&self, pi: &PlacedItem) -> Cutout {
+        let parent = self
+            .placed_items
+            .values()
+            .filter(|p| !p.subtract && p.shape.encloses(&pi.shape))
+            .min_by(|a, b| a.shape.area().partial_cmp(&b.shape.area()).unwrap())
+            .map(HazardEntity::from)
+            .expect("a cutout has to lie entirely inside a placed item");
+        Cutout {
+            entity: pi.into(),
+            parent,
+            shape: pi.shape.clone(),
+        }
+    }
+
     pub fn remove_item(&mut self, key: PItemKey, commit_instant: bool) -> PlacedItem {
         let pi = self
             .placed_items
@@ -97,8 +219,21 @@ impl Layout {
             .expect("key is not valid anymore");
 
         // update the collision detection engine
-        self.cde
-            .deregister_hazard(HazardEntity::from(&pi), commit_instant);
+        match pi.subtract {
+            true => self.cde.deregister_cutout(HazardEntity::from(&pi)),
+            false => {
+                debug_assert!(
+                    !self
+                        .cde
+                        .cutouts()
+                        .iter()
+                        .any(|c| c.parent == HazardEntity::from(&pi)),
+                    "cutouts have to be removed before the item they were cut out of"
+                );
+                self.cde
+                    .deregister_hazard(HazardEntity::from(&pi), commit_instant)
+            }
+        }
 
         debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
 
@@ -121,19 +256,182 @@ impl Layout {
             .map(|(k, _)| k)
     }
 
+    /// Returns all maximal axis-aligned rectangles which fit in the empty space of the layout.
+    /// Placed items and holes are approximated by their bounding box, the bin by its bounding box.
+    pub fn maximal_empty_rects(&self) -> Vec<AARectangle> {
+        let obstacles = self.bin.holes.iter().map(|h| h.bbox()).chain(
+            self.placed_items
+                .values()
+                .filter(|pi| !pi.subtract)
+                .map(|pi| pi.shape.bbox()),
+        );
+
+        let mut empty_rects = vec![self.bin.bbox()];
+        for obstacle in obstacles {
+            empty_rects = empty_rects
+                .iter()
+                .flat_map(|r| r.subtract(&obstacle))
+                .collect_vec();
+            AARectangle::remove_enclosed(&mut empty_rects);
+        }
+        empty_rects
+    }
+
+    /// Returns the placed item closest to `p`, along with its distance to `p` (0 if `p` lies inside the item).
+    /// Regions cut out of items are not items themselves, see [`PlacedItem::subtract`].
+    /// Ties are broken in favor of the smallest key.
+    pub fn nearest_item(&self, p: Point) -> Option<(PItemKey, fsize)> {
+        let mut nearest = None;
+        self.nearest_item_in_node(self.cde.quadtree(), p, &mut nearest, &mut vec![]);
+        nearest
+    }
+
+    /// Branch and bound over the quadtree of the CDE: the children of a node are visited from near to far,
+    /// nodes further away from `p` than the nearest item found so far are pruned.
+    fn nearest_item_in_node(
+        &self,
+        node: &QTNode,
+        p: Point,
+        nearest: &mut Option<(PItemKey, fsize)>,
+        evaluated: &mut Vec<HazardEntity>,
+    ) {
+        if nearest.is_some_and(|(_, nd)| node.bbox.distance(&p) > nd) {
+            return;
+        }
+        match &node.children {
+            Some(children) => {
+                let by_distance = children
+                    .iter()
+                    .sorted_by(|a, b| a.bbox.distance(&p).total_cmp(&b.bbox.distance(&p)));
+                for child in by_distance {
+                    self.nearest_item_in_node(child, p, nearest, evaluated);
+                }
+            }
+            None => {
+                let entities = node.hazards.active_hazards().iter().map(|hz| hz.entity);
+                for entity in entities.filter(|e| matches!(e, HazardEntity::PlacedItem { .. })) {
+                    if evaluated.contains(&entity) {
+                        continue;
+                    }
+                    evaluated.push(entity);
+                    let k = self
+                        .hazard_to_p_item_key(&entity)
+                        .expect("hazard of an item which is not placed");
+                    let distance = self.placed_items[k].shape.distance(&p);
+                    *nearest = match *nearest {
+                        Some((nk, nd)) if nd < distance || (nd == distance && nk < k) => {
+                            Some((nk, nd))
+                        }
+                        _ => Some((k, distance)),
+                    };
+                }
+            }
+        }
+    }
+
+    /// Returns the placed item which contains `p`, if any.
+    /// A point in a region cut out of an item does not belong to that item, but to the item placed in the cutout (if any).
+    /// Points on the boundary between two touching items are attributed to the one with the smallest key.
+    pub fn item_at(&self, p: Point) -> Option<PItemKey> {
+        let cut_out_of = self
+            .cde
+            .cutouts()
+            .iter()
+            .filter(|c| c.shape.bbox().collides_with(&p) && c.shape.collides_with(&p))
+            .map(|c| c.parent)
+            .collect_vec();
+
+        //broad phase on the quadtree of the CDE, narrow phase on the shapes
+        let mut candidates = vec![];
+        collect_items_at(self.cde.quadtree(), p, &mut candidates);
+        candidates
+            .iter()
+            .filter(|e| !cut_out_of.contains(e))
+            .filter_map(|e| self.hazard_to_p_item_key(e))
+            .filter(|k| self.placed_items[*k].shape.collides_with(&p))
+            .min()
+    }
+
+    /// Total length of the boundary shared by placed items `a` and `b`: the overlapping parts of their collinear edges.
+    /// Edges within the geometric tolerance of the CDE (relative to the larger diameter of both shapes) count as collinear.
+    pub fn edge_contact(&self, a: PItemKey, b: PItemKey) -> fsize {
+        let (shape_a, shape_b) = (&self.placed_items[a].shape, &self.placed_items[b].shape);
+        let tolerance = self.contact_tolerance(fsize::max(shape_a.diameter(), shape_b.diameter()));
+        shape_a.edge_contact(shape_b, tolerance)
+    }
+
+    /// Total length of the boundary placed item `a` shares with the contour and holes of the bin, see [Layout::edge_contact].
+    pub fn bin_edge_contact(&self, a: PItemKey) -> fsize {
+        let shape = &self.placed_items[a].shape;
+        let tolerance = self.contact_tolerance(shape.diameter());
+        [&self.bin.outer]
+            .into_iter()
+            .chain(self.bin.holes.iter())
+            .map(|s| shape.edge_contact(s, tolerance))
+            .sum()
+    }
+
+    /// Total length of the boundary placed item `a` shares with all other placed items and the bin.
+    pub fn total_edge_contact(&self, a: PItemKey) -> fsize {
+        let item_contact = self
+            .placed_items
+            .keys()
+            .filter(|&b| b != a)
+            .map(|b| self.edge_contact(a, b))
+            .sum::<fsize>();
+        item_contact + self.bin_edge_contact(a)
+    }
+
+    /// Returns the placed items which share less of their boundary than their [Item::min_edge_contact] requires,
+    /// see [Layout::total_edge_contact].
+    pub fn edge_contact_violations(&self, instance: &impl InstanceGeneric) -> Vec<PItemKey> {
+        self.placed_items
+            .iter()
+            .filter(|(k, pi)| {
+                instance
+                    .item(pi.item_id)
+                    .min_edge_contact
+                    .is_some_and(|min_contact| self.total_edge_contact(*k) < min_contact)
+            })
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    fn contact_tolerance(&self, diameter: fsize) -> fsize {
+        self.cde.config().geo_tolerance.margin(diameter)
+    }
+
     /// Returns the usage of the bin with the items placed.
-    /// It is the ratio of the area of the items placed to the area of the bin.
+    /// It is the ratio of the area of the items placed (minus the area of any cutouts) to the area of the bin.
     pub fn usage(&self) -> fsize {
         let bin_area = self.bin.area;
         let item_area = self
             .placed_items
             .iter()
-            .map(|(_, pi)| pi.shape.area())
+            .map(|(_, pi)| match pi.subtract {
+                false => pi.shape.area(),
+                true => -pi.shape.area(),
+            })
             .sum::<fsize>();
 
         item_area / bin_area
     }
 
+    /// Polar second moment of area of the placed items about `p` (minus that of any cutouts).
+    /// This is the rotational inertia of the layout around `p`, assuming all items have the same areal density.
+    pub fn second_moment_about(&self, p: Point) -> fsize {
+        self.placed_items
+            .values()
+            .map(|pi| {
+                let (i_x, i_y) = pi.shape.second_moments_about(p);
+                match pi.subtract {
+                    false => i_x + i_y,
+                    true => -(i_x + i_y),
+                }
+            })
+            .sum()
+    }
+
     pub fn id(&self) -> usize {
         self.id
     }
@@ -147,6 +445,39 @@ impl Layout {
     pub fn flush_changes(&mut self) {
         self.cde.flush_haz_prox_grid();
     }
+
+    /// Creates a new instance in which `items` can be packed into the free space of this layout.
+    /// The instance contains a single bin: a copy of this layout's bin, with every placed item turned into a hole.
+    /// Cutouts cannot be represented in a bin, so items are turned into holes as a whole.
+    /// Useful for hierarchical nesting, where small items are nested in the gaps of a previous pass.
+    pub fn to_sub_instance(&self, items: Vec<(Item, usize)>) -> BPInstance {
+        let holes = self
+            .bin
+            .holes
+            .iter()
+            .map(|h| h.as_ref().clone())
+            .chain(
+                self.placed_items
+                    .values()
+                    .filter(|pi| !pi.subtract)
+                    .map(|pi| pi.shape.as_ref().clone()),
+            )
+            .collect();
+        let quality_zones = self.bin.quality_zones.iter().flatten().cloned().collect();
+
+        let bin = Bin::new(
+            0,
+            self.bin.outer.as_ref().clone(),
+            self.bin.value,
+            self.bin.pretransform.clone(),
+            holes,
+            quality_zones,
+            self.bin.base_cde.config(),
+        )
+        .with_inset(self.bin.inset);
+
+        BPInstance::new(items, vec![(bin, 1)])
+    }
 }
 
 /// Immutable and compact representation of a [Layout].
@@ -164,3 +495,105 @@ pub struct LayoutSnapshot {
     /// The usage of the bin with the items placed
     pub usage: fsize,
 }
+
+/// Item id, whether it is a cutout, and the quantized rotation and translation of a placement
+type QuantizedPlacement = (usize, bool, [i64; 3]);
+
+impl LayoutSnapshot {
+    /// Canonical representation of the layout, independent of the order in which the items were placed:
+    /// the bin and the sorted placements, with all coordinates quantized to the geometric tolerance of the bin
+    /// (see [GeoTolerance::margin](crate::util::config::GeoTolerance::margin)).
+    /// Placements which differ by less than the tolerance usually map to the same key,
+    /// unless they lie on opposite sides of a quantization boundary.
+    fn canonical_key(&self) -> (usize, [i64; 4], Vec<QuantizedPlacement>) {
+        let bbox = self.bin.bbox();
+        let tol = self.bin.base_cde.config().geo_tolerance;
+        let step = tol
+            .margin(fsize::max(bbox.width(), bbox.height()))
+            .max(fsize::EPSILON);
+        let quantize = |v: fsize| (v / step).round() as i64;
+
+        //rotations are quantized to a fraction of a full turn, equivalent rotations map to the same value
+        let n_rot_steps = (1.0 / tol.rel.max(fsize::EPSILON)).round() as i64;
+        let quantize_rot = |r: fsize| {
+            ((r.rem_euclid(2.0 * PI) / (2.0 * PI) * n_rot_steps as fsize).round() as i64)
+                % n_rot_steps
+        };
+
+        let bin_key = [bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max].map(quantize);
+        let placements = self
+            .placed_items
+            .values()
+            .map(|pi| {
+                let (x, y) = pi.d_transf.translation();
+                (
+                    pi.item_id,
+                    pi.subtract,
+                    [
+                        quantize_rot(pi.d_transf.rotation()),
+                        quantize(x),
+                        quantize(y),
+                    ],
+                )
+            })
+            .sorted()
+            .collect_vec();
+        (self.bin.id, bin_key, placements)
+    }
+}
+
+/// Two snapshots are equal if they place the same items at the same positions (within tolerance) in the same bin,
+/// regardless of the order in which the items were placed. Ids of the snapshots are not compared.
+impl PartialEq for LayoutSnapshot {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical_key() == other.canonical_key()
+    }
+}
+
+impl Eq for LayoutSnapshot {}
+
+/// Consistent with [LayoutSnapshot]'s equality, allows snapshots to be used as keys in a transposition table.
+impl Hash for LayoutSnapshot {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_key().hash(state);
+    }
+}
+
+/// Collects the placed items present in the leaves of the quadtree which contain `p`
+fn collect_items_at(node: &QTNode, p: Point, detected: &mut Vec<HazardEntity>) {
+    if !node.bbox.collides_with(&p) {
+        return;
+    }
+    match &node.children {
+        Some(children) => children
+            .iter()
+            .for_each(|child| collect_items_at(child, p, detected)),
+        None => {
+            for hz in node.hazards.active_hazards() {
+                if matches!(hz.entity, HazardEntity::PlacedItem { .. })
+                    && !detected.contains(&hz.entity)
+                {
+                    detected.push(hz.entity);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the placed items in an order in which they can be placed back into an empty layout:
+/// regular items first, then the cutouts, and finally the items placed inside of a cutout.
+pub fn placement_order(
+    placed_items: &SlotMap<PItemKey, PlacedItem>,
+) -> impl Iterator<Item = (PItemKey, &PlacedItem)> {
+    let cutouts = placed_items.values().filter(|pi| pi.subtract).collect_vec();
+    placed_items.iter().sorted_by_key(move |(_, pi)| {
+        match (
+            pi.subtract,
+            cutouts.iter().any(|c| c.shape.encloses(&pi.shape)),
+        ) {
+            (false, false) => 0,
+            (true, _) => 1,
+            (false, true) => 2,
+        }
+    })
+}
@@ -2,6 +2,7 @@ pub mod bin;
 pub mod instances;
 pub mod item;
 pub mod layout;
+pub mod pareto_archive;
 pub mod placed_item;
 pub mod placing_option;
 pub mod problems;
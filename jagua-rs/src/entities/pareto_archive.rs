@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::solution::Solution;
+use crate::fsize;
+
+/// Objective on which solutions are compared in a [ParetoArchive]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolutionObjective {
+    /// Minimize the number of bins used
+    NBins,
+    /// Minimize the total cost of the bins used
+    Cost,
+    /// Minimize the total length of the outlines of the placed items, see [Solution::cut_length]
+    CutLength,
+    /// Maximize the number of placed items
+    NItemsPlaced,
+}
+
+impl SolutionObjective {
+    /// Value of the objective for `solution`, lower is better
+    pub fn evaluate(&self, solution: &Solution) -> fsize {
+        match self {
+            SolutionObjective::NBins => solution.layout_snapshots.len() as fsize,
+            SolutionObjective::Cost => solution.total_cost() as fsize,
+            SolutionObjective::CutLength => solution.cut_length(),
+            SolutionObjective::NItemsPlaced => -(solution.n_items_placed() as fsize),
+        }
+    }
+}
+
+/// Collection of solutions which are not [dominated](https://en.wikipedia.org/wiki/Pareto_front) by one another on a set of objectives.
+/// A solution dominates another if it is at least as good on all objectives and strictly better on at least one.
+#[derive(Debug, Clone)]
+pub struct ParetoArchive {
+    objectives: Vec<SolutionObjective>,
+    /// Non-dominated solutions, together with their objective values
+    entries: Vec<(Vec<fsize>, Solution)>,
+    /// Maximum number of solutions kept, see [ParetoArchive::with_max_solutions]
+    max_solutions: Option<usize>,
+}
+
+impl ParetoArchive {
+    pub fn new(objectives: Vec<SolutionObjective>) -> Self {
+        assert!(
+            !objectives.is_empty(),
+            "a Pareto archive needs at least one objective"
+        );
+        Self {
+            objectives,
+            entries: vec![],
+            max_solutions: None,
+        }
+    }
+
+    /// Limits the number of solutions kept in the archive, bounding its memory usage.
+    /// Once the limit is exceeded, the solution which is worst on the first objective (ties broken by the subsequent ones) is dropped,
+    /// so the archive keeps the best solutions of the front, see [ParetoArchive::best].
+    pub fn with_max_solutions(mut self, max_solutions: Option<usize>) -> Self {
+        assert!(
+            max_solutions != Some(0),
+            "a Pareto archive has to keep at least one solution"
+        );
+        self.max_solutions = max_solutions;
+        self
+    }
+
+    /// Adds `solution` to the archive, unless it is dominated by (or equivalent to) one already present.
+    /// Solutions in the archive which are dominated by the new one are removed.
+    /// If the archive is full (see [ParetoArchive::with_max_solutions]), the worst solution is dropped, which can be the new one.
+    /// Returns whether the solution was added.
+    pub fn insert(&mut self, solution: Solution) -> bool {
+        let values = self
+            .objectives
+            .iter()
+            .map(|o| o.evaluate(&solution))
+            .collect::<Vec<_>>();
+        if self
+            .entries
+            .iter()
+            .any(|(other, _)| dominates(other, &values) || *other == values)
+        {
+            return false;
+        }
+        self.entries.retain(|(other, _)| !dominates(&values, other));
+        self.entries.push((values, solution));
+        if self
+            .max_solutions
+            .is_some_and(|max| self.entries.len() > max)
+        {
+            let (worst, _) = self
+                .entries
+                .iter()
+                .enumerate()
+                .max_by(|(_, (a, _)), (_, (b, _))| lexicographic_cmp(a, b))
+                .unwrap();
+            self.entries.remove(worst);
+            return worst != self.entries.len();
+        }
+        true
+    }
+
+    /// The non-dominated solutions, in the order in which they were added
+    pub fn solutions(&self) -> impl Iterator<Item = &Solution> {
+        self.entries.iter().map(|(_, s)| s)
+    }
+
+    /// The solution which is best on the first objective, ties are broken by the subsequent objectives
+    pub fn best(&self) -> Option<&Solution> {
+        self.entries
+            .iter()
+            .min_by(|(a, _), (b, _)| lexicographic_cmp(a, b))
+            .map(|(_, s)| s)
+    }
+
+    pub fn objectives(&self) -> &[SolutionObjective] {
+        &self.objectives
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Compares objective values on the first objective, ties broken by the subsequent objectives
+fn lexicographic_cmp(a: &[fsize], b: &[fsize]) -> Ordering {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Whether objective values `a` dominate `b` (all objectives minimized)
+fn dominates(a: &[fsize], b: &[fsize]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b) && a.iter().zip(b).any(|(a, b)| a < b)
+}
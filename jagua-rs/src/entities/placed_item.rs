@@ -2,6 +2,7 @@ use crate::collision_detection::hazard_filter::QZHazardFilter;
 use crate::entities::item::Item;
 use crate::geometry::d_transformation::DTransformation;
 use crate::geometry::geo_traits::Transformable;
+use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use slotmap::new_key_type;
 use std::sync::Arc;
@@ -22,6 +23,10 @@ pub struct PlacedItem {
     pub hazard_filter: Option<QZHazardFilter>,
     /// The shape of the `Item` after it has been transformed and placed in a `Layout`
     pub shape: Arc<SimplePolygon>,
+    /// If true, the `Item` does not occupy space but cuts its shape out of the placed item it lies in
+    pub subtract: bool,
+    /// The circle of the `Item` after it has been transformed and placed in a `Layout`, if it is circular
+    pub circle: Option<Circle>,
 }
 
 impl PlacedItem {
@@ -35,6 +40,8 @@ impl PlacedItem {
             d_transf,
             shape,
             hazard_filter: qz_haz_filter,
+            subtract: false,
+            circle: item.circle.as_ref().map(|c| c.transform_clone(&transf)),
         }
     }
 }
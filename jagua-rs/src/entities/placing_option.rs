@@ -11,6 +11,8 @@ pub struct PlacingOption {
     pub item_id: usize,
     /// The decomposition of the transformation
     pub d_transf: DTransformation,
+    /// Whether the item cuts its shape out of the placed item it lies in, instead of occupying space
+    pub subtract: bool,
 }
 
 impl PlacingOption {
@@ -19,6 +21,7 @@ impl PlacingOption {
             layout_idx,
             item_id: placed_item.item_id,
             d_transf: placed_item.d_transf,
+            subtract: placed_item.subtract,
         }
     }
 }
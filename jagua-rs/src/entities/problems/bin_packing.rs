@@ -1,5 +1,9 @@
+use std::borrow::Borrow;
+
 use itertools::Itertools;
+use ordered_float::NotNan;
 
+use crate::entities::bin::Bin;
 use crate::entities::instances::bin_packing::BPInstance;
 use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::layout::Layout;
@@ -8,6 +12,7 @@ use crate::entities::placing_option::PlacingOption;
 use crate::entities::problems::problem_generic::private::ProblemGenericPrivate;
 use crate::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
 use crate::entities::solution::Solution;
+use crate::geometry::geo_traits::Shape;
 use crate::util::assertions;
 
 /// Bin Packing Problem
@@ -15,14 +20,19 @@ use crate::util::assertions;
 pub struct BPProblem {
     pub instance: BPInstance,
     pub layouts: Vec<Layout>,
+    /// Maximum number of items a single layout can hold, regardless of whether more would fit.
+    /// Placing an item in a full layout is not allowed, forcing a new bin to be opened instead.
+    pub max_items_per_layout: Option<usize>,
     template_layouts: Vec<Layout>,
     missing_item_qtys: Vec<isize>,
+    placement_order: Vec<usize>,
     bin_qtys: Vec<usize>,
     layout_id_counter: usize,
     solution_id_counter: usize,
     unmodified_layout_ids: Vec<usize>,
     unmodified_layouts_ref_solution: Option<usize>,
     uncommitted_removed_layouts: Vec<Layout>,
+    locked_layout_ids: Vec<usize>,
 }
 
 impl BPProblem {
@@ -48,14 +58,39 @@ impl BPProblem {
         Self {
             instance,
             layouts,
+            max_items_per_layout: None,
             template_layouts,
             missing_item_qtys,
+            placement_order: vec![],
             bin_qtys,
             layout_id_counter,
             solution_id_counter: 0,
             unmodified_layout_ids: unchanged_layouts,
             unmodified_layouts_ref_solution: unchanged_layouts_solution_id,
             uncommitted_removed_layouts,
+            locked_layout_ids: vec![],
+        }
+    }
+
+    /// Locks a layout, e.g. a sheet already approved for production.
+    /// Its items can no longer be removed and no more items are placed in it, see [ProblemGeneric::layout_is_locked].
+    /// The lock follows the layout (not the index), and persists when restoring a solution which contains the layout.
+    pub fn lock_layout(&mut self, layout_index: LayoutIndex) {
+        match layout_index {
+            LayoutIndex::Real(i) => {
+                let id = self.layouts[i].id();
+                if !self.locked_layout_ids.contains(&id) {
+                    self.locked_layout_ids.push(id);
+                }
+            }
+            LayoutIndex::Template(_) => panic!("cannot lock template layout"),
+        }
+    }
+
+    pub fn unlock_layout(&mut self, layout_index: LayoutIndex) {
+        if let LayoutIndex::Real(i) = layout_index {
+            let id = self.layouts[i].id();
+            self.locked_layout_ids.retain(|&l_id| l_id != id);
         }
     }
 
@@ -63,6 +98,55 @@ impl BPProblem {
         self.deregister_layout(layout_index);
     }
 
+    /// Opens a new, empty layout for the item, using the cheapest bin with remaining stock that is large enough (by area) to hold it.
+    /// Among equally expensive bins, the smallest one is used.
+    /// Bins of which an empty layout is already open are skipped, as the item evidently did not fit there.
+    /// Returns the index of the opened layout, or `None` if no such bin is available.
+    pub fn ensure_open_bin_for(&mut self, item_id: usize) -> Option<LayoutIndex> {
+        self.ensure_open_bin_for_by_key(item_id, |bin| {
+            (bin.value, NotNan::new(bin.area).expect("bin area is NaN"))
+        })
+    }
+
+    /// Same as [BPProblem::ensure_open_bin_for], but opens the eligible bin with the lowest `key` instead of the cheapest one.
+    pub fn ensure_open_bin_for_by_key<K: Ord>(
+        &mut self,
+        item_id: usize,
+        key: impl Fn(&Bin) -> K,
+    ) -> Option<LayoutIndex> {
+        let item_area = self.instance.item(item_id).shape.area();
+        let bin_id = self
+            .instance
+            .bins
+            .iter()
+            .map(|(bin, _)| bin)
+            .filter(|bin| self.bin_qtys[bin.id] > 0 && bin.area >= item_area)
+            .filter(|bin| {
+                !self
+                    .layouts
+                    .iter()
+                    .any(|l| l.bin.id == bin.id && l.is_empty())
+            })
+            .min_by_key(|bin| key(bin))
+            .map(|bin| bin.id)?;
+
+        let next_layout_id = self.next_layout_id();
+        let layout = self.template_layouts[bin_id].clone_with_id(next_layout_id);
+        Some(self.register_layout(layout))
+    }
+
+    /// Removes all layouts which do not contain any items, returning their bins to the stock.
+    /// Locked layouts are kept.
+    pub fn remove_empty_layouts(&mut self) {
+        while let Some(i) = self
+            .layouts
+            .iter()
+            .position(|l| l.is_empty() && !self.locked_layout_ids.contains(&l.id()))
+        {
+            self.deregister_layout(LayoutIndex::Real(i));
+        }
+    }
+
     pub fn register_layout(&mut self, layout: Layout) -> LayoutIndex {
         self.register_bin(layout.bin.id);
         layout
@@ -76,6 +160,11 @@ impl BPProblem {
     pub fn deregister_layout(&mut self, layout_index: LayoutIndex) {
         match layout_index {
             LayoutIndex::Real(i) => {
+                assert!(
+                    !self.layout_is_locked(layout_index),
+                    "cannot remove locked layout {:?}",
+                    layout_index
+                );
                 let layout = self.layouts.remove(i);
                 self.layout_has_changed(layout.id());
                 self.deregister_bin(layout.bin.id);
@@ -113,6 +202,11 @@ impl BPProblem {
 
 impl ProblemGeneric for BPProblem {
     fn place_item(&mut self, p_opt: PlacingOption) -> (LayoutIndex, PItemKey) {
+        assert!(
+            !self.layout_is_full(p_opt.layout_idx),
+            "layout {:?} is locked or has reached the maximum number of items",
+            p_opt.layout_idx
+        );
         let layout_index = match &p_opt.layout_idx {
             LayoutIndex::Real(i) => LayoutIndex::Real(*i),
             LayoutIndex::Template(i) => {
@@ -128,7 +222,10 @@ impl ProblemGeneric for BPProblem {
             LayoutIndex::Template(_) => unreachable!("cannot place item in template layout"),
         };
         let item = self.instance.item(p_opt.item_id);
-        let pik = layout.place_item(item, p_opt.d_transf);
+        let pik = match p_opt.subtract {
+            false => layout.place_item(item, p_opt.d_transf),
+            true => layout.place_cutout(item, p_opt.d_transf),
+        };
         let layout_id = layout.id();
 
         self.register_included_item(p_opt.item_id);
@@ -143,17 +240,27 @@ impl ProblemGeneric for BPProblem {
         pik: PItemKey,
         commit_instantly: bool,
     ) -> PlacingOption {
+        assert!(
+            !self.layout_is_locked(layout_index),
+            "cannot remove item from locked layout {:?}",
+            layout_index
+        );
         match layout_index {
             LayoutIndex::Real(i) => {
                 self.layout_has_changed(self.layouts[i].id());
                 let layout = &mut self.layouts[i];
                 let pi = layout.remove_item(pik, commit_instantly);
-                if layout.is_empty() {
-                    //if layout is empty, remove it
-                    self.deregister_layout(layout_index);
-                }
+                let bin_id = layout.bin.id;
                 self.deregister_included_item(pi.item_id);
-                PlacingOption::from_placed_item(layout_index, &pi)
+                match self.layouts[i].is_empty() {
+                    true => {
+                        //if layout is empty, remove it.
+                        //Placing the item back requires opening a new layout from the template of the same bin
+                        self.deregister_layout(layout_index);
+                        PlacingOption::from_placed_item(LayoutIndex::Template(bin_id), &pi)
+                    }
+                    false => PlacingOption::from_placed_item(layout_index, &pi),
+                }
             }
             LayoutIndex::Template(_) => panic!("cannot remove item from template layout"),
         }
@@ -207,6 +314,7 @@ impl ProblemGeneric for BPProblem {
             included_item_qtys,
             target_item_qtys,
             bin_qtys,
+            self.placement_order.clone(),
         );
         debug_assert!(assertions::problem_matches_solution(self, &solution));
 
@@ -291,6 +399,7 @@ impl ProblemGeneric for BPProblem {
             });
 
         self.bin_qtys.clone_from_slice(&solution.bin_qtys);
+        self.placement_order.clone_from(&solution.placement_order);
 
         self.uncommitted_removed_layouts.clear();
         self.reset_unmodified_layouts(solution.id);
@@ -314,6 +423,10 @@ impl ProblemGeneric for BPProblem {
         &self.missing_item_qtys
     }
 
+    fn placement_order(&self) -> &[usize] {
+        &self.placement_order
+    }
+
     fn bin_qtys(&self) -> &[usize] {
         &self.bin_qtys
     }
@@ -321,6 +434,17 @@ impl ProblemGeneric for BPProblem {
     fn instance(&self) -> &dyn InstanceGeneric {
         &self.instance
     }
+
+    fn max_items_per_layout(&self) -> Option<usize> {
+        self.max_items_per_layout
+    }
+
+    fn layout_is_locked(&self, index: impl Borrow<LayoutIndex>) -> bool {
+        match index.borrow() {
+            LayoutIndex::Real(i) => self.locked_layout_ids.contains(&self.layouts[*i].id()),
+            LayoutIndex::Template(_) => false,
+        }
+    }
 }
 
 impl ProblemGenericPrivate for BPProblem {
@@ -337,4 +461,8 @@ impl ProblemGenericPrivate for BPProblem {
     fn missing_item_qtys_mut(&mut self) -> &mut [isize] {
         &mut self.missing_item_qtys
     }
+
+    fn placement_order_mut(&mut self) -> &mut Vec<usize> {
+        &mut self.placement_order
+    }
 }
@@ -1,5 +1,7 @@
+use std::borrow::Borrow;
+
 use crate::entities::instances::instance_generic::InstanceGeneric;
-use crate::entities::layout::Layout;
+use crate::entities::layout::{Layout, LayoutSnapshot};
 use crate::entities::placed_item::PItemKey;
 use crate::entities::placing_option::PlacingOption;
 use crate::entities::problems::bin_packing::BPProblem;
@@ -7,6 +9,7 @@ use crate::entities::problems::problem_generic::private::ProblemGenericPrivate;
 use crate::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
 use crate::entities::problems::strip_packing::SPProblem;
 use crate::entities::solution::Solution;
+use crate::util::assertions;
 
 /// Enum which contains all the different problem types.
 /// A `Problem` represents a problem instance in a modifiable state.
@@ -23,6 +26,32 @@ pub enum Problem {
     SP(SPProblem),
 }
 
+impl Problem {
+    /// Replays a recorded sequence of placements on a copy of the problem, leaving `self` untouched.
+    /// Returns a snapshot of the layout modified by each step, in order.
+    /// Every placing option has to be valid for the state of the problem at that step,
+    /// panics if one of them results in a layout with colliding items.
+    pub fn replay(&self, p_opts: &[PlacingOption]) -> Vec<LayoutSnapshot> {
+        let mut problem = self.clone();
+        p_opts
+            .iter()
+            .enumerate()
+            .map(|(i, p_opt)| {
+                let (layout_idx, _) = problem.place_item(*p_opt);
+                let layout = match layout_idx {
+                    LayoutIndex::Real(j) => &mut problem.layouts_mut()[j],
+                    LayoutIndex::Template(_) => unreachable!("items are never placed in templates"),
+                };
+                assert!(
+                    assertions::layout_is_collision_free(layout),
+                    "step {i} of the replay ({p_opt:?}) results in a collision"
+                );
+                layout.create_snapshot()
+            })
+            .collect()
+    }
+}
+
 impl ProblemGeneric for Problem {
     fn place_item(&mut self, p_opt: PlacingOption) -> (LayoutIndex, PItemKey) {
         match self {
@@ -85,6 +114,13 @@ impl ProblemGeneric for Problem {
         }
     }
 
+    fn placement_order(&self) -> &[usize] {
+        match self {
+            Problem::BP(bp) => bp.placement_order(),
+            Problem::SP(sp) => sp.placement_order(),
+        }
+    }
+
     fn bin_qtys(&self) -> &[usize] {
         match self {
             Problem::BP(bp) => bp.bin_qtys(),
@@ -98,6 +134,20 @@ impl ProblemGeneric for Problem {
             Problem::SP(sp) => sp.instance(),
         }
     }
+
+    fn max_items_per_layout(&self) -> Option<usize> {
+        match self {
+            Problem::BP(bp) => bp.max_items_per_layout(),
+            Problem::SP(sp) => sp.max_items_per_layout(),
+        }
+    }
+
+    fn layout_is_locked(&self, index: impl Borrow<LayoutIndex>) -> bool {
+        match self {
+            Problem::BP(bp) => bp.layout_is_locked(index),
+            Problem::SP(sp) => sp.layout_is_locked(index),
+        }
+    }
 }
 
 impl ProblemGenericPrivate for Problem {
@@ -121,6 +171,13 @@ impl ProblemGenericPrivate for Problem {
             Problem::SP(sp) => sp.missing_item_qtys_mut(),
         }
     }
+
+    fn placement_order_mut(&mut self) -> &mut Vec<usize> {
+        match self {
+            Problem::BP(bp) => bp.placement_order_mut(),
+            Problem::SP(sp) => sp.placement_order_mut(),
+        }
+    }
 }
 
 impl From<BPProblem> for Problem {
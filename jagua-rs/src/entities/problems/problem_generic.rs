@@ -1,5 +1,7 @@
 use std::borrow::Borrow;
 
+use itertools::Itertools;
+
 use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::layout::Layout;
 use crate::entities::placed_item::PItemKey;
@@ -12,10 +14,25 @@ use crate::fsize;
 pub trait ProblemGeneric: ProblemGenericPrivate {
     /// Places an item into the problem instance according to the given `PlacingOption`.
     /// Returns the index of the layout where the item was placed.
+    /// The layout must not be [full](ProblemGeneric::layout_is_full) and the item must allow the rotation
+    /// of the placement, otherwise this panics.
+    /// See [`ProblemGeneric::try_place_item`] to have the placement rejected instead.
     fn place_item(&mut self, p_opt: PlacingOption) -> (LayoutIndex, PItemKey);
 
+    /// Same as [`ProblemGeneric::place_item`], but returns `None` instead of placing the item
+    /// if its layout is [full](ProblemGeneric::layout_is_full) or the item does not allow the rotation of the placement.
+    fn try_place_item(&mut self, p_opt: PlacingOption) -> Option<(LayoutIndex, PItemKey)> {
+        let item = self.instance().item(p_opt.item_id);
+        let rotation_allowed = item.is_rotation_allowed(p_opt.d_transf.rotation());
+        match !rotation_allowed || self.layout_is_full(p_opt.layout_idx) {
+            true => None,
+            false => Some(self.place_item(p_opt)),
+        }
+    }
+
     /// Removes a placed item (with its unique key) from a specific `Layout`.
     /// Returns a `PlacingOption` that can be used to place the item back in the same configuration.
+    /// If the removal closes the layout, the option refers to a template layout, so placing the item back reopens it.
     /// For more information about `commit_instantly`, see [`crate::collision_detection::cd_engine::CDEngine::deregister_hazard`].
     fn remove_item(
         &mut self,
@@ -42,6 +59,23 @@ pub trait ProblemGeneric: ProblemGenericPrivate {
     /// The quantity of each item that is requested but currently missing in the problem instance, indexed by item id.
     fn missing_item_qtys(&self) -> &[isize];
 
+    /// The ids of all currently placed items, in the order in which they were placed.
+    fn placement_order(&self) -> &[usize];
+
+    /// Overrides the order in which the currently placed items are considered to have been placed.
+    /// Useful to keep the original order when items are moved by removing and placing them again.
+    /// Has to be a permutation of the current [`ProblemGeneric::placement_order`].
+    fn set_placement_order(&mut self, placement_order: Vec<usize>) {
+        debug_assert!(
+            placement_order
+                .iter()
+                .sorted()
+                .eq(self.placement_order().iter().sorted()),
+            "placement order has to contain the same items"
+        );
+        *self.placement_order_mut() = placement_order;
+    }
+
     /// The quantity of each item that is currently placed in the problem instance, indexed by item id.
     fn placed_item_qtys(&self) -> impl Iterator<Item = usize> {
         self.missing_item_qtys()
@@ -70,6 +104,8 @@ pub trait ProblemGeneric: ProblemGenericPrivate {
     }
 
     /// Returns the `LayoutIndex` of all template layouts that have remaining stock.
+    /// Templates which are [full](ProblemGeneric::layout_is_full) are left out as well,
+    /// which is the case for all of them if [`ProblemGeneric::max_items_per_layout`] is zero.
     fn template_layout_indices_with_stock(&self) -> impl Iterator<Item = LayoutIndex> {
         self.template_layouts()
             .iter()
@@ -78,6 +114,29 @@ pub trait ProblemGeneric: ProblemGenericPrivate {
                 0 => None,
                 _ => Some(LayoutIndex::Template(i)),
             })
+            .filter(|l_idx| !self.layout_is_full(l_idx))
+    }
+
+    /// The maximum number of items a single `Layout` is allowed to contain, if any.
+    fn max_items_per_layout(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns true if the `Layout` is locked: its items cannot be removed and no more items can be placed in it.
+    fn layout_is_locked(&self, _index: impl Borrow<LayoutIndex>) -> bool {
+        false
+    }
+
+    /// Returns true if the `Layout` cannot accept any more items,
+    /// due to [`ProblemGeneric::max_items_per_layout`] or because it is [locked](ProblemGeneric::layout_is_locked).
+    fn layout_is_full(&self, index: impl Borrow<LayoutIndex>) -> bool {
+        if self.layout_is_locked(index.borrow()) {
+            return true;
+        }
+        match self.max_items_per_layout() {
+            Some(max) => self.get_layout(index).placed_items().len() >= max,
+            None => false,
+        }
     }
 
     fn get_layout(&self, index: impl Borrow<LayoutIndex>) -> &Layout {
@@ -97,6 +156,24 @@ pub trait ProblemGeneric: ProblemGenericPrivate {
     }
 
     fn instance(&self) -> &dyn InstanceGeneric;
+
+    /// Summarizes the placed and remaining quantity of every item, along with the remaining stock of every bin.
+    fn demand_report(&self) -> DemandReport {
+        let items = self
+            .placed_item_qtys()
+            .zip(self.missing_item_qtys())
+            .enumerate()
+            .map(|(item_id, (placed, missing))| ItemDemand {
+                item_id,
+                placed,
+                remaining: (*missing).max(0) as usize,
+            })
+            .collect();
+        DemandReport {
+            items,
+            bin_stock: self.bin_qtys().to_vec(),
+        }
+    }
 }
 
 pub(super) mod private {
@@ -108,12 +185,20 @@ pub(super) mod private {
 
         fn missing_item_qtys_mut(&mut self) -> &mut [isize];
 
+        fn placement_order_mut(&mut self) -> &mut Vec<usize>;
+
         fn register_included_item(&mut self, item_id: usize) {
             self.missing_item_qtys_mut()[item_id] -= 1;
+            self.placement_order_mut().push(item_id);
         }
 
         fn deregister_included_item(&mut self, item_id: usize) {
             self.missing_item_qtys_mut()[item_id] += 1;
+            //copies of the same item are interchangeable, forget the most recent placement
+            let placement_order = self.placement_order_mut();
+            if let Some(i) = placement_order.iter().rposition(|&id| id == item_id) {
+                placement_order.remove(i);
+            }
         }
     }
 }
@@ -134,3 +219,29 @@ impl Into<usize> for LayoutIndex {
         }
     }
 }
+
+/// Snapshot of the progress of a problem towards its demand, see [ProblemGeneric::demand_report].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemandReport {
+    /// Placed and remaining quantity of each item, indexed by item id
+    pub items: Vec<ItemDemand>,
+    /// Remaining stock of each bin, indexed by bin id
+    pub bin_stock: Vec<usize>,
+}
+
+impl DemandReport {
+    pub fn total_placed(&self) -> usize {
+        self.items.iter().map(|i| i.placed).sum()
+    }
+
+    pub fn total_remaining(&self) -> usize {
+        self.items.iter().map(|i| i.remaining).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemDemand {
+    pub item_id: usize,
+    pub placed: usize,
+    pub remaining: usize,
+}
@@ -1,9 +1,12 @@
 use std::{iter, slice};
 
 use crate::collision_detection::hazard::HazardEntity;
+use crate::collision_detection::hazard_filter;
+use crate::collision_detection::hazard_filter::{EntityHazardFilter, HazardFilter};
 use crate::entities::bin::Bin;
 use crate::entities::instances::instance_generic::InstanceGeneric;
-use crate::entities::instances::strip_packing::SPInstance;
+use crate::entities::instances::strip_packing::{PackingAxis, SPInstance};
+use crate::entities::layout;
 use crate::entities::layout::Layout;
 use crate::entities::placed_item::PItemKey;
 use crate::entities::placing_option::PlacingOption;
@@ -12,11 +15,11 @@ use crate::entities::problems::problem_generic::ProblemGeneric;
 use crate::entities::problems::problem_generic::{LayoutIndex, STRIP_LAYOUT_IDX};
 use crate::entities::solution::Solution;
 use crate::fsize;
-use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::geo_traits::{Shape, Transformable, TransformableFrom};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::transformation::Transformation;
 use crate::util::assertions;
 use crate::util::config::CDEConfig;
-use crate::util::fpa::FPA;
 use itertools::Itertools;
 use log::error;
 
@@ -26,8 +29,11 @@ pub struct SPProblem {
     pub instance: SPInstance,
     pub layout: Layout,
     missing_item_qtys: Vec<isize>,
+    placement_order: Vec<usize>,
     layout_id_counter: usize,
     solution_id_counter: usize,
+    /// Widths the strip was modified to, and whether all items fit in them, `None` if not recorded
+    width_history: Option<Vec<(fsize, bool)>>,
 }
 
 impl SPProblem {
@@ -39,7 +45,7 @@ impl SPProblem {
             .map(|(_, qty)| *qty as isize)
             .collect_vec();
         let strip_rect = AARectangle::new(0.0, 0.0, strip_width, strip_height);
-        let strip_bin = Bin::from_strip(strip_rect, cde_config);
+        let strip_bin = strip_bin(&instance, strip_rect, cde_config);
         let layout_id_counter = 0;
         let layout = Layout::new(layout_id_counter, strip_bin);
 
@@ -47,11 +53,25 @@ impl SPProblem {
             instance,
             layout,
             missing_item_qtys,
+            placement_order: vec![],
             layout_id_counter,
             solution_id_counter: 0,
+            width_history: None,
         }
     }
 
+    /// Starts (or stops) recording the history of strip widths, see [SPProblem::width_history].
+    /// Starting clears any previously recorded history.
+    pub fn record_width_history(&mut self, record: bool) {
+        self.width_history = record.then(Vec::new);
+    }
+
+    /// Every width the strip was modified to since recording started, together with whether all items fit in it.
+    /// `None` if the history is not being recorded, see [SPProblem::record_width_history].
+    pub fn width_history(&self) -> Option<&[(fsize, bool)]> {
+        self.width_history.as_deref()
+    }
+
     /// Adds or removes width in the back of the strip.
     pub fn modify_strip_in_back(&mut self, new_width: fsize) {
         let bbox = self.layout.bin.outer.bbox();
@@ -91,64 +111,88 @@ impl SPProblem {
     /// Modifies the shape of the strip to a new rectangle.
     /// All items that fit in the new strip are kept, the rest are removed.
     pub fn modify_strip(&mut self, rect: AARectangle) {
-        let placed_items = self
-            .layout
-            .placed_items()
-            .iter()
-            .map(|(_, pi)| (pi.item_id, pi.d_transf))
+        //cutouts can only be placed back after the items they were cut out of
+        let placed_items = layout::placement_order(self.layout.placed_items())
+            .map(|(_, pi)| (pi.item_id, pi.d_transf, pi.subtract))
             .collect_vec();
+        let (n_placed_items, width) = (placed_items.len(), rect.width());
 
-        //reset the missing item quantities
+        //reset the missing item quantities, the items placed back keep their original placement order
+        let mut placement_order = std::mem::take(&mut self.placement_order);
         self.missing_item_qtys
             .iter_mut()
             .enumerate()
             .for_each(|(i, qty)| *qty = self.instance.item_qty(i) as isize);
 
         //Modifying the width causes the bin to change, so the layout must be replaced
-        self.layout = Layout::new(
-            self.next_layout_id(),
-            Bin::from_strip(rect, self.layout.bin.base_cde.config()),
-        );
+        let strip_bin = strip_bin(&self.instance, rect, self.layout.bin.base_cde.config())
+            .with_inset(self.layout.bin.inset);
+        self.layout = Layout::new(self.next_layout_id(), strip_bin);
+
+        //only the exterior of the new strip is relevant
+        let filter = EntityHazardFilter(vec![HazardEntity::BinExterior]).not();
 
         //place the items back in the new layout
-        for (item_id, d_transf) in placed_items {
+        for (item_id, d_transf, subtract) in placed_items {
             let item = self.instance.item(item_id);
-            let entities_to_ignore = self
-                .layout
-                .cde()
-                .all_hazards()
-                .filter(|h| h.entity != HazardEntity::BinExterior)
-                .map(|h| h.entity)
-                .collect_vec();
-            let shape = &item.shape;
+            let entities_to_ignore = hazard_filter::generate_irrelevant_hazards(
+                &filter,
+                self.layout.cde().all_hazards(),
+            );
             let transform = d_transf.compose();
-            let transformed_shape = shape.transform_clone(&transform);
+            let mut transformed_shape = item.shape.as_ref().clone();
             let cde = self.layout.cde();
-            if !cde.poly_collides(&transformed_shape, entities_to_ignore.as_ref()) {
+            if !cde.item_collides(
+                item,
+                &transform,
+                &mut transformed_shape,
+                entities_to_ignore.as_ref(),
+            ) {
                 let insert_opt = PlacingOption {
                     layout_idx: STRIP_LAYOUT_IDX,
                     item_id,
                     d_transf,
+                    subtract,
                 };
                 self.place_item(insert_opt);
             } else {
                 let mut collisions = vec![];
+                transformed_shape.transform_from(&item.shape, &transform);
                 cde.collect_poly_collisions(
                     &transformed_shape,
                     entities_to_ignore.as_ref(),
                     &mut collisions,
                 );
                 error!("Item {} could not be placed back in the strip after resizing. Collisions: {:?}", item_id, collisions);
+                if let Some(i) = placement_order.iter().rposition(|&id| id == item_id) {
+                    placement_order.remove(i);
+                }
             }
         }
+        self.placement_order = placement_order;
+
+        if let Some(history) = &mut self.width_history {
+            let feasible = self.layout.placed_items().len() == n_placed_items;
+            history.push((width, feasible));
+        }
+    }
+
+    /// Makes a border of width `inset` along the edges of the strip unusable, see [`Bin::with_inset`].
+    /// Items which no longer fit are removed.
+    pub fn set_strip_inset(&mut self, inset: fsize) {
+        let bin = self.layout.bin.clone().with_inset(inset);
+        self.layout.change_bin(bin);
+        self.modify_strip(self.layout.bin.bbox());
     }
 
     /// Shrinks the strip to the minimum width that fits all items.
     pub fn fit_strip(&mut self) {
         let n_items_in_old_strip = self.layout.placed_items().len();
 
-        let fitted_width = self.occupied_width() * (1.0 + FPA::tolerance()); //add some tolerance to avoid rounding errors or false collision positives
-        self.modify_strip_centered(fitted_width);
+        //add some tolerance to avoid rounding errors or false collision positives
+        let tol = self.layout.bin.base_cde.config().geo_tolerance;
+        let fitted_width = self.occupied_width() + tol.margin(self.occupied_width());
+        self.modify_strip_centered(fitted_width + 2.0 * self.layout.bin.inset);
 
         assert_eq!(
             n_items_in_old_strip,
@@ -157,6 +201,34 @@ impl SPProblem {
         );
     }
 
+    /// Extends the strip in the back by `growth_step`, repeatedly if needed, until the free space behind the placed items
+    /// is at least as wide as item `item_id` at its narrowest allowed rotation. Returns the new width of the strip.
+    /// Meant to be called whenever an item could not be placed, the strip can be tightened afterwards with [SPProblem::fit_strip].
+    pub fn grow_to_fit(&mut self, item_id: usize, growth_step: fsize) -> fsize {
+        assert!(growth_step > 0.0, "growth step must be positive");
+        let item = &self.instance.items[item_id].0;
+        let rotation = item.min_footprint_rotation(PackingAxis::X);
+        let item_width = item
+            .shape
+            .transform_clone(&Transformation::from_rotation(rotation))
+            .bbox()
+            .width();
+
+        let inset = self.layout.bin.inset;
+        let strip_bbox = self.layout.bin.outer.bbox();
+        let occupied_until = self
+            .occupied_range()
+            .map_or(strip_bbox.x_min + inset, |(_, x_max)| x_max);
+        let required_width = occupied_until + item_width + inset - strip_bbox.x_min;
+
+        let mut new_width = self.strip_width() + growth_step;
+        while new_width < required_width {
+            new_width += growth_step;
+        }
+        self.modify_strip_in_back(new_width);
+        self.strip_width()
+    }
+
     /// Returns the horizontal range occupied by the placed items. If no items are placed, returns None.
     pub fn occupied_range(&self) -> Option<(fsize, fsize)> {
         occupied_range(&self.layout)
@@ -176,6 +248,17 @@ impl SPProblem {
     }
 }
 
+/// Creates a strip bin for the given rectangle in internal coordinates.
+/// For strips which extend along the Y axis, the pretransform also rotates the strip back, see [`PackingAxis`].
+fn strip_bin(instance: &SPInstance, rect: AARectangle, cde_config: CDEConfig) -> Bin {
+    let mut bin = Bin::from_strip(rect, cde_config);
+    bin.pretransform = instance
+        .packing_axis
+        .strip_pretransform(instance.strip_height)
+        .transform(&bin.pretransform);
+    bin
+}
+
 impl ProblemGeneric for SPProblem {
     fn place_item(&mut self, p_opt: PlacingOption) -> (LayoutIndex, PItemKey) {
         assert_eq!(
@@ -184,7 +267,10 @@ impl ProblemGeneric for SPProblem {
         );
         let item_id = p_opt.item_id;
         let item = self.instance.item(item_id);
-        let placed_item_key = self.layout.place_item(item, p_opt.d_transf);
+        let placed_item_key = match p_opt.subtract {
+            false => self.layout.place_item(item, p_opt.d_transf),
+            true => self.layout.place_cutout(item, p_opt.d_transf),
+        };
 
         self.register_included_item(item_id);
         (STRIP_LAYOUT_IDX, placed_item_key)
@@ -225,6 +311,7 @@ impl ProblemGeneric for SPProblem {
             included_item_qtys,
             target_item_qtys,
             bin_qtys,
+            self.placement_order.clone(),
         );
 
         debug_assert!(assertions::problem_matches_solution(self, &solution));
@@ -249,6 +336,7 @@ impl ProblemGeneric for SPProblem {
             .for_each(|(i, qty)| {
                 *qty = (self.instance.item_qty(i) - solution.placed_item_qtys[i]) as isize
             });
+        self.placement_order.clone_from(&solution.placement_order);
 
         debug_assert!(assertions::problem_matches_solution(self, solution));
     }
@@ -273,6 +361,10 @@ impl ProblemGeneric for SPProblem {
         iter::empty::<LayoutIndex>()
     }
 
+    fn placement_order(&self) -> &[usize] {
+        &self.placement_order
+    }
+
     fn bin_qtys(&self) -> &[usize] {
         &[0]
     }
@@ -296,6 +388,10 @@ impl ProblemGenericPrivate for SPProblem {
     fn missing_item_qtys_mut(&mut self) -> &mut [isize] {
         &mut self.missing_item_qtys
     }
+
+    fn placement_order_mut(&mut self) -> &mut Vec<usize> {
+        &mut self.placement_order
+    }
 }
 
 /// Returns the horizontal range occupied by the placed items. If no items are placed, returns None.
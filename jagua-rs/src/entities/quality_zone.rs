@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use itertools::Itertools;
+
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 
 /// Maximum number of qualities that can be used
@@ -12,6 +14,8 @@ pub struct InferiorQualityZone {
     pub quality: usize,
     /// The outer shapes of all zones of this quality
     pub zones: Vec<Arc<SimplePolygon>>,
+    /// The convex parts of each zone (at the same index as in `zones`), see [SimplePolygon::convex_decomposition]
+    pub convex_parts: Vec<Arc<[SimplePolygon]>>,
 }
 
 impl InferiorQualityZone {
@@ -20,7 +24,15 @@ impl InferiorQualityZone {
             quality < N_QUALITIES,
             "Quality must be in range of N_QUALITIES"
         );
-        let zones = shapes.into_iter().map(Arc::new).collect();
-        Self { quality, zones }
+        let zones = shapes.into_iter().map(Arc::new).collect_vec();
+        let convex_parts = zones
+            .iter()
+            .map(|zone| zone.convex_decomposition().into())
+            .collect();
+        Self {
+            quality,
+            zones,
+            convex_parts,
+        }
     }
 }
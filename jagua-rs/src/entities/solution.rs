@@ -1,3 +1,4 @@
+use std::fmt::{Display, Formatter};
 use std::time::Instant;
 
 use itertools::Itertools;
@@ -7,6 +8,7 @@ use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::layout::LayoutSnapshot;
 use crate::fsize;
 use crate::geometry::geo_traits::Shape;
+use crate::geometry::primitives::aa_rectangle::AARectangle;
 
 /// Represents a snapshot of a `Problem` at a specific moment.
 /// Solutions can be used to restore the state of a `Problem` to a previous state.
@@ -24,6 +26,8 @@ pub struct Solution {
     pub target_item_qtys: Vec<usize>,
     /// Quantity of bins used for each type of bin
     pub bin_qtys: Vec<usize>,
+    /// Ids of the placed items, in the order in which they were placed
+    pub placement_order: Vec<usize>,
     /// Instant the solution was created
     pub time_stamp: Instant,
 }
@@ -36,6 +40,7 @@ impl Solution {
         placed_item_qtys: Vec<usize>,
         target_item_qtys: Vec<usize>,
         bin_qtys: Vec<usize>,
+        placement_order: Vec<usize>,
     ) -> Self {
         Solution {
             id,
@@ -44,10 +49,16 @@ impl Solution {
             placed_item_qtys,
             target_item_qtys,
             bin_qtys,
+            placement_order,
             time_stamp: Instant::now(),
         }
     }
 
+    /// Sum of the costs of all bins used in the solution
+    pub fn total_cost(&self) -> u64 {
+        self.layout_snapshots.iter().map(|sl| sl.bin.value).sum()
+    }
+
     /// Whether all items demanded in the `instance` are placed
     pub fn is_complete(&self, instance: &dyn InstanceGeneric) -> bool {
         self.placed_item_qtys
@@ -81,4 +92,134 @@ impl Solution {
     pub fn n_items_placed(&self) -> usize {
         self.placed_item_qtys.iter().sum()
     }
+
+    /// Total length of the outlines of all placed items, an upper bound on the length of the cuts needed to separate them
+    /// (edges shared between adjacent items only have to be cut once).
+    pub fn cut_length(&self) -> fsize {
+        self.layout_snapshots
+            .iter()
+            .flat_map(|sl| sl.placed_items.values())
+            .map(|pi| pi.shape.perimeter())
+            .sum()
+    }
+
+    /// Returns the tight bounding rectangle around all placed items, for each layout in the solution.
+    /// Unlike the bin (or the nominal strip), this is the region that is actually used.
+    /// Layouts without any placed items have no bounds.
+    pub fn used_bounds(&self) -> Vec<Option<AARectangle>> {
+        self.layout_snapshots
+            .iter()
+            .map(|sl| {
+                sl.placed_items
+                    .values()
+                    .map(|pi| pi.shape.bbox())
+                    .reduce(|a, b| AARectangle::bounding_rectangle(&a, &b))
+            })
+            .collect_vec()
+    }
+
+    /// Returns the precedence constraints `(a, b)` of the `instance` which are violated by the placement order,
+    /// i.e. for which a copy of item `b` was placed before the last placed copy of item `a`.
+    /// Only placed items are considered, unplaced copies of `a` do not cause a violation.
+    pub fn precedence_violations(&self, instance: &dyn InstanceGeneric) -> Vec<(usize, usize)> {
+        let first_placed =
+            |item_id: usize| self.placement_order.iter().position(|&id| id == item_id);
+        let last_placed =
+            |item_id: usize| self.placement_order.iter().rposition(|&id| id == item_id);
+
+        instance
+            .precedence()
+            .iter()
+            .filter(|(a, b)| match (last_placed(*a), first_placed(*b)) {
+                (Some(last_a), Some(first_b)) => first_b < last_a,
+                _ => false,
+            })
+            .cloned()
+            .collect_vec()
+    }
+
+    /// Combines solutions of disjoint sub-problems (e.g. one per material) into a single `Solution`.
+    /// All solutions should be indexed on the same item and bin ids, but no item can be demanded by more than one of them.
+    /// Layouts are concatenated and re-indexed, quantities are summed and the usage is recalculated.
+    /// The merged solution does not originate from any `Problem` and can therefore not be used to restore one.
+    pub fn merge(solutions: &[Solution]) -> Result<Solution, SolutionMergeError> {
+        let first = solutions.first().ok_or(SolutionMergeError::NoSolutions)?;
+        let (n_items, n_bins) = (first.target_item_qtys.len(), first.bin_qtys.len());
+        if solutions.iter().any(|s| {
+            s.placed_item_qtys.len() != n_items
+                || s.target_item_qtys.len() != n_items
+                || s.bin_qtys.len() != n_bins
+        }) {
+            return Err(SolutionMergeError::MismatchedIds);
+        }
+
+        let mut placed_item_qtys = vec![0; n_items];
+        let mut target_item_qtys = vec![0; n_items];
+        let mut bin_qtys = vec![0; n_bins];
+        for s in solutions {
+            for item_id in 0..n_items {
+                if s.target_item_qtys[item_id] > 0 && target_item_qtys[item_id] > 0 {
+                    return Err(SolutionMergeError::OverlappingItem(item_id));
+                }
+                placed_item_qtys[item_id] += s.placed_item_qtys[item_id];
+                target_item_qtys[item_id] += s.target_item_qtys[item_id];
+            }
+            bin_qtys
+                .iter_mut()
+                .zip(s.bin_qtys.iter())
+                .for_each(|(qty, s_qty)| *qty += s_qty);
+        }
+
+        let layout_snapshots = solutions
+            .iter()
+            .flat_map(|s| s.layout_snapshots.iter().cloned())
+            .enumerate()
+            .map(|(id, sl)| LayoutSnapshot { id, ..sl })
+            .collect_vec();
+
+        let placement_order = solutions
+            .iter()
+            .flat_map(|s| s.placement_order.iter().cloned())
+            .collect_vec();
+
+        let (total_bin_area, total_used_area) =
+            layout_snapshots.iter().fold((0.0, 0.0), |acc, sl| {
+                (acc.0 + sl.bin.area, acc.1 + sl.bin.area * sl.usage)
+            });
+
+        Ok(Solution::new(
+            0,
+            layout_snapshots,
+            total_used_area / total_bin_area,
+            placed_item_qtys,
+            target_item_qtys,
+            bin_qtys,
+            placement_order,
+        ))
+    }
+}
+
+/// Error type for when solutions cannot be merged with [`Solution::merge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionMergeError {
+    /// No solutions were provided
+    NoSolutions,
+    /// The solutions are not indexed on the same item and bin ids
+    MismatchedIds,
+    /// The item is demanded by more than one of the solutions
+    OverlappingItem(usize),
+}
+
+impl Display for SolutionMergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolutionMergeError::NoSolutions => write!(f, "no solutions to merge"),
+            SolutionMergeError::MismatchedIds => {
+                write!(f, "solutions do not share the same item and bin ids")
+            }
+            SolutionMergeError::OverlappingItem(item_id) => {
+                write!(f, "item {item_id} is demanded by multiple solutions")
+            }
+        }
+    }
 }
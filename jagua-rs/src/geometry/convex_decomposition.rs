@@ -0,0 +1,103 @@
+use itertools::Itertools;
+
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::{cross, SimplePolygon};
+
+/// Decomposes a simple polygon into convex parts, using the Hertel-Mehlhorn algorithm:
+/// the polygon is triangulated, after which diagonals are removed as long as the merged parts remain convex.
+/// Yields at most four times the minimum number of convex parts.
+/// Returns the parts as indices into the points of the polygon, in counterclockwise order.
+pub fn convex_decomposition_indices(shape: &SimplePolygon) -> Vec<Vec<usize>> {
+    let points = &shape.points;
+    let mut parts = triangulation_indices(points);
+
+    //remove diagonals as long as it results in convex parts
+    while let Some((i, j, merged)) = parts
+        .iter()
+        .enumerate()
+        .tuple_combinations()
+        .find_map(|((i, p), (j, q))| merge_convex(p, q, points).map(|m| (i, j, m)))
+    {
+        parts[i] = merged;
+        parts.swap_remove(j);
+    }
+    parts
+}
+
+/// Triangulates a simple, counterclockwise polygon by ear clipping.
+/// Returns the triangles as indices into `points`, in counterclockwise order.
+/// Only due to numerical issues, the last part can be a remainder of more than three points.
+pub fn triangulation_indices(points: &[Point]) -> Vec<Vec<usize>> {
+    let mut remaining = (0..points.len()).collect_vec();
+    let mut triangles = vec![];
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |k: usize| {
+            let (a, b, c) = (
+                remaining[(k + n - 1) % n],
+                remaining[k],
+                remaining[(k + 1) % n],
+            );
+            (a, b, c)
+        };
+        //collinear vertices do not contribute to the triangulation, drop them
+        if let Some(k) = (0..n).find(|&k| {
+            let (a, b, c) = corner(k);
+            cross(points[a], points[b], points[c]) == 0.0
+        }) {
+            remaining.remove(k);
+            continue;
+        }
+        let ear = (0..n).find(|&k| {
+            let (a, b, c) = corner(k);
+            cross(points[a], points[b], points[c]) > 0.0
+                && !remaining
+                    .iter()
+                    .filter(|&&v| v != a && v != b && v != c)
+                    .any(|&v| in_triangle(points[v], points[a], points[b], points[c]))
+        });
+        match ear {
+            Some(k) => {
+                let (a, b, c) = corner(k);
+                triangles.push(vec![a, b, c]);
+                remaining.remove(k);
+            }
+            None => {
+                //only possible due to numerical issues, keep the remainder as a single part
+                triangles.push(remaining);
+                return triangles;
+            }
+        }
+    }
+    triangles.push(remaining);
+    triangles
+}
+
+/// Merges two parts sharing an edge, if the result is convex.
+fn merge_convex(p: &[usize], q: &[usize], points: &[Point]) -> Option<Vec<usize>> {
+    let (n_p, n_q) = (p.len(), q.len());
+    //edge a->b in p, which appears as b->a in q
+    let (i_p, i_q) = (0..n_p).find_map(|i| {
+        let (a, b) = (p[i], p[(i + 1) % n_p]);
+        (0..n_q)
+            .find(|&j| q[j] == b && q[(j + 1) % n_q] == a)
+            .map(|j| (i, j))
+    })?;
+    //p from b around to a, followed by q strictly between a and b
+    let merged = (0..n_p)
+        .map(|k| p[(i_p + 1 + k) % n_p])
+        .chain((0..n_q - 2).map(|k| q[(i_q + 2 + k) % n_q]))
+        .collect_vec();
+
+    let merged_points = merged.iter().map(|&i| points[i]).collect_vec();
+    match SimplePolygon::calculate_convexity(&merged_points) {
+        true => Some(merged),
+        false => None,
+    }
+}
+
+/// Whether `p` lies inside or on the boundary of the counterclockwise triangle `a`-`b`-`c`
+fn in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
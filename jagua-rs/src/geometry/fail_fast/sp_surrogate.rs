@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::fsize;
 use crate::geometry::convex_hull;
 use crate::geometry::fail_fast::{piers, poi};
@@ -6,9 +8,9 @@ use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
-use crate::util::config::SPSurrogateConfig;
+use crate::util::config::{CDEConfig, CdePreset, SPSurrogateConfig};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 /// Surrogate representation of a [SimplePolygon] for fail-fast purposes
 pub struct SPSurrogate {
     /// Indices of the points in the [SimplePolygon] that form the convex hull
@@ -66,6 +68,60 @@ impl SPSurrogate {
     }
 }
 
+/// Builds an [SPSurrogate] for any [SimplePolygon], independently of a [CDEConfig].
+/// Starts from the surrogate configuration of the [balanced](CdePreset::Balanced) preset.
+#[derive(Clone, Copy, Debug)]
+pub struct SPSurrogateBuilder {
+    config: SPSurrogateConfig,
+}
+
+impl SPSurrogateBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: CDEConfig::preset(CdePreset::Balanced).item_surrogate_config,
+        }
+    }
+
+    pub fn with_config(mut self, config: SPSurrogateConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_pole_coverage_goal(mut self, pole_coverage_goal: fsize) -> Self {
+        self.config.pole_coverage_goal = pole_coverage_goal;
+        self
+    }
+
+    pub fn with_max_poles(mut self, max_poles: usize) -> Self {
+        self.config.max_poles = max_poles;
+        self
+    }
+
+    pub fn with_n_ff_poles(mut self, n_ff_poles: usize) -> Self {
+        self.config.n_ff_poles = n_ff_poles;
+        self
+    }
+
+    pub fn with_n_ff_piers(mut self, n_ff_piers: usize) -> Self {
+        self.config.n_ff_piers = n_ff_piers;
+        self
+    }
+
+    pub fn config(&self) -> SPSurrogateConfig {
+        self.config
+    }
+
+    pub fn build(&self, simple_poly: &SimplePolygon) -> SPSurrogate {
+        SPSurrogate::new(simple_poly, self.config)
+    }
+}
+
+impl Default for SPSurrogateBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Transformable for SPSurrogate {
     fn transform(&mut self, t: &Transformation) -> &mut Self {
         //destructuring pattern used to ensure that the code is updated accordingly when the struct changes
@@ -1,4 +1,4 @@
-use crate::fsize;
+use crate::{fsize, PI};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GeoPosition {
@@ -28,4 +28,32 @@ pub enum AllowedRotation {
     Continuous,
     /// Only a limited set of rotations is allowed
     Discrete(Vec<fsize>),
+    /// Any rotation within the range [min, max] is allowed
+    Range { min: fsize, max: fsize },
+}
+
+impl AllowedRotation {
+    /// Maximum deviation (in radians) for a rotation to be considered equal to an allowed one
+    pub const TOLERANCE: fsize = 1e-4;
+
+    /// Whether `rotation` (in radians) is allowed, up to full turns and within [`AllowedRotation::TOLERANCE`]
+    pub fn allows(&self, rotation: fsize) -> bool {
+        //smallest angle between two rotations
+        let angle_between = |a: fsize, b: fsize| {
+            let d = (a - b).rem_euclid(2.0 * PI);
+            d.min(2.0 * PI - d)
+        };
+        match self {
+            AllowedRotation::None => angle_between(rotation, 0.0) <= Self::TOLERANCE,
+            AllowedRotation::Continuous => true,
+            AllowedRotation::Discrete(rotations) => rotations
+                .iter()
+                .any(|&r| angle_between(rotation, r) <= Self::TOLERANCE),
+            AllowedRotation::Range { min, max } => {
+                max - min >= 2.0 * PI
+                    || (rotation - min).rem_euclid(2.0 * PI) <= max - min + Self::TOLERANCE
+                    || angle_between(rotation, *min) <= Self::TOLERANCE
+            }
+        }
+    }
 }
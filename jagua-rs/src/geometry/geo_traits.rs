@@ -61,4 +61,23 @@ pub trait Shape {
 
     /// The distance between the two furthest points in the shape.
     fn diameter(&self) -> fsize;
+
+    /// Length of the boundary of the shape
+    fn perimeter(&self) -> fsize;
+
+    /// Area of the bounding box of the shape
+    fn bbox_area(&self) -> fsize {
+        self.bbox().area()
+    }
+
+    /// Fraction of the bounding box covered by the shape, in [0, 1].
+    /// Low ratios indicate shapes which are hard to nest compactly.
+    /// Shapes with a degenerate bounding box (e.g. an axis-aligned edge) have a ratio of 0.
+    fn bbox_fill_ratio(&self) -> fsize {
+        let bbox_area = self.bbox_area();
+        match bbox_area > 0.0 {
+            true => self.area() / bbox_area,
+            false => 0.0,
+        }
+    }
 }
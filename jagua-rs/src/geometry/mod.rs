@@ -1,3 +1,4 @@
+pub mod convex_decomposition;
 pub mod convex_hull;
 
 pub mod d_transformation;
@@ -5,4 +6,7 @@ pub mod fail_fast;
 pub mod geo_enums;
 pub mod geo_traits;
 pub mod primitives;
+pub mod separation;
 pub mod transformation;
+
+pub use separation::mtv;
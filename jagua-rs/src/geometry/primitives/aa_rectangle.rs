@@ -3,7 +3,9 @@ use crate::geometry::geo_enums::{GeoPosition, GeoRelation};
 use crate::geometry::geo_traits::{AlmostCollidesWith, CollidesWith, DistanceFrom, Shape};
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
+use crate::util::config::GeoTolerance;
 use crate::util::fpa::FPA;
+use itertools::Itertools;
 use ordered_float::{NotNan, OrderedFloat};
 use std::cmp::Ordering;
 
@@ -34,6 +36,32 @@ impl AARectangle {
         }
     }
 
+    /// Returns the smallest rectangle containing all points.
+    /// A single point, or points on a horizontal or vertical line, result in a degenerate rectangle with zero area.
+    pub fn from_points(points: &[Point]) -> Self {
+        assert!(
+            !points.is_empty(),
+            "cannot create a rectangle from zero points"
+        );
+        let Point(x, y) = points[0];
+        let mut rect = AARectangle {
+            x_min: x,
+            y_min: y,
+            x_max: x,
+            y_max: y,
+        };
+        points[1..].iter().for_each(|p| rect.grow_to_include(*p));
+        rect
+    }
+
+    /// Expands the rectangle, if necessary, so that it contains the point.
+    pub fn grow_to_include(&mut self, p: Point) {
+        self.x_min = fsize::min(self.x_min, p.0);
+        self.y_min = fsize::min(self.y_min, p.1);
+        self.x_max = fsize::max(self.x_max, p.0);
+        self.y_max = fsize::max(self.y_max, p.1);
+    }
+
     /// Returns the relation between self and another AARectangle
     pub fn relation_to(&self, other: &AARectangle) -> GeoRelation {
         if self.collides_with(other) {
@@ -60,17 +88,29 @@ impl AARectangle {
     /// Returns the relation between self and another AARectangle, with a tolerance for floating point precision.
     /// Leaning towards `Surrounding` and `Enclosed` instead of `Intersecting` in edge cases.
     pub fn almost_relation_to(&self, other: &AARectangle) -> GeoRelation {
-        if self.almost_collides_with(other) {
-            if FPA::from(self.x_min) <= FPA::from(other.x_min)
-                && FPA::from(self.y_min) <= FPA::from(other.y_min)
-                && FPA::from(self.x_max) >= FPA::from(other.x_max)
-                && FPA::from(self.y_max) >= FPA::from(other.y_max)
+        self.relation_to_within(other, &GeoTolerance::DEFAULT)
+    }
+
+    /// Same as [AARectangle::almost_relation_to], with an explicit tolerance.
+    pub fn relation_to_within(&self, other: &AARectangle, tol: &GeoTolerance) -> GeoRelation {
+        let collides = tol.le(
+            fsize::max(self.x_min, other.x_min),
+            fsize::min(self.x_max, other.x_max),
+        ) && tol.le(
+            fsize::max(self.y_min, other.y_min),
+            fsize::min(self.y_max, other.y_max),
+        );
+        if collides {
+            if tol.le(self.x_min, other.x_min)
+                && tol.le(self.y_min, other.y_min)
+                && tol.ge(self.x_max, other.x_max)
+                && tol.ge(self.y_max, other.y_max)
             {
                 GeoRelation::Surrounding
-            } else if FPA::from(self.x_min) >= FPA::from(other.x_min)
-                && FPA::from(self.y_min) >= FPA::from(other.y_min)
-                && FPA::from(self.x_max) <= FPA::from(other.x_max)
-                && FPA::from(self.y_max) <= FPA::from(other.y_max)
+            } else if tol.ge(self.x_min, other.x_min)
+                && tol.ge(self.y_min, other.y_min)
+                && tol.le(self.x_max, other.x_max)
+                && tol.le(self.y_max, other.y_max)
             {
                 GeoRelation::Enclosed
             } else {
@@ -111,6 +151,17 @@ impl AARectangle {
         self
     }
 
+    /// Returns the smallest axis-aligned rectangle containing the rectangle rotated by `angle` (in radians) around the origin.
+    /// A cheap bound on the bounding box of any shape inside the rectangle after the same rotation,
+    /// without transforming the shape itself.
+    pub fn rotated_bounds(&self, angle: fsize) -> AARectangle {
+        let (sin, cos) = angle.sin_cos();
+        let rotated_corners = self
+            .corners()
+            .map(|Point(x, y)| Point(x * cos - y * sin, x * sin + y * cos));
+        AARectangle::from_points(&rotated_corners)
+    }
+
     /// For all quadrants, contains indices of the two neighbors of the quadrant at that index.
     pub const QUADRANT_NEIGHBOR_LAYOUT: [[usize; 2]; 4] = [[1, 3], [0, 2], [1, 3], [0, 2]];
 
@@ -170,6 +221,64 @@ impl AARectangle {
         }
     }
 
+    /// Returns the maximal rectangles covering the part of `self` which is not covered by `other`.
+    /// The returned rectangles (at most four) can overlap each other.
+    pub fn subtract(&self, other: &AARectangle) -> Vec<AARectangle> {
+        if AARectangle::from_intersection(self, other).is_none() {
+            return vec![self.clone()];
+        }
+        let mut rects = vec![];
+        if other.x_min > self.x_min {
+            rects.push(AARectangle::new(
+                self.x_min,
+                self.y_min,
+                other.x_min,
+                self.y_max,
+            ));
+        }
+        if other.x_max < self.x_max {
+            rects.push(AARectangle::new(
+                other.x_max,
+                self.y_min,
+                self.x_max,
+                self.y_max,
+            ));
+        }
+        if other.y_min > self.y_min {
+            rects.push(AARectangle::new(
+                self.x_min,
+                self.y_min,
+                self.x_max,
+                other.y_min,
+            ));
+        }
+        if other.y_max < self.y_max {
+            rects.push(AARectangle::new(
+                self.x_min,
+                other.y_max,
+                self.x_max,
+                self.y_max,
+            ));
+        }
+        rects
+    }
+
+    /// Removes all rectangles which are enclosed by another one, only one copy of identical rectangles is kept.
+    pub fn remove_enclosed(rects: &mut Vec<AARectangle>) {
+        let n = rects.len();
+        let redundant = (0..n)
+            .map(|i| {
+                (0..n).any(|j| {
+                    i != j
+                        && rects[i].relation_to(&rects[j]) == GeoRelation::Enclosed
+                        && (rects[j].relation_to(&rects[i]) != GeoRelation::Enclosed || j < i)
+                })
+            })
+            .collect_vec();
+        let mut redundant = redundant.into_iter();
+        rects.retain(|_| !redundant.next().unwrap());
+    }
+
     pub fn bounding_rectangle(a: &AARectangle, b: &AARectangle) -> AARectangle {
         let x_min = fsize::min(a.x_min, b.x_min);
         let y_min = fsize::min(a.y_min, b.y_min);
@@ -200,6 +309,10 @@ impl Shape for AARectangle {
         let dy = self.y_max - self.y_min;
         (dx.powi(2) + dy.powi(2)).sqrt()
     }
+
+    fn perimeter(&self) -> fsize {
+        2.0 * (self.width() + self.height())
+    }
 }
 
 impl CollidesWith<AARectangle> for AARectangle {
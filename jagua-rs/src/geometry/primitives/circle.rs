@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
 
+use serde::{Deserialize, Serialize};
+
 use crate::geometry::geo_enums::GeoPosition;
 use crate::geometry::geo_traits::{
     CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
@@ -7,11 +9,12 @@ use crate::geometry::geo_traits::{
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
 use crate::{fsize, PI};
 
 /// Geometric primitive representing a circle
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Circle {
     pub center: Point,
     pub radius: fsize,
@@ -54,6 +57,25 @@ impl Circle {
         }
         bounding_circle
     }
+
+    /// Regular polygon with `n_sides` whose edges touch the circle, so it fully contains the circle.
+    /// Its first vertex lies in the direction of the positive x-axis.
+    pub fn circumscribed_polygon(&self, n_sides: usize) -> SimplePolygon {
+        assert!(n_sides >= 3, "a polygon needs at least three sides");
+        let circumradius = self.radius / (PI / n_sides as fsize).cos();
+        let Point(c_x, c_y) = self.center;
+        SimplePolygon::new(
+            (0..n_sides)
+                .map(|i| {
+                    let angle = 2.0 * PI * i as fsize / n_sides as fsize;
+                    Point(
+                        c_x + circumradius * angle.cos(),
+                        c_y + circumradius * angle.sin(),
+                    )
+                })
+                .collect(),
+        )
+    }
 }
 
 impl Transformable for Circle {
@@ -119,9 +141,7 @@ impl DistanceFrom<Point> for Circle {
     }
 
     fn distance(&self, point: &Point) -> fsize {
-        let Point(x, y) = point;
-        let Point(cx, cy) = self.center;
-        let sq_d = (x - cx).powi(2) + (y - cy).powi(2);
+        let sq_d = point.sq_distance(self.center);
         if sq_d < self.radius.powi(2) {
             0.0 //point is inside circle
         } else {
@@ -131,9 +151,7 @@ impl DistanceFrom<Point> for Circle {
     }
 
     fn distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
-        let Point(x, y) = point;
-        let Point(cx, cy) = self.center;
-        let d_center = fsize::sqrt((x - cx).powi(2) + (y - cy).powi(2));
+        let d_center = point.distance(self.center);
         match d_center.partial_cmp(&self.radius).unwrap() {
             Ordering::Less | Ordering::Equal => (GeoPosition::Interior, self.radius - d_center),
             Ordering::Greater => (GeoPosition::Exterior, d_center - self.radius),
@@ -220,4 +238,8 @@ impl Shape for Circle {
     fn diameter(&self) -> fsize {
         self.radius * 2.0
     }
+
+    fn perimeter(&self) -> fsize {
+        2.0 * PI * self.radius
+    }
 }
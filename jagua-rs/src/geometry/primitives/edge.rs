@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::fsize;
 use crate::geometry::geo_enums::GeoPosition;
 use crate::geometry::geo_traits::{
@@ -8,7 +10,7 @@ use crate::geometry::primitives::point::Point;
 use crate::geometry::transformation::Transformation;
 
 /// Geometric primitive representing a line segment
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Edge {
     pub start: Point,
     pub end: Point,
@@ -50,6 +52,46 @@ impl Edge {
         self
     }
 
+    /// Moves the edge by `d` to its left-hand side (the interior of a counterclockwise polygon) and shortens it by `d` at both ends.
+    /// Returns `None` if the edge is not longer than `2d`.
+    pub fn shrink_inward(&self, d: fsize) -> Option<Self> {
+        let l = self.diameter();
+        if l <= 2.0 * d {
+            return None;
+        }
+        let (dx, dy) = (
+            (self.end.0 - self.start.0) / l,
+            (self.end.1 - self.start.1) / l,
+        );
+        //offset along the left-hand normal (-dy, dx) and along the direction of the edge
+        let start = Point(self.start.0 + d * (dx - dy), self.start.1 + d * (dy + dx));
+        let end = Point(self.end.0 - d * (dx + dy), self.end.1 - d * (dy - dx));
+        Some(Edge::new(start, end))
+    }
+
+    /// Length of the part of `other` that runs along `self`, regardless of their directions.
+    /// Returns 0 unless both endpoints of `other` lie within `tolerance` of the line through `self`.
+    pub fn collinear_overlap(&self, other: &Edge, tolerance: fsize) -> fsize {
+        let l = self.diameter();
+        let (dx, dy) = (
+            (self.end.0 - self.start.0) / l,
+            (self.end.1 - self.start.1) / l,
+        );
+        //coordinates along and across the edge, relative to its start
+        let project = |Point(x, y): Point| {
+            let (rx, ry) = (x - self.start.0, y - self.start.1);
+            (rx * dx + ry * dy, ry * dx - rx * dy)
+        };
+        let (along_start, across_start) = project(other.start);
+        let (along_end, across_end) = project(other.end);
+        if across_start.abs() > tolerance || across_end.abs() > tolerance {
+            return 0.0;
+        }
+        let overlap_start = fsize::max(0.0, fsize::min(along_start, along_end));
+        let overlap_end = fsize::min(l, fsize::max(along_start, along_end));
+        fsize::max(0.0, overlap_end - overlap_start)
+    }
+
     pub fn reverse(mut self) -> Self {
         std::mem::swap(&mut self.start, &mut self.end);
         self
@@ -130,10 +172,7 @@ impl TransformableFrom for Edge {
 
 impl Shape for Edge {
     fn centroid(&self) -> Point {
-        Point(
-            (self.start.0 + self.end.0) / 2.0,
-            (self.start.1 + self.end.1) / 2.0,
-        )
+        self.start.midpoint(self.end)
     }
 
     fn area(&self) -> fsize {
@@ -147,6 +186,10 @@ impl Shape for Edge {
     fn diameter(&self) -> fsize {
         self.start.distance(self.end)
     }
+
+    fn perimeter(&self) -> fsize {
+        self.start.distance(self.end)
+    }
 }
 
 impl DistanceFrom<Point> for Edge {
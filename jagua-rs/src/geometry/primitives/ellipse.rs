@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_traits::{CollidesWith, DistanceFrom, Shape};
+use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::circle::Circle;
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::{fsize, PI};
+
+/// Minimum number of vertices of the polygon approximating an ellipse, see [Ellipse::circumscribed_polygon]
+const MIN_POLYGON_VERTICES: usize = 16;
+/// Maximum number of vertices of the polygon approximating an ellipse, see [Ellipse::circumscribed_polygon]
+const MAX_POLYGON_VERTICES: usize = 4096;
+/// Upper bound on the iterations of the bisection in [Ellipse::distance_from_border]
+const MAX_BISECTION_ITERATIONS: usize = 256;
+
+/// Geometric primitive representing an axis-aligned ellipse
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ellipse {
+    pub center: Point,
+    /// Semi-axis along the x-axis
+    pub rx: fsize,
+    /// Semi-axis along the y-axis
+    pub ry: fsize,
+}
+
+impl Ellipse {
+    pub fn new(center: Point, rx: fsize, ry: fsize) -> Self {
+        debug_assert!(
+            rx.is_finite() && rx > 0.0 && ry.is_finite() && ry > 0.0,
+            "invalid ellipse semi-axes: ({}, {})",
+            rx,
+            ry
+        );
+        debug_assert!(
+            center.0.is_finite() && center.1.is_finite(),
+            "invalid ellipse center: {:?}",
+            center
+        );
+
+        Self { center, rx, ry }
+    }
+
+    /// Polygon fully containing the ellipse, in counterclockwise order: a regular polygon circumscribing the unit circle, scaled by the semi-axes.
+    /// Its vertices deviate at most `max_deviation` from the ellipse,
+    /// unless this would require more than [MAX_POLYGON_VERTICES] vertices, in which case the deviation is larger.
+    pub fn circumscribed_polygon(&self, max_deviation: fsize) -> SimplePolygon {
+        //the vertices of a circumscribed polygon with n sides lie r_max * (1 / cos(π/n) - 1) outside the ellipse
+        let r_max = fsize::max(self.rx, self.ry);
+        let n_vertices = match max_deviation / r_max {
+            rel if rel <= 0.0 => MAX_POLYGON_VERTICES,
+            rel if rel >= 1.0 => MIN_POLYGON_VERTICES,
+            rel => (PI / (1.0 / (1.0 + rel)).acos()).ceil() as usize,
+        }
+        .clamp(MIN_POLYGON_VERTICES, MAX_POLYGON_VERTICES);
+
+        let unit = Circle::new(Point(0.0, 0.0), 1.0).circumscribed_polygon(n_vertices);
+        let Point(c_x, c_y) = self.center;
+        let points = unit
+            .points
+            .iter()
+            .map(|p| Point(c_x + self.rx * p.0, c_y + self.ry * p.1))
+            .collect();
+        SimplePolygon::new(points)
+    }
+
+    /// Whether the circle lies entirely inside the ellipse
+    pub fn contains_circle(&self, circle: &Circle) -> bool {
+        match self.distance_from_border(&circle.center) {
+            (GeoPosition::Interior, d) => d >= circle.radius,
+            (GeoPosition::Exterior, _) => false,
+        }
+    }
+}
+
+impl From<Circle> for Ellipse {
+    fn from(c: Circle) -> Self {
+        Ellipse::new(c.center, c.radius, c.radius)
+    }
+}
+
+impl CollidesWith<Point> for Ellipse {
+    fn collides_with(&self, point: &Point) -> bool {
+        let dx = (point.0 - self.center.0) / self.rx;
+        let dy = (point.1 - self.center.1) / self.ry;
+        dx * dx + dy * dy <= 1.0
+    }
+}
+
+impl DistanceFrom<Point> for Ellipse {
+    fn sq_distance(&self, point: &Point) -> fsize {
+        self.distance(point).powi(2)
+    }
+
+    fn distance(&self, point: &Point) -> fsize {
+        match self.collides_with(point) {
+            true => 0.0,
+            false => self.distance_from_border(point).1,
+        }
+    }
+
+    fn distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+        let position = match self.collides_with(point) {
+            true => GeoPosition::Interior,
+            false => GeoPosition::Exterior,
+        };
+        //by symmetry, the problem can be reduced to the first quadrant, with the major axis along x
+        let (dx, dy) = (
+            (point.0 - self.center.0).abs(),
+            (point.1 - self.center.1).abs(),
+        );
+        let distance = match self.rx >= self.ry {
+            true => quadrant_distance(self.rx, self.ry, dx, dy),
+            false => quadrant_distance(self.ry, self.rx, dy, dx),
+        };
+        (position, distance)
+    }
+
+    fn sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+        let (pos, distance) = self.distance_from_border(point);
+        (pos, distance.powi(2))
+    }
+}
+
+impl Shape for Ellipse {
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn area(&self) -> fsize {
+        PI * self.rx * self.ry
+    }
+
+    fn bbox(&self) -> AARectangle {
+        let Point(x, y) = self.center;
+        AARectangle::new(x - self.rx, y - self.ry, x + self.rx, y + self.ry)
+    }
+
+    fn diameter(&self) -> fsize {
+        2.0 * fsize::max(self.rx, self.ry)
+    }
+
+    fn perimeter(&self) -> fsize {
+        //Ramanujan's approximation
+        let (a, b) = (self.rx, self.ry);
+        PI * (3.0 * (a + b) - ((3.0 * a + b) * (a + 3.0 * b)).sqrt())
+    }
+}
+
+/// Distance from `(y0, y1)` to an origin-centered ellipse with semi-axes `e0 >= e1`, for `y0, y1 >= 0`.
+/// The closest point on the ellipse is found by bisection, following D. Eberly, "Distance from a Point to an Ellipse, an Ellipsoid, or a Hyperellipsoid".
+fn quadrant_distance(e0: fsize, e1: fsize, y0: fsize, y1: fsize) -> fsize {
+    if y1 > 0.0 {
+        if y0 > 0.0 {
+            let (z0, z1) = (y0 / e0, y1 / e1);
+            let g = z0 * z0 + z1 * z1 - 1.0;
+            if g == 0.0 {
+                return 0.0;
+            }
+            let r0 = (e0 / e1).powi(2);
+            let s = bisect_root(r0, z0, z1, g);
+            let (x0, x1) = (r0 * y0 / (s + r0), y1 / (s + 1.0));
+            ((x0 - y0).powi(2) + (x1 - y1).powi(2)).sqrt()
+        } else {
+            (y1 - e1).abs()
+        }
+    } else {
+        let (numer0, denom0) = (e0 * y0, e0 * e0 - e1 * e1);
+        if numer0 < denom0 {
+            let xde0 = numer0 / denom0;
+            let (x0, x1) = (e0 * xde0, e1 * (1.0 - xde0 * xde0).sqrt());
+            ((x0 - y0).powi(2) + x1 * x1).sqrt()
+        } else {
+            (y0 - e0).abs()
+        }
+    }
+}
+
+/// Root of `(r0 * z0 / (s + r0))^2 + (z1 / (s + 1))^2 - 1`, bisected until the floating point precision is exhausted
+fn bisect_root(r0: fsize, z0: fsize, z1: fsize, g: fsize) -> fsize {
+    let n0 = r0 * z0;
+    let mut s0 = z1 - 1.0;
+    let mut s1 = match g < 0.0 {
+        true => 0.0,
+        false => n0.hypot(z1) - 1.0,
+    };
+    let mut s = 0.0;
+    for _ in 0..MAX_BISECTION_ITERATIONS {
+        s = (s0 + s1) / 2.0;
+        if s == s0 || s == s1 {
+            break;
+        }
+        let (ratio0, ratio1) = (n0 / (s + r0), z1 / (s + 1.0));
+        let g = ratio0 * ratio0 + ratio1 * ratio1 - 1.0;
+        if g > 0.0 {
+            s0 = s;
+        } else if g < 0.0 {
+            s1 = s;
+        } else {
+            break;
+        }
+    }
+    s
+}
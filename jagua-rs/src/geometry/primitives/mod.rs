@@ -1,5 +1,6 @@
 pub mod aa_rectangle;
 pub mod circle;
 pub mod edge;
+pub mod ellipse;
 pub mod point;
 pub mod simple_polygon;
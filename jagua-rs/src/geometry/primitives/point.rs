@@ -1,11 +1,13 @@
 use std::hash::{Hash, Hasher};
 
+use serde::{Deserialize, Serialize};
+
 use crate::fsize;
 use crate::geometry::geo_traits::{CollidesWith, Transformable, TransformableFrom};
 use crate::geometry::transformation::Transformation;
 
 /// Geometric primitive representing a point: (x, y)
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Copy, Default, Serialize, Deserialize)]
 pub struct Point(pub fsize, pub fsize);
 
 impl Transformable for Point {
@@ -41,6 +43,19 @@ impl Point {
         (self.0 - other.0).powi(2) + (self.1 - other.1).powi(2)
     }
 
+    /// The point halfway between `self` and `other`
+    pub fn midpoint(&self, other: Point) -> Point {
+        self.lerp(other, 0.5)
+    }
+
+    /// Linear interpolation between `self` (t = 0) and `other` (t = 1)
+    pub fn lerp(&self, other: Point, t: fsize) -> Point {
+        Point(
+            self.0 + (other.0 - self.0) * t,
+            self.1 + (other.1 - self.1) * t,
+        )
+    }
+
     pub fn x(&self) -> fsize {
         self.0
     }
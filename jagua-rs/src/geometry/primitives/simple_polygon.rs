@@ -3,12 +3,14 @@ use std::borrow::Borrow;
 use itertools::Itertools;
 use num_integer::Integer;
 use ordered_float::NotNan;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
 
-use crate::fsize;
+use crate::geometry::convex_decomposition::{convex_decomposition_indices, triangulation_indices};
 use crate::geometry::convex_hull::convex_hull_from_points;
 use crate::geometry::fail_fast::poi;
 use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
-use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_enums::{GeoPosition, GeoRelation};
 use crate::geometry::geo_traits::{
     CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
 };
@@ -17,8 +19,9 @@ use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::transformation::Transformation;
-use crate::util::config::SPSurrogateConfig;
+use crate::util::config::{GeoTolerance, SPSurrogateConfig};
 use crate::util::fpa::FPA;
+use crate::{fsize, PI};
 
 /// Geometric primitive representing a simple polygon: <https://en.wikipedia.org/wiki/Simple_polygon>
 #[derive(Clone, Debug)]
@@ -32,6 +35,8 @@ pub struct SimplePolygon {
     pub diameter: fsize,
     /// Pole of inaccessibility
     pub poi: Circle,
+    /// Whether the polygon is convex, enables faster distance queries
+    pub is_convex: bool,
     /// Surrogate representation (subset of the simple polygon)
     pub surrogate: Option<SPSurrogate>,
 }
@@ -63,6 +68,7 @@ impl SimplePolygon {
         let diameter = SimplePolygon::calculate_diameter(points.clone());
         let bbox = SimplePolygon::generate_bounding_box(&points);
         let poi = SimplePolygon::calculate_poi(&points, diameter);
+        let is_convex = SimplePolygon::calculate_convexity(&points);
 
         SimplePolygon {
             points,
@@ -70,6 +76,7 @@ impl SimplePolygon {
             area,
             diameter,
             poi,
+            is_convex,
             surrogate: None,
         }
     }
@@ -144,6 +151,42 @@ impl SimplePolygon {
         0.5 * sigma
     }
 
+    /// Checks whether a counterclockwise ordered set of points forms a convex polygon.
+    /// Collinear consecutive points are allowed.
+    pub fn calculate_convexity(points: &[Point]) -> bool {
+        let n = points.len();
+        (0..n).all(|i| {
+            let (Point(x_a, y_a), Point(x_b, y_b), Point(x_c, y_c)) =
+                (points[i], points[(i + 1) % n], points[(i + 2) % n]);
+            (x_b - x_a) * (y_c - y_b) - (y_b - y_a) * (x_c - x_b) >= 0.0
+        })
+    }
+
+    /// Checks whether the boundary formed by the points does not intersect itself.
+    /// A boundary which touches itself, even in a single point, is not considered simple.
+    pub fn calculate_simplicity(points: &[Point]) -> bool {
+        let n = points.len();
+        let edge = |i: usize| (points[i], points[(i + 1) % n]);
+        (0..n).all(|i| {
+            (i + 1..n).all(|j| {
+                let ((a1, a2), (b1, b2)) = (edge(i), edge(j));
+                if j == i + 1 {
+                    //consecutive edges share a point and should not fold back onto each other
+                    !folds_back(a1, a2, b2)
+                } else if i == 0 && j == n - 1 {
+                    !folds_back(a2, a1, b1)
+                } else {
+                    !segments_touch(a1, a2, b1, b2)
+                }
+            })
+        })
+    }
+
+    /// Whether the polygon is simple, see [SimplePolygon::calculate_simplicity].
+    pub fn is_simple(&self) -> bool {
+        SimplePolygon::calculate_simplicity(&self.points)
+    }
+
     pub fn calculate_poi(points: &[Point], diameter: fsize) -> Circle {
         //need to make a dummy simple polygon, because the pole generation algorithm
         //relies on many of the methods provided by the simple polygon struct
@@ -158,6 +201,7 @@ impl SimplePolygon {
                 area,
                 diameter,
                 poi: dummy_poi,
+                is_convex: SimplePolygon::calculate_convexity(points),
                 surrogate: None,
             }
         };
@@ -165,6 +209,217 @@ impl SimplePolygon {
         poi::generate_next_pole(&dummy_sp, &[])
     }
 
+    /// Order of the rotational symmetry of the polygon around its centroid:
+    /// the largest `n` for which a rotation over `2π/n` maps every vertex onto another vertex (within tolerance).
+    /// Returns 1 if the polygon has no rotational symmetry.
+    pub fn rotational_symmetry(&self) -> u32 {
+        let n_points = self.number_of_points();
+        let Point(c_x, c_y) = self.centroid();
+        let tolerance = self.diameter * FPA::tolerance();
+        (2..=n_points)
+            .rev()
+            .filter(|n| n_points % n == 0)
+            .find(|&n| {
+                let (sin, cos) = (2.0 * PI / n as fsize).sin_cos();
+                self.points.iter().all(|Point(x, y)| {
+                    let (dx, dy) = (x - c_x, y - c_y);
+                    let rotated = Point(c_x + dx * cos - dy * sin, c_y + dx * sin + dy * cos);
+                    self.points.iter().any(|p| p.distance(rotated) < tolerance)
+                })
+            })
+            .map_or(1, |n| n as u32)
+    }
+
+    /// Decomposes the polygon into convex parts which together cover exactly the same area.
+    /// A convex polygon results in a single part. See [convex_decomposition_indices].
+    pub fn convex_decomposition(&self) -> Vec<SimplePolygon> {
+        convex_decomposition_indices(self)
+            .into_iter()
+            .map(|part| SimplePolygon::new(part.into_iter().map(|i| self.points[i]).collect()))
+            .collect()
+    }
+
+    /// Triangulates the polygon by ear clipping, see [triangulation_indices].
+    /// The triangles tile the polygon and are ordered counterclockwise.
+    pub fn triangulate(&self) -> Vec<[Point; 3]> {
+        triangulation_indices(&self.points)
+            .into_iter()
+            .flat_map(|part| {
+                //a remainder of more than three points (due to numerical issues) is fanned out
+                (1..part.len() - 1).map(move |k| [part[0], part[k], part[k + 1]])
+            })
+            .map(|t| t.map(|i| self.points[i]))
+            .collect()
+    }
+
+    /// Samples a point uniformly at random within the polygon.
+    /// A triangle of the [triangulation](SimplePolygon::triangulate) is chosen with a probability proportional to its area,
+    /// and a point within it is sampled using barycentric coordinates.
+    /// The triangulation is recomputed on every call.
+    pub fn sample_interior(&self, rng: &mut impl Rng) -> Point {
+        let triangles = self.triangulate();
+        let weights = triangles
+            .iter()
+            .map(|t| SimplePolygon::calculate_area(t).abs());
+        let distr = WeightedIndex::new(weights).expect("polygon has no area");
+        let [a, b, c] = triangles[distr.sample(rng)];
+
+        let (mut r1, mut r2): (fsize, fsize) = (rng.gen(), rng.gen());
+        if r1 + r2 > 1.0 {
+            //reflect back into the triangle
+            (r1, r2) = (1.0 - r1, 1.0 - r2);
+        }
+        Point(
+            a.0 + r1 * (b.0 - a.0) + r2 * (c.0 - a.0),
+            a.1 + r1 * (b.1 - a.1) + r2 * (c.1 - a.1),
+        )
+    }
+
+    /// Area of the intersection between `self` and a convex polygon.
+    /// Clips `self` with every edge of `convex`, see [Sutherland–Hodgman](https://en.wikipedia.org/wiki/Sutherland%E2%80%93Hodgman_algorithm).
+    pub fn intersection_area_with_convex(&self, convex: &SimplePolygon) -> fsize {
+        debug_assert!(convex.is_convex);
+        let mut clipped = self.points.clone();
+        for Edge { start, end } in convex.edge_iter() {
+            //positive for points to the left of the (counterclockwise) edge, inside the convex polygon
+            let side = |p: &Point| {
+                (end.0 - start.0) * (p.1 - start.1) - (end.1 - start.1) * (p.0 - start.0)
+            };
+            let input = std::mem::take(&mut clipped);
+            for (i, &cur) in input.iter().enumerate() {
+                let prev = input[(i + input.len() - 1) % input.len()];
+                let (s_prev, s_cur) = (side(&prev), side(&cur));
+                if (s_prev >= 0.0) != (s_cur >= 0.0) {
+                    clipped.push(prev.lerp(cur, s_prev / (s_prev - s_cur)));
+                }
+                if s_cur >= 0.0 {
+                    clipped.push(cur);
+                }
+            }
+            if clipped.len() < 3 {
+                return 0.0;
+            }
+        }
+        fsize::max(0.0, SimplePolygon::calculate_area(&clipped))
+    }
+
+    /// Checks whether `other` lies entirely within the interior of `self`.
+    pub fn encloses(&self, other: &SimplePolygon) -> bool {
+        self.bbox.relation_to(&other.bbox) == GeoRelation::Surrounding
+            && other.points.iter().all(|p| self.collides_with(p))
+            && !other
+                .edge_iter()
+                .any(|e| self.edge_iter().any(|s_e| s_e.collides_with(&e)))
+    }
+
+    /// Total length of the boundary shared with `other`: the overlapping parts of their collinear edges,
+    /// where edges within `tolerance` of each other count as collinear (see [Edge::collinear_overlap]).
+    pub fn edge_contact(&self, other: &SimplePolygon, tolerance: fsize) -> fsize {
+        //bounds (x_min, y_min, x_max, y_max), edges along an axis have no valid bounding box
+        let bounds = |e: &Edge| (e.x_min(), e.y_min(), e.x_max(), e.y_max());
+        let near = |a: (fsize, fsize, fsize, fsize), b: (fsize, fsize, fsize, fsize)| {
+            a.0 - tolerance <= b.2
+                && b.0 - tolerance <= a.2
+                && a.1 - tolerance <= b.3
+                && b.1 - tolerance <= a.3
+        };
+        let self_bounds = (
+            self.bbox.x_min,
+            self.bbox.y_min,
+            self.bbox.x_max,
+            self.bbox.y_max,
+        );
+        let other_edges = other
+            .edge_iter()
+            .filter(|e| near(bounds(e), self_bounds))
+            .collect_vec();
+        self.edge_iter()
+            .map(|e| {
+                other_edges
+                    .iter()
+                    .filter(|o| near(bounds(o), bounds(&e)))
+                    .map(|o| e.collinear_overlap(o, tolerance))
+                    .sum::<fsize>()
+            })
+            .sum()
+    }
+
+    /// Returns the polygon as an [AARectangle] if it is an axis-aligned rectangle (within [FPA] tolerance).
+    pub fn as_rect(&self) -> Option<AARectangle> {
+        let bbox = &self.bbox;
+        let on_corner = |Point(x, y): &Point| {
+            (FPA(*x) == FPA(bbox.x_min) || FPA(*x) == FPA(bbox.x_max))
+                && (FPA(*y) == FPA(bbox.y_min) || FPA(*y) == FPA(bbox.y_max))
+        };
+        //four corners of the bounding box, enclosing the same area as the bounding box itself
+        match self.number_of_points() == 4
+            && self.points.iter().all(on_corner)
+            && FPA(self.area) == FPA(bbox.area())
+        {
+            true => Some(bbox.clone()),
+            false => None,
+        }
+    }
+
+    /// Whether the point lies inside the polygon.
+    /// `tol` determines when the point is considered level with a vertex of the polygon, in which case the vertex is handled explicitly.
+    pub fn collides_with_point_within(&self, point: &Point, tol: &GeoTolerance) -> bool {
+        //based on the ray casting algorithm: https://en.wikipedia.org/wiki/Point_in_polygon#Ray_casting_algorithm
+        match self.bbox().collides_with(point) {
+            false => false,
+            true => {
+                //horizontal ray shot to the right.
+                //Starting from the point to another point that is certainly outside the shape
+                let point_outside = Point(self.bbox.x_max + self.bbox.width(), point.1);
+                let ray = Edge::new(*point, point_outside);
+
+                let mut n_intersections = 0;
+                for edge in self.edge_iter() {
+                    //Check if the ray does not go through (or almost through) a vertex
+                    //This can result in funky behaviour, which could incorrect results
+                    //Therefore we handle this case
+                    let (Point(s_x, s_y), Point(e_x, e_y)) = (edge.start, edge.end);
+                    let Point(p_x, p_y) = *point;
+
+                    if (tol.eq(s_y, p_y) && !tol.le(s_x, p_x))
+                        || (tol.eq(e_y, p_y) && !tol.le(e_x, p_x))
+                    {
+                        //in this case, the ray passes through (or dangerously close to) a vertex
+                        //We handle this case by only counting an intersection if the edge is below the ray
+                        if !tol.ge(s_y, p_y) || !tol.ge(e_y, p_y) {
+                            n_intersections += 1;
+                        }
+                    } else if ray.collides_with(&edge) {
+                        n_intersections += 1;
+                    }
+                }
+
+                n_intersections.is_odd()
+            }
+        }
+    }
+
+    /// Second moments of area `(I_x, I_y)` about the horizontal and vertical axis through `p`.
+    /// Equivalent to the moments about the centroid shifted by the parallel axis theorem,
+    /// see [Second moment of area](https://en.wikipedia.org/wiki/Second_moment_of_area#Any_polygon).
+    pub fn second_moments_about(&self, p: Point) -> (fsize, fsize) {
+        let Point(p_x, p_y) = p;
+        let mut i_x = 0.0;
+        let mut i_y = 0.0;
+
+        for i in 0..self.number_of_points() {
+            let j = (i + 1) % self.number_of_points();
+            let Point(x_i, y_i) = self.get_point(i);
+            let Point(x_j, y_j) = self.get_point(j);
+            let (x_i, y_i, x_j, y_j) = (x_i - p_x, y_i - p_y, x_j - p_x, y_j - p_y);
+            let cross = x_i * y_j - x_j * y_i;
+            i_x += (y_i * y_i + y_i * y_j + y_j * y_j) * cross;
+            i_y += (x_i * x_i + x_i * x_j + x_j * x_j) * cross;
+        }
+
+        (i_x / 12.0, i_y / 12.0)
+    }
+
     pub fn center_around_centroid(mut self) -> (SimplePolygon, Transformation) {
         let Point(c_x, c_y) = self.centroid();
         let transformation = Transformation::from_translation((-c_x, -c_y));
@@ -212,6 +467,10 @@ impl Shape for SimplePolygon {
     fn diameter(&self) -> fsize {
         self.diameter
     }
+
+    fn perimeter(&self) -> fsize {
+        self.edge_iter().map(|e| e.diameter()).sum()
+    }
 }
 
 impl Transformable for SimplePolygon {
@@ -223,6 +482,7 @@ impl Transformable for SimplePolygon {
             area: _,
             diameter: _,
             poi,
+            is_convex: _,
             surrogate,
         } = self;
 
@@ -254,6 +514,7 @@ impl TransformableFrom for SimplePolygon {
             area: _,
             diameter: _,
             poi,
+            is_convex: _,
             surrogate,
         } = self;
 
@@ -276,37 +537,16 @@ impl TransformableFrom for SimplePolygon {
 
 impl CollidesWith<Point> for SimplePolygon {
     fn collides_with(&self, point: &Point) -> bool {
-        //based on the ray casting algorithm: https://en.wikipedia.org/wiki/Point_in_polygon#Ray_casting_algorithm
-        match self.bbox().collides_with(point) {
-            false => false,
-            true => {
-                //horizontal ray shot to the right.
-                //Starting from the point to another point that is certainly outside the shape
-                let point_outside = Point(self.bbox.x_max + self.bbox.width(), point.1);
-                let ray = Edge::new(*point, point_outside);
-
-                let mut n_intersections = 0;
-                for edge in self.edge_iter() {
-                    //Check if the ray does not go through (or almost through) a vertex
-                    //This can result in funky behaviour, which could incorrect results
-                    //Therefore we handle this case
-                    let (s_x, s_y) = (FPA(edge.start.0), FPA(edge.start.1));
-                    let (e_x, e_y) = (FPA(edge.end.0), FPA(edge.end.1));
-                    let (p_x, p_y) = (FPA(point.0), FPA(point.1));
-
-                    if (s_y == p_y && s_x > p_x) || (e_y == p_y && e_x > p_x) {
-                        //in this case, the ray passes through (or dangerously close to) a vertex
-                        //We handle this case by only counting an intersection if the edge is below the ray
-                        if s_y < p_y || e_y < p_y {
-                            n_intersections += 1;
-                        }
-                    } else if ray.collides_with(&edge) {
-                        n_intersections += 1;
-                    }
-                }
+        self.collides_with_point_within(point, &GeoTolerance::DEFAULT)
+    }
+}
 
-                n_intersections.is_odd()
-            }
+impl CollidesWith<Circle> for SimplePolygon {
+    /// Exact test: the circle collides if its center lies inside the polygon, or within its radius from the border
+    fn collides_with(&self, circle: &Circle) -> bool {
+        match self.distance_from_border(&circle.center) {
+            (GeoPosition::Interior, _) => true,
+            (GeoPosition::Exterior, distance) => distance <= circle.radius,
         }
     }
 }
@@ -332,6 +572,10 @@ impl DistanceFrom<Point> for SimplePolygon {
     }
 
     fn sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+        if self.is_convex {
+            return self.convex_sq_distance_from_border(point);
+        }
+
         let distance_to_border = self
             .edge_iter()
             .map(|edge| edge.sq_distance(point))
@@ -345,6 +589,36 @@ impl DistanceFrom<Point> for SimplePolygon {
     }
 }
 
+impl SimplePolygon {
+    /// Specialized version of [`DistanceFrom::sq_distance_from_border`] for convex polygons, requiring only a single pass over the edges.
+    /// The point lies in the interior if it is on the left side of every (counterclockwise) edge.
+    /// In that case, the closest point on the border is the projection onto one of the edges' supporting lines.
+    /// Otherwise, the closest point on the border always lies on an edge that has the point on its right side.
+    fn convex_sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+        debug_assert!(self.is_convex);
+        let mut interior = true;
+        let mut min_sq_line_distance = fsize::MAX;
+        let mut min_sq_edge_distance = fsize::MAX;
+        for edge in self.edge_iter() {
+            let (d_x, d_y) = (edge.end.0 - edge.start.0, edge.end.1 - edge.start.1);
+            let cross = d_x * (point.1 - edge.start.1) - d_y * (point.0 - edge.start.0);
+            if cross < 0.0 {
+                interior = false;
+                min_sq_edge_distance = fsize::min(min_sq_edge_distance, edge.sq_distance(point));
+            } else if interior {
+                min_sq_line_distance = fsize::min(
+                    min_sq_line_distance,
+                    cross * cross / (d_x * d_x + d_y * d_y),
+                );
+            }
+        }
+        match interior {
+            true => (GeoPosition::Interior, min_sq_line_distance),
+            false => (GeoPosition::Exterior, min_sq_edge_distance),
+        }
+    }
+}
+
 impl<T> From<T> for SimplePolygon
 where
     T: Borrow<AARectangle>,
@@ -359,3 +633,36 @@ where
         ])
     }
 }
+
+/// Cross product of `o`->`a` and `o`->`b`: positive if `o`, `a`, `b` make a counterclockwise turn
+pub(crate) fn cross(o: Point, a: Point, b: Point) -> fsize {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Whether segment `s`-`b` folds back onto segment `a`-`s`, i.e. they overlap in more than `s`
+fn folds_back(a: Point, s: Point, b: Point) -> bool {
+    cross(s, a, b) == 0.0 && (a.0 - s.0) * (b.0 - s.0) + (a.1 - s.1) * (b.1 - s.1) > 0.0
+}
+
+/// Whether segments `a1`-`a2` and `b1`-`b2` share at least one point
+fn segments_touch(a1: Point, a2: Point, b1: Point, b2: Point) -> bool {
+    //whether `p` lies within the bounding box of segment `s1`-`s2` (only valid if the three points are collinear)
+    let on_segment = |s1: Point, s2: Point, p: Point| {
+        fsize::min(s1.0, s2.0) <= p.0
+            && p.0 <= fsize::max(s1.0, s2.0)
+            && fsize::min(s1.1, s2.1) <= p.1
+            && p.1 <= fsize::max(s1.1, s2.1)
+    };
+    let (d1, d2) = (cross(b1, b2, a1), cross(b1, b2, a2));
+    let (d3, d4) = (cross(a1, a2, b1), cross(a1, a2, b2));
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    (d1 == 0.0 && on_segment(b1, b2, a1))
+        || (d2 == 0.0 && on_segment(b1, b2, a2))
+        || (d3 == 0.0 && on_segment(a1, a2, b1))
+        || (d4 == 0.0 && on_segment(a1, a2, b2))
+}
@@ -0,0 +1,71 @@
+use crate::fsize;
+use crate::geometry::convex_hull::convex_hull_indices;
+use crate::geometry::geo_traits::Transformable;
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::transformation::Transformation;
+
+/// Computes the minimum translation vector (MTV) separating two overlapping shapes, using the [separating axis theorem](https://en.wikipedia.org/wiki/Hyperplane_separation_theorem).
+/// Returns the penetration depth and the unit direction in which `b` has to be translated by that depth to resolve the overlap
+/// (or, equivalently, `a` in the opposite direction).
+/// Returns `None` if the shapes do not overlap, shapes which only touch are not considered overlapping.
+/// The theorem only holds for convex shapes, concave shapes are replaced by their convex hull, which can overestimate the penetration.
+pub fn mtv(
+    a: &SimplePolygon,
+    a_t: &Transformation,
+    b: &SimplePolygon,
+    b_t: &Transformation,
+) -> Option<(fsize, (fsize, fsize))> {
+    let a_hull = transformed_hull(a, a_t);
+    let b_hull = transformed_hull(b, b_t);
+
+    let mut best: Option<(fsize, (fsize, fsize))> = None;
+    for hull in [&a_hull, &b_hull] {
+        for i in 0..hull.len() {
+            let Point(x1, y1) = hull[i];
+            let Point(x2, y2) = hull[(i + 1) % hull.len()];
+            let length = fsize::sqrt((x2 - x1).powi(2) + (y2 - y1).powi(2));
+            if length == 0.0 {
+                continue;
+            }
+            let axis = ((y2 - y1) / length, (x1 - x2) / length);
+
+            let (a_min, a_max) = project(&a_hull, axis);
+            let (b_min, b_max) = project(&b_hull, axis);
+            //depth to push b along the axis, and against it
+            let (depth_pos, depth_neg) = (a_max - b_min, b_max - a_min);
+            if depth_pos <= 0.0 || depth_neg <= 0.0 {
+                //separating axis found
+                return None;
+            }
+            let candidate = match depth_pos <= depth_neg {
+                true => (depth_pos, axis),
+                false => (depth_neg, (-axis.0, -axis.1)),
+            };
+            if best.map_or(true, |(depth, _)| candidate.0 < depth) {
+                best = Some(candidate);
+            }
+        }
+    }
+    best
+}
+
+fn transformed_hull(shape: &SimplePolygon, t: &Transformation) -> Vec<Point> {
+    let indices = match shape.surrogate.as_ref() {
+        Some(surrogate) => surrogate.convex_hull_indices.clone(),
+        None => convex_hull_indices(shape),
+    };
+    indices
+        .into_iter()
+        .map(|i| shape.points[i].transform_clone(t))
+        .collect()
+}
+
+fn project(points: &[Point], axis: (fsize, fsize)) -> (fsize, fsize) {
+    points
+        .iter()
+        .map(|p| p.0 * axis.0 + p.1 * axis.1)
+        .fold((fsize::INFINITY, fsize::NEG_INFINITY), |(min, max), d| {
+            (fsize::min(min, d), fsize::max(max, d))
+        })
+}
@@ -1,4 +1,5 @@
 use std::borrow::Borrow;
+use std::fmt::{Display, Formatter};
 use std::ops::{Add, Div, Mul, Sub};
 
 use ordered_float::NotNan;
@@ -81,6 +82,40 @@ impl Transformation {
         &self.matrix
     }
 
+    /// Returns the transformation as a 3x3 affine matrix in row-major order (for interoperability).
+    pub fn as_matrix(&self) -> [[f64; 3]; 3] {
+        self.matrix.map(|row| row.map(|v| v.into_inner() as f64))
+    }
+
+    /// Creates a transformation from a 3x3 affine matrix in row-major order.
+    /// The matrix has to represent a proper rigid transformation (rotation + translation) within [`MATRIX_RIGIDITY_TOLERANCE`],
+    /// the rest of the crate relies on this assumption.
+    pub fn from_matrix(m: [[f64; 3]; 3]) -> Result<Self, MatrixError> {
+        if m.iter().flatten().any(|v| !v.is_finite()) {
+            return Err(MatrixError::NonFinite);
+        }
+        let is_close = |a: f64, b: f64| (a - b).abs() <= MATRIX_RIGIDITY_TOLERANCE;
+        if !(is_close(m[2][0], 0.0) && is_close(m[2][1], 0.0) && is_close(m[2][2], 1.0)) {
+            return Err(MatrixError::NotAffine);
+        }
+        //the columns of the rotation part have to be orthonormal and the determinant must be 1 (no reflection)
+        let (a, b, c, d) = (m[0][0], m[0][1], m[1][0], m[1][1]);
+        let orthonormal = is_close(a * a + c * c, 1.0)
+            && is_close(b * b + d * d, 1.0)
+            && is_close(a * b + c * d, 0.0);
+        if !orthonormal || !is_close(a * d - b * c, 1.0) {
+            return Err(MatrixError::NotRigid);
+        }
+        let to_nn = |v: f64| NotNan::new(v as fsize).expect("matrix value is NaN");
+        Ok(Self {
+            matrix: [
+                [to_nn(a), to_nn(b), to_nn(m[0][2])],
+                [to_nn(c), to_nn(d), to_nn(m[1][2])],
+                [_0, _0, _1],
+            ],
+        })
+    }
+
     pub fn decompose(&self) -> DTransformation {
         let m = self.matrix();
         let angle = m[1][0].atan2(m[0][0].into_inner());
@@ -98,6 +133,30 @@ where
     }
 }
 
+/// Absolute tolerance used by [`Transformation::from_matrix`] to verify a matrix is rigid
+pub const MATRIX_RIGIDITY_TOLERANCE: f64 = 1e-5;
+
+/// Error type for when a matrix cannot be converted with [`Transformation::from_matrix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    /// The matrix contains NaN or infinite values
+    NonFinite,
+    /// The bottom row of the matrix is not `[0, 0, 1]`
+    NotAffine,
+    /// The matrix contains scaling, shearing or reflection
+    NotRigid,
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatrixError::NonFinite => write!(f, "matrix contains non-finite values"),
+            MatrixError::NotAffine => write!(f, "matrix is not an affine transformation"),
+            MatrixError::NotRigid => write!(f, "matrix is not a proper rigid transformation"),
+        }
+    }
+}
+
 const _0: NotNan<fsize> = unsafe { NotNan::new_unchecked(0.0) };
 const _1: NotNan<fsize> = unsafe { NotNan::new_unchecked(1.0) };
 
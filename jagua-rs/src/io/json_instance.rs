@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::entities::instances::strip_packing::PackingAxis;
 use crate::fsize;
 
 /// The JSON representation of a problem instance
@@ -19,6 +20,37 @@ pub struct JsonInstance {
     #[serde(rename = "Strip")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strip: Option<JsonStrip>,
+    /// Pairs of item ids `[a, b]`, stating that all copies of item `a` have to be placed before any copy of item `b`
+    #[serde(rename = "Precedence")]
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub precedence: Vec<(usize, usize)>,
+    /// Unit of the angles in the allowed orientations and orientation ranges of the items, degrees if absent
+    #[serde(rename = "RotationUnit", alias = "rotation_unit")]
+    #[serde(default, skip_serializing_if = "is_default_unit")]
+    pub rotation_unit: RotationUnit,
+}
+
+/// Unit of the angles in the JSON representation of an instance
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationUnit {
+    #[default]
+    Deg,
+    Rad,
+}
+
+impl RotationUnit {
+    /// Converts an angle expressed in this unit to radians
+    pub fn to_radians(self, angle: fsize) -> fsize {
+        match self {
+            RotationUnit::Deg => angle.to_radians(),
+            RotationUnit::Rad => angle,
+        }
+    }
+}
+
+fn is_default_unit(unit: &RotationUnit) -> bool {
+    *unit == RotationUnit::default()
 }
 
 /// The JSON representation of a bin
@@ -36,11 +68,19 @@ pub struct JsonBin {
     pub zones: Vec<JsonQualityZone>,
 }
 
-/// The JSON representation of a strip with fixed height and variable width
+/// The JSON representation of a strip with fixed height and variable width, or fixed width and variable height
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct JsonStrip {
+    /// The fixed dimension of the strip: its height, or its width when packing along the Y axis
     pub height: fsize,
+    /// The axis along which the strip extends, [`PackingAxis::Y`] fixes the width and minimizes the height
+    #[serde(default, skip_serializing_if = "is_default_axis")]
+    pub axis: PackingAxis,
+}
+
+fn is_default_axis(axis: &PackingAxis) -> bool {
+    *axis == PackingAxis::default()
 }
 
 /// The JSON representation of an item
@@ -49,15 +89,45 @@ pub struct JsonStrip {
 pub struct JsonItem {
     /// Number of times this item should be produced
     pub demand: u64,
-    /// List of allowed orientations angles (in degrees). If none any orientation is allowed
+    /// List of allowed orientations angles (in the [`RotationUnit`] of the instance). If none any orientation is allowed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_orientations: Option<Vec<fsize>>,
+    /// Range of allowed orientation angles (in the [`RotationUnit`] of the instance) as [min, max]. Cannot be combined with `allowed_orientations`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allowed_orientation_range: Option<[fsize; 2]>,
     /// Polygon shape of the item
     pub shape: JsonShape,
     /// The value of the item (for knapsack problems)
     pub value: Option<u64>,
     /// The quality required for the entire item, if not defined maximum quality is required
     pub base_quality: Option<usize>,
+    /// Steps (x, y) of the lattice the position of the item is restricted to, with the origin of the bin as one of its points.
+    /// If not present, the item can be placed anywhere
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub position_lattice: Option<(fsize, fsize)>,
+    /// Reference point of the item: the translation of a placement is where this point of the item ends up.
+    /// If not present, the origin of the item's coordinate system is used
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub anchor: Option<JsonAnchor>,
+    /// Minimum length of its boundary every placement of the item has to share with other items or the bin,
+    /// e.g. for tab-and-slot assemblies. If not present, no contact is required
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub min_edge_contact: Option<fsize>,
+    /// Arbitrary metadata of the item (a part number, description...), which is not interpreted
+    /// but copied to every placement of the item in the solution
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub meta: Option<serde_json::Value>,
+}
+
+/// Reference point of an item, in the coordinates of its shape
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum JsonAnchor {
+    /// The centroid of the shape
+    Centroid,
+    /// A vertex of the shape, by its index in the outer boundary
+    Vertex(usize),
+    /// An arbitrary point (x, y)
+    Point(fsize, fsize),
 }
 
 /// Different ways to represent a shape
@@ -73,6 +143,8 @@ pub enum JsonShape {
     Polygon(JsonPoly),
     /// Multiple disjoint polygons
     MultiPolygon(Vec<JsonPoly>),
+    /// Circle with its center at (0, 0)
+    Circle { radius: fsize },
 }
 
 /// A polygon represented as an outer boundary and a list of holes
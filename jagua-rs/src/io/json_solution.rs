@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::fsize;
+use crate::io::json_instance::JsonSimplePoly;
 
 /// Representation of a solution
 #[derive(Serialize, Deserialize, Clone)]
@@ -24,6 +25,24 @@ pub struct JsonLayout {
     pub placed_items: Vec<JsonPlacedItem>,
     /// Some statistics about the layout
     pub statistics: JsonLayoutStats,
+    /// The internal state of the layout, only present in verbose output
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub snapshot: Option<JsonLayoutSnapshot>,
+}
+
+/// Internal state of a layout, for debugging.
+/// Unlike the rest of the solution, all transformations are in the internal coordinates of the items and the bin.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct JsonLayoutSnapshot {
+    /// The id of the layout snapshot
+    pub id: usize,
+    /// The id of the bin
+    pub bin_id: usize,
+    /// The usage of the bin, as calculated internally
+    pub usage: fsize,
+    /// The items in the layout, with the transformation applied to their internal shape
+    pub placed_items: Vec<JsonPlacedItem>,
 }
 
 /// Represents an item placed in a container
@@ -32,8 +51,22 @@ pub struct JsonLayout {
 pub struct JsonPlacedItem {
     /// The index of the item in the instance
     pub index: usize,
-    /// The transformation applied to the item to place it in the container
+    /// The transformation applied to the item to place it in the container (relative to the anchor of the item, if it has one)
     pub transformation: JsonTransformation,
+    /// Whether the item is cut out of the item it lies in, instead of occupying space
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub subtract: bool,
+    /// The metadata of the item, as defined in the instance
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub meta: Option<serde_json::Value>,
+    /// The simplified shape used for collision detection, as placed in the container.
+    /// Only present if polygon simplification changed the shape of the item.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub collision_shape: Option<JsonSimplePoly>,
+    /// The shape of the item as defined in the instance, as placed in the container.
+    /// Only present if polygon simplification changed the shape of the item.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub original_shape: Option<JsonSimplePoly>,
 }
 
 /// Represents a proper rigid transformation defined as a rotation followed by translation
@@ -65,10 +98,10 @@ pub enum JsonContainer {
         index: usize,
     },
     Strip {
-        /// The width of the strip (variable)
+        /// The width of the strip (variable, fixed when packing along the Y axis)
         #[serde(rename = "Width")]
         width: fsize,
-        /// The height of the strip (fixed)
+        /// The height of the strip (fixed, variable when packing along the Y axis)
         #[serde(rename = "Height")]
         height: fsize,
     },
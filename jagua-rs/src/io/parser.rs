@@ -1,12 +1,21 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 
+use crate::collision_detection::hazard_filter;
 use crate::entities::bin::Bin;
 use crate::entities::instances::bin_packing::BPInstance;
 use crate::entities::instances::instance::Instance;
 use crate::entities::instances::instance_generic::InstanceGeneric;
-use crate::entities::instances::strip_packing::SPInstance;
+use crate::entities::instances::strip_packing::{PackingAxis, SPInstance};
 use crate::entities::item::Item;
+use crate::entities::layout;
+use crate::entities::layout::{Layout, LayoutSnapshot};
+use crate::entities::placed_item::{PItemKey, PlacedItem};
 use crate::entities::placing_option::PlacingOption;
 use crate::entities::problems::bin_packing::BPProblem;
 use crate::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric, STRIP_LAYOUT_IDX};
@@ -16,30 +25,41 @@ use crate::entities::quality_zone::N_QUALITIES;
 use crate::entities::solution::Solution;
 use crate::fsize;
 use crate::geometry::d_transformation::DTransformation;
+use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
 use crate::geometry::geo_enums::AllowedRotation;
 use crate::geometry::geo_traits::{Shape, Transformable};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
-use crate::io::json_instance::{JsonBin, JsonInstance, JsonItem, JsonShape, JsonSimplePoly};
+use crate::io::json_instance::{
+    JsonAnchor, JsonBin, JsonInstance, JsonItem, JsonShape, JsonSimplePoly, RotationUnit,
+};
 use crate::io::json_solution::{
-    JsonContainer, JsonLayout, JsonLayoutStats, JsonPlacedItem, JsonSolution, JsonTransformation,
+    JsonContainer, JsonLayout, JsonLayoutSnapshot, JsonLayoutStats, JsonPlacedItem, JsonSolution,
+    JsonTransformation,
 };
 use crate::util::config::CDEConfig;
+use crate::util::fingerprint::Fingerprinter;
 use crate::util::polygon_simplification;
-use crate::util::polygon_simplification::{PolySimplConfig, PolySimplMode};
+use crate::util::polygon_simplification::{PolySimplConfig, PolySimplDirection, PolySimplMode};
 use itertools::Itertools;
 use log::{log, Level};
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::ParallelIterator;
 use rayon::prelude::IntoParallelRefIterator;
+use slotmap::SecondaryMap;
+
+/// Number of sides of the polygon circumscribing a circular item, see [Item::circle]
+pub const N_CIRCLE_ITEM_SIDES: usize = 64;
 
 /// Parses a `JsonInstance` into an `Instance`.
 pub struct Parser {
     poly_simpl_config: PolySimplConfig,
     cde_config: CDEConfig,
     center_polygons: bool,
+    winding_policy: WindingPolicy,
 }
 
 impl Parser {
@@ -52,28 +72,178 @@ impl Parser {
             poly_simpl_config,
             cde_config,
             center_polygons,
+            winding_policy: WindingPolicy::default(),
         }
     }
 
+    /// Sets how holes wound in the same direction as the outline of their shape are handled, see [WindingPolicy].
+    pub fn with_winding_policy(mut self, winding_policy: WindingPolicy) -> Self {
+        self.winding_policy = winding_policy;
+        self
+    }
+
     /// Parses a `JsonInstance` into an `Instance`.
+    /// Items and bins are parsed in parallel, including the generation of all item surrogates.
+    /// The resulting `Instance` is identical regardless of the number of threads used.
+    /// Any [ParseWarning]s are logged at their [level](ParseWarning::level), use [Parser::parse_with_warnings] to collect them instead.
+    /// Panics if the instance is invalid, see [ParseError].
     pub fn parse(&self, json_instance: &JsonInstance) -> Instance {
-        let items = json_instance
+        let (instance, warnings) = self
+            .parse_with_warnings(json_instance)
+            .unwrap_or_else(|e| panic!("[PARSE] invalid instance: {e}"));
+        for warning in warnings {
+            log!(warning.level(), "[PARSE] {}", warning);
+        }
+        instance
+    }
+
+    /// Same as [Parser::parse], but returns all modifications the parser made to the input shapes
+    /// as [ParseWarning]s instead of logging them, and returns a [ParseError] instead of panicking on invalid shapes.
+    pub fn parse_with_warnings(
+        &self,
+        json_instance: &JsonInstance,
+    ) -> Result<(Instance, Vec<ParseWarning>), ParseError> {
+        self.parse_with_surrogates(json_instance, None)
+            .map(|(instance, warnings, _)| (instance, warnings))
+    }
+
+    /// Same as [Parser::parse], but keeps the surrogates of the items in `cache_dir`, as generating them
+    /// makes up most of the parsing time.
+    /// Entries are keyed by [Parser::input_fingerprint], so parsing the same input with the same configuration again
+    /// restores the surrogates instead of generating them.
+    /// Missing or unreadable entries are (re)written, failing to do so is logged but does not affect the result.
+    /// Strips along the Y axis still generate the surrogates of their rotated items.
+    pub fn parse_cached(&self, json_instance: &JsonInstance, cache_dir: &Path) -> Instance {
+        let cache_path = cache_dir.join(format!(
+            "{:016x}.json",
+            self.input_fingerprint(json_instance)
+        ));
+        let cached_surrogates = fs::read(&cache_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Vec<SPSurrogate>>(&bytes).ok())
+            .filter(|surrogates| surrogates.len() == json_instance.items.len());
+
+        let (instance, warnings, surrogates) = self
+            .parse_with_surrogates(json_instance, cached_surrogates.as_deref())
+            .unwrap_or_else(|e| panic!("[PARSE] invalid instance: {e}"));
+        for warning in warnings {
+            log!(warning.level(), "[PARSE] {}", warning);
+        }
+
+        match cached_surrogates {
+            Some(_) => log!(
+                Level::Info,
+                "[PARSE] restored surrogates from {}",
+                cache_path.display()
+            ),
+            None => {
+                let written = fs::create_dir_all(cache_dir)
+                    .and_then(|_| serde_json::to_vec(&surrogates).map_err(io::Error::from))
+                    .and_then(|bytes| fs::write(&cache_path, bytes));
+                if let Err(e) = written {
+                    log!(
+                        Level::Warn,
+                        "[PARSE] failed to cache surrogates in {}: {e}",
+                        cache_path.display()
+                    );
+                }
+            }
+        }
+        instance
+    }
+
+    /// Stable hash of the input of the parser: the `JsonInstance`, the configuration of the parser and the version of the library.
+    /// Unlike [Instance::fingerprint], it is known before parsing, see [Parser::parse_cached].
+    pub fn input_fingerprint(&self, json_instance: &JsonInstance) -> u64 {
+        let mut fp = Fingerprinter::new();
+        fp.write_serialized(env!("CARGO_PKG_VERSION"));
+        fp.write_serialized(json_instance);
+        fp.write_serialized(&self.poly_simpl_config);
+        fp.write_serialized(&self.cde_config);
+        fp.write_u8(self.center_polygons as u8);
+        fp.finish()
+    }
+
+    /// Parses the instance, using `cached_surrogates` (one per item) for the items if provided.
+    /// Also returns the surrogates of the items, before any strip rotation.
+    fn parse_with_surrogates(
+        &self,
+        json_instance: &JsonInstance,
+        cached_surrogates: Option<&[SPSurrogate]>,
+    ) -> Result<(Instance, Vec<ParseWarning>, Vec<SPSurrogate>), ParseError> {
+        let mut warnings = vec![];
+
+        let (items, item_warnings): (Vec<(Item, usize)>, Vec<Vec<ParseWarning>>) = json_instance
             .items
             .par_iter()
             .enumerate()
-            .map(|(item_id, json_item)| self.parse_item(json_item, item_id))
-            .collect();
+            .map(|(item_id, json_item)| {
+                let mut item_warnings = vec![];
+                let item = self.parse_item(
+                    json_item,
+                    item_id,
+                    json_instance.rotation_unit,
+                    cached_surrogates.map(|surrogates| &surrogates[item_id]),
+                    &mut item_warnings,
+                )?;
+                Ok((item, item_warnings))
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?
+            .into_iter()
+            .unzip();
+        warnings.extend(item_warnings.into_iter().flatten());
+        //items without demand keep their place in the instance, so the ids of the other items do not change
+        warnings.extend(
+            json_instance
+                .items
+                .iter()
+                .positions(|json_item| json_item.demand == 0)
+                .map(|item_id| ParseWarning::ZeroDemandItem { item_id }),
+        );
+
+        let precedence = json_instance.precedence.clone();
+        validate_precedence(&precedence, items.len())?;
+        let surrogates = items
+            .iter()
+            .map(|(item, _)| item.surrogate().clone())
+            .collect_vec();
 
         let instance: Instance = match (json_instance.bins.as_ref(), json_instance.strip.as_ref()) {
             (Some(json_bins), None) => {
-                let bins: Vec<(Bin, usize)> = json_bins
+                let (bins, bin_warnings): (Vec<(Bin, usize)>, Vec<Vec<ParseWarning>>) = json_bins
                     .par_iter()
                     .enumerate()
-                    .map(|(bin_id, json_bin)| self.parse_bin(json_bin, bin_id))
-                    .collect();
-                BPInstance::new(items, bins).into()
+                    .map(|(bin_id, json_bin)| {
+                        let mut bin_warnings = vec![];
+                        let bin = self.parse_bin(json_bin, bin_id, &mut bin_warnings)?;
+                        Ok((bin, bin_warnings))
+                    })
+                    .collect::<Result<Vec<_>, ParseError>>()?
+                    .into_iter()
+                    .unzip();
+                warnings.extend(bin_warnings.into_iter().flatten());
+                BPInstance::new(items, bins)
+                    .with_precedence(precedence)
+                    .into()
+            }
+            (None, Some(json_strip)) => {
+                //strips extending along the Y axis are rotated to extend along the X axis internally
+                let axis = json_strip.axis;
+                let items = match axis {
+                    PackingAxis::X => items,
+                    PackingAxis::Y => {
+                        let rotation = Transformation::from_rotation(axis.rotation());
+                        items
+                            .into_iter()
+                            .map(|(item, qty)| (pretransform_item(&item, &rotation), qty))
+                            .collect()
+                    }
+                };
+                SPInstance::new(items, json_strip.height)
+                    .with_precedence(precedence)
+                    .with_packing_axis(axis)
+                    .into()
             }
-            (None, Some(json_strip)) => SPInstance::new(items, json_strip.height).into(),
             (Some(_), Some(_)) => {
                 panic!("Both bins and strip packing specified, has to be one or the other")
             }
@@ -104,7 +274,7 @@ impl Parser {
             }
         }
 
-        instance
+        Ok((instance, warnings, surrogates))
     }
 
     /// Parses a `JsonInstance` and accompanying `JsonLayout`s into an `Instance` and `Solution`.
@@ -120,14 +290,43 @@ impl Parser {
         (instance, solution)
     }
 
-    fn parse_item(&self, json_item: &JsonItem, item_id: usize) -> (Item, usize) {
-        let shape = match &json_item.shape {
-            JsonShape::Rectangle { width, height } => {
-                SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height))
-            }
+    fn parse_item(
+        &self,
+        json_item: &JsonItem,
+        item_id: usize,
+        rotation_unit: RotationUnit,
+        cached_surrogate: Option<&SPSurrogate>,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<(Item, usize), ParseError> {
+        let circle = match &json_item.shape {
+            JsonShape::Circle { radius } => Some(Circle::new(Point(0.0, 0.0), *radius)),
+            _ => None,
+        };
+        let (shape, original_shape) = match &json_item.shape {
+            JsonShape::Rectangle { width, height } => (
+                SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height)),
+                None,
+            ),
             JsonShape::SimplePolygon(sp) => {
-                convert_json_simple_poly(sp, self.poly_simpl_config, PolySimplMode::Inflate)
+                let shape = convert_json_simple_poly(
+                    sp,
+                    self.poly_simpl_config,
+                    PolySimplMode::Inflate,
+                    ShapeSource::Item(item_id),
+                    warnings,
+                )?;
+                //keep the contour of the input file if simplification changed it, to report placements against it
+                let original_shape = match self.poly_simpl_config {
+                    PolySimplConfig::Disabled => None,
+                    _ => Some(SimplePolygon::new(json_simple_poly_to_points(sp)))
+                        .filter(|original| original.points != shape.points),
+                };
+                (shape, original_shape)
             }
+            JsonShape::Circle { radius } => (
+                Circle::new(Point(0.0, 0.0), *radius).circumscribed_polygon(N_CIRCLE_ITEM_SIDES),
+                None,
+            ),
             JsonShape::Polygon(_) => {
                 unimplemented!("No support for polygon shapes yet")
             }
@@ -139,71 +338,147 @@ impl Parser {
         let item_value = json_item.value.unwrap_or(0);
         let base_quality = json_item.base_quality;
 
-        let allowed_orientations = match json_item.allowed_orientations.as_ref() {
-            Some(a_o) => {
+        let allowed_orientations = match (
+            json_item.allowed_orientations.as_ref(),
+            json_item.allowed_orientation_range,
+        ) {
+            (Some(_), Some(_)) => return Err(ParseError::ConflictingOrientations { item_id }),
+            (Some(a_o), None) => {
                 if a_o.is_empty() || (a_o.len() == 1 && a_o[0] == 0.0) {
                     AllowedRotation::None
                 } else {
-                    AllowedRotation::Discrete(a_o.iter().map(|angle| angle.to_radians()).collect())
+                    AllowedRotation::Discrete(
+                        a_o.iter()
+                            .map(|&angle| rotation_unit.to_radians(angle))
+                            .collect(),
+                    )
                 }
             }
-            None => AllowedRotation::Continuous,
+            (None, Some([min, max])) => match min <= max {
+                true => AllowedRotation::Range {
+                    min: rotation_unit.to_radians(min),
+                    max: rotation_unit.to_radians(max),
+                },
+                false => return Err(ParseError::InvalidOrientationRange { item_id }),
+            },
+            (None, None) => AllowedRotation::Continuous,
         };
 
-        let base_item = Item::new(
+        //express the shape relative to its anchor, so the translation of a placement is where the anchor ends up
+        //(the anchored shape acts as the original shape, the absolute transformations refer to it)
+        let Point(anchor_x, anchor_y) = match json_item.anchor {
+            None => Point(0.0, 0.0),
+            //of the contour in the input file, which simplification may have moved
+            Some(JsonAnchor::Centroid) => original_shape.as_ref().unwrap_or(&shape).centroid(),
+            Some(JsonAnchor::Vertex(vertex)) => json_shape_vertex(&json_item.shape, vertex)
+                .ok_or(ParseError::AnchorVertexOutOfRange { item_id, vertex })?,
+            Some(JsonAnchor::Point(x, y)) => Point(x, y),
+        };
+        let anchor_transform = Transformation::from_translation((-anchor_x, -anchor_y));
+        let shape = shape.transform_clone(&anchor_transform);
+        let original_shape = original_shape.map(|s| s.transform_clone(&anchor_transform));
+        let circle = circle.map(|c| c.transform_clone(&anchor_transform));
+
+        //center the shape before creating the item, so its surrogate only has to be generated once
+        let (shape, pretransform) = match self.center_polygons {
+            false => (shape, Transformation::empty()),
+            true => {
+                let centering_transform = centering_transformation(&shape).compose();
+                (
+                    shape.transform_clone(&centering_transform),
+                    centering_transform,
+                )
+            }
+        };
+
+        let circle = circle.map(|c| c.transform_clone(&pretransform));
+
+        //a surrogate restored from the cache was generated for exactly this shape
+        let mut shape = shape;
+        let new_item = match cached_surrogate {
+            Some(surrogate) => {
+                shape.surrogate = Some(surrogate.clone());
+                Item::new_with_surrogate
+            }
+            None => Item::new,
+        };
+        let item = new_item(
             item_id,
             shape,
             allowed_orientations,
             base_quality,
             item_value,
-            Transformation::empty(),
+            pretransform,
             self.cde_config.item_surrogate_config,
-        );
+        )
+        .with_position_lattice(json_item.position_lattice)
+        .with_min_edge_contact(json_item.min_edge_contact)
+        .with_meta(json_item.meta.clone())
+        .with_original_shape(original_shape)
+        .with_circle(circle);
 
-        let item = match self.center_polygons {
-            false => base_item,
-            true => {
-                let centering_transform = centering_transformation(&base_item.shape);
-                pretransform_item(&base_item, &centering_transform.compose())
-            }
-        };
-
-        (item, json_item.demand as usize)
+        Ok((item, json_item.demand as usize))
     }
 
-    fn parse_bin(&self, json_bin: &JsonBin, bin_id: usize) -> (Bin, usize) {
+    fn parse_bin(
+        &self,
+        json_bin: &JsonBin,
+        bin_id: usize,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<(Bin, usize), ParseError> {
         let bin_outer = match &json_bin.shape {
             JsonShape::Rectangle { width, height } => {
                 SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height))
             }
-            JsonShape::SimplePolygon(jsp) => {
-                convert_json_simple_poly(jsp, self.poly_simpl_config, PolySimplMode::Deflate)
-            }
-            JsonShape::Polygon(jp) => {
-                convert_json_simple_poly(&jp.outer, self.poly_simpl_config, PolySimplMode::Deflate)
-            }
+            JsonShape::SimplePolygon(jsp) => convert_json_simple_poly(
+                jsp,
+                self.poly_simpl_config,
+                PolySimplMode::Deflate,
+                ShapeSource::Bin(bin_id),
+                warnings,
+            )?,
+            JsonShape::Polygon(jp) => convert_json_simple_poly(
+                &jp.outer,
+                self.poly_simpl_config,
+                PolySimplMode::Deflate,
+                ShapeSource::Bin(bin_id),
+                warnings,
+            )?,
             JsonShape::MultiPolygon(_) => {
                 unimplemented!("No support for multipolygon shapes yet")
             }
+            JsonShape::Circle { .. } => {
+                unimplemented!("No support for circular bins yet")
+            }
         };
 
         let bin_holes = match &json_bin.shape {
-            JsonShape::SimplePolygon(_) | JsonShape::Rectangle { .. } => vec![],
+            JsonShape::SimplePolygon(_)
+            | JsonShape::Rectangle { .. }
+            | JsonShape::Circle { .. } => {
+                vec![]
+            }
             JsonShape::Polygon(jp) => jp
                 .inner
                 .iter()
-                .map(|jsp| {
-                    convert_json_simple_poly(jsp, self.poly_simpl_config, PolySimplMode::Inflate)
+                .enumerate()
+                .map(|(hole_idx, jsp)| {
+                    let source = ShapeSource::BinHole { bin_id, hole_idx };
+                    check_hole_winding(&jp.outer, jsp, source, self.winding_policy, warnings)?;
+                    convert_json_simple_poly(
+                        jsp,
+                        self.poly_simpl_config,
+                        PolySimplMode::Inflate,
+                        source,
+                        warnings,
+                    )
                 })
-                .collect_vec(),
+                .collect::<Result<Vec<_>, ParseError>>()?,
             JsonShape::MultiPolygon(_) => {
                 unimplemented!("No support for multipolygon shapes yet")
             }
         };
 
-        let material_value =
-            (bin_outer.area() - bin_holes.iter().map(|hole| hole.area()).sum::<fsize>()) as u64;
-
         assert!(
             json_bin.zones.iter().all(|zone| zone.quality < N_QUALITIES),
             "Quality must be less than N_QUALITIES"
@@ -216,13 +491,15 @@ impl Parser {
                     .iter()
                     .filter(|zone| zone.quality == quality)
                     .map(|zone| match &zone.shape {
-                        JsonShape::Rectangle { width, height } => {
-                            SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height))
-                        }
+                        JsonShape::Rectangle { width, height } => Ok(SimplePolygon::from(
+                            AARectangle::new(0.0, 0.0, *width, *height),
+                        )),
                         JsonShape::SimplePolygon(jsp) => convert_json_simple_poly(
                             jsp,
                             self.poly_simpl_config,
                             PolySimplMode::Inflate,
+                            ShapeSource::QualityZone { bin_id, quality },
+                            warnings,
                         ),
                         JsonShape::Polygon(_) => {
                             unimplemented!("No support for polygon to simplepolygon conversion yet")
@@ -230,16 +507,26 @@ impl Parser {
                         JsonShape::MultiPolygon(_) => {
                             unimplemented!("No support for multipolygon shapes yet")
                         }
+                        JsonShape::Circle { .. } => {
+                            unimplemented!("No support for circular quality zones yet")
+                        }
                     })
-                    .collect_vec();
-                InferiorQualityZone::new(quality, zones)
+                    .collect::<Result<Vec<_>, ParseError>>()?;
+                if zones.len() > 1 {
+                    warnings.push(ParseWarning::MergedQualityZones {
+                        bin_id,
+                        quality,
+                        n_zones: zones.len(),
+                    });
+                }
+                Ok(InferiorQualityZone::new(quality, zones))
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, ParseError>>()?;
 
         let base_bin = Bin::new(
             bin_id,
             bin_outer,
-            material_value,
+            json_bin.cost,
             Transformation::empty(),
             bin_holes,
             quality_zones,
@@ -256,7 +543,7 @@ impl Parser {
 
         let stock = json_bin.stock.unwrap_or(u64::MAX) as usize;
 
-        (bin, stock)
+        Ok((bin, stock))
     }
 }
 
@@ -284,8 +571,12 @@ pub fn build_strip_packing_solution(
         JsonContainer::Bin { .. } => {
             panic!("Strip packing solution should not contain layouts with references to an Object")
         }
-        JsonContainer::Strip { width, height: _ } => {
-            SPProblem::new(instance.clone(), width, cde_config)
+        JsonContainer::Strip { width, height } => {
+            let length = match instance.packing_axis {
+                PackingAxis::X => width,
+                PackingAxis::Y => height,
+            };
+            SPProblem::new(instance.clone(), length, cde_config)
         }
     };
 
@@ -307,6 +598,7 @@ pub fn build_strip_packing_solution(
             layout_idx: STRIP_LAYOUT_IDX,
             item_id: item.id,
             d_transf,
+            subtract: json_item.subtract,
         };
 
         problem.place_item(placing_opt);
@@ -356,6 +648,7 @@ pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLay
             layout_idx: LayoutIndex::Template(template_index),
             item_id: first_item.id,
             d_transf,
+            subtract: json_first_item.subtract,
         };
         let (layout_idx, _) = problem.place_item(initial_insert_opt);
         problem.flush_changes();
@@ -379,6 +672,7 @@ pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLay
                 layout_idx,
                 item_id: item.id,
                 d_transf,
+                subtract: json_item.subtract,
             };
             problem.place_item(insert_opt);
             problem.flush_changes();
@@ -389,10 +683,14 @@ pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLay
 }
 
 /// Composes a `JsonSolution` from a `Solution` and an `Instance`.
+/// If an `output_grid` is given, the translations of the placements are snapped to multiples of it, see [`snap_layout_to_grid`].
+/// If `verbose`, the internal state of every layout is included as well, see [`JsonLayoutSnapshot`].
 pub fn compose_json_solution(
     solution: &Solution,
     instance: &Instance,
     epoch: Instant,
+    output_grid: Option<fsize>,
+    verbose: bool,
 ) -> JsonSolution {
     let layouts = solution
         .layout_snapshots
@@ -400,25 +698,54 @@ pub fn compose_json_solution(
         .map(|sl| {
             let container = match &instance {
                 Instance::BP(_bpi) => JsonContainer::Bin { index: sl.bin.id },
-                Instance::SP(spi) => JsonContainer::Strip {
-                    width: sl.bin.bbox().width(),
-                    height: spi.strip_height,
+                Instance::SP(spi) => match spi.packing_axis {
+                    PackingAxis::X => JsonContainer::Strip {
+                        width: sl.bin.bbox().width(),
+                        height: spi.strip_height,
+                    },
+                    PackingAxis::Y => JsonContainer::Strip {
+                        width: spi.strip_height,
+                        height: sl.bin.bbox().width(),
+                    },
                 },
             };
 
-            let placed_items = sl
-                .placed_items
-                .values()
-                .map(|placed_item| {
+            let snapped_transfs = output_grid.map(|grid| snap_layout_to_grid(sl, instance, grid));
+
+            //items are listed in an order in which they can be placed back
+            let placed_items = layout::placement_order(&sl.placed_items)
+                .map(|(pik, placed_item)| {
                     let item_index = placed_item.item_id;
                     let item = instance.item(item_index);
 
-                    let abs_transf = internal_to_absolute_transform(
-                        &placed_item.d_transf,
-                        &item.pretransform,
-                        &sl.bin.pretransform,
-                    )
-                    .decompose();
+                    let abs_transf = match snapped_transfs.as_ref() {
+                        Some(snapped_transfs) => snapped_transfs[pik],
+                        None => internal_to_absolute_transform(
+                            &placed_item.d_transf,
+                            &item.pretransform,
+                            &sl.bin.pretransform,
+                        )
+                        .decompose(),
+                    };
+
+                    //for simplified items, both the collision shape and the original contour are reported in absolute coordinates
+                    let (collision_shape, original_shape) = match &item.original_shape {
+                        None => (None, None),
+                        Some(original_shape) => {
+                            let abs_transf = abs_transf.compose();
+                            let collision_transf = Transformation::empty()
+                                .transform(&item.pretransform.clone().inverse())
+                                .transform(&abs_transf);
+                            (
+                                Some(simple_poly_to_json(
+                                    &item.shape.transform_clone(&collision_transf),
+                                )),
+                                Some(simple_poly_to_json(
+                                    &original_shape.transform_clone(&abs_transf),
+                                )),
+                            )
+                        }
+                    };
 
                     JsonPlacedItem {
                         index: item_index,
@@ -426,14 +753,20 @@ pub fn compose_json_solution(
                             rotation: abs_transf.rotation(),
                             translation: abs_transf.translation(),
                         },
+                        subtract: placed_item.subtract,
+                        meta: item.meta.clone(),
+                        collision_shape,
+                        original_shape,
                     }
                 })
                 .collect::<Vec<JsonPlacedItem>>();
             let statistics = JsonLayoutStats { usage: sl.usage };
+            let snapshot = verbose.then(|| compose_json_layout_snapshot(sl));
             JsonLayout {
                 container,
                 placed_items,
                 statistics,
+                snapshot,
             }
         })
         .collect::<Vec<JsonLayout>>();
@@ -445,21 +778,427 @@ pub fn compose_json_solution(
     }
 }
 
+fn compose_json_layout_snapshot(sl: &LayoutSnapshot) -> JsonLayoutSnapshot {
+    let placed_items = layout::placement_order(&sl.placed_items)
+        .map(|(_, pi)| JsonPlacedItem {
+            index: pi.item_id,
+            transformation: JsonTransformation {
+                rotation: pi.d_transf.rotation(),
+                translation: pi.d_transf.translation(),
+            },
+            subtract: pi.subtract,
+            meta: None,
+            collision_shape: None,
+            original_shape: None,
+        })
+        .collect_vec();
+    JsonLayoutSnapshot {
+        id: sl.id,
+        bin_id: sl.bin.id,
+        usage: sl.usage,
+        placed_items,
+    }
+}
+
+/// Returns the absolute transformations (in the coordinates of the input file) of all placements in the layout,
+/// with their translations snapped to multiples of `grid`. Rotations are left untouched.
+/// Each placement is moved to the closest corner of its grid cell at which it does not collide with the already snapped placements.
+/// Placements for which no such corner exists, and all placements in layouts with cutouts, keep their exact translation.
+pub fn snap_layout_to_grid(
+    sl: &LayoutSnapshot,
+    instance: &Instance,
+    grid: fsize,
+) -> SecondaryMap<PItemKey, DTransformation> {
+    assert!(grid > 0.0, "output grid should be positive");
+    let abs_transf = |pi: &PlacedItem| {
+        internal_to_absolute_transform(
+            &pi.d_transf,
+            &instance.item(pi.item_id).pretransform,
+            &sl.bin.pretransform,
+        )
+        .decompose()
+    };
+
+    if sl.placed_items.values().any(|pi| pi.subtract) {
+        log!(
+            Level::Warn,
+            "layout {} contains cutouts, its placements are not snapped to the output grid",
+            sl.id
+        );
+        return sl
+            .placed_items
+            .iter()
+            .map(|(pik, pi)| (pik, abs_transf(pi)))
+            .collect();
+    }
+
+    //multiples of the grid, computed by dividing by its inverse if it is integral, to avoid needless digits (e.g. 0.01 * 3 = 0.030000001)
+    let inv_grid = (1.0 / grid).round();
+    let grid_multiple = |n: fsize| match (inv_grid * grid - 1.0).abs() < 1e-6 {
+        true => n / inv_grid,
+        false => n * grid,
+    };
+
+    let mut layout = Layout::from_snapshot(sl);
+    let mut snapped = SecondaryMap::new();
+    for (pik, pi) in sl.placed_items.iter() {
+        let item = instance.item(pi.item_id);
+        let exact = abs_transf(pi);
+        let (x, y) = exact.translation();
+        let (n_x, n_y) = ((x / grid).floor(), (y / grid).floor());
+        let corners = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+            .map(|(dx, dy)| (grid_multiple(n_x + dx), grid_multiple(n_y + dy)))
+            .into_iter()
+            .sorted_by(|a, b| {
+                let d = |(cx, cy): (fsize, fsize)| (cx - x).powi(2) + (cy - y).powi(2);
+                d(*a).total_cmp(&d(*b))
+            });
+
+        //remove the placement and put it back at the closest corner which is collision-free
+        layout.remove_item(pik, true);
+        let irrel_hazards = match item.hazard_filter.as_ref() {
+            None => vec![],
+            Some(hf) => hazard_filter::generate_irrelevant_hazards(hf, layout.cde().all_hazards()),
+        };
+        let to_internal = |abs: &DTransformation| {
+            absolute_to_internal_transform(abs, &item.pretransform, &sl.bin.pretransform)
+        };
+        let snapped_transf = corners
+            .map(|corner| DTransformation::new(exact.rotation(), corner))
+            .find(|abs| {
+                let shape = item.shape.transform_clone(&to_internal(abs));
+                !layout.cde().poly_collides(&shape, &irrel_hazards)
+            });
+
+        match snapped_transf {
+            Some(abs) => {
+                layout.place_item(item, to_internal(&abs).decompose());
+                snapped.insert(pik, abs);
+            }
+            None => {
+                log!(
+                    Level::Warn,
+                    "item {} at [{}] in layout {} cannot be snapped to the output grid without collisions",
+                    item.id,
+                    exact,
+                    sl.id
+                );
+                layout.place_item(item, pi.d_transf);
+                snapped.insert(pik, exact);
+            }
+        }
+    }
+    snapped
+}
+
+/// Checks whether `hole` is wound opposite to `outer`, handling it according to the `policy` otherwise.
+fn check_hole_winding(
+    outer: &JsonSimplePoly,
+    hole: &JsonSimplePoly,
+    source: ShapeSource,
+    policy: WindingPolicy,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<(), ParseError> {
+    let outer_area = SimplePolygon::calculate_area(&json_simple_poly_to_points(outer));
+    let hole_area = SimplePolygon::calculate_area(&json_simple_poly_to_points(hole));
+    match (outer_area.signum() == hole_area.signum(), policy) {
+        (false, _) => Ok(()),
+        (true, WindingPolicy::Lenient) => {
+            warnings.push(ParseWarning::HoleWindingNotOpposite { source });
+            Ok(())
+        }
+        (true, WindingPolicy::Strict) => Err(ParseError::HoleWindingNotOpposite { source }),
+    }
+}
+
 fn convert_json_simple_poly(
     s_json_shape: &JsonSimplePoly,
     simpl_config: PolySimplConfig,
     simpl_mode: PolySimplMode,
-) -> SimplePolygon {
-    let shape = SimplePolygon::new(json_simple_poly_to_points(s_json_shape));
+    source: ShapeSource,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<SimplePolygon, ParseError> {
+    let points = json_simple_poly_to_points(s_json_shape);
+    if !SimplePolygon::calculate_simplicity(&points) {
+        return Err(ParseError::SelfIntersectingPolygon { source });
+    }
+    if SimplePolygon::calculate_area(&points) < 0.0 {
+        warnings.push(ParseWarning::FlippedWinding { source });
+    }
+    let shape = SimplePolygon::new(points);
 
     let shape = match simpl_config {
-        PolySimplConfig::Enabled { tolerance } => {
-            polygon_simplification::simplify_shape(&shape, simpl_mode, tolerance)
+        PolySimplConfig::Enabled {
+            tolerance,
+            direction,
+        } => {
+            let simplified = match direction {
+                PolySimplDirection::OutwardOnly => {
+                    polygon_simplification::simplify_shape(&shape, simpl_mode, tolerance)
+                }
+                PolySimplDirection::InwardAllowed => {
+                    //simplify in both directions, keep the one with the fewest points
+                    [simpl_mode, simpl_mode.flip()]
+                        .map(|mode| polygon_simplification::simplify_shape(&shape, mode, tolerance))
+                        .into_iter()
+                        .min_by_key(|s| s.number_of_points())
+                        .unwrap()
+                }
+            };
+            let simplified = match direction == PolySimplDirection::OutwardOnly
+                && !polygon_simplification::simplification_is_conservative(
+                    &shape,
+                    &simplified,
+                    simpl_mode,
+                ) {
+                true => {
+                    warnings.push(ParseWarning::RejectedSimplification { source });
+                    shape.clone()
+                }
+                false => simplified,
+            };
+            if simplified.number_of_points() != shape.number_of_points() {
+                warnings.push(ParseWarning::SimplifiedPolygon {
+                    source,
+                    n_points_before: shape.number_of_points(),
+                    n_points_after: simplified.number_of_points(),
+                });
+            }
+            simplified
+        }
+        PolySimplConfig::Adaptive {
+            max_deviation,
+            target_vertices,
+        } => {
+            let simplified = polygon_simplification::simplify_shape_adaptive(
+                &shape,
+                simpl_mode,
+                max_deviation,
+                target_vertices,
+            );
+            if simplified.number_of_points() != shape.number_of_points() {
+                warnings.push(ParseWarning::SimplifiedPolygon {
+                    source,
+                    n_points_before: shape.number_of_points(),
+                    n_points_after: simplified.number_of_points(),
+                });
+            }
+            simplified
         }
         PolySimplConfig::Disabled => shape,
     };
 
-    shape
+    Ok(shape)
+}
+
+/// Checks whether all precedence constraints refer to existing items and whether they can all be satisfied,
+/// i.e. whether they contain no cycles.
+fn validate_precedence(precedence: &[(usize, usize)], n_items: usize) -> Result<(), ParseError> {
+    if let Some(&(a, b)) = precedence
+        .iter()
+        .find(|(a, b)| *a >= n_items || *b >= n_items)
+    {
+        return Err(ParseError::PrecedenceUnknownItem {
+            item_id: usize::max(a, b),
+        });
+    }
+
+    //repeatedly remove the items without any remaining predecessors, whatever remains is part of a cycle
+    let mut n_predecessors = vec![0; n_items];
+    precedence.iter().for_each(|&(_, b)| n_predecessors[b] += 1);
+    let mut free = (0..n_items)
+        .filter(|&i| n_predecessors[i] == 0)
+        .collect_vec();
+    while let Some(a) = free.pop() {
+        for &(_, b) in precedence.iter().filter(|(pa, _)| *pa == a) {
+            n_predecessors[b] -= 1;
+            if n_predecessors[b] == 0 {
+                free.push(b);
+            }
+        }
+    }
+    match n_predecessors.iter().position(|&n| n > 0) {
+        Some(item_id) => Err(ParseError::PrecedenceCycle { item_id }),
+        None => Ok(()),
+    }
+}
+
+/// Identifies the shape in a `JsonInstance` a [ParseWarning] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeSource {
+    Item(usize),
+    Bin(usize),
+    BinHole { bin_id: usize, hole_idx: usize },
+    QualityZone { bin_id: usize, quality: usize },
+}
+
+impl Display for ShapeSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeSource::Item(item_id) => write!(f, "item {item_id}"),
+            ShapeSource::Bin(bin_id) => write!(f, "bin {bin_id}"),
+            ShapeSource::BinHole { bin_id, hole_idx } => {
+                write!(f, "hole {hole_idx} of bin {bin_id}")
+            }
+            ShapeSource::QualityZone { bin_id, quality } => {
+                write!(f, "quality zone {quality} of bin {bin_id}")
+            }
+        }
+    }
+}
+
+/// How the [Parser] handles holes which are wound in the same direction as the outline of their shape,
+/// instead of in the opposite direction (e.g. a clockwise hole in a counterclockwise outline).
+/// Every polygon is normalized to a counterclockwise winding internally regardless, see [ParseWarning::FlippedWinding].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WindingPolicy {
+    /// The hole is accepted as a hole, with a [ParseWarning::HoleWindingNotOpposite]
+    #[default]
+    Lenient,
+    /// The shape is rejected with a [ParseError::HoleWindingNotOpposite], as mixed windings can indicate
+    /// an export error in which holes and solids are inverted
+    Strict,
+}
+
+/// Reason a `JsonInstance` could not be parsed by the [Parser].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The contour of the polygon intersects or touches itself, see [SimplePolygon::calculate_simplicity]
+    SelfIntersectingPolygon { source: ShapeSource },
+    /// The item defines both allowed orientations and an orientation range
+    ConflictingOrientations { item_id: usize },
+    /// The orientation range of the item has a minimum larger than its maximum
+    InvalidOrientationRange { item_id: usize },
+    /// A precedence constraint refers to an item which does not exist
+    PrecedenceUnknownItem { item_id: usize },
+    /// The precedence constraints contain a cycle (which includes the item), so they cannot all be satisfied
+    PrecedenceCycle { item_id: usize },
+    /// The anchor of the item refers to a vertex which its shape does not have
+    AnchorVertexOutOfRange { item_id: usize, vertex: usize },
+    /// The hole is wound in the same direction as the outline of its shape, see [WindingPolicy::Strict]
+    HoleWindingNotOpposite { source: ShapeSource },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::SelfIntersectingPolygon { source } => {
+                write!(
+                    f,
+                    "{source} is not a simple polygon, its contour intersects itself"
+                )
+            }
+            ParseError::ConflictingOrientations { item_id } => {
+                write!(
+                    f,
+                    "item {item_id} defines both allowed orientations and an orientation range"
+                )
+            }
+            ParseError::InvalidOrientationRange { item_id } => {
+                write!(
+                    f,
+                    "orientation range of item {item_id} has a minimum larger than its maximum"
+                )
+            }
+            ParseError::PrecedenceUnknownItem { item_id } => {
+                write!(f, "precedence constraint refers to unknown item {item_id}")
+            }
+            ParseError::PrecedenceCycle { item_id } => {
+                write!(f, "precedence constraints of item {item_id} form a cycle")
+            }
+            ParseError::AnchorVertexOutOfRange { item_id, vertex } => {
+                write!(f, "anchor of item {item_id} refers to vertex {vertex}, which its shape does not have")
+            }
+            ParseError::HoleWindingNotOpposite { source } => {
+                write!(
+                    f,
+                    "{source} is wound in the same direction as its outline, instead of the opposite one"
+                )
+            }
+        }
+    }
+}
+
+/// Modification made by the [Parser] to the shapes of a `JsonInstance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// The polygon was simplified, changing its number of points
+    SimplifiedPolygon {
+        source: ShapeSource,
+        n_points_before: usize,
+        n_points_after: usize,
+    },
+    /// The points of the polygon were ordered clockwise and have been reversed
+    FlippedWinding { source: ShapeSource },
+    /// The simplified polygon was not conservative for collision detection, the original polygon is used instead
+    RejectedSimplification { source: ShapeSource },
+    /// Multiple zones of the same quality were merged into a single quality zone
+    MergedQualityZones {
+        bin_id: usize,
+        quality: usize,
+        n_zones: usize,
+    },
+    /// The item has a demand of zero, it remains part of the instance but never has to be placed
+    ZeroDemandItem { item_id: usize },
+    /// The hole is wound in the same direction as the outline of its shape, but was kept as a hole, see [WindingPolicy::Lenient]
+    HoleWindingNotOpposite { source: ShapeSource },
+}
+
+impl ParseWarning {
+    /// Level at which [Parser::parse] logs the warning.
+    /// Only modifications which may not be what the input intended are logged as warnings.
+    pub fn level(&self) -> Level {
+        match self {
+            ParseWarning::SimplifiedPolygon { .. } => Level::Debug,
+            ParseWarning::MergedQualityZones { .. } | ParseWarning::ZeroDemandItem { .. } => {
+                Level::Info
+            }
+            ParseWarning::FlippedWinding { .. }
+            | ParseWarning::RejectedSimplification { .. }
+            | ParseWarning::HoleWindingNotOpposite { .. } => Level::Warn,
+        }
+    }
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseWarning::SimplifiedPolygon {
+                source,
+                n_points_before,
+                n_points_after,
+            } => write!(
+                f,
+                "simplified {source} from {n_points_before} to {n_points_after} points"
+            ),
+            ParseWarning::FlippedWinding { source } => {
+                write!(f, "{source} is ordered clockwise, reversed its points")
+            }
+            ParseWarning::RejectedSimplification { source } => {
+                write!(
+                    f,
+                    "simplification of {source} is not conservative, kept the original polygon"
+                )
+            }
+            ParseWarning::MergedQualityZones {
+                bin_id,
+                quality,
+                n_zones,
+            } => write!(
+                f,
+                "merged {n_zones} zones into quality zone {quality} of bin {bin_id}"
+            ),
+            ParseWarning::ZeroDemandItem { item_id } => {
+                write!(f, "item {item_id} has a demand of zero")
+            }
+            ParseWarning::HoleWindingNotOpposite { source } => {
+                write!(
+                    f,
+                    "{source} is wound in the same direction as its outline, kept it as a hole"
+                )
+            }
+        }
+    }
 }
 
 fn json_simple_poly_to_points(jsp: &JsonSimplePoly) -> Vec<Point> {
@@ -472,6 +1211,10 @@ fn json_simple_poly_to_points(jsp: &JsonSimplePoly) -> Vec<Point> {
     (0..n_vertices).map(|i| Point::from(jsp.0[i])).collect_vec()
 }
 
+fn simple_poly_to_json(shape: &SimplePolygon) -> JsonSimplePoly {
+    JsonSimplePoly(shape.points.iter().map(|&p| p.into()).collect())
+}
+
 pub fn internal_to_absolute_transform(
     placed_item_transf: &DTransformation,
     item_pretransf: &Transformation,
@@ -537,6 +1280,7 @@ pub fn pretransform_bin(bin: &Bin, extra_pretransf: &Transformation) -> Bin {
             .collect(),
         bin.base_cde.config(),
     )
+    .with_inset(bin.inset)
 }
 
 pub fn pretransform_item(item: &Item, extra_pretransf: &Transformation) -> Item {
@@ -548,6 +1292,11 @@ pub fn pretransform_item(item: &Item, extra_pretransf: &Transformation) -> Item
         value,
         pretransform,
         surrogate_config,
+        position_lattice,
+        min_edge_contact,
+        meta,
+        circle,
+        original_shape,
         ..
     } = item;
 
@@ -560,6 +1309,29 @@ pub fn pretransform_item(item: &Item, extra_pretransf: &Transformation) -> Item
         pretransform.clone().transform(extra_pretransf),
         *surrogate_config,
     )
+    .with_position_lattice(*position_lattice)
+    .with_min_edge_contact(*min_edge_contact)
+    .with_meta(meta.clone())
+    .with_circle(circle.as_ref().map(|c| c.transform_clone(extra_pretransf)))
+    //the original shape is not pretransformed, it stays as is
+    .with_original_shape(original_shape.as_deref().cloned())
+}
+
+/// Returns vertex `index` of the outer boundary of a JSON shape, as defined in the JSON (before any simplification).
+/// Rectangles start at their bottom-left corner and run counterclockwise.
+fn json_shape_vertex(shape: &JsonShape, index: usize) -> Option<Point> {
+    match shape {
+        JsonShape::Rectangle { width, height } => {
+            [(0.0, 0.0), (*width, 0.0), (*width, *height), (0.0, *height)]
+                .get(index)
+                .map(|&(x, y)| Point(x, y))
+        }
+        JsonShape::SimplePolygon(JsonSimplePoly(points)) => {
+            points.get(index).map(|&(x, y)| Point(x, y))
+        }
+        JsonShape::Polygon(poly) => poly.outer.0.get(index).map(|&(x, y)| Point(x, y)),
+        JsonShape::MultiPolygon(_) | JsonShape::Circle { .. } => None,
+    }
 }
 
 pub fn centering_transformation(shape: &SimplePolygon) -> DTransformation {
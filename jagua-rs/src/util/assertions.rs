@@ -6,8 +6,6 @@ use crate::collision_detection::cd_engine::CDEngine;
 use crate::collision_detection::hazard::Hazard;
 use crate::collision_detection::hazard::HazardEntity;
 use crate::collision_detection::hazard_filter;
-use crate::collision_detection::hazard_filter::CombinedHazardFilter;
-use crate::collision_detection::hazard_filter::EntityHazardFilter;
 use crate::collision_detection::hpg::hazard_proximity_grid::HazardProximityGrid;
 use crate::collision_detection::hpg::hpg_cell::HPGCellUpdate;
 use crate::collision_detection::quadtree::qt_hazard::QTHazPresence;
@@ -19,7 +17,7 @@ use crate::entities::layout::Layout;
 use crate::entities::layout::LayoutSnapshot;
 use crate::entities::problems::problem_generic::ProblemGeneric;
 use crate::entities::solution::Solution;
-use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::geo_traits::Shape;
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::transformation::Transformation;
 use crate::util;
@@ -102,43 +100,22 @@ pub fn item_to_place_does_not_collide(
 ) -> bool {
     let haz_filter = &item.hazard_filter;
 
-    let shape = item.shape.as_ref();
-    let t_shape = shape.transform_clone(transformation);
+    let mut buffer = item.shape.as_ref().clone();
 
     let entities_to_ignore = haz_filter.as_ref().map_or(vec![], |f| {
         hazard_filter::generate_irrelevant_hazards(f, layout.cde().all_hazards())
     });
 
-    if layout
+    !layout
         .cde()
-        .surrogate_collides(shape.surrogate(), transformation, &entities_to_ignore)
-        || layout.cde().poly_collides(&t_shape, &entities_to_ignore)
-    {
-        return false;
-    }
-    true
+        .item_collides(item, transformation, &mut buffer, &entities_to_ignore)
 }
 
 pub fn layout_is_collision_free(layout: &Layout) -> bool {
-    for (_, pi) in layout.placed_items().iter() {
-        let ehf = EntityHazardFilter(vec![pi.into()]);
-
-        let combo_filter = match &pi.hazard_filter {
-            None => CombinedHazardFilter {
-                filters: vec![Box::new(&ehf)],
-            },
-            Some(hf) => CombinedHazardFilter {
-                filters: vec![Box::new(&ehf), Box::new(hf)],
-            },
-        };
-        let entities_to_ignore =
-            hazard_filter::generate_irrelevant_hazards(&combo_filter, layout.cde().all_hazards());
-
-        if layout.cde().poly_collides(&pi.shape, &entities_to_ignore) {
-            println!("Collision detected for item {:.?}", pi.item_id);
-            util::print_layout(layout);
-            return false;
-        }
+    if !layout.is_collision_free() {
+        println!("Collision detected in layout {}", layout.id());
+        util::print_layout(layout);
+        return false;
     }
     true
 }
@@ -256,7 +233,7 @@ pub fn layout_qt_matches_fresh_qt(layout: &Layout) -> bool {
     //rebuild the quadtree
     let bin = &layout.bin;
     let mut fresh_cde = bin.base_cde.as_ref().clone();
-    for (_, pi) in layout.placed_items().iter() {
+    for (_, pi) in layout.placed_items().iter().filter(|(_, pi)| !pi.subtract) {
         let hazard = Hazard::new(pi.into(), pi.shape.clone());
         fresh_cde.register_hazard(hazard);
     }
@@ -437,6 +414,27 @@ pub fn hpg_update_no_affected_cells_remain(
     }
 }
 
+pub fn hpg_matches_full_rebuild<'a, I>(hpg: &HazardProximityGrid, remaining: I) -> bool
+where
+    I: Iterator<Item = &'a Hazard> + Clone,
+{
+    //Every cell should have the same proximity as if it was evaluated against all remaining hazards from scratch
+    hpg.grid.cells.iter().flatten().all(|cell| {
+        let mut rebuilt = cell.clone();
+        rebuilt.uni_prox = rebuilt.static_uni_prox;
+        rebuilt.register_hazards(remaining.clone());
+        if rebuilt.uni_prox.0 != cell.uni_prox.0 {
+            println!(
+                "cell at {:?} incremental {:?}, rebuilt {:?}",
+                cell.centroid, cell.uni_prox, rebuilt.uni_prox
+            );
+            false
+        } else {
+            true
+        }
+    })
+}
+
 /// Checks if the quadrants follow the layout set in [AARectangle::QUADRANT_NEIGHBOR_LAYOUT]
 pub fn quadrants_have_valid_layout(quadrants: &[&AARectangle; 4]) -> bool {
     let layout = AARectangle::QUADRANT_NEIGHBOR_LAYOUT;
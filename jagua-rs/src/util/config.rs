@@ -1,9 +1,13 @@
-use serde::{Deserialize, Serialize};
+use almost::AlmostEqual;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::fsize;
 
-///Configuration of the Collision Detection Engine
+///Configuration of the Collision Detection Engine.
+///Missing fields are deserialized from the [default](CdePreset::Balanced) preset.
+///See [`deserialize_preset_or_fields`] to refer to a preset by name instead (e.g. `"cde_config": "Fast"`)
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
 pub struct CDEConfig {
     ///Maximum depth of the quadtree
     pub quadtree_depth: u8,
@@ -11,6 +15,157 @@ pub struct CDEConfig {
     pub hpg_n_cells: usize,
     ///Configuration of the surrogate generation for items
     pub item_surrogate_config: SPSurrogateConfig,
+    ///Verify every placement for collisions, and panic if a colliding placement slips through (also in release builds).
+    ///Disallowed rotations are always rejected, regardless of this setting
+    pub verify_placements: bool,
+    ///Tolerance for floating point comparisons in containment checks and when fitting the strip
+    pub geo_tolerance: GeoTolerance,
+    ///Minimum number of fail-fast poles in a surrogate to check them for collisions in parallel, `None` to always check serially.
+    ///Only pays off for surrogates with many poles, for few poles the overhead of parallelization dominates.
+    pub parallel_pole_threshold: Option<usize>,
+    ///Test the bounding circle of the surrogate's poles before the poles themselves, skipping them if the circle is clear of hazards.
+    ///Pays off in sparse layouts, in dense layouts the circle almost always collides and the extra query is wasted.
+    pub bounding_circle_broad_phase: bool,
+    ///Treat the exterior of the bin as a soft boundary: items are allowed to poke outside the bin without being rejected as colliding.
+    ///The extent of the violation can be measured with [`CDEngine::boundary_violation`](crate::collision_detection::cd_engine::CDEngine::boundary_violation).
+    pub soft_bin_boundary: bool,
+    ///Allow shapes to touch: contact within the geometric tolerance of the queried shape is not treated as a collision.
+    ///By default, shapes sharing (part of) an edge or a vertex collide.
+    pub touching_allowed: bool,
+}
+
+impl CDEConfig {
+    ///Creates a configuration in which all fields are set coherently for the trade-off of the `preset`
+    pub fn preset(preset: CdePreset) -> Self {
+        let (quadtree_depth, hpg_n_cells, item_surrogate_config) = match preset {
+            CdePreset::Fast => (
+                4,
+                1000,
+                SPSurrogateConfig {
+                    pole_coverage_goal: 0.8,
+                    max_poles: 5,
+                    n_ff_poles: 1,
+                    n_ff_piers: 0,
+                },
+            ),
+            CdePreset::Balanced => (
+                5,
+                2000,
+                SPSurrogateConfig {
+                    pole_coverage_goal: 0.9,
+                    max_poles: 10,
+                    n_ff_poles: 2,
+                    n_ff_piers: 0,
+                },
+            ),
+            CdePreset::Accurate => (
+                7,
+                4000,
+                SPSurrogateConfig {
+                    pole_coverage_goal: 0.95,
+                    max_poles: 20,
+                    n_ff_poles: 4,
+                    n_ff_piers: 2,
+                },
+            ),
+        };
+        Self {
+            quadtree_depth,
+            hpg_n_cells,
+            item_surrogate_config,
+            verify_placements: false,
+            geo_tolerance: GeoTolerance::DEFAULT,
+            parallel_pole_threshold: None,
+            bounding_circle_broad_phase: false,
+            soft_bin_boundary: false,
+            touching_allowed: false,
+        }
+    }
+}
+
+impl From<CdePreset> for CDEConfig {
+    fn from(preset: CdePreset) -> Self {
+        Self::preset(preset)
+    }
+}
+
+impl Default for CDEConfig {
+    fn default() -> Self {
+        Self::preset(CdePreset::default())
+    }
+}
+
+///Deserializes a [`CDEConfig`] either from the name of a [`CdePreset`] or from its fields.
+///Use with `#[serde(deserialize_with = "...")]` on fields holding a configuration.
+pub fn deserialize_preset_or_fields<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<CDEConfig, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Source {
+        Preset(CdePreset),
+        Fields(CDEConfig),
+    }
+    match Source::deserialize(deserializer)? {
+        Source::Preset(preset) => Ok(CDEConfig::preset(preset)),
+        Source::Fields(config) => Ok(config),
+    }
+}
+
+///Predefined trade-offs between speed and precision of the Collision Detection Engine
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CdePreset {
+    ///Shallow quadtree, coarse hazard proximity grid and few poles: fastest, but collisions are detected late
+    Fast,
+    ///Default trade-off between speed and precision
+    #[default]
+    Balanced,
+    ///Deep quadtree, fine hazard proximity grid and many poles: slowest, but collisions are detected early
+    Accurate,
+}
+
+///Tolerance used to compare coordinates in geometric checks.
+///Two values are considered equal if they lie within `abs` of each other or are relatively equal within `rel`.
+///Scale `abs` along with the instance to get the same behavior at every scale.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GeoTolerance {
+    ///Absolute tolerance, in the units of the instance
+    pub abs: fsize,
+    ///Relative tolerance, as a fraction of the magnitude of the compared values
+    pub rel: fsize,
+}
+
+impl GeoTolerance {
+    ///Same tolerance as [FPA](crate::util::fpa::FPA): purely relative
+    pub const DEFAULT: GeoTolerance = GeoTolerance {
+        abs: 0.0,
+        rel: <fsize as AlmostEqual>::DEFAULT_TOLERANCE,
+    };
+
+    pub fn eq(&self, a: fsize, b: fsize) -> bool {
+        (a - b).abs() <= self.abs || a.almost_equals_with(b, self.rel)
+    }
+
+    ///`a <= b`, within tolerance
+    pub fn le(&self, a: fsize, b: fsize) -> bool {
+        a < b || self.eq(a, b)
+    }
+
+    ///`a >= b`, within tolerance
+    pub fn ge(&self, a: fsize, b: fsize) -> bool {
+        a > b || self.eq(a, b)
+    }
+
+    ///Largest deviation from `value` still considered equal to it
+    pub fn margin(&self, value: fsize) -> fsize {
+        fsize::max(self.abs, value.abs() * self.rel)
+    }
+}
+
+impl Default for GeoTolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
@@ -0,0 +1,76 @@
+use std::hash::Hasher;
+
+use serde::Serialize;
+
+use crate::fsize;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::transformation::Transformation;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+///64-bit FNV-1a hasher with a stable output.
+///Unlike [`std::collections::hash_map::DefaultHasher`], the result does not depend on the run, the platform or the Rust version,
+///so fingerprints can be persisted, for example as keys of an on-disk cache.
+#[derive(Debug, Clone, Copy)]
+pub struct Fingerprinter(u64);
+
+impl Fingerprinter {
+    pub fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    /// Floats are hashed by their bit pattern, so `0.0` and `-0.0` are different
+    pub fn write_fsize(&mut self, value: fsize) {
+        self.write_u64(value.to_bits() as u64);
+    }
+
+    pub fn write_polygon(&mut self, shape: &SimplePolygon) {
+        self.write_usize(shape.number_of_points());
+        for p in shape.points.iter() {
+            self.write_fsize(p.0);
+            self.write_fsize(p.1);
+        }
+    }
+
+    pub fn write_transformation(&mut self, transformation: &Transformation) {
+        for value in transformation.matrix().iter().flatten() {
+            self.write_fsize(value.into_inner());
+        }
+    }
+
+    /// Hashes the JSON representation of `value`, intended for configurations and metadata
+    pub fn write_serialized<T: Serialize + ?Sized>(&mut self, value: &T) {
+        let bytes = serde_json::to_vec(value).expect("failed to serialize value to fingerprint");
+        self.write_usize(bytes.len());
+        self.write(&bytes);
+    }
+}
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Fingerprinter {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    //integers are hashed in little endian and usizes as u64, to be independent of the platform
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
@@ -6,6 +6,9 @@ pub mod assertions;
 /// Configuration options for the library
 pub mod config;
 
+/// Stable hashing of instances and configurations
+pub mod fingerprint;
+
 pub mod fpa;
 
 /// Functions to simplify polygons in preprocessing
@@ -6,10 +6,11 @@ use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
 
 use crate::fsize;
-use crate::geometry::geo_traits::{CollidesWith, Shape};
+use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_traits::{CollidesWith, DistanceFrom, Shape};
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
-use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::primitives::simple_polygon::{cross, SimplePolygon};
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 #[serde(tag = "mode", content = "params")]
@@ -20,7 +21,33 @@ pub enum PolySimplConfig {
     Enabled {
         /// max deviation from the original polygon area as a fraction of the original area
         tolerance: fsize,
+        /// whether shapes are only allowed to be simplified in the direction which keeps collision detection safe
+        #[serde(default)]
+        direction: PolySimplDirection,
     },
+    /// Tolerances are chosen per shape, see [`simplify_shape_adaptive`]
+    #[serde(rename = "adaptive")]
+    Adaptive {
+        /// max deviation from the original polygon area as a fraction of the original area
+        max_deviation: fsize,
+        /// number of vertices at which no coarser tolerance is tried
+        target_vertices: usize,
+    },
+}
+
+/// Number of tolerances tried by [`simplify_shape_adaptive`], each one double the previous, ending at the max deviation
+const ADAPTIVE_TOLERANCE_STEPS: i32 = 6;
+
+/// Directions in which a shape can be simplified, relative to the direction which keeps collision detection safe.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum PolySimplDirection {
+    /// Shapes are only simplified in the safe direction (see [`PolySimplMode`]): items and holes grow, bins shrink.
+    /// A simplified shape which does not contain the original (or is not contained by it, for bins) is rejected.
+    #[default]
+    OutwardOnly,
+    /// Shapes are simplified in whichever direction removes the most points.
+    /// Simplified items can then be smaller than the real ones, allowing placements where the real items collide.
+    InwardAllowed,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -40,6 +67,54 @@ impl PolySimplMode {
     }
 }
 
+/// Checks whether `simplified` is a conservative simplification of `original` for the given `mode`:
+/// it should contain the original shape when inflating and be contained by it when deflating.
+/// All vertices and edge midpoints of the inner shape have to lie within the outer one, up to a small tolerance relative to its diameter,
+/// and no edge of the inner shape may cross an edge of the outer one.
+pub fn simplification_is_conservative(
+    original: &SimplePolygon,
+    simplified: &SimplePolygon,
+    mode: PolySimplMode,
+) -> bool {
+    let (outer, inner) = match mode {
+        PolySimplMode::Inflate => (simplified, original),
+        PolySimplMode::Deflate => (original, simplified),
+    };
+    let tolerance = outer.diameter() * 1e-4;
+
+    let points_inside = edge_iter(&inner.points)
+        .flat_map(|e| [e.start, e.centroid()])
+        .all(|p| match outer.distance_from_border(&p) {
+            (GeoPosition::Interior, _) => true,
+            (GeoPosition::Exterior, dist) => dist <= tolerance,
+        });
+
+    //an inner edge can leave and re-enter the outer shape between the points checked above
+    points_inside
+        && edge_iter(&inner.points).all(|i_edge| {
+            edge_iter(&outer.points).all(|o_edge| !edges_cross(&i_edge, &o_edge, tolerance))
+        })
+}
+
+/// Whether the edges properly intersect: the endpoints of each edge lie on opposite sides of the other,
+/// further than `tolerance` from it. Touching or (nearly) collinear edges do not cross.
+fn edges_cross(a: &Edge, b: &Edge, tolerance: fsize) -> bool {
+    if a.x_max() < b.x_min()
+        || b.x_max() < a.x_min()
+        || a.y_max() < b.y_min()
+        || b.y_max() < a.y_min()
+    {
+        return false;
+    }
+    //signed distance of `p` to the line through `e`
+    let side = |e: &Edge, p: Point| cross(e.start, e.end, p) / e.diameter();
+    let straddles = |e: &Edge, p1: Point, p2: Point| {
+        let (d1, d2) = (side(e, p1), side(e, p2));
+        d1.abs() > tolerance && d2.abs() > tolerance && d1.signum() != d2.signum()
+    };
+    straddles(b, a.start, a.end) && straddles(a, b.start, b.end)
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum Candidate {
     Concave(Corner),
@@ -187,6 +262,31 @@ pub fn simplify_shape(
     simpl_shape
 }
 
+/// Simplifies a shape at increasingly coarse tolerances, up to `max_deviation`, and keeps the coarsest result which is still
+/// a conservative simplification of the original (see [`simplification_is_conservative`]).
+/// Coarsening stops as soon as the shape has no more than `target_vertices` vertices,
+/// so shapes with little detail are left (nearly) untouched, while detailed ones are simplified further.
+pub fn simplify_shape_adaptive(
+    shape: &SimplePolygon,
+    mode: PolySimplMode,
+    max_deviation: fsize,
+    target_vertices: usize,
+) -> SimplePolygon {
+    let mut best = shape.clone();
+    for step in (0..ADAPTIVE_TOLERANCE_STEPS).rev() {
+        if best.number_of_points() <= target_vertices {
+            break;
+        }
+        let tolerance = max_deviation / (2.0 as fsize).powi(step);
+        let simplified = simplify_shape(shape, mode, tolerance);
+        if !simplification_is_conservative(shape, &simplified, mode) {
+            break;
+        }
+        best = simplified;
+    }
+    best
+}
+
 fn calculate_area_delta(
     shape: &[Point],
     candidate: &Candidate,
@@ -0,0 +1,98 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::Itertools;
+use rand::SeedableRng;
+
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::placing_option::PlacingOption;
+use jagua_rs::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
+use jagua_rs::entities::problems::strip_packing::SPProblem;
+use jagua_rs::io::json_instance::JsonInstance;
+use lbf::rng::LBFRng;
+use lbf::samplers::uniform_rect_sampler::UniformAARectSampler;
+
+use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
+
+criterion_main!(benches);
+criterion_group!(benches, bounding_circle_bench);
+
+mod util;
+
+const SELECTED_ITEM_ID: usize = 1; // relatively small and "round" item
+const STRIP_WIDTH_FACTOR: f32 = 4.0; // the items of the base layout only occupy a quarter of the strip
+const ITEM_STEP: usize = 4; // only every 4th item of the base layout is placed
+
+const N_SAMPLES_PER_ITER: usize = 1000;
+
+/// Benchmark the surrogate collision query in a sparse layout, with and without the bounding circle broad phase
+fn bounding_circle_bench(c: &mut Criterion) {
+    let json_instance: JsonInstance =
+        serde_json::from_reader(BufReader::new(File::open(SWIM_PATH).unwrap())).unwrap();
+    let base_config = create_base_config();
+    let base_instance = util::create_instance(
+        &json_instance,
+        base_config.cde_config,
+        base_config.poly_simpl_tolerance,
+    );
+    let (base_problem, _) =
+        util::create_blf_problem(base_instance.clone(), base_config, N_ITEMS_REMOVED);
+    let base_p_opts = base_problem
+        .get_layout(LayoutIndex::Real(0))
+        .placed_items()
+        .values()
+        .step_by(ITEM_STEP)
+        .map(|pi| PlacingOption {
+            layout_idx: LayoutIndex::Real(0),
+            item_id: pi.item_id,
+            d_transf: pi.d_transf,
+            subtract: false,
+        })
+        .collect_vec();
+
+    let item = base_instance.item(SELECTED_ITEM_ID);
+    let mut group = c.benchmark_group("bounding_circle_bench");
+    for broad_phase in [false, true] {
+        let mut config = base_config;
+        config.cde_config.bounding_circle_broad_phase = broad_phase;
+        let mut problem = match base_instance.clone() {
+            Instance::BP(_) => panic!("Expected SPInstance"),
+            Instance::SP(instance) => SPProblem::new(
+                instance,
+                base_problem.strip_width() * STRIP_WIDTH_FACTOR,
+                config.cde_config,
+            ),
+        };
+        for p_opt in base_p_opts.iter() {
+            problem.place_item(*p_opt);
+        }
+        problem.flush_changes();
+
+        let layout = problem.get_layout(LayoutIndex::Real(0));
+        let mut rng = LBFRng::seed_from_u64(0);
+        let sampler = UniformAARectSampler::new(layout.bin.bbox(), item);
+        let samples = (0..N_SAMPLES_PER_ITER)
+            .map(|_| sampler.sample(&mut rng).compose())
+            .collect_vec();
+
+        let id = match broad_phase {
+            false => "poles_only",
+            true => "bounding_circle",
+        };
+        group.bench_function(BenchmarkId::from_parameter(id), |b| {
+            b.iter(|| {
+                samples
+                    .iter()
+                    .filter(|transf| {
+                        layout
+                            .cde()
+                            .surrogate_collides(item.shape.surrogate(), transf, &[])
+                    })
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
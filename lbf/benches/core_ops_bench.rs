@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use itertools::Itertools;
+use rand::SeedableRng;
+
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
+use jagua_rs::fsize;
+use jagua_rs::geometry::fail_fast::sp_surrogate::SPSurrogate;
+use jagua_rs::io::json_instance::JsonInstance;
+use lbf::lbf_optimizer::LBFOptimizer;
+use lbf::rng::LBFRng;
+use lbf::samplers::uniform_rect_sampler::UniformAARectSampler;
+
+use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
+
+criterion_main!(benches);
+criterion_group!(
+    benches,
+    parse_bench,
+    surrogate_bench,
+    collides_bench,
+    strip_width_bench,
+    solve_bench
+);
+
+mod util;
+
+const ALBANO_PATH: &str = "../assets/albano.json"; // small instance (24 items) for the end-to-end benches
+const SELECTED_ITEM_ID: usize = 1; // relatively small and "round" item
+
+const N_SAMPLES_PER_ITER: usize = 1000;
+const STRIP_WIDTH_DELTA: fsize = 1.1; // the strip is widened by this factor and shrunk back again, keeping all items
+
+fn read_json_instance(path: &str) -> JsonInstance {
+    serde_json::from_reader(BufReader::new(File::open(path).unwrap())).unwrap()
+}
+
+/// Benchmark parsing a small instance, including polygon simplification and surrogate generation
+fn parse_bench(c: &mut Criterion) {
+    let json_instance = read_json_instance(ALBANO_PATH);
+    let config = create_base_config();
+    c.bench_function("parse_albano", |b| {
+        b.iter(|| {
+            util::create_instance(
+                &json_instance,
+                config.cde_config,
+                config.poly_simpl_tolerance,
+            )
+        })
+    });
+}
+
+/// Benchmark generating the surrogate of every item in the instance
+fn surrogate_bench(c: &mut Criterion) {
+    let config = create_base_config();
+    let instance = util::create_instance(
+        &read_json_instance(SWIM_PATH),
+        config.cde_config,
+        config.poly_simpl_tolerance,
+    );
+    let surrogate_config = config.cde_config.item_surrogate_config;
+    c.bench_function("surrogate_build_swim", |b| {
+        b.iter(|| {
+            instance
+                .items()
+                .iter()
+                .map(|(item, _)| SPSurrogate::new(&item.shape, surrogate_config))
+                .collect_vec()
+        })
+    });
+}
+
+/// Benchmark the full collision query (surrogate first, then the actual shape) in a dense layout
+fn collides_bench(c: &mut Criterion) {
+    let config = create_base_config();
+    let instance = util::create_instance(
+        &read_json_instance(SWIM_PATH),
+        config.cde_config,
+        config.poly_simpl_tolerance,
+    );
+    let (problem, _) = util::create_blf_problem(instance.clone(), config, N_ITEMS_REMOVED);
+    let layout = problem.get_layout(LayoutIndex::Real(0));
+
+    let item = instance.item(SELECTED_ITEM_ID);
+    let mut rng = LBFRng::seed_from_u64(0);
+    let sampler = UniformAARectSampler::new(layout.bin.bbox(), item);
+    let samples = (0..N_SAMPLES_PER_ITER)
+        .map(|_| sampler.sample(&mut rng).compose())
+        .collect_vec();
+    let mut buffer_shape = item.shape.as_ref().clone();
+
+    c.bench_function("shape_collides_swim", |b| {
+        b.iter(|| {
+            samples
+                .iter()
+                .filter(|transf| {
+                    layout.cde().surrogate_or_poly_collides(
+                        &item.shape,
+                        transf,
+                        &mut buffer_shape,
+                        &[],
+                    )
+                })
+                .count()
+        })
+    });
+}
+
+/// Benchmark widening the strip of a dense layout and shrinking it back again, which re-registers all placed items
+fn strip_width_bench(c: &mut Criterion) {
+    let config = create_base_config();
+    let instance = util::create_instance(
+        &read_json_instance(SWIM_PATH),
+        config.cde_config,
+        config.poly_simpl_tolerance,
+    );
+    let (mut problem, _) = util::create_blf_problem(instance, config, N_ITEMS_REMOVED);
+    let strip_width = problem.strip_width();
+
+    c.bench_function("modify_strip_width_swim", |b| {
+        b.iter(|| {
+            problem.modify_strip_in_back(strip_width * STRIP_WIDTH_DELTA);
+            problem.modify_strip_in_back(strip_width);
+        })
+    });
+}
+
+/// Benchmark a complete LBF run on a small instance
+fn solve_bench(c: &mut Criterion) {
+    let config = create_base_config();
+    let instance = util::create_instance(
+        &read_json_instance(ALBANO_PATH),
+        config.cde_config,
+        config.poly_simpl_tolerance,
+    );
+    assert!(matches!(&instance, &Instance::SP(_)));
+
+    let mut group = c.benchmark_group("solve_bench");
+    group.sample_size(10);
+    group.bench_function("lbf_albano", |b| {
+        b.iter_batched(
+            || LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0)),
+            |mut optimizer| optimizer.solve(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
@@ -5,7 +5,6 @@ use std::path::Path;
 use criterion::measurement::WallTime;
 use criterion::{criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
 use itertools::Itertools;
-use rand::prelude::SmallRng;
 use rand::SeedableRng;
 
 use jagua_rs::entities::instances::bin_packing::BPInstance;
@@ -22,6 +21,7 @@ use jagua_rs::io::json_instance::JsonInstance;
 use lbf::io;
 use lbf::io::svg_util::SvgDrawOptions;
 use lbf::lbf_config::LBFConfig;
+use lbf::rng::LBFRng;
 use lbf::samplers::hpg_sampler::HPGSampler;
 
 use crate::util::{N_ITEMS_REMOVED, SWIM_PATH};
@@ -89,7 +89,7 @@ fn edge_sensitivity_bench(config: LBFConfig, mut g: BenchmarkGroup<WallTime>) {
             );
         }
 
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         let layout = problem.get_layout(LayoutIndex::Real(0));
         /*let samples = {
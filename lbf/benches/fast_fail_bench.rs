@@ -3,7 +3,6 @@ use std::io::BufReader;
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use itertools::Itertools;
-use rand::prelude::SmallRng;
 use rand::SeedableRng;
 
 use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
@@ -16,6 +15,7 @@ use jagua_rs::geometry::geo_traits::{Shape, TransformableFrom};
 use jagua_rs::geometry::primitives::circle::Circle;
 use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
 use jagua_rs::io::json_instance::JsonInstance;
+use lbf::rng::LBFRng;
 use lbf::samplers::hpg_sampler::HPGSampler;
 
 use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
@@ -72,7 +72,7 @@ fn fast_fail_query_bench(c: &mut Criterion) {
             / ITEMS_ID_TO_TEST.len() as fsize
     );
 
-    let mut rng = SmallRng::seed_from_u64(0);
+    let mut rng = LBFRng::seed_from_u64(0);
     let layout = problem.get_layout(LayoutIndex::Real(0));
     let samples = ITEMS_ID_TO_TEST
         .iter()
@@ -1,9 +1,9 @@
 use std::fs::File;
 use std::io::BufReader;
+use std::time::{Duration, Instant};
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use itertools::Itertools;
-use rand::rngs::SmallRng;
 use rand::SeedableRng;
 
 use jagua_rs::entities::instances::instance::Instance;
@@ -14,12 +14,13 @@ use jagua_rs::entities::problems::strip_packing::SPProblem;
 use jagua_rs::geometry::geo_traits::Shape;
 use jagua_rs::geometry::geo_traits::TransformableFrom;
 use jagua_rs::io::json_instance::JsonInstance;
+use lbf::rng::LBFRng;
 use lbf::samplers::hpg_sampler::HPGSampler;
 
 use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
 
 criterion_main!(benches);
-criterion_group!(benches, hpg_update_bench, hpg_query_bench);
+criterion_group!(benches, hpg_update_bench, hpg_query_bench, hpg_remove_bench);
 
 mod util;
 
@@ -48,6 +49,7 @@ fn hpg_query_bench(c: &mut Criterion) {
             layout_idx: LayoutIndex::Real(0),
             item_id: pi.item_id,
             d_transf: pi.d_transf,
+            subtract: false,
         })
         .collect_vec();
 
@@ -83,7 +85,7 @@ fn hpg_query_bench(c: &mut Criterion) {
             io::write_svg(&svg, Path::new(&format!("removed_items_{n_hpg_cells}.svg")));
         }*/
 
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         // Search N_VALID_SAMPLES for each item
         let item = instance.item(SELECTED_ITEM_ID);
@@ -136,6 +138,7 @@ fn hpg_update_bench(c: &mut Criterion) {
             layout_idx: LayoutIndex::Real(0),
             item_id: pi.item_id,
             d_transf: pi.d_transf,
+            subtract: false,
         })
         .collect_vec();
 
@@ -171,7 +174,7 @@ fn hpg_update_bench(c: &mut Criterion) {
             io::write_svg(&svg, Path::new(&format!("removed_items_{n_hpg_cells}.svg")));
         }*/
 
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         // Search N_VALID_SAMPLES for each item
         let item = instance.item(SELECTED_ITEM_ID);
@@ -198,6 +201,7 @@ fn hpg_update_bench(c: &mut Criterion) {
                         layout_idx: LayoutIndex::Real(0),
                         item_id: SELECTED_ITEM_ID,
                         d_transf,
+                        subtract: false,
                     });
                 }
             }
@@ -216,3 +220,73 @@ fn hpg_update_bench(c: &mut Criterion) {
     }
     group.finish();
 }
+
+fn hpg_remove_bench(c: &mut Criterion) {
+    //Remove-heavy workload: every iteration removes one of the placed items and places it back in the same position
+    let json_instance: JsonInstance =
+        serde_json::from_reader(BufReader::new(File::open(SWIM_PATH).unwrap())).unwrap();
+    let base_config = create_base_config();
+    let base_instance = util::create_instance(
+        &json_instance,
+        base_config.cde_config,
+        base_config.poly_simpl_tolerance,
+    );
+    let (base_problem, _) =
+        util::create_blf_problem(base_instance.clone(), base_config, N_ITEMS_REMOVED);
+    let base_p_opts = base_problem
+        .get_layout(LayoutIndex::Real(0))
+        .placed_items()
+        .values()
+        .map(|pi| PlacingOption {
+            layout_idx: LayoutIndex::Real(0),
+            item_id: pi.item_id,
+            d_transf: pi.d_transf,
+            subtract: false,
+        })
+        .collect_vec();
+
+    let mut group = c.benchmark_group("hpg_bench_remove");
+    for n_hpg_cells in N_HPG_CELLS {
+        let mut config = base_config;
+        config.cde_config.hpg_n_cells = n_hpg_cells;
+        let instance = util::create_instance(
+            &json_instance,
+            config.cde_config,
+            config.poly_simpl_tolerance,
+        );
+        let mut problem = match instance.clone() {
+            Instance::BP(_) => panic!("Expected SPInstance"),
+            Instance::SP(instance) => {
+                SPProblem::new(instance, base_problem.strip_width(), config.cde_config)
+            }
+        };
+        for p_opt in base_p_opts.iter() {
+            problem.place_item(*p_opt);
+        }
+
+        let mut p_opts_cycler = base_p_opts.iter().cycle();
+
+        //only the removals are timed
+        group.bench_function(BenchmarkId::from_parameter(n_hpg_cells), |b| {
+            b.iter_custom(|iters| {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iters {
+                    let opt = p_opts_cycler.next().unwrap();
+                    let pik = problem
+                        .get_layout(LayoutIndex::Real(0))
+                        .placed_items()
+                        .iter()
+                        .find(|(_, pi)| pi.item_id == opt.item_id && pi.d_transf == opt.d_transf)
+                        .map(|(pik, _)| pik)
+                        .unwrap();
+                    let start = Instant::now();
+                    problem.remove_item(LayoutIndex::Real(0), pik, true);
+                    elapsed += start.elapsed();
+                    problem.place_item(*opt);
+                }
+                elapsed
+            })
+        });
+    }
+    group.finish();
+}
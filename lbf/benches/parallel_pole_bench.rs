@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::Itertools;
+use rand::SeedableRng;
+
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::placing_option::PlacingOption;
+use jagua_rs::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
+use jagua_rs::entities::problems::strip_packing::SPProblem;
+use jagua_rs::geometry::fail_fast::sp_surrogate::SPSurrogate;
+use jagua_rs::io::json_instance::JsonInstance;
+use jagua_rs::util::config::SPSurrogateConfig;
+use lbf::rng::LBFRng;
+use lbf::samplers::hpg_sampler::HPGSampler;
+
+use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
+
+criterion_main!(benches);
+criterion_group!(benches, parallel_pole_bench);
+
+mod util;
+
+const N_POLES: usize = 500;
+const SELECTED_ITEM_ID: usize = 0; // large, concave item
+const PARALLEL_POLE_THRESHOLDS: [Option<usize>; 2] = [None, Some(0)];
+
+const N_SAMPLES_PER_ITER: usize = 1000;
+
+/// Benchmark the surrogate collision query of a part with many fail-fast poles, serially and in parallel
+fn parallel_pole_bench(c: &mut Criterion) {
+    let json_instance: JsonInstance =
+        serde_json::from_reader(BufReader::new(File::open(SWIM_PATH).unwrap())).unwrap();
+    let base_config = create_base_config();
+    let base_instance = util::create_instance(
+        &json_instance,
+        base_config.cde_config,
+        base_config.poly_simpl_tolerance,
+    );
+    let (base_problem, _) =
+        util::create_blf_problem(base_instance.clone(), base_config, N_ITEMS_REMOVED);
+    let base_p_opts = base_problem
+        .get_layout(LayoutIndex::Real(0))
+        .placed_items()
+        .values()
+        .map(|pi| PlacingOption {
+            layout_idx: LayoutIndex::Real(0),
+            item_id: pi.item_id,
+            d_transf: pi.d_transf,
+            subtract: false,
+        })
+        .collect_vec();
+
+    let item = base_instance.item(SELECTED_ITEM_ID);
+    let surrogate = SPSurrogate::new(
+        &item.shape,
+        SPSurrogateConfig {
+            pole_coverage_goal: 1.0,
+            max_poles: N_POLES,
+            n_ff_poles: N_POLES,
+            n_ff_piers: 0,
+        },
+    );
+    println!("surrogate with {} poles", surrogate.ff_poles().len());
+
+    let mut group = c.benchmark_group("parallel_pole_bench");
+    for threshold in PARALLEL_POLE_THRESHOLDS {
+        let mut config = base_config;
+        config.cde_config.parallel_pole_threshold = threshold;
+        let mut problem = match base_instance.clone() {
+            Instance::BP(_) => panic!("Expected SPInstance"),
+            Instance::SP(instance) => {
+                SPProblem::new(instance, base_problem.strip_width(), config.cde_config)
+            }
+        };
+        // Place the items in exactly the same way as the base problem
+        for p_opt in base_p_opts.iter() {
+            problem.place_item(*p_opt);
+        }
+        problem.flush_changes();
+
+        let layout = problem.get_layout(LayoutIndex::Real(0));
+        let mut rng = LBFRng::seed_from_u64(0);
+        let mut sampler = HPGSampler::new(item, layout).unwrap();
+        let samples = (0..N_SAMPLES_PER_ITER)
+            .map(|_| sampler.sample(&mut rng))
+            .collect_vec();
+
+        let id = match threshold {
+            None => "serial".to_string(),
+            Some(threshold) => format!("parallel_{threshold}"),
+        };
+        group.bench_function(BenchmarkId::from_parameter(id), |b| {
+            b.iter(|| {
+                samples
+                    .iter()
+                    .filter(|transf| layout.cde().surrogate_collides(&surrogate, transf, &[]))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
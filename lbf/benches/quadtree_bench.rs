@@ -3,7 +3,6 @@ use std::io::BufReader;
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use itertools::Itertools;
-use rand::prelude::SmallRng;
 use rand::seq::IteratorRandom;
 use rand::SeedableRng;
 
@@ -13,6 +12,7 @@ use jagua_rs::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric}
 use jagua_rs::fsize;
 use jagua_rs::geometry::geo_traits::TransformableFrom;
 use jagua_rs::io::json_instance::JsonInstance;
+use lbf::rng::LBFRng;
 use lbf::samplers::uniform_rect_sampler::UniformAARectSampler;
 
 use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
@@ -56,7 +56,7 @@ fn quadtree_update_bench(c: &mut Criterion) {
         let (mut problem, _) = util::create_blf_problem(instance.clone(), config, 0);
 
         let layout_idx = LayoutIndex::Real(0);
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         group.bench_function(BenchmarkId::from_parameter(depth), |b| {
             b.iter(|| {
@@ -72,6 +72,7 @@ fn quadtree_update_bench(c: &mut Criterion) {
                     layout_idx,
                     item_id: pi.item_id,
                     d_transf: pi.d_transf,
+                    subtract: false,
                 };
 
                 //println!("Removing item with id: {}\n", pi_uid.item_id);
@@ -111,7 +112,7 @@ fn quadtree_query_bench(c: &mut Criterion) {
 
         let layout = problem.get_layout(LayoutIndex::Real(0));
         let sampler = UniformAARectSampler::new(layout.bin.bbox(), instance.item(0));
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         let samples = (0..N_TOTAL_SAMPLES)
             .map(|_| sampler.sample(&mut rng).compose())
@@ -171,7 +172,7 @@ fn quadtree_query_update_1000_1(c: &mut Criterion) {
 
         let layout = problem.get_layout(LayoutIndex::Real(0));
         let sampler = UniformAARectSampler::new(layout.bin.bbox(), instance.item(0));
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         let samples = (0..N_TOTAL_SAMPLES)
             .map(|_| sampler.sample(&mut rng).compose())
@@ -194,6 +195,7 @@ fn quadtree_query_update_1000_1(c: &mut Criterion) {
                     layout_idx,
                     item_id: pi.item_id,
                     d_transf: pi.d_transf,
+                    subtract: false,
                 };
 
                 problem.remove_item(layout_idx, pik, true);
@@ -242,7 +244,7 @@ fn quadtree_collect_query_bench(c: &mut Criterion) {
 
         let layout = problem.get_layout(LayoutIndex::Real(0));
         let sampler = UniformAARectSampler::new(layout.bin.bbox(), instance.item(0));
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         let samples = (0..N_TOTAL_SAMPLES)
             .map(|_| sampler.sample(&mut rng).compose())
@@ -1,6 +1,6 @@
 use itertools::Itertools;
 use log::info;
-use rand::prelude::{IteratorRandom, SmallRng};
+use rand::prelude::IteratorRandom;
 use rand::SeedableRng;
 use std::path::Path;
 
@@ -13,12 +13,13 @@ use jagua_rs::entities::problems::strip_packing::SPProblem;
 use jagua_rs::fsize;
 use jagua_rs::io::json_instance::JsonInstance;
 use jagua_rs::io::parser::Parser;
-use jagua_rs::util::config::{CDEConfig, SPSurrogateConfig};
-use jagua_rs::util::polygon_simplification::PolySimplConfig;
+use jagua_rs::util::config::{CDEConfig, GeoTolerance, SPSurrogateConfig};
+use jagua_rs::util::polygon_simplification::{PolySimplConfig, PolySimplDirection};
 use lbf::io;
 use lbf::io::svg_util::SvgDrawOptions;
-use lbf::lbf_config::LBFConfig;
+use lbf::lbf_config::{Acceptance, BinObjective, LBFConfig, RotationStrategy, Solver};
 use lbf::lbf_optimizer::LBFOptimizer;
+use lbf::rng::LBFRng;
 
 pub const SWIM_PATH: &str = "../assets/swim.json";
 pub const N_ITEMS_REMOVED: usize = 5;
@@ -29,7 +30,10 @@ pub fn create_instance(
     poly_simpl_tolerance: Option<fsize>,
 ) -> Instance {
     let poly_simpl_config = match poly_simpl_tolerance {
-        Some(tolerance) => PolySimplConfig::Enabled { tolerance },
+        Some(tolerance) => PolySimplConfig::Enabled {
+            tolerance,
+            direction: PolySimplDirection::OutwardOnly,
+        },
         None => PolySimplConfig::Disabled,
     };
     let parser = Parser::new(poly_simpl_config, cde_config, true);
@@ -45,7 +49,7 @@ pub fn create_blf_problem(
     n_items_removed: usize,
 ) -> (SPProblem, Vec<PlacingOption>) {
     assert!(matches!(&instance, &Instance::SP(_)));
-    let mut lbf_optimizer = LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0));
+    let mut lbf_optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
     lbf_optimizer.solve();
 
     let mut problem = match lbf_optimizer.problem.clone() {
@@ -53,7 +57,7 @@ pub fn create_blf_problem(
         _ => panic!("Expected SPProblem"),
     };
 
-    let mut rng = SmallRng::seed_from_u64(0);
+    let mut rng = LBFRng::seed_from_u64(0);
     // Remove some items from the layout
     let placed_items_to_remove = problem
         .get_layout(&STRIP_LAYOUT_IDX)
@@ -70,6 +74,7 @@ pub fn create_blf_problem(
                 layout_idx: STRIP_LAYOUT_IDX,
                 item_id: pi.item_id,
                 d_transf: pi.d_transf,
+                subtract: false,
             }
         })
         .collect_vec();
@@ -118,11 +123,33 @@ pub fn create_base_config() -> LBFConfig {
                 n_ff_poles: 4,
                 n_ff_piers: 0,
             },
+            verify_placements: false,
+            geo_tolerance: GeoTolerance::default(),
+            parallel_pole_threshold: None,
+            bounding_circle_broad_phase: false,
+            soft_bin_boundary: false,
+            touching_allowed: false,
         },
         poly_simpl_tolerance: Some(0.001),
+        poly_simpl_direction: PolySimplDirection::OutwardOnly,
         prng_seed: Some(0),
         n_samples: 5000,
         ls_frac: 0.2,
+        max_samples_per_item: None,
+        compact: false,
+        max_items_per_layout: None,
+        rect_packing: false,
+        rotation_penalty: 0.0,
+        quality_penalty: 0.0,
+        sampling_bias: 0.0,
+        acceptance: Acceptance::Greedy,
+        bin_objective: BinObjective::CheapestBin,
+        output_grid: None,
+        min_footprint_guess: false,
+        rotation_strategy: RotationStrategy::Sample,
+        strip_growth_step: None,
+        max_stored_solutions: None,
+        solver: Solver::Lbf,
         svg_draw_options: Default::default(),
     }
 }
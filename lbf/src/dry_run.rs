@@ -0,0 +1,130 @@
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use ordered_float::NotNan;
+
+use jagua_rs::entities::instances::bin_packing::BPInstance;
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::fsize;
+use jagua_rs::geometry::geo_traits::Shape;
+
+use crate::lbf_optimizer::item_fits_bin;
+
+/// Cheap analysis of an instance, to estimate its difficulty without running the optimizer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunReport {
+    /// Total number of items to place
+    pub n_items: usize,
+    /// Total area of all items to place
+    pub total_item_area: fsize,
+    /// Ids of the items which cannot fit in any bin (or the strip), at any of their allowed rotations
+    pub unfit_item_ids: Vec<usize>,
+    /// Lower bound on the number of bins needed, based on area (only for bin packing problems).
+    /// `None` if the entire stock is not large enough to hold all items.
+    pub min_bins: Option<usize>,
+    /// Lower bound on the width of the strip, based on area (only for strip packing problems)
+    pub min_strip_width: Option<fsize>,
+    /// Upper bound on the usage of the bins needed (only for bin packing problems).
+    /// The bins holding all items are at least `min_bins` in number and at least as large as the items together,
+    /// so their area is at least the maximum of the total item area and the area of the `min_bins` smallest bins.
+    pub max_usage: Option<fsize>,
+}
+
+impl DryRunReport {
+    pub fn new(instance: &Instance) -> Self {
+        let total_item_area = instance
+            .items()
+            .iter()
+            .map(|(item, qty)| item.shape.area() * *qty as fsize)
+            .sum::<fsize>();
+
+        let unfit_item_ids = instance
+            .items()
+            .iter()
+            .map(|(item, _)| item)
+            .filter(|item| match instance {
+                Instance::BP(bpi) => !bpi.bins.iter().any(|(bin, _)| {
+                    let bbox = bin.outer.bbox();
+                    item_fits_bin(item, bbox.width(), bbox.height())
+                }),
+                Instance::SP(spi) => !item_fits_bin(item, fsize::INFINITY, spi.strip_height),
+            })
+            .map(|item| item.id)
+            .collect_vec();
+
+        let (min_bins, min_strip_width, max_usage) = match instance {
+            Instance::BP(bpi) => {
+                //open the largest bins first, until their area covers the area of all items
+                let mut covered_area = 0.0;
+                let n_bins = bin_areas_asc(bpi)
+                    .rev()
+                    .take_while_inclusive(|area| {
+                        covered_area += area;
+                        covered_area < total_item_area
+                    })
+                    .count();
+                let n_bins = usize::max(n_bins, 1);
+                //the smallest area any set of `n_bins` bins can have
+                let min_bins_area = bin_areas_asc(bpi).take(n_bins).sum::<fsize>();
+                match covered_area >= total_item_area {
+                    true => (
+                        Some(n_bins),
+                        None,
+                        Some(total_item_area / fsize::max(total_item_area, min_bins_area)),
+                    ),
+                    false => (None, None, None),
+                }
+            }
+            Instance::SP(spi) => (None, Some(total_item_area / spi.strip_height), None),
+        };
+
+        Self {
+            n_items: instance.total_item_qty(),
+            total_item_area,
+            unfit_item_ids,
+            min_bins,
+            min_strip_width,
+            max_usage,
+        }
+    }
+
+    /// Whether no obstacle to placing all items was found.
+    /// Being feasible does not guarantee all items can be placed.
+    pub fn is_feasible(&self) -> bool {
+        let enough_stock = self.min_bins.is_some() || self.min_strip_width.is_some();
+        self.unfit_item_ids.is_empty() && enough_stock
+    }
+}
+
+/// Areas of all bins in stock, from small to large
+fn bin_areas_asc(bpi: &BPInstance) -> impl DoubleEndedIterator<Item = fsize> + '_ {
+    bpi.bins
+        .iter()
+        .flat_map(|(bin, stock)| std::iter::repeat_n(bin.area, *stock))
+        .sorted_by_key(|area| NotNan::new(*area).unwrap())
+}
+
+impl Display for DryRunReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "items: {}", self.n_items)?;
+        writeln!(f, "total item area: {:.3}", self.total_item_area)?;
+        match self.unfit_item_ids.is_empty() {
+            true => writeln!(f, "all items fit")?,
+            false => writeln!(f, "items which do not fit: {:?}", self.unfit_item_ids)?,
+        }
+        if let Some(min_bins) = self.min_bins {
+            writeln!(f, "minimum number of bins: {}", min_bins)?;
+        }
+        if let Some(min_strip_width) = self.min_strip_width {
+            writeln!(f, "minimum strip width: {:.3}", min_strip_width)?;
+        }
+        if let Some(max_usage) = self.max_usage {
+            writeln!(f, "maximum usage: {:.3}%", max_usage * 100.0)?;
+        }
+        match self.is_feasible() {
+            true => write!(f, "feasible: yes"),
+            false => write!(f, "feasible: no"),
+        }
+    }
+}
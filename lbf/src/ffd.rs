@@ -0,0 +1,142 @@
+use jagua_rs::collision_detection::hazard_filter;
+use jagua_rs::entities::item::Item;
+use jagua_rs::entities::layout::Layout;
+use jagua_rs::fsize;
+use jagua_rs::geometry::d_transformation::DTransformation;
+use jagua_rs::geometry::geo_enums::AllowedRotation;
+use jagua_rs::geometry::geo_traits::{Shape, Transformable};
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+
+use crate::rect_packer::{axis_aligned_rotations, RECT_PACKING_GAP};
+
+/// Free space of a container for the first fit decreasing baseline.
+/// The container is divided into shelves: columns which are opened from left to right, as wide as the rectangle that opened them.
+/// Within a shelf, rectangles are stacked from bottom to top.
+#[derive(Clone, Debug)]
+pub struct Shelves {
+    container: AARectangle,
+    shelves: Vec<Shelf>,
+    /// Left side of the next shelf to be opened
+    next_x: fsize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Shelf {
+    x_min: fsize,
+    width: fsize,
+    /// Top of the rectangles stacked in the shelf
+    y_top: fsize,
+}
+
+impl Shelves {
+    pub fn new(container: AARectangle) -> Self {
+        let next_x = container.x_min;
+        Self {
+            container,
+            shelves: vec![],
+            next_x,
+        }
+    }
+
+    /// Footprints for a `width` x `height` rectangle, in first fit order:
+    /// on top of every open shelf it fits in, followed by the bottom of a new shelf.
+    pub fn candidates(
+        &self,
+        width: fsize,
+        height: fsize,
+    ) -> impl Iterator<Item = AARectangle> + '_ {
+        let c = &self.container;
+        let on_shelves = self
+            .shelves
+            .iter()
+            .filter(move |s| width <= s.width && s.y_top + height <= c.y_max)
+            .map(move |s| AARectangle::new(s.x_min, s.y_top, s.x_min + width, s.y_top + height));
+        let new_shelf = (self.next_x + width <= c.x_max && c.y_min + height <= c.y_max)
+            .then(|| AARectangle::new(self.next_x, c.y_min, self.next_x + width, c.y_min + height));
+        on_shelves.chain(new_shelf)
+    }
+
+    /// Occupies a footprint returned by [Shelves::candidates]
+    pub fn occupy(&mut self, footprint: &AARectangle) {
+        match self.shelves.iter_mut().find(|s| s.x_min == footprint.x_min) {
+            Some(shelf) => shelf.y_top = footprint.y_max,
+            None => {
+                self.shelves.push(Shelf {
+                    x_min: footprint.x_min,
+                    width: footprint.width(),
+                    y_top: footprint.y_max,
+                });
+                self.next_x = footprint.x_max;
+            }
+        }
+    }
+
+    /// Only opens new shelves to the right of `x`
+    pub fn open_from(&mut self, x: fsize) {
+        self.next_x = fsize::max(self.next_x, x);
+    }
+}
+
+/// Shelves of a layout, opened to the right of all items already placed in it.
+/// Also returns the gap kept around every rectangle, see [RECT_PACKING_GAP].
+pub fn layout_shelves(layout: &Layout) -> (Shelves, fsize) {
+    let bbox = layout.bin.bbox();
+    let gap = fsize::min(bbox.width(), bbox.height()) * RECT_PACKING_GAP;
+    let mut shelves = Shelves::new(bbox);
+    for pi in layout.placed_items().values() {
+        shelves.open_from(pi.shape.bbox().x_max + gap / 2.0);
+    }
+    (shelves, gap)
+}
+
+/// Rotations tried by the first fit decreasing baseline, in order.
+/// Axis-aligned rotations are preferred, as they keep the bounding box of rectangular items tight.
+pub fn ffd_rotations(allowed_rotation: &AllowedRotation) -> Vec<fsize> {
+    match (axis_aligned_rotations(allowed_rotation), allowed_rotation) {
+        (aligned, _) if !aligned.is_empty() => aligned,
+        (_, AllowedRotation::Discrete(rotations)) => rotations.clone(),
+        (_, AllowedRotation::Range { min, .. }) => vec![*min],
+        (_, AllowedRotation::None | AllowedRotation::Continuous) => vec![0.0],
+    }
+}
+
+/// Finds the first footprint in `shelves` where the bounding box of the item fits, over all of its [ffd_rotations],
+/// and which the collision detection engine of the layout confirms (e.g. it avoids holes and inferior quality zones).
+/// Returns the transformation to apply to the item and the occupied footprint (including the gap).
+pub fn find_ffd_placement(
+    layout: &Layout,
+    shelves: &Shelves,
+    item: &Item,
+    gap: fsize,
+) -> Option<(DTransformation, AARectangle)> {
+    let irrel_hazards = match item.hazard_filter.as_ref() {
+        None => vec![],
+        Some(hf) => hazard_filter::generate_irrelevant_hazards(hf, layout.cde().all_hazards()),
+    };
+    let mut buffer = (*item.shape).clone();
+    buffer.surrogate = None;
+
+    ffd_rotations(&item.allowed_rotation)
+        .into_iter()
+        .find_map(|rotation| {
+            let rotated_bbox = item
+                .shape
+                .transform_clone(&DTransformation::new(rotation, (0.0, 0.0)).compose())
+                .bbox();
+            let (width, height) = (rotated_bbox.width() + gap, rotated_bbox.height() + gap);
+            shelves.candidates(width, height).find_map(|footprint| {
+                let translation = (
+                    footprint.x_min + gap / 2.0 - rotated_bbox.x_min,
+                    footprint.y_min + gap / 2.0 - rotated_bbox.y_min,
+                );
+                let d_transf = DTransformation::new(rotation, translation);
+                let collides = layout.cde().item_collides(
+                    item,
+                    &d_transf.compose(),
+                    &mut buffer,
+                    &irrel_hazards,
+                );
+                (!collides).then_some((d_transf, footprint))
+            })
+        })
+}
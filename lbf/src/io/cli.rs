@@ -19,4 +19,13 @@ pub struct Cli {
         default_value = "info"
     )]
     pub log_level: LevelFilter,
+    /// Disable the progress bar, progress is still reported periodically in the log
+    #[arg(short, long)]
+    pub quiet: bool,
+    /// Only report the feasibility and estimated usage of the instance, without optimizing
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Also write a G-code skeleton tracing the outlines of the placed items
+    #[arg(long)]
+    pub gcode: bool,
 }
@@ -0,0 +1,364 @@
+//! Import and export of the XML format used by the ESICUP (EURO Special Interest Group on Cutting and Packing)
+//! nesting benchmark instances.
+//!
+//! A document consists of a `<problem>` section listing the boards (containers) and the lot (items to produce),
+//! a `<polygons>` section with the contours referenced by both, and an optional `<solutions>` section
+//! with the placement of every produced piece.
+
+use std::fmt::{Display, Formatter, Write};
+use std::time::Instant;
+
+use itertools::Itertools;
+
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::instances::strip_packing::PackingAxis;
+use jagua_rs::entities::solution::Solution;
+use jagua_rs::fsize;
+use jagua_rs::geometry::geo_enums::AllowedRotation;
+use jagua_rs::geometry::geo_traits::Transformable;
+use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+use jagua_rs::io::json_instance::{
+    JsonBin, JsonInstance, JsonItem, JsonShape, JsonSimplePoly, JsonStrip, RotationUnit,
+};
+use jagua_rs::io::json_solution::JsonContainer;
+use jagua_rs::io::parser;
+
+/// Reason an ESICUP nesting document could not be converted by [`parse_esicup_instance`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EsicupError {
+    /// The document is not well-formed XML
+    Xml(String),
+    /// A required attribute of an element is absent
+    MissingAttribute { element: String, attribute: String },
+    /// The value of an attribute could not be parsed
+    InvalidAttribute { attribute: String, value: String },
+    /// A piece or board does not consist of exactly one component
+    UnsupportedComponents { piece_id: String },
+    /// A component refers to a polygon which is not defined
+    UnknownPolygon { polygon_id: String },
+    /// The document does not define any boards
+    NoBoards,
+}
+
+impl Display for EsicupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EsicupError::Xml(err) => write!(f, "invalid XML: {err}"),
+            EsicupError::MissingAttribute { element, attribute } => {
+                write!(f, "<{element}> is missing attribute \"{attribute}\"")
+            }
+            EsicupError::InvalidAttribute { attribute, value } => {
+                write!(f, "invalid value for attribute \"{attribute}\": {value}")
+            }
+            EsicupError::UnsupportedComponents { piece_id } => {
+                write!(f, "piece {piece_id} does not consist of a single component")
+            }
+            EsicupError::UnknownPolygon { polygon_id } => {
+                write!(f, "reference to undefined polygon {polygon_id}")
+            }
+            EsicupError::NoBoards => write!(f, "no boards defined"),
+        }
+    }
+}
+
+/// Converts an ESICUP nesting document into a [`JsonInstance`].
+/// Every piece of the lot becomes an item with the same demand and allowed orientations (in degrees),
+/// pieces without an `<orientation>` element can be rotated freely.
+/// A single board with quantity 1 is interpreted as the strip of a strip packing problem with the board's height,
+/// as is customary for the benchmark instances. Otherwise, every board becomes a bin with the quantity as stock.
+pub fn parse_esicup_instance(name: &str, xml: &str) -> Result<JsonInstance, EsicupError> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| EsicupError::Xml(e.to_string()))?;
+    let root = doc.root_element();
+
+    let polygons = root
+        .descendants()
+        .filter(|n| n.has_tag_name("polygon"))
+        .map(|n| Ok((attribute::<String>(&n, "id")?, parse_polygon(&n)?)))
+        .collect::<Result<Vec<(String, Vec<(fsize, fsize)>)>, EsicupError>>()?;
+
+    //resolves the single component of a piece to the outer contour of the piece
+    let piece_shape = |piece: &roxmltree::Node| -> Result<JsonSimplePoly, EsicupError> {
+        let piece_id = attribute::<String>(piece, "id")?;
+        let component = match piece
+            .children()
+            .filter(|n| n.has_tag_name("component"))
+            .collect_vec()
+            .as_slice()
+        {
+            [component] => *component,
+            _ => return Err(EsicupError::UnsupportedComponents { piece_id }),
+        };
+        let polygon_id = attribute::<String>(&component, "idPolygon")?;
+        let x_offset = optional_attribute::<fsize>(&component, "xOffset")?.unwrap_or(0.0);
+        let y_offset = optional_attribute::<fsize>(&component, "yOffset")?.unwrap_or(0.0);
+        let (_, points) = polygons
+            .iter()
+            .find(|(id, _)| *id == polygon_id)
+            .ok_or(EsicupError::UnknownPolygon { polygon_id })?;
+        Ok(JsonSimplePoly(
+            points
+                .iter()
+                .map(|(x, y)| (x + x_offset, y + y_offset))
+                .collect(),
+        ))
+    };
+
+    let pieces_in = |section: &str| {
+        root.descendants()
+            .filter(|n| n.has_tag_name(section))
+            .flat_map(|n| n.children().filter(|c| c.has_tag_name("piece")))
+            .collect_vec()
+    };
+
+    let items = pieces_in("lot")
+        .iter()
+        .map(|piece| {
+            let allowed_orientations = piece
+                .children()
+                .find(|n| n.has_tag_name("orientation"))
+                .map(|o| {
+                    o.children()
+                        .filter(|n| n.has_tag_name("enumeration"))
+                        .map(|e| attribute::<fsize>(&e, "angle"))
+                        .collect::<Result<Vec<fsize>, EsicupError>>()
+                })
+                .transpose()?;
+            Ok(JsonItem {
+                demand: attribute(piece, "quantity")?,
+                allowed_orientations,
+                allowed_orientation_range: None,
+                shape: JsonShape::SimplePolygon(piece_shape(piece)?),
+                value: None,
+                base_quality: None,
+                position_lattice: None,
+                anchor: None,
+                min_edge_contact: None,
+                meta: None,
+            })
+        })
+        .collect::<Result<Vec<JsonItem>, EsicupError>>()?;
+
+    let boards = pieces_in("boards")
+        .iter()
+        .map(|board| Ok((attribute::<u64>(board, "quantity")?, piece_shape(board)?)))
+        .collect::<Result<Vec<(u64, JsonSimplePoly)>, EsicupError>>()?;
+
+    let (bins, strip) = match boards.as_slice() {
+        [] => return Err(EsicupError::NoBoards),
+        [(1, shape)] => {
+            let (y_min, y_max) = shape
+                .0
+                .iter()
+                .map(|(_, y)| *y)
+                .minmax()
+                .into_option()
+                .unwrap_or((0.0, 0.0));
+            (
+                None,
+                Some(JsonStrip {
+                    height: y_max - y_min,
+                    axis: PackingAxis::X,
+                }),
+            )
+        }
+        _ => {
+            let bins = boards
+                .into_iter()
+                .map(|(qty, shape)| JsonBin {
+                    cost: 1,
+                    stock: Some(qty),
+                    shape: JsonShape::SimplePolygon(shape),
+                    zones: vec![],
+                })
+                .collect();
+            (Some(bins), None)
+        }
+    };
+
+    Ok(JsonInstance {
+        name: name.to_string(),
+        items,
+        bins,
+        strip,
+        precedence: vec![],
+        rotation_unit: RotationUnit::Deg,
+    })
+}
+
+/// Composes an ESICUP nesting document of an instance and a solution to it.
+/// Items become pieces of the lot and bins become boards, for strip packing problems the board is the strip of the solution.
+/// Continuous rotation ranges cannot be represented in the format and are exported as unrestricted.
+/// Every placed item is listed with the board it was placed on, its rotation (in degrees) and its translation,
+/// both with respect to the original (non-pretransformed) shapes.
+pub fn compose_esicup_output(solution: &Solution, instance: &Instance) -> String {
+    let json_solution =
+        parser::compose_json_solution(solution, instance, Instant::now(), None, false);
+    let mut polygons = vec![];
+
+    //boards
+    let boards = match instance {
+        Instance::BP(bpi) => bpi
+            .bins
+            .iter()
+            .map(|(bin, stock)| {
+                let outer = bin
+                    .outer
+                    .transform_clone(&bin.pretransform.clone().inverse());
+                polygons.push(outer);
+                (format!("board{}", bin.id), *stock)
+            })
+            .collect_vec(),
+        Instance::SP(_) => {
+            let layout = &solution.layout_snapshots[0];
+            let bin = &layout.bin;
+            let outer = bin
+                .outer
+                .transform_clone(&bin.pretransform.clone().inverse());
+            polygons.push(outer);
+            vec![("board0".to_string(), 1)]
+        }
+    };
+
+    //pieces
+    let pieces = instance
+        .items()
+        .iter()
+        .map(|(item, qty)| {
+            let shape = item
+                .shape
+                .transform_clone(&item.pretransform.clone().inverse());
+            polygons.push(shape);
+            let orientations = match &item.allowed_rotation {
+                AllowedRotation::None => Some(vec![0.0]),
+                AllowedRotation::Discrete(angles) => {
+                    Some(angles.iter().map(|a| a.to_degrees()).collect_vec())
+                }
+                AllowedRotation::Continuous | AllowedRotation::Range { .. } => None,
+            };
+            (format!("piece{}", item.id), qty, orientations)
+        })
+        .collect_vec();
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(xml, "<nesting>").unwrap();
+    writeln!(xml, "  <problem>").unwrap();
+    writeln!(xml, "    <boards>").unwrap();
+    for (i, (id, qty)) in boards.iter().enumerate() {
+        writeln!(xml, r#"      <piece id="{id}" quantity="{qty}">"#).unwrap();
+        writeln!(
+            xml,
+            r#"        <component idPolygon="polygon{i}" type="0" xOffset="0" yOffset="0"/>"#
+        )
+        .unwrap();
+        writeln!(xml, "      </piece>").unwrap();
+    }
+    writeln!(xml, "    </boards>").unwrap();
+    writeln!(xml, "    <lot>").unwrap();
+    for (i, (id, qty, orientations)) in pieces.iter().enumerate() {
+        writeln!(xml, r#"      <piece id="{id}" quantity="{qty}">"#).unwrap();
+        if let Some(orientations) = orientations {
+            writeln!(xml, "        <orientation>").unwrap();
+            for angle in orientations {
+                writeln!(xml, r#"          <enumeration angle="{angle}"/>"#).unwrap();
+            }
+            writeln!(xml, "        </orientation>").unwrap();
+        }
+        let polygon_idx = boards.len() + i;
+        writeln!(xml, r#"        <component idPolygon="polygon{polygon_idx}" type="0" xOffset="0" yOffset="0"/>"#).unwrap();
+        writeln!(xml, "      </piece>").unwrap();
+    }
+    writeln!(xml, "    </lot>").unwrap();
+    writeln!(xml, "  </problem>").unwrap();
+
+    writeln!(xml, "  <polygons>").unwrap();
+    for (i, polygon) in polygons.iter().enumerate() {
+        write_polygon(&mut xml, &format!("polygon{i}"), polygon);
+    }
+    writeln!(xml, "  </polygons>").unwrap();
+
+    writeln!(xml, "  <solutions>").unwrap();
+    writeln!(xml, "    <solution>").unwrap();
+    for layout in json_solution.layouts.iter() {
+        let board_id = match layout.container {
+            JsonContainer::Bin { index } => format!("board{index}"),
+            JsonContainer::Strip { .. } => "board0".to_string(),
+        };
+        for pi in layout.placed_items.iter() {
+            let angle = pi.transformation.rotation.to_degrees();
+            let (x, y) = pi.transformation.translation;
+            writeln!(
+                xml,
+                r#"      <placement idBoard="{board_id}" idPiece="piece{}" angle="{angle}" mirror="none" x="{x}" y="{y}"/>"#,
+                pi.index
+            )
+            .unwrap();
+        }
+    }
+    writeln!(xml, "    </solution>").unwrap();
+    writeln!(xml, "  </solutions>").unwrap();
+    writeln!(xml, "</nesting>").unwrap();
+
+    xml
+}
+
+fn write_polygon(xml: &mut String, id: &str, polygon: &SimplePolygon) {
+    let n = polygon.number_of_points();
+    writeln!(xml, r#"    <polygon id="{id}" nVertices="{n}">"#).unwrap();
+    writeln!(xml, "      <lines>").unwrap();
+    for (i, edge) in polygon.edge_iter().enumerate() {
+        writeln!(
+            xml,
+            r#"        <segment n="{}" x0="{}" x1="{}" y0="{}" y1="{}"/>"#,
+            i + 1,
+            edge.start.0,
+            edge.end.0,
+            edge.start.1,
+            edge.end.1
+        )
+        .unwrap();
+    }
+    writeln!(xml, "      </lines>").unwrap();
+    writeln!(xml, "    </polygon>").unwrap();
+}
+
+/// Collects the vertices of a polygon from the starting points of its segments, in the order of their `n` attribute.
+fn parse_polygon(node: &roxmltree::Node) -> Result<Vec<(fsize, fsize)>, EsicupError> {
+    let segments = node
+        .descendants()
+        .filter(|n| n.has_tag_name("segment"))
+        .map(|s| {
+            let n = optional_attribute::<usize>(&s, "n")?.unwrap_or(0);
+            Ok((n, (attribute(&s, "x0")?, attribute(&s, "y0")?)))
+        })
+        .collect::<Result<Vec<(usize, (fsize, fsize))>, EsicupError>>()?;
+    Ok(segments
+        .into_iter()
+        .sorted_by_key(|(n, _)| *n)
+        .map(|(_, p)| p)
+        .collect())
+}
+
+fn attribute<T: std::str::FromStr>(node: &roxmltree::Node, name: &str) -> Result<T, EsicupError> {
+    optional_attribute(node, name)?.ok_or_else(|| EsicupError::MissingAttribute {
+        element: node.tag_name().name().to_string(),
+        attribute: name.to_string(),
+    })
+}
+
+fn optional_attribute<T: std::str::FromStr>(
+    node: &roxmltree::Node,
+    name: &str,
+) -> Result<Option<T>, EsicupError> {
+    node.attribute(name)
+        .map(|v| {
+            v.trim()
+                .parse::<T>()
+                .map_err(|_| EsicupError::InvalidAttribute {
+                    attribute: name.to_string(),
+                    value: v.to_string(),
+                })
+        })
+        .transpose()
+}
@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::solution::Solution;
+use jagua_rs::fsize;
+use jagua_rs::geometry::geo_traits::Transformable;
+use jagua_rs::io::parser;
+
+/// Options for the G-code export, see [`compose_gcode`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GcodeOptions {
+    /// Feed rate of the cutting (G1) moves
+    pub feed_rate: fsize,
+    /// Lines written before the first move
+    pub header: Vec<String>,
+    /// Lines written after the last move
+    pub footer: Vec<String>,
+    /// Number of decimals of the coordinates
+    pub decimals: usize,
+}
+
+impl Default for GcodeOptions {
+    fn default() -> Self {
+        Self {
+            feed_rate: 1000.0,
+            //millimeters, absolute coordinates
+            header: vec!["G21".to_string(), "G90".to_string()],
+            //end of program
+            footer: vec!["M2".to_string()],
+            decimals: 3,
+        }
+    }
+}
+
+/// Composes a G-code skeleton to cut the placed items of a solution, in the coordinates of the input file.
+/// Every layout is preceded by a comment, every placed item by a comment stating its origin and rotation (in degrees).
+/// The outline of every item, as defined in the input file (before any polygon simplification), is traced from its first vertex: a rapid (G0) move to it, followed by one cutting (G1) move per vertex, closing the contour.
+/// No tool changes, lead-ins or z-axis moves are emitted, these are up to the header, footer or a post-processor.
+pub fn compose_gcode(solution: &Solution, instance: &Instance, options: &GcodeOptions) -> String {
+    let d = options.decimals;
+    let mut gcode = String::new();
+    for line in &options.header {
+        writeln!(gcode, "{line}").unwrap();
+    }
+    for (i, layout) in solution.layout_snapshots.iter().enumerate() {
+        let bin = &layout.bin;
+        writeln!(gcode, "; layout {i} (bin {})", bin.id).unwrap();
+        let abs_bin_transf = bin.pretransform.clone().inverse();
+        for placed_item in layout.placed_items.values() {
+            let item = instance.item(placed_item.item_id);
+            let abs_transf = parser::internal_to_absolute_transform(
+                &placed_item.d_transf,
+                &item.pretransform,
+                &bin.pretransform,
+            )
+            .decompose();
+            let (o_x, o_y) = abs_transf.translation();
+            writeln!(
+                gcode,
+                "; item {} at ({o_x:.d$}, {o_y:.d$}), rotation {:.d$}",
+                item.id,
+                abs_transf.rotation().to_degrees()
+            )
+            .unwrap();
+
+            //trace the contour of the input file, not its simplification
+            let outline = match &item.original_shape {
+                Some(original_shape) => original_shape.transform_clone(&abs_transf.compose()),
+                None => placed_item.shape.transform_clone(&abs_bin_transf),
+            };
+            let start = outline.points[0];
+            writeln!(gcode, "G0 X{:.d$} Y{:.d$}", start.0, start.1).unwrap();
+            for (j, p) in outline
+                .points
+                .iter()
+                .cycle()
+                .skip(1)
+                .take(outline.number_of_points())
+                .enumerate()
+            {
+                match j {
+                    0 => writeln!(gcode, "G1 X{:.d$} Y{:.d$} F{}", p.0, p.1, options.feed_rate),
+                    _ => writeln!(gcode, "G1 X{:.d$} Y{:.d$}", p.0, p.1),
+                }
+                .unwrap();
+            }
+        }
+    }
+    for line in &options.footer {
+        writeln!(gcode, "{line}").unwrap();
+    }
+    gcode
+}
@@ -1,15 +1,19 @@
-use crate::io::svg_util::SvgDrawOptions;
+use crate::io::svg_util::{SvgDrawOptions, YAxis};
 use crate::io::{svg_export, svg_util};
+use itertools::Itertools;
 use jagua_rs::entities::instances::instance::Instance;
 use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
 use jagua_rs::entities::layout::Layout;
 use jagua_rs::entities::layout::LayoutSnapshot;
+use jagua_rs::entities::solution::Solution;
 use jagua_rs::fsize;
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
 use jagua_rs::geometry::primitives::circle::Circle;
+use jagua_rs::geometry::primitives::point::Point;
 use jagua_rs::geometry::transformation::Transformation;
 use jagua_rs::io::parser;
-use svg::node::element::{Definitions, Group, Title, Use};
-use svg::Document;
+use svg::node::element::{Animate, Definitions, Group, Text, Title, Use};
+use svg::{Document, Node};
 
 pub fn s_layout_to_svg(
     s_layout: &LayoutSnapshot,
@@ -20,7 +24,167 @@ pub fn s_layout_to_svg(
     layout_to_svg(&layout, instance, options)
 }
 
+/// Composes an animation of the `frames` (e.g. from a replay), shown one after the other for `frame_duration` seconds each and repeated indefinitely.
+/// Every frame is drawn with [layout_to_svg] and embedded as a nested SVG, which is only visible during its own time slot (SMIL animation).
+/// The ids within each frame are prefixed with `frame_<i>_`, so references resolve to the elements of their own frame.
+pub fn svg_animation(
+    frames: &[LayoutSnapshot],
+    instance: &Instance,
+    options: SvgDrawOptions,
+    frame_duration: fsize,
+) -> Document {
+    assert!(!frames.is_empty(), "an animation needs at least one frame");
+    let n_frames = frames.len() as fsize;
+
+    let frame_docs = frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let layout = Layout::from_snapshot(frame);
+            layout_to_svg_with_id_prefix(&layout, instance, options, &format!("frame_{i}_"))
+        })
+        .collect_vec();
+
+    //the view box of the first frame is used for the whole animation, the other frames are scaled to fit
+    let view_box = frame_docs[0]
+        .get_attributes()
+        .and_then(|attrs| attrs.get("viewBox"))
+        .cloned()
+        .expect("frame has no view box");
+
+    frame_docs.into_iter().enumerate().fold(
+        Document::new()
+            .set("viewBox", view_box)
+            .set("xmlns:xlink", "http://www.w3.org/1999/xlink"),
+        |animation, (i, frame_doc)| {
+            let visibility = Animate::new()
+                .set("attributeName", "visibility")
+                .set("values", "hidden;visible;hidden")
+                .set(
+                    "keyTimes",
+                    format!(
+                        "0;{};{}",
+                        i as fsize / n_frames,
+                        (i + 1) as fsize / n_frames
+                    ),
+                )
+                .set("calcMode", "discrete")
+                .set("dur", format!("{}s", frame_duration * n_frames))
+                .set("repeatCount", "indefinite");
+            let frame = frame_doc
+                .set("id", format!("frame_{i}"))
+                .set("width", "100%")
+                .set("height", "100%")
+                .set("visibility", "hidden")
+                .add(visibility);
+            animation.add(frame)
+        },
+    )
+}
+
+/// Tiles the layouts of the `solution` on a single sheet, in a grid with `cols` columns, filled row by row.
+/// Every layout is drawn with [layout_to_svg] and embedded as a nested SVG, in a cell sized to fit the largest layout.
+/// Each cell is labeled with the id of its bin and its usage.
+/// The ids within each layout are prefixed with `layout_<i>_`, so references resolve to the elements of their own layout.
+pub fn layouts_to_svg(
+    solution: &Solution,
+    instance: &Instance,
+    options: SvgDrawOptions,
+    cols: usize,
+) -> Document {
+    assert!(cols > 0, "the grid needs at least one column");
+    let layout_docs = solution
+        .layout_snapshots
+        .iter()
+        .enumerate()
+        .map(|(i, sl)| {
+            let layout = Layout::from_snapshot(sl);
+            let doc =
+                layout_to_svg_with_id_prefix(&layout, instance, options, &format!("layout_{i}_"));
+            (sl, doc)
+        })
+        .collect_vec();
+
+    //all cells are as large as the largest view box, with a band above each layout for its label
+    let (cell_width, layout_height) = layout_docs
+        .iter()
+        .map(|(_, doc)| view_box_dims(doc))
+        .fold((0.0, 0.0), |(w, h), (vw, vh)| {
+            (fsize::max(w, vw), fsize::max(h, vh))
+        });
+    let label_height = layout_height * 0.08;
+    let cell_height = layout_height + label_height;
+    let rows = layout_docs.len().div_ceil(cols);
+
+    layout_docs.into_iter().enumerate().fold(
+        Document::new()
+            .set(
+                "viewBox",
+                (
+                    0.0,
+                    0.0,
+                    cell_width * cols as fsize,
+                    cell_height * rows as fsize,
+                ),
+            )
+            .set("xmlns:xlink", "http://www.w3.org/1999/xlink"),
+        |sheet, (i, (sl, layout_doc))| {
+            let (x, y) = (
+                (i % cols) as fsize * cell_width,
+                (i / cols) as fsize * cell_height,
+            );
+            let label = Text::new(format!(
+                "bin {}, usage: {:.3}%",
+                sl.bin.id,
+                sl.usage * 100.0
+            ))
+            .set("x", x + cell_width / 2.0)
+            .set("y", y + label_height * 0.75)
+            .set("font-size", label_height * 0.6)
+            .set("text-anchor", "middle");
+            let layout = layout_doc
+                .set("id", format!("layout_{i}"))
+                .set("x", x)
+                .set("y", y + label_height)
+                .set("width", cell_width)
+                .set("height", layout_height);
+            sheet.add(
+                Group::new()
+                    .set("id", format!("cell_{i}"))
+                    .add(label)
+                    .add(layout),
+            )
+        },
+    )
+}
+
+/// Width and height of the view box of a document
+fn view_box_dims(doc: &Document) -> (fsize, fsize) {
+    let view_box = doc
+        .get_attributes()
+        .and_then(|attrs| attrs.get("viewBox"))
+        .expect("document has no view box")
+        .to_string();
+    let dims = view_box
+        .split([' ', ','])
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse::<fsize>().expect("invalid view box"))
+        .collect_vec();
+    (dims[2], dims[3])
+}
+
 pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptions) -> Document {
+    layout_to_svg_with_id_prefix(layout, instance, options, "")
+}
+
+/// Same as [layout_to_svg], but with `id_prefix` prepended to every element id (and reference to it),
+/// so multiple layouts can be embedded in one document without their ids clashing.
+fn layout_to_svg_with_id_prefix(
+    layout: &Layout,
+    instance: &Instance,
+    options: SvgDrawOptions,
+    id_prefix: &str,
+) -> Document {
     let internal_bin = &layout.bin;
     let inv_bin_transf = internal_bin.pretransform.clone().inverse();
     let bin = parser::pretransform_bin(internal_bin, &inv_bin_transf);
@@ -28,13 +192,14 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
     let vbox = bin.bbox().clone().scale(1.05);
 
     let theme = &options.theme;
+    let decimals = options.round_coordinates;
 
     let stroke_width =
         fsize::min(vbox.width(), vbox.height()) * 0.001 * theme.stroke_width_multiplier;
 
     //draw bin
     let bin_group = {
-        let mut bin_group = Group::new().set("id", format!("bin_{}", bin.id));
+        let mut bin_group = Group::new().set("id", format!("{id_prefix}bin_{}", bin.id));
         let bbox = bin.bbox();
         let title = Title::new(format!(
             "bin, id: {}, bbox: [x_min: {:.3}, y_min: {:.3}, x_max: {:.3}, y_max: {:.3}]",
@@ -44,7 +209,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
         //outer
         bin_group = bin_group
             .add(svg_export::data_to_path(
-                svg_export::simple_polygon_data(&bin.outer),
+                svg_export::simple_polygon_data(&bin.outer, decimals),
                 &[
                     ("fill", &*format!("{}", theme.bin_fill)),
                     ("stroke", "black"),
@@ -53,11 +218,34 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
             ))
             .add(title);
 
+        //margin
+        if let Some(margin) = options.margin {
+            let margin_rect = AARectangle::new(
+                bbox.x_min + margin,
+                bbox.y_min + margin,
+                bbox.x_max - margin,
+                bbox.y_max - margin,
+            );
+            bin_group = bin_group.add(
+                svg_export::data_to_path(
+                    svg_export::aa_rect_data(&margin_rect, decimals),
+                    &[
+                        ("id", &*format!("{id_prefix}bin_margin")),
+                        ("fill", "none"),
+                        ("stroke", "black"),
+                        ("stroke-width", &*format!("{}", stroke_width)),
+                        ("stroke-dasharray", &*format!("{}", 5.0 * stroke_width)),
+                    ],
+                )
+                .add(Title::new(format!("margin: {margin}"))),
+            );
+        }
+
         //holes
         for (hole_idx, hole) in bin.holes.iter().enumerate() {
             bin_group = bin_group.add(
                 svg_export::data_to_path(
-                    svg_export::simple_polygon_data(hole),
+                    svg_export::simple_polygon_data(hole, decimals),
                     &[
                         ("fill", &*format!("{}", theme.hole_fill)),
                         ("stroke", "black"),
@@ -71,7 +259,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
     };
 
     let qz_group = {
-        let mut qz_group = Group::new().set("id", "quality_zones");
+        let mut qz_group = Group::new().set("id", format!("{id_prefix}quality_zones"));
 
         //quality zones
         for qz in bin.quality_zones.iter().rev().flatten() {
@@ -80,7 +268,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
             for qz_shape in qz.zones.iter() {
                 qz_group = qz_group.add(
                     svg_export::data_to_path(
-                        svg_export::simple_polygon_data(qz_shape),
+                        svg_export::simple_polygon_data(qz_shape, decimals),
                         &[
                             ("fill", &*format!("{}", color)),
                             ("fill-opacity", "0.50"),
@@ -109,26 +297,32 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                 internal_item,
                 &internal_item.pretransform.clone().inverse(),
             );
-            let shape = item.shape.as_ref();
+            let exploded_shape = options
+                .explode_gap
+                .map(|gap| svg_export::shrink_towards_centroid(&item.shape, gap / 2.0));
+            let shape = exploded_shape.as_ref().unwrap_or(item.shape.as_ref());
             let color = match item.base_quality {
                 None => theme.item_fill.to_owned(),
                 Some(q) => svg_util::blend_colors(theme.item_fill, theme.qz_fill[q]),
             };
-            item_defs = item_defs.add(Group::new().set("id", format!("item_{}", item.id)).add(
-                svg_export::data_to_path(
-                    svg_export::simple_polygon_data(shape),
-                    &[
-                        ("fill", &*format!("{}", color)),
-                        ("stroke-width", &*format!("{}", stroke_width)),
-                        ("fill-rule", "nonzero"),
-                        ("stroke", "black"),
-                        ("opacity", "0.9"),
-                    ],
-                ),
-            ));
+            item_defs = item_defs.add(
+                Group::new()
+                    .set("id", format!("{id_prefix}item_{}", item.id))
+                    .add(svg_export::data_to_path(
+                        svg_export::simple_polygon_data(shape, decimals),
+                        &[
+                            ("fill", &*format!("{}", color)),
+                            ("stroke-width", &*format!("{}", stroke_width)),
+                            ("fill-rule", "nonzero"),
+                            ("stroke", "black"),
+                            ("opacity", "0.9"),
+                        ],
+                    )),
+            );
 
             if options.surrogate {
-                let mut surrogate_group = Group::new().set("id", format!("surrogate_{}", item.id));
+                let mut surrogate_group =
+                    Group::new().set("id", format!("{id_prefix}surrogate_{}", item.id));
                 let poi_style = [
                     ("fill", "black"),
                     ("fill-opacity", "0.1"),
@@ -158,26 +352,32 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
 
                 for pole in surrogate.poles.iter() {
                     if pole == poi {
-                        surrogate_group = surrogate_group.add(svg_export::circle(pole, &poi_style));
+                        surrogate_group =
+                            surrogate_group.add(svg_export::circle(pole, &poi_style, decimals));
                     }
                     if ff_poles.contains(pole) {
-                        surrogate_group = surrogate_group.add(svg_export::circle(pole, &ff_style));
+                        surrogate_group =
+                            surrogate_group.add(svg_export::circle(pole, &ff_style, decimals));
                     } else {
                         surrogate_group =
-                            surrogate_group.add(svg_export::circle(pole, &no_ff_style));
+                            surrogate_group.add(svg_export::circle(pole, &no_ff_style, decimals));
                     }
                 }
                 for pier in &surrogate.piers {
                     surrogate_group = surrogate_group.add(svg_export::data_to_path(
-                        svg_export::edge_data(pier),
+                        svg_export::edge_data(pier, decimals),
                         &ff_style,
                     ));
                 }
                 surrogate_defs = surrogate_defs.add(surrogate_group)
             }
         }
-        let mut items_group = Group::new().set("id", "items").add(item_defs);
-        let mut surrogate_group = Group::new().set("id", "surrogates").add(surrogate_defs);
+        let mut items_group = Group::new()
+            .set("id", format!("{id_prefix}items"))
+            .add(item_defs);
+        let mut surrogate_group = Group::new()
+            .set("id", format!("{id_prefix}surrogates"))
+            .add(surrogate_defs);
 
         for pi in layout.placed_items().values() {
             let abs_transf = parser::internal_to_absolute_transform(
@@ -191,16 +391,19 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                 abs_transf.decompose()
             ));
             let pi_ref = Use::new()
-                .set("transform", transform_to_svg(&abs_transf))
-                .set("xlink:href", format!("#item_{}", pi.item_id))
+                .set("transform", transform_to_svg(&abs_transf, decimals))
+                .set("xlink:href", format!("#{id_prefix}item_{}", pi.item_id))
                 .add(title);
 
             items_group = items_group.add(pi_ref);
 
             if options.surrogate {
                 let pi_surr_ref = Use::new()
-                    .set("transform", transform_to_svg(&abs_transf))
-                    .set("xlink:href", format!("#surrogate_{}", pi.item_id));
+                    .set("transform", transform_to_svg(&abs_transf, decimals))
+                    .set(
+                        "xlink:href",
+                        format!("#{id_prefix}surrogate_{}", pi.item_id),
+                    );
 
                 surrogate_group = surrogate_group.add(pi_surr_ref);
             }
@@ -215,10 +418,10 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
     let qt_group = match options.quadtree {
         false => None,
         true => {
-            let qt_data = svg_export::quad_tree_data(layout.cde().quadtree(), &[]);
+            let qt_data = svg_export::quad_tree_data(layout.cde().quadtree(), &[], decimals);
             let qt_group = Group::new()
-                .set("id", "quadtree")
-                .set("transform", transform_to_svg(&inv_bin_transf))
+                .set("id", format!("{id_prefix}quadtree"))
+                .set("transform", transform_to_svg(&inv_bin_transf, decimals))
                 .add(svg_export::data_to_path(
                     qt_data.0,
                     &[
@@ -256,8 +459,8 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
         false => None,
         true => {
             let mut hpg_group = Group::new()
-                .set("id", "haz_prox_grid")
-                .set("transform", transform_to_svg(&inv_bin_transf));
+                .set("id", format!("{id_prefix}haz_prox_grid"))
+                .set("transform", transform_to_svg(&inv_bin_transf, decimals));
             let hpg = layout.cde().haz_prox_grid().unwrap();
             for hp_cell in hpg.grid.cells.iter().flatten() {
                 let center = hp_cell.centroid;
@@ -265,7 +468,12 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                 let color = if prox == 0.0 { "red" } else { "blue" };
 
                 hpg_group = hpg_group
-                    .add(svg_export::point(center, Some(color), Some(stroke_width)))
+                    .add(svg_export::point(
+                        center,
+                        Some(color),
+                        Some(stroke_width),
+                        decimals,
+                    ))
                     .add(svg_export::circle(
                         &Circle::new(center, prox),
                         &[
@@ -273,33 +481,66 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                             ("stroke", color),
                             ("stroke-width", &*format!("{}", stroke_width / 2.0)),
                         ],
+                        decimals,
                     ));
             }
             Some(hpg_group)
         }
     };
 
-    let vbox_svg = (vbox.x_min, vbox.y_min, vbox.width(), vbox.height());
+    //map the view box to the coordinate frame of the drawing (subtracting from zero avoids writing negative zeros)
+    let Point(o_x, o_y) = options.origin;
+    let (vbox_y_min, frame_transform) = match options.y_axis {
+        YAxis::Down => (
+            vbox.y_min - o_y,
+            format!("translate({} {})", 0.0 - o_x, 0.0 - o_y),
+        ),
+        YAxis::Up => (
+            o_y - vbox.y_max,
+            format!("matrix(1 0 0 -1 {} {})", 0.0 - o_x, o_y),
+        ),
+    };
+    let vbox_svg = (
+        svg_export::round_coord(vbox.x_min - o_x, decimals),
+        svg_export::round_coord(vbox_y_min, decimals),
+        svg_export::round_coord(vbox.width(), decimals),
+        svg_export::round_coord(vbox.height(), decimals),
+    );
 
     let optionals = [surrogate_group, qt_group, hpg_group]
         .into_iter()
         .flatten()
-        .fold(Group::new().set("id", "optionals"), |g, opt| g.add(opt));
+        .fold(
+            Group::new().set("id", format!("{id_prefix}optionals")),
+            |g, opt| g.add(opt),
+        );
 
-    Document::new()
+    let content = [bin_group, items_group, qz_group, optionals];
+    let document = Document::new()
         .set("viewBox", vbox_svg)
-        .set("xmlns:xlink", "http://www.w3.org/1999/xlink")
-        .add(bin_group)
-        .add(items_group)
-        .add(qz_group)
-        .add(optionals)
+        .set("xmlns:xlink", "http://www.w3.org/1999/xlink");
+
+    //the coordinates of the instance are used as they are, unless another frame is requested
+    match options.y_axis == YAxis::Down && options.origin == Point::default() {
+        true => content.into_iter().fold(document, |doc, g| doc.add(g)),
+        false => {
+            let frame = Group::new()
+                .set("id", format!("{id_prefix}frame"))
+                .set("transform", frame_transform);
+            document.add(content.into_iter().fold(frame, |frame, g| frame.add(g)))
+        }
+    }
 }
 
-fn transform_to_svg(t: &Transformation) -> String {
+fn transform_to_svg(t: &Transformation, decimals: Option<u32>) -> String {
     //https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/transform
     //operations are effectively applied from right to left
     let dt = t.decompose();
     let (tx, ty) = dt.translation();
-    let r = dt.rotation().to_degrees();
+    let (tx, ty) = (
+        svg_export::round_coord(tx, decimals),
+        svg_export::round_coord(ty, decimals),
+    );
+    let r = svg_export::round_coord(dt.rotation().to_degrees(), decimals);
     format!("translate({tx} {ty}), rotate({r})")
 }
@@ -6,17 +6,35 @@ use std::path::Path;
 use log::{info, log, Level, LevelFilter};
 use svg::Document;
 
-use jagua_rs::io::json_instance::JsonInstance;
+use jagua_rs::collision_detection::cd_engine::CDEngine;
+use jagua_rs::entities::bin::Bin;
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::layout::{Layout, LayoutSnapshot};
+use jagua_rs::entities::solution::Solution;
+use jagua_rs::fsize;
+use jagua_rs::geometry::geo_traits::Transformable;
+use jagua_rs::geometry::primitives::point::Point;
+use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+use jagua_rs::io::json_instance::{
+    JsonBin, JsonInstance, JsonPoly, JsonQualityZone, JsonShape, JsonSimplePoly,
+};
 
+use crate::io::gcode::GcodeOptions;
 use crate::io::json_output::JsonOutput;
+use crate::io::svg_util::SvgDrawOptions;
 use crate::EPOCH;
 
 pub mod cli;
+pub mod esicup;
+pub mod gcode;
 pub mod json_output;
 pub mod layout_to_svg;
 pub mod svg_export;
 pub mod svg_util;
 
+/// Time (in seconds) each frame is shown in an SVG animation, see [`write_svg_animation`]
+pub const SVG_FRAME_DURATION: fsize = 0.25;
+
 pub fn read_json_instance(path: &Path) -> JsonInstance {
     let file = File::open(path)
         .unwrap_or_else(|err| panic!("could not open instance file: {}, {}", path.display(), err));
@@ -43,6 +61,106 @@ pub fn write_json_output(json_output: &JsonOutput, path: &Path) {
     );
 }
 
+/// Reads an instance in the ESICUP nesting XML format, see [`esicup::parse_esicup_instance`].
+/// The instance is named after the file.
+pub fn read_esicup_instance(path: &Path) -> JsonInstance {
+    let xml = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("could not open instance file: {}, {}", path.display(), err));
+    let name = path
+        .file_stem()
+        .map_or(String::new(), |s| s.to_string_lossy().into_owned());
+    esicup::parse_esicup_instance(&name, &xml)
+        .unwrap_or_else(|err| panic!("could not parse instance file: {}, {}", path.display(), err))
+}
+
+/// Writes the instance and solution in the ESICUP nesting XML format, see [`esicup::compose_esicup_output`].
+pub fn write_esicup_output(solution: &Solution, instance: &Instance, path: &Path) {
+    fs::write(path, esicup::compose_esicup_output(solution, instance))
+        .unwrap_or_else(|_| panic!("could not write solution file: {}", path.display()));
+    info!(
+        "Solution XML written to file://{}",
+        fs::canonicalize(path)
+            .expect("could not canonicalize path")
+            .to_str()
+            .unwrap()
+    );
+}
+
+/// Writes a G-code skeleton to cut the placed items of the solution, see [`gcode::compose_gcode`].
+pub fn write_gcode(solution: &Solution, instance: &Instance, path: &Path, options: &GcodeOptions) {
+    fs::write(path, gcode::compose_gcode(solution, instance, options))
+        .unwrap_or_else(|_| panic!("could not write G-code file: {}", path.display()));
+    info!(
+        "Solution G-code written to file://{}",
+        fs::canonicalize(path)
+            .expect("could not canonicalize path")
+            .to_str()
+            .unwrap()
+    );
+}
+
+/// Describes the free space of `layout` as a bin of `instance`, so the items of another material can be packed into the same region.
+/// Returns a copy of `instance` with a single bin, replacing its bins or strip: the bin of the layout in the coordinates of the input file,
+/// with every placed item turned into a hole (see [`Layout::to_sub_instance`]).
+/// Insets of the bin cannot be represented in the JSON format and are not carried over.
+pub fn free_space_to_instance(layout: &Layout, instance: &JsonInstance) -> JsonInstance {
+    //undo the pretransformation of the bin
+    let abs_transf = layout.bin.pretransform.clone().inverse();
+    let to_json_poly = |shape: &SimplePolygon| {
+        JsonSimplePoly(
+            shape
+                .transform_clone(&abs_transf)
+                .points
+                .iter()
+                .map(|p| (p.0, p.1))
+                .collect(),
+        )
+    };
+
+    let holes = layout
+        .bin
+        .holes
+        .iter()
+        .map(|h| h.as_ref())
+        .chain(
+            layout
+                .placed_items()
+                .values()
+                .filter(|pi| !pi.subtract)
+                .map(|pi| pi.shape.as_ref()),
+        )
+        .map(to_json_poly)
+        .collect();
+    let zones = layout
+        .bin
+        .quality_zones
+        .iter()
+        .flatten()
+        .flat_map(|qz| {
+            qz.zones.iter().map(|zone| JsonQualityZone {
+                quality: qz.quality,
+                shape: JsonShape::SimplePolygon(to_json_poly(zone)),
+            })
+        })
+        .collect();
+
+    let bin = JsonBin {
+        cost: layout.bin.value,
+        stock: Some(1),
+        shape: JsonShape::Polygon(JsonPoly {
+            outer: to_json_poly(&layout.bin.outer),
+            inner: holes,
+        }),
+        zones,
+    };
+
+    JsonInstance {
+        bins: Some(vec![bin]),
+        strip: None,
+        ..instance.clone()
+    }
+}
+
 pub fn write_svg(document: &Document, path: &Path) {
     svg::save(path, document).expect("failed to write svg file");
     info!(
@@ -54,6 +172,68 @@ pub fn write_svg(document: &Document, path: &Path) {
     );
 }
 
+/// Writes an animation of the `frames` to a single SVG file, see [`layout_to_svg::svg_animation`].
+pub fn write_svg_animation(
+    frames: &[LayoutSnapshot],
+    instance: &Instance,
+    options: SvgDrawOptions,
+    path: &Path,
+) {
+    let document = layout_to_svg::svg_animation(frames, instance, options, SVG_FRAME_DURATION);
+    write_svg(&document, path);
+}
+
+/// Writes a PNG heatmap of the proximity to the closest hazard (see [`CDEngine::proximity_at`]) over the bin's bounding box.
+/// Each pixel is sampled at its center. Pixels inside a hazard are black,
+/// the others range from red (close to a hazard) to green (furthest away from any hazard).
+/// `resolution` is the (width, height) of the image in pixels.
+pub fn write_proximity_heatmap(cde: &CDEngine, bin: &Bin, resolution: (u32, u32), path: &Path) {
+    let (width, height) = resolution;
+    assert!(
+        width > 0 && height > 0,
+        "heatmap resolution must be non-zero"
+    );
+    let bbox = bin.bbox();
+    let (dx, dy) = (
+        bbox.width() / width as fsize,
+        bbox.height() / height as fsize,
+    );
+
+    //row 0 of the image is the top of the bin
+    let proximities = (0..height)
+        .flat_map(|row| (0..width).map(move |col| (row, col)))
+        .map(|(row, col)| {
+            let x = bbox.x_min + (col as fsize + 0.5) * dx;
+            let y = bbox.y_max - (row as fsize + 0.5) * dy;
+            cde.proximity_at(&Point(x, y), &[])
+        })
+        .collect::<Vec<fsize>>();
+
+    let max_prox = proximities.iter().cloned().fold(0.0, fsize::max);
+    let pixels = proximities
+        .iter()
+        .flat_map(|&prox| match prox {
+            0.0 => [0, 0, 0],
+            _ => {
+                let t = prox / max_prox;
+                [(255.0 * (1.0 - t)) as u8, (255.0 * t) as u8, 0]
+            }
+        })
+        .collect::<Vec<u8>>();
+
+    image::RgbImage::from_raw(width, height, pixels)
+        .expect("pixel buffer does not match the image dimensions")
+        .save(path)
+        .expect("failed to write heatmap file");
+    info!(
+        "Proximity heatmap written to file://{}",
+        fs::canonicalize(path)
+            .expect("could not canonicalize path")
+            .to_str()
+            .unwrap()
+    );
+}
+
 pub fn init_logger(level_filter: LevelFilter) {
     fern::Dispatch::new()
         // Perform allocation-free log formatting
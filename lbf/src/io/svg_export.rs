@@ -4,26 +4,66 @@ use svg::node::element::{Circle, Path};
 use jagua_rs::collision_detection::hazard::HazardEntity;
 use jagua_rs::collision_detection::quadtree::qt_hazard::QTHazPresence;
 use jagua_rs::collision_detection::quadtree::qt_node::QTNode;
+use jagua_rs::geometry::geo_traits::Shape;
 use jagua_rs::geometry::primitives::edge::Edge;
 use jagua_rs::geometry::primitives::point::Point;
 use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
 use jagua_rs::{fsize, geometry};
 
-pub fn simple_polygon_data(s_poly: &SimplePolygon) -> Data {
-    let mut data = Data::new().move_to::<(fsize, fsize)>(s_poly.get_point(0).into());
+/// Rounds a coordinate to `decimals` decimal places. Returns the coordinate unchanged if `decimals` is `None`.
+pub fn round_coord(value: fsize, decimals: Option<u32>) -> fsize {
+    match decimals {
+        None => value,
+        Some(d) => {
+            let factor = (10.0 as fsize).powi(d as i32);
+            (value * factor).round() / factor
+        }
+    }
+}
+
+fn round_point(Point(x, y): Point, decimals: Option<u32>) -> (fsize, fsize) {
+    (round_coord(x, decimals), round_coord(y, decimals))
+}
+
+pub fn simple_polygon_data(s_poly: &SimplePolygon, decimals: Option<u32>) -> Data {
+    let mut data = Data::new().move_to(round_point(s_poly.get_point(0), decimals));
     for i in 1..s_poly.number_of_points() {
-        data = data.line_to::<(fsize, fsize)>(s_poly.get_point(i).into());
+        data = data.line_to(round_point(s_poly.get_point(i), decimals));
     }
     data.close()
 }
 
-pub fn quad_tree_data(qt_root: &QTNode, irrelevant_hazards: &[HazardEntity]) -> (Data, Data, Data) {
+/// Moves every vertex of the polygon `distance` towards its centroid, but at most halfway, so the polygon never collapses.
+pub fn shrink_towards_centroid(s_poly: &SimplePolygon, distance: fsize) -> SimplePolygon {
+    let Point(cx, cy) = s_poly.centroid();
+    let points = s_poly
+        .points
+        .iter()
+        .map(|&Point(x, y)| {
+            let (dx, dy) = (cx - x, cy - y);
+            let d = (dx * dx + dy * dy).sqrt();
+            let t = match d > 0.0 {
+                true => fsize::min(distance / d, 0.5),
+                false => 0.0,
+            };
+            Point(x + t * dx, y + t * dy)
+        })
+        .collect();
+    SimplePolygon::new(points)
+}
+
+pub fn quad_tree_data(
+    qt_root: &QTNode,
+    irrelevant_hazards: &[HazardEntity],
+    decimals: Option<u32>,
+) -> (Data, Data, Data) {
     qt_node_data(
         qt_root,
         Data::new(),
         Data::new(),
         Data::new(),
         irrelevant_hazards,
+        decimals,
     )
 }
 
@@ -33,6 +73,7 @@ fn qt_node_data(
     mut data_ph: Data, //partial hazards data
     mut data_nh: Data, //no hazards data
     irrelevant_hazards: &[HazardEntity],
+    decimals: Option<u32>,
 ) -> (Data, Data, Data) {
     //Only draw qt_nodes that do not have a child
 
@@ -43,7 +84,14 @@ fn qt_node_data(
         (true, Some(_)) => {
             //not a leaf node, go to children
             for child in qt_node.children.as_ref().unwrap().iter() {
-                let data = qt_node_data(child, data_eh, data_ph, data_nh, irrelevant_hazards);
+                let data = qt_node_data(
+                    child,
+                    data_eh,
+                    data_ph,
+                    data_nh,
+                    irrelevant_hazards,
+                    decimals,
+                );
                 data_eh = data.0;
                 data_ph = data.1;
                 data_nh = data.2;
@@ -52,13 +100,7 @@ fn qt_node_data(
         (true, None) | (false, _) => {
             //leaf node, draw it
             let rect = &qt_node.bbox;
-            let draw = |data: Data| -> Data {
-                data.move_to((rect.x_min, rect.y_min))
-                    .line_to((rect.x_max, rect.y_min))
-                    .line_to((rect.x_max, rect.y_max))
-                    .line_to((rect.x_min, rect.y_max))
-                    .close()
-            };
+            let draw = |data: Data| -> Data { append_aa_rect(data, rect, decimals) };
 
             match qt_node.hazards.strongest(irrelevant_hazards) {
                 Some(ch) => match ch.presence {
@@ -82,36 +124,57 @@ pub fn data_to_path(data: Data, params: &[(&str, &str)]) -> Path {
     path.set("d", data)
 }
 
-pub fn point(Point(x, y): Point, fill: Option<&str>, rad: Option<fsize>) -> Circle {
+pub fn point(
+    Point(x, y): Point,
+    fill: Option<&str>,
+    rad: Option<fsize>,
+    decimals: Option<u32>,
+) -> Circle {
     Circle::new()
-        .set("cx", x)
-        .set("cy", y)
+        .set("cx", round_coord(x, decimals))
+        .set("cy", round_coord(y, decimals))
         .set("r", rad.unwrap_or(0.5))
         .set("fill", fill.unwrap_or("black"))
 }
 
-pub fn circle(circle: &geometry::primitives::circle::Circle, params: &[(&str, &str)]) -> Circle {
+pub fn circle(
+    circle: &geometry::primitives::circle::Circle,
+    params: &[(&str, &str)],
+    decimals: Option<u32>,
+) -> Circle {
     let mut circle = Circle::new()
-        .set("cx", circle.center.0)
-        .set("cy", circle.center.1)
-        .set("r", circle.radius);
+        .set("cx", round_coord(circle.center.0, decimals))
+        .set("cy", round_coord(circle.center.1, decimals))
+        .set("r", round_coord(circle.radius, decimals));
     for param in params {
         circle = circle.set(param.0, param.1)
     }
     circle
 }
 
-pub fn edge_data(edge: &Edge) -> Data {
+pub fn edge_data(edge: &Edge, decimals: Option<u32>) -> Data {
     Data::new()
-        .move_to((edge.start.0, edge.start.1))
-        .line_to((edge.end.0, edge.end.1))
+        .move_to(round_point(edge.start, decimals))
+        .line_to(round_point(edge.end, decimals))
 }
 
-pub fn aa_rect_data(rect: &geometry::primitives::aa_rectangle::AARectangle) -> Data {
-    Data::new()
-        .move_to((rect.x_min, rect.y_min))
-        .line_to((rect.x_max, rect.y_min))
-        .line_to((rect.x_max, rect.y_max))
-        .line_to((rect.x_min, rect.y_max))
+pub fn aa_rect_data(
+    rect: &geometry::primitives::aa_rectangle::AARectangle,
+    decimals: Option<u32>,
+) -> Data {
+    append_aa_rect(Data::new(), rect, decimals)
+}
+
+fn append_aa_rect(
+    data: Data,
+    rect: &geometry::primitives::aa_rectangle::AARectangle,
+    decimals: Option<u32>,
+) -> Data {
+    let (x_min, y_min) = round_point(Point(rect.x_min, rect.y_min), decimals);
+    let (x_max, y_max) = round_point(Point(rect.x_max, rect.y_max), decimals);
+    data.move_to((x_min, y_min))
+        .line_to((x_max, y_min))
+        .line_to((x_max, y_max))
+        .line_to((x_min, y_max))
         .close()
 }
@@ -5,6 +5,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use jagua_rs::entities::quality_zone::N_QUALITIES;
 use jagua_rs::fsize;
+use jagua_rs::geometry::primitives::point::Point;
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Copy, Default)]
 pub struct SvgDrawOptions {
@@ -20,6 +21,32 @@ pub struct SvgDrawOptions {
     ///Draw the fail fast surrogate on top of each item
     #[serde(default)]
     pub surrogate: bool,
+    ///Draw a dashed safe-area rectangle, inset from the bin's bounding box by this margin
+    #[serde(default)]
+    pub margin: Option<fsize>,
+    ///Round all emitted coordinates to this number of decimal places, if defined
+    #[serde(default)]
+    pub round_coordinates: Option<u32>,
+    ///Draw the items with a visual gap of roughly this width between them, by moving each vertex `explode_gap / 2` towards the item's centroid.
+    ///Only affects the drawing, not the placements
+    #[serde(default)]
+    pub explode_gap: Option<fsize>,
+    ///Direction in which the y-axis of the instance points in the drawing
+    #[serde(default)]
+    pub y_axis: YAxis,
+    ///Point of the instance which is drawn at the origin of the SVG coordinate frame
+    #[serde(default)]
+    pub origin: Point,
+}
+
+///Direction of the y-axis in a drawing
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Copy, Default)]
+pub enum YAxis {
+    ///The y-axis points up, as in the mathematical convention. The drawing is mirrored vertically with respect to the SVG coordinates
+    Up,
+    ///The y-axis points down, as in the screen coordinates of SVG. The coordinates of the instance are used as they are
+    #[default]
+    Down,
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Copy)]
@@ -1,23 +1,86 @@
 use serde::{Deserialize, Serialize};
 
 use jagua_rs::fsize;
-use jagua_rs::util::config::{CDEConfig, SPSurrogateConfig};
+use jagua_rs::util::config;
+use jagua_rs::util::config::{CDEConfig, CdePreset};
+use jagua_rs::util::polygon_simplification::PolySimplDirection;
 
 use crate::io::svg_util::SvgDrawOptions;
 
 /// Configuration for the LBF optimizer
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct LBFConfig {
-    /// Configuration of the Collision Detection Engine
+    /// Configuration of the Collision Detection Engine, either the name of a preset or its fields
+    #[serde(deserialize_with = "config::deserialize_preset_or_fields")]
     pub cde_config: CDEConfig,
     /// Max deviation from the original polygon area as a fraction. If undefined, the algorithm will run without simplification
     pub poly_simpl_tolerance: Option<fsize>,
+    /// Whether polygons may be simplified inward, see [PolySimplDirection]
+    #[serde(default)]
+    pub poly_simpl_direction: PolySimplDirection,
     /// Seed for the PRNG. If undefined, the algorithm will run in non-deterministic mode using entropy
     pub prng_seed: Option<u64>,
     /// Total budget of samples per item per layout
     pub n_samples: usize,
-    /// Fraction of `n_samples_per_item` used for the local search sampler, the rest is sampled uniformly.
+    /// Fraction of `n_samples` used for the local search sampler, the rest is sampled uniformly.
     pub ls_frac: f32,
+    /// Maximum number of samples spent on placing a single item, summed over all open layouts it is tried in.
+    /// When it runs out without a valid placement, a new bin is opened or the strip is extended, which get a fresh budget.
+    /// Bounds the effort spent on items that hardly fit anywhere. If undefined, each open layout is sampled `n_samples` times
+    #[serde(default)]
+    pub max_samples_per_item: Option<usize>,
+    /// Whether to run a bottom-left compaction pass after all items are placed
+    #[serde(default)]
+    pub compact: bool,
+    /// Maximum number of items per layout (only applies to bin packing problems). If undefined, there is no limit
+    #[serde(default)]
+    pub max_items_per_layout: Option<usize>,
+    /// Whether to place items with a maximal rectangles algorithm when all items and bins are rectangular.
+    /// Falls back to the general sampling approach otherwise
+    #[serde(default)]
+    pub rect_packing: bool,
+    /// Cost added per radian an item is rotated away from its nominal rotation.
+    /// Favors un-rotated placements among similar ones, 0 disables the penalty
+    #[serde(default)]
+    pub rotation_penalty: fsize,
+    /// Cost added per quality level an item requires, for the fraction of the item lying in inferior quality zones it is allowed in.
+    /// Steers demanding items towards the better regions of the bin, 0 disables the penalty
+    #[serde(default)]
+    pub quality_penalty: fsize,
+    /// Strength of the bias toward the bottom-left when sampling positions, see [BiasedSampler](crate::samplers::biased_rect_sampler::BiasedSampler).
+    /// 0 samples uniformly within the eligible cells of the hazard proximity grid
+    #[serde(default)]
+    pub sampling_bias: fsize,
+    /// Criterion to decide whether a sampled placement replaces the best one found so far
+    #[serde(default)]
+    pub acceptance: Acceptance,
+    /// Criterion to choose which bin to open when an item does not fit in any of the open ones (only applies to bin packing problems)
+    #[serde(default)]
+    pub bin_objective: BinObjective,
+    /// Step of the grid to which the translations of the placements are snapped in the output, e.g. the resolution of the cutting machine.
+    /// Placements are only snapped if they remain collision-free. If undefined, the exact translations are written
+    #[serde(default)]
+    pub output_grid: Option<fsize>,
+    /// Whether the first sample of an item in every layout uses the allowed rotation which minimizes the item's width
+    /// (see [Item::min_footprint_rotation](jagua_rs::entities::item::Item::min_footprint_rotation)), instead of a random one.
+    /// Only affects items which can be rotated
+    #[serde(default)]
+    pub min_footprint_guess: bool,
+    /// How the rotation of items with a discrete set of allowed rotations is chosen
+    #[serde(default)]
+    pub rotation_strategy: RotationStrategy,
+    /// Step by which the strip is grown whenever an item cannot be placed (only applies to strip packing problems).
+    /// If defined, the strip starts out empty and is grown to fit every item, see [SPProblem::grow_to_fit](jagua_rs::entities::problems::strip_packing::SPProblem::grow_to_fit).
+    /// If undefined, the strip starts at an estimated width and is extended by 10% whenever an item cannot be placed
+    #[serde(default)]
+    pub strip_growth_step: Option<fsize>,
+    /// Maximum number of solutions kept in memory when solving with multiple seeds (see [solve_pareto](crate::lbf_optimizer::solve_pareto)),
+    /// only the best ones are retained. If undefined, all non-dominated solutions are kept
+    #[serde(default)]
+    pub max_stored_solutions: Option<usize>,
+    /// Algorithm used to place the items
+    #[serde(default)]
+    pub solver: Solver,
     /// Optional SVG drawing options
     #[serde(default)]
     pub svg_draw_options: SvgDrawOptions,
@@ -26,21 +89,74 @@ pub struct LBFConfig {
 impl Default for LBFConfig {
     fn default() -> Self {
         Self {
-            cde_config: CDEConfig {
-                quadtree_depth: 5,
-                hpg_n_cells: 2000,
-                item_surrogate_config: SPSurrogateConfig {
-                    pole_coverage_goal: 0.9,
-                    max_poles: 10,
-                    n_ff_poles: 2,
-                    n_ff_piers: 0,
-                },
-            },
+            cde_config: CDEConfig::preset(CdePreset::Balanced),
             poly_simpl_tolerance: Some(0.001),
+            poly_simpl_direction: PolySimplDirection::OutwardOnly,
             prng_seed: Some(0),
             n_samples: 5000,
             ls_frac: 0.2,
+            max_samples_per_item: None,
+            compact: false,
+            max_items_per_layout: None,
+            rect_packing: false,
+            rotation_penalty: 0.0,
+            quality_penalty: 0.0,
+            sampling_bias: 0.0,
+            acceptance: Acceptance::Greedy,
+            bin_objective: BinObjective::CheapestBin,
+            output_grid: None,
+            min_footprint_guess: false,
+            rotation_strategy: RotationStrategy::Sample,
+            strip_growth_step: None,
+            max_stored_solutions: None,
+            solver: Solver::Lbf,
             svg_draw_options: SvgDrawOptions::default(),
         }
     }
 }
+
+/// Criterion to decide whether a sampled placement replaces the best one found so far, while searching for a placement of an item
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum Acceptance {
+    /// Only placements with a lower cost are accepted
+    #[default]
+    Greedy,
+    /// Placements with a higher cost are also accepted, with probability `exp(-Δcost / t)`.
+    /// The temperature `t` (in units of [LBFPlacingCost](crate::lbf_cost::LBFPlacingCost)) starts at `t0`
+    /// and is multiplied by `cooling` after every placed item
+    SimulatedAnnealing { t0: fsize, cooling: fsize },
+}
+
+/// Criterion to choose which bin to open for an item which does not fit in any of the open layouts
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinObjective {
+    /// The cheapest bin which can hold the item
+    #[default]
+    CheapestBin,
+    /// The bin which minimizes the estimated total cost of producing all remaining items:
+    /// its cost times the number of such bins needed to hold their combined area.
+    /// Prefers one large bin over several cheaper small ones if they would cost more in total
+    TotalCost,
+}
+
+/// Strategy to choose the rotation of items which can only be placed in a discrete set of rotations
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RotationStrategy {
+    /// Rotations are sampled along with the positions
+    #[default]
+    Sample,
+    /// The best position is searched for every allowed rotation separately, with the full sample budget each,
+    /// and the overall cheapest placement is kept. Multiplies the work per item by the number of allowed rotations
+    Sweep,
+}
+
+/// Algorithm used to place the items
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Solver {
+    /// Left-bottom-fill: every item is placed at the cheapest position found by sampling
+    #[default]
+    Lbf,
+    /// First fit decreasing on the bounding boxes of the items, see [ffd](crate::ffd).
+    /// Fast but leaves a lot of space unused, intended as a baseline to compare against
+    FirstFitDecreasing,
+}
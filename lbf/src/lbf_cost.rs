@@ -1,8 +1,8 @@
 use ordered_float::NotNan;
 
-use jagua_rs::fsize;
 use jagua_rs::geometry::geo_traits::Shape;
 use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+use jagua_rs::{fsize, PI};
 
 const X_MULTIPLIER: fsize = 10.0;
 
@@ -22,4 +22,28 @@ impl LBFPlacingCost {
     pub fn from_shape(shape: &SimplePolygon) -> Self {
         LBFPlacingCost::new(shape.bbox().x_max, shape.bbox().y_max)
     }
+
+    pub fn value(&self) -> fsize {
+        self.0.into_inner()
+    }
+
+    /// Adds `penalty` per radian the `rotation` deviates from the nominal rotation (0) of the item.
+    pub fn with_rotation_penalty(self, rotation: fsize, penalty: fsize) -> Self {
+        if penalty == 0.0 {
+            return self;
+        }
+        let rotation = rotation.rem_euclid(2.0 * PI);
+        let angular_distance = fsize::min(rotation, 2.0 * PI - rotation);
+        LBFPlacingCost(self.0 + penalty * angular_distance)
+    }
+
+    /// Adds `penalty` per quality level the item requires (`base_quality`), scaled by the fraction of the item lying in inferior quality zones.
+    pub fn with_quality_penalty(
+        self,
+        base_quality: usize,
+        inferior_fraction: fsize,
+        penalty: fsize,
+    ) -> Self {
+        LBFPlacingCost(self.0 + penalty * base_quality as fsize * inferior_fraction)
+    }
 }
@@ -1,18 +1,21 @@
-use std::cmp::{Ordering, Reverse};
+use std::cmp::Reverse;
 use std::time::Instant;
 
 use itertools::Itertools;
-use log::{debug, info};
+use log::{debug, info, warn};
 use ordered_float::NotNan;
-use rand::prelude::SmallRng;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use thousands::Separable;
 
+use jagua_rs::collision_detection::hazard::HazardEntity;
 use jagua_rs::collision_detection::hazard_filter;
+use jagua_rs::entities::bin::Bin;
 use jagua_rs::entities::instances::instance::Instance;
 use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::instances::strip_packing::PackingAxis;
 use jagua_rs::entities::item::Item;
 use jagua_rs::entities::layout::Layout;
+use jagua_rs::entities::pareto_archive::{ParetoArchive, SolutionObjective};
 use jagua_rs::entities::placing_option::PlacingOption;
 use jagua_rs::entities::problems::bin_packing::BPProblem;
 use jagua_rs::entities::problems::problem::Problem;
@@ -21,49 +24,158 @@ use jagua_rs::entities::problems::strip_packing::SPProblem;
 use jagua_rs::entities::solution::Solution;
 use jagua_rs::fsize;
 use jagua_rs::geometry::convex_hull::convex_hull_from_points;
-use jagua_rs::geometry::geo_traits::{Shape, TransformableFrom};
+use jagua_rs::geometry::d_transformation::DTransformation;
+use jagua_rs::geometry::geo_enums::AllowedRotation;
+use jagua_rs::geometry::geo_traits::{CollidesWith, Shape, Transformable, TransformableFrom};
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::geometry::primitives::point::Point;
 use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+use jagua_rs::geometry::transformation::Transformation;
 
-use crate::lbf_config::LBFConfig;
+use crate::ffd;
+use crate::lbf_config::{Acceptance, BinObjective, LBFConfig, RotationStrategy, Solver};
 use crate::lbf_cost::LBFPlacingCost;
+use crate::rect_packer;
+use crate::rect_packer::{MaxRects, MaxRectsHeuristic, RECT_PACKING_GAP};
+use crate::rng::LBFRng;
 use crate::samplers::hpg_sampler::HPGSampler;
 use crate::samplers::ls_sampler::LSSampler;
+use crate::solve_report::SolveReport;
 
 //limits the number of items to be placed, for debugging purposes
 pub const ITEM_LIMIT: usize = usize::MAX;
 
+/// Maximum number of passes over all placed items during compaction
+pub const COMPACTION_MAX_ITERATIONS: usize = 20;
+
+/// Minimum distance (as a fraction of the item's diameter) a slide has to cover to be accepted during compaction.
+/// Only accepting strict improvements guarantees the compaction cannot oscillate.
+pub const COMPACTION_MIN_SLIDE: fsize = 1e-4;
+
+/// Maximum number of steps taken to slide an item over its full distance, see [max_slide]
+const MAX_SLIDE_STEPS: usize = 1000;
+
+/// Number of bisection steps used to refine the maximum slide distance
+const SLIDE_BISECTION_STEPS: usize = 20;
+
+/// Items which take this many times longer per placement than the average item are reported after solving
+const DISPROPORTIONATE_SAMPLING_FACTOR: f64 = 5.0;
+
+/// Progress of an ongoing [LBFOptimizer::solve], reported after every placed item
+#[derive(Debug, Clone, Copy)]
+pub struct LBFProgress {
+    pub n_items_placed: usize,
+    pub n_items_total: usize,
+    /// Usage of the layouts at the moment of reporting
+    pub usage: fsize,
+    /// Number of placements accepted so far while being worse than the best one found, see [Acceptance]
+    pub n_worse_accepted: usize,
+}
+
+/// State of the [Acceptance] criterion throughout an [LBFOptimizer::solve]
+#[derive(Debug, Clone, Copy)]
+pub struct AcceptanceState {
+    pub acceptance: Acceptance,
+    /// Current temperature, only relevant for [Acceptance::SimulatedAnnealing]
+    pub temperature: fsize,
+    /// Number of placements accepted while being worse than the best one found
+    pub n_worse_accepted: usize,
+}
+
+impl AcceptanceState {
+    pub fn new(acceptance: Acceptance) -> Self {
+        let temperature = match acceptance {
+            Acceptance::Greedy => 0.0,
+            Acceptance::SimulatedAnnealing { t0, .. } => t0,
+        };
+        Self {
+            acceptance,
+            temperature,
+            n_worse_accepted: 0,
+        }
+    }
+
+    /// Whether a sample with `cost` is worth replacing the best sample with `best_cost`, if it turns out to be valid.
+    /// Samples which are not better are only considered during simulated annealing, with a probability decreasing with the temperature.
+    pub fn considers(
+        &self,
+        cost: LBFPlacingCost,
+        best_cost: LBFPlacingCost,
+        rng: &mut impl Rng,
+    ) -> bool {
+        if cost < best_cost {
+            return true;
+        }
+        match self.acceptance {
+            Acceptance::Greedy => false,
+            Acceptance::SimulatedAnnealing { .. } => {
+                let delta = cost.value() - best_cost.value();
+                self.temperature > 0.0 && rng.gen::<fsize>() < (-delta / self.temperature).exp()
+            }
+        }
+    }
+
+    /// Lowers the temperature, after an item has been placed.
+    pub fn cool_down(&mut self) {
+        if let Acceptance::SimulatedAnnealing { cooling, .. } = self.acceptance {
+            self.temperature *= cooling;
+        }
+    }
+}
+
 pub struct LBFOptimizer {
     pub instance: Instance,
     pub problem: Problem,
     pub config: LBFConfig,
-    /// SmallRng is a fast, non-cryptographic PRNG <https://rust-random.github.io/book/guide-rngs.html>
-    pub rng: SmallRng,
+    /// Seedable PRNG, a given seed yields the same solution on every platform
+    pub rng: LBFRng,
     pub sample_counter: usize,
+    pub acceptance: AcceptanceState,
+    /// Sampling effort spent per item, accumulated over all solves (like `sample_counter`)
+    pub report: SolveReport,
 }
 
 impl LBFOptimizer {
-    pub fn new(instance: Instance, config: LBFConfig, rng: SmallRng) -> Self {
+    pub fn new(instance: Instance, config: LBFConfig, rng: LBFRng) -> Self {
         assert!(config.n_samples > 0);
+        assert!(config.max_samples_per_item != Some(0));
         let problem = match instance.clone() {
-            Instance::BP(bpi) => BPProblem::new(bpi.clone()).into(),
+            Instance::BP(bpi) => {
+                let mut bp_problem = BPProblem::new(bpi.clone());
+                bp_problem.max_items_per_layout = config.max_items_per_layout;
+                bp_problem.into()
+            }
             Instance::SP(spi) => {
-                let strip_width = instance.item_area() * 2.0 / spi.strip_height; //initiate with 50% usage
+                let strip_width = match config.strip_growth_step {
+                    Some(growth_step) => growth_step,
+                    None => instance.item_area() * 2.0 / spi.strip_height, //initiate with 50% usage
+                };
                 SPProblem::new(spi.clone(), strip_width, config.cde_config).into()
             }
         };
 
+        let report = SolveReport::new(instance.items().len());
+
         Self {
             instance,
             problem,
             config,
             rng,
             sample_counter: 0,
+            acceptance: AcceptanceState::new(config.acceptance),
+            report,
         }
     }
 
     pub fn solve(&mut self) -> Solution {
-        //sort the items by descending diameter of convex hull
+        self.solve_with_progress(|_| ())
+    }
+
+    /// Same as [LBFOptimizer::solve], but calls `on_progress` every time an item is placed.
+    pub fn solve_with_progress(&mut self, mut on_progress: impl FnMut(LBFProgress)) -> Solution {
+        //sort the items by descending diameter of convex hull, items without demand are left out entirely
         let sorted_item_indices = (0..self.instance.items().len())
+            .filter(|i| self.instance.item_qty(*i) > 0)
             .sorted_by_cached_key(|i| {
                 let item = &self.instance.items()[*i].0;
                 let ch = SimplePolygon::new(convex_hull_from_points(item.shape.points.clone()));
@@ -72,73 +184,830 @@ impl LBFOptimizer {
             })
             .collect_vec();
 
+        //items which can never fit, at any of their allowed rotations, are not worth sampling
+        let (sorted_item_indices, infeasible_item_indices): (Vec<usize>, Vec<usize>) =
+            sorted_item_indices
+                .into_iter()
+                .partition(|i| self.item_fits_instance(&self.instance.items()[*i].0));
+        for item_index in infeasible_item_indices {
+            warn!(
+                "[LBF] item {} does not fit in any bin at any allowed rotation, skipping it",
+                item_index
+            );
+        }
+        let sorted_item_indices =
+            order_by_precedence(&sorted_item_indices, self.instance.precedence());
+
         let start = Instant::now();
 
-        'outer: for item_index in sorted_item_indices {
+        let rect_instance =
+            self.config.rect_packing && rect_packer::is_rect_instance(&self.instance);
+        match (self.config.solver, rect_instance) {
+            (Solver::FirstFitDecreasing, _) => {
+                info!("[LBF] placing items first fit decreasing on their bounding boxes");
+                self.place_items_ffd(&sorted_item_indices, &mut on_progress);
+            }
+            (Solver::Lbf, true) => {
+                info!("[LBF] all items and bins are rectangular, placing items with maximal rectangles");
+                self.place_items_rect(&sorted_item_indices, &mut on_progress);
+            }
+            (Solver::Lbf, false) => self.place_items_lbf(&sorted_item_indices, &mut on_progress),
+        }
+
+        if self.config.compact {
+            self.compact();
+        }
+
+        match &mut self.problem {
+            Problem::BP(bp_problem) => {
+                info!(
+                    "[LBF] used {} bins, lower bound {}",
+                    bp_problem.layouts.len(),
+                    bp_problem.instance.lower_bound()
+                );
+            }
+            Problem::SP(sp_problem) => {
+                sp_problem.fit_strip();
+                let dimension = match sp_problem.instance.packing_axis {
+                    PackingAxis::X => "width",
+                    PackingAxis::Y => "height",
+                };
+                info!(
+                    "[LBF] fitted strip {} to {:.3}",
+                    dimension,
+                    sp_problem.strip_width()
+                );
+            }
+        }
+
+        let solution: Solution = self.problem.create_solution(None);
+
+        info!(
+            "[LBF] optimization finished in {:.3}ms ({} samples)",
+            start.elapsed().as_secs_f64() * 1000.0,
+            self.sample_counter.separate_with_commas()
+        );
+
+        info!(
+            "[LBF] solution contains {} items with a usage of {:.3}%",
+            solution.n_items_placed(),
+            solution.usage * 100.0
+        );
+
+        let unplaced_item_ids = self
+            .problem
+            .missing_item_qtys()
+            .iter()
+            .enumerate()
+            .filter(|(_, qty)| **qty > 0)
+            .map(|(id, _)| id)
+            .collect_vec();
+        if !unplaced_item_ids.is_empty() {
+            info!("[LBF] items with unplaced copies: {:?}", unplaced_item_ids);
+        }
+
+        let slow_item_ids = self
+            .report
+            .disproportionate_items(DISPROPORTIONATE_SAMPLING_FACTOR);
+        if !slow_item_ids.is_empty() {
+            info!(
+                "[LBF] items taking over {}x the average time to place: {:?}",
+                DISPROPORTIONATE_SAMPLING_FACTOR, slow_item_ids
+            );
+        }
+
+        //the minimum edge contact of items is not enforced while sampling, only reported
+        let n_contact_violations = self
+            .problem
+            .layouts()
+            .iter()
+            .map(|l| l.edge_contact_violations(&self.instance).len())
+            .sum::<usize>();
+        if n_contact_violations > 0 {
+            warn!(
+                "[LBF] {} placed items do not achieve their minimum edge contact",
+                n_contact_violations
+            );
+        }
+        solution
+    }
+
+    /// Places all items, in the given order, at the best position found by sampling.
+    fn place_items_lbf(
+        &mut self,
+        sorted_item_indices: &[usize],
+        on_progress: &mut impl FnMut(LBFProgress),
+    ) {
+        'items: for &item_index in sorted_item_indices {
             let item = &self.instance.items()[item_index].0;
             //place all items of this type
             while self.problem.missing_item_qtys()[item_index] > 0 {
+                let sampling_start = Instant::now();
+                let n_samples_before = self.sample_counter;
                 //find a position and insert it
-                match find_lbf_placement(
+                let mut placement = find_lbf_placement(
                     &self.problem,
                     item,
                     &self.config,
                     &mut self.rng,
                     &mut self.sample_counter,
-                ) {
+                    &mut self.acceptance,
+                );
+                let budget_exhausted = self
+                    .config
+                    .max_samples_per_item
+                    .is_some_and(|max| self.sample_counter - n_samples_before >= max);
+                //bins are opened lazily, only when the item does not fit in any of the open ones
+                while placement.is_none() {
+                    let remaining_area = remaining_item_area(&self.problem, &self.instance);
+                    let Problem::BP(bp_problem) = &mut self.problem else {
+                        break;
+                    };
+                    let Some(layout_idx) = bp_problem
+                        .ensure_open_bin_for_by_key(item_index, |bin| {
+                            bin_opening_key(bin, self.config.bin_objective, remaining_area)
+                        })
+                    else {
+                        break;
+                    };
+                    //a new bin gets a fresh sample budget
+                    let budgeted_config = LBFConfig {
+                        n_samples: self
+                            .config
+                            .max_samples_per_item
+                            .map_or(self.config.n_samples, |m| m.min(self.config.n_samples)),
+                        ..self.config
+                    };
+                    placement = sample_layout(
+                        &self.problem,
+                        layout_idx,
+                        item,
+                        &budgeted_config,
+                        &mut self.rng,
+                        &mut self.sample_counter,
+                        &mut self.acceptance,
+                    );
+                }
+                self.report.record_attempt(
+                    item_index,
+                    self.sample_counter - n_samples_before,
+                    sampling_start.elapsed(),
+                    placement.is_some(),
+                    budget_exhausted,
+                );
+                match placement {
                     Some(i_opt) => {
-                        let l_index = self.problem.place_item(i_opt);
-                        info!(
-                            "[LBF] placing item {}/{} with id {} at [{}] in Layout {:?}",
-                            self.problem.placed_item_qtys().sum::<usize>(),
-                            self.instance.total_item_qty(),
-                            i_opt.item_id,
-                            i_opt.d_transf,
-                            l_index
+                        self.acceptance.cool_down();
+                        place_and_report(
+                            &mut self.problem,
+                            &self.instance,
+                            i_opt,
+                            self.acceptance.n_worse_accepted,
+                            on_progress,
                         );
                         #[allow(clippy::absurd_extreme_comparisons)]
                         if self.problem.placed_item_qtys().sum::<usize>() >= ITEM_LIMIT {
-                            break 'outer;
+                            break 'items;
                         }
                     }
-                    None => {
-                        match &mut self.problem {
-                            Problem::BP(_) => break,
-                            Problem::SP(sp_problem) => {
+                    None => match &mut self.problem {
+                        Problem::BP(_) => break,
+                        Problem::SP(sp_problem) => match self.config.strip_growth_step {
+                            Some(growth_step) => {
+                                let new_width = sp_problem.grow_to_fit(item_index, growth_step);
+                                info!(
+                                    "[LBF] no placement found, growing strip width to {:.3}",
+                                    new_width
+                                );
+                            }
+                            None => {
                                 let new_width = sp_problem.strip_width() * 1.1;
                                 info!("[LBF] no placement found, extending strip width by 10% to {:.3}", new_width);
                                 sp_problem.modify_strip_in_back(new_width);
                             }
+                        },
+                    },
+                }
+            }
+        }
+        if let Problem::BP(bp_problem) = &mut self.problem {
+            //close the bins in which no item ended up fitting
+            bp_problem.remove_empty_layouts();
+        }
+    }
+
+    /// Places all items using the maximal rectangles algorithm (see [rect_packer]).
+    /// Only valid for instances for which [rect_packer::is_rect_instance] holds.
+    fn place_items_rect(
+        &mut self,
+        sorted_item_indices: &[usize],
+        on_progress: &mut impl FnMut(LBFProgress),
+    ) {
+        let heuristic = match &self.problem {
+            Problem::BP(_) => MaxRectsHeuristic::BestShortSideFit,
+            Problem::SP(_) => MaxRectsHeuristic::BottomLeft,
+        };
+        self.widen_strip_for(sorted_item_indices);
+
+        //free space of every existing layout
+        let mut free_spaces = self
+            .problem
+            .layouts()
+            .iter()
+            .map(layout_free_space)
+            .collect_vec();
+
+        //maximal rectangles performs best when rectangles are placed in order of descending area
+        let sorted_item_indices = sorted_item_indices
+            .iter()
+            .cloned()
+            .sorted_by_cached_key(|&i| {
+                let area = self.instance.item(i).shape.area();
+                Reverse(NotNan::new(area).expect("item area is NaN"))
+            })
+            .collect_vec();
+        let sorted_item_indices =
+            order_by_precedence(&sorted_item_indices, self.instance.precedence());
+
+        for &item_index in &sorted_item_indices {
+            let item = self.instance.item(item_index);
+            while self.problem.missing_item_qtys()[item_index] > 0 {
+                //first layout in which the item fits
+                let existing = free_spaces.iter().enumerate().find_map(|(i, (fs, gap))| {
+                    let layout_idx = LayoutIndex::Real(i);
+                    match self.problem.layout_is_full(layout_idx) {
+                        true => None,
+                        false => rect_packer::find_rect_placement(fs, item, *gap, heuristic)
+                            .map(|(d_transf, footprint, _)| (layout_idx, d_transf, footprint)),
+                    }
+                });
+                //otherwise, open a new layout
+                let remaining_area = remaining_item_area(&self.problem, &self.instance);
+                let placement = existing.or_else(|| {
+                    self.problem
+                        .template_layout_indices_with_stock()
+                        .sorted_by_cached_key(|&layout_idx| {
+                            let bin = &self.problem.get_layout(layout_idx).bin;
+                            bin_opening_key(bin, self.config.bin_objective, remaining_area)
+                        })
+                        .find_map(|layout_idx| {
+                            let (fs, gap) = layout_free_space(self.problem.get_layout(layout_idx));
+                            rect_packer::find_rect_placement(&fs, item, gap, heuristic)
+                                .map(|(d_transf, footprint, _)| (layout_idx, d_transf, footprint))
+                        })
+                });
+
+                match placement {
+                    Some((layout_idx, d_transf, footprint)) => {
+                        let p_opt = PlacingOption {
+                            layout_idx,
+                            item_id: item.id,
+                            d_transf,
+                            subtract: false,
+                        };
+                        let l_index = place_and_report(
+                            &mut self.problem,
+                            &self.instance,
+                            p_opt,
+                            self.acceptance.n_worse_accepted,
+                            on_progress,
+                        );
+                        if let LayoutIndex::Real(i) = l_index {
+                            if i == free_spaces.len() {
+                                free_spaces
+                                    .push(layout_free_space(self.problem.get_layout(l_index)));
+                            }
+                            free_spaces[i].0.occupy(&footprint);
                         }
                     }
+                    None => {
+                        warn!("[LBF] no free rectangle found for item {}", item.id);
+                        break;
+                    }
                 }
             }
         }
-        match &mut self.problem {
-            Problem::BP(_) => {}
-            Problem::SP(sp_problem) => {
-                sp_problem.fit_strip();
-                info!(
-                    "[LBF] fitted strip width to {:.3}",
-                    sp_problem.strip_width()
+    }
+
+    /// Places all items with the first fit decreasing baseline (see [ffd]), on their bounding boxes.
+    /// Items are sorted by descending width of their bounding box (respecting precedence),
+    /// so every shelf is as wide as the first item placed in it.
+    fn place_items_ffd(
+        &mut self,
+        sorted_item_indices: &[usize],
+        on_progress: &mut impl FnMut(LBFProgress),
+    ) {
+        self.widen_strip_for(sorted_item_indices);
+
+        //shelves of every existing layout
+        let mut shelves = self
+            .problem
+            .layouts()
+            .iter()
+            .map(ffd::layout_shelves)
+            .collect_vec();
+
+        let sorted_item_indices = sorted_item_indices
+            .iter()
+            .cloned()
+            .sorted_by_cached_key(|&i| {
+                let width = self.instance.item(i).shape.bbox().width();
+                Reverse(NotNan::new(width).expect("item width is NaN"))
+            })
+            .collect_vec();
+        let sorted_item_indices =
+            order_by_precedence(&sorted_item_indices, self.instance.precedence());
+
+        for &item_index in &sorted_item_indices {
+            let item = self.instance.item(item_index);
+            if item.position_lattice.is_some() {
+                warn!(
+                    "[LBF] item {} is restricted to a position lattice, which first fit decreasing does not support, skipping it",
+                    item.id
                 );
+                continue;
+            }
+            while self.problem.missing_item_qtys()[item_index] > 0 {
+                //first layout in which the item fits
+                let existing = shelves.iter().enumerate().find_map(|(i, (sh, gap))| {
+                    let layout_idx = LayoutIndex::Real(i);
+                    match self.problem.layout_is_full(layout_idx) {
+                        true => None,
+                        false => {
+                            let layout = self.problem.get_layout(layout_idx);
+                            ffd::find_ffd_placement(layout, sh, item, *gap)
+                                .map(|(d_transf, footprint)| (layout_idx, d_transf, footprint))
+                        }
+                    }
+                });
+                //otherwise, open a new layout
+                let remaining_area = remaining_item_area(&self.problem, &self.instance);
+                let placement = existing.or_else(|| {
+                    self.problem
+                        .template_layout_indices_with_stock()
+                        .sorted_by_cached_key(|&layout_idx| {
+                            let bin = &self.problem.get_layout(layout_idx).bin;
+                            bin_opening_key(bin, self.config.bin_objective, remaining_area)
+                        })
+                        .find_map(|layout_idx| {
+                            let layout = self.problem.get_layout(layout_idx);
+                            let (sh, gap) = ffd::layout_shelves(layout);
+                            ffd::find_ffd_placement(layout, &sh, item, gap)
+                                .map(|(d_transf, footprint)| (layout_idx, d_transf, footprint))
+                        })
+                });
+
+                match placement {
+                    Some((layout_idx, d_transf, footprint)) => {
+                        let p_opt = PlacingOption {
+                            layout_idx,
+                            item_id: item.id,
+                            d_transf,
+                            subtract: false,
+                        };
+                        let l_index = place_and_report(
+                            &mut self.problem,
+                            &self.instance,
+                            p_opt,
+                            self.acceptance.n_worse_accepted,
+                            on_progress,
+                        );
+                        if let LayoutIndex::Real(i) = l_index {
+                            if i == shelves.len() {
+                                shelves.push(ffd::layout_shelves(self.problem.get_layout(l_index)));
+                            }
+                            shelves[i].0.occupy(&footprint);
+                        }
+                    }
+                    None => {
+                        warn!("[LBF] no first fit found for item {}", item.id);
+                        break;
+                    }
+                }
             }
         }
+    }
 
-        let solution: Solution = self.problem.create_solution(None);
+    /// Widens the strip enough to hold all remaining copies of the items side by side (only applies to strip packing problems)
+    fn widen_strip_for(&mut self, item_indices: &[usize]) {
+        if let Problem::SP(sp_problem) = &mut self.problem {
+            let required_width = item_indices
+                .iter()
+                .map(|&i| {
+                    let bbox = self.instance.item(i).shape.bbox();
+                    let max_dim = fsize::max(bbox.width(), bbox.height());
+                    max_dim * sp_problem.missing_item_qtys()[i] as fsize
+                })
+                .sum::<fsize>();
+            let new_width = sp_problem.occupied_width() + required_width * (1.0 + RECT_PACKING_GAP);
+            sp_problem.modify_strip_in_back(fsize::max(sp_problem.strip_width(), new_width));
+        }
+    }
+
+    /// Searches for the cheapest valid placement of one more copy of item `item_id` in the current layouts, without placing it.
+    /// Every existing layout is sampled, but no bins are opened and the strip is not extended,
+    /// e.g. to add one more part to a finished solution. The demand of the item is not taken into account.
+    /// Returns `None` if the item does not fit in any of the layouts.
+    pub fn best_placement_for(&mut self, item_id: usize) -> Option<PlacingOption> {
+        let item = self.instance.item(item_id);
+        self.problem
+            .layout_indices()
+            .filter(|l_idx| !self.problem.layout_is_full(l_idx))
+            .filter_map(|l_idx| {
+                sample_layout_with_strategy(
+                    &self.problem,
+                    l_idx,
+                    item,
+                    &self.config,
+                    &mut self.rng,
+                    &mut self.sample_counter,
+                    &mut self.acceptance,
+                )
+            })
+            .min_by_key(|(_, cost)| *cost)
+            .map(|(p_opt, _)| p_opt)
+    }
+
+    /// Checks whether the item fits in at least one of the bins (or the strip) of the instance.
+    pub fn item_fits_instance(&self, item: &Item) -> bool {
+        match &self.instance {
+            Instance::BP(bpi) => bpi.bins.iter().any(|(bin, _)| {
+                let bbox = bin.outer.bbox();
+                item_fits_bin(item, bbox.width(), bbox.height())
+            }),
+            Instance::SP(spi) => item_fits_bin(item, fsize::INFINITY, spi.strip_height),
+        }
+    }
+
+    /// Compacts all unlocked layouts by repeatedly sliding every placed item down and then left, as far as possible.
+    /// Stops when no item can be moved anymore or after [COMPACTION_MAX_ITERATIONS] passes.
+    pub fn compact(&mut self) {
+        let start = Instant::now();
+        let mut n_slides = 0;
+        //moving an item does not change the order in which it was placed
+        let placement_order = self.problem.placement_order().to_vec();
+
+        for i in 0..COMPACTION_MAX_ITERATIONS {
+            let mut n_slides_in_pass = 0;
+            for layout_idx in self.problem.layout_indices().collect_vec() {
+                if self.problem.layout_is_locked(layout_idx) {
+                    continue;
+                }
+                let p_item_keys = self
+                    .problem
+                    .get_layout(layout_idx)
+                    .placed_items()
+                    .keys()
+                    .collect_vec();
+
+                for pik in p_item_keys {
+                    let pi = &self.problem.get_layout(layout_idx).placed_items()[pik];
+                    let item = self.instance.item(pi.item_id);
+                    if item.position_lattice.is_some() {
+                        //sliding would move the item off its lattice
+                        continue;
+                    }
+                    let layout = self.problem.get_layout(layout_idx);
+                    let entity = HazardEntity::from(pi);
+                    if pi.subtract || layout.cde().cutouts().iter().any(|c| c.parent == entity) {
+                        //cutouts are tied to the region of the item they were cut out of
+                        continue;
+                    }
+                    if let Some(d_transf) = compact_item(layout, item, &pi.d_transf, entity) {
+                        let p_opt = PlacingOption {
+                            layout_idx,
+                            item_id: item.id,
+                            d_transf,
+                            subtract: pi.subtract,
+                        };
+                        if layout.placed_items().len() > 1 {
+                            self.problem.remove_item(layout_idx, pik, true);
+                            self.problem.place_item(p_opt);
+                        } else if !self.problem.layout_is_full(layout_idx) {
+                            //place the item at its new position before removing the old one, so the layout never becomes empty
+                            self.problem.place_item(p_opt);
+                            self.problem.remove_item(layout_idx, pik, true);
+                        } else {
+                            //a lone item in a full layout can only be moved by emptying the layout
+                            continue;
+                        }
+                        n_slides_in_pass += 1;
+                    }
+                }
+            }
+            debug!("[LBF] compaction pass {i}: {n_slides_in_pass} items moved");
+            n_slides += n_slides_in_pass;
+            if n_slides_in_pass == 0 {
+                break;
+            }
+        }
+        self.problem.set_placement_order(placement_order);
 
         info!(
-            "[LBF] optimization finished in {:.3}ms ({} samples)",
+            "[LBF] compaction finished in {:.3}ms ({} items moved)",
             start.elapsed().as_secs_f64() * 1000.0,
-            self.sample_counter.separate_with_commas()
+            n_slides
         );
+    }
+}
 
-        info!(
-            "[LBF] solution contains {} items with a usage of {:.3}%",
-            solution.n_items_placed(),
-            solution.usage * 100.0
+/// Solves the instance once for every seed, collecting the solutions which are non-dominated on `objectives` in a [ParetoArchive].
+/// At most [LBFConfig::max_stored_solutions] solutions are kept in the archive.
+/// Returns the solution which is best on the first objective (see [ParetoArchive::best]), alongside the archive to choose another trade-off from.
+pub fn solve_pareto(
+    instance: &Instance,
+    config: LBFConfig,
+    seeds: &[u64],
+    objectives: Vec<SolutionObjective>,
+) -> (Solution, ParetoArchive) {
+    assert!(!seeds.is_empty(), "at least one seed is required");
+    let mut archive =
+        ParetoArchive::new(objectives).with_max_solutions(config.max_stored_solutions);
+    for &seed in seeds {
+        let mut optimizer =
+            LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(seed));
+        let solution = optimizer.solve();
+        if archive.insert(solution) {
+            info!("[LBF] solution of seed {seed} added to the Pareto archive");
+        }
+    }
+    let best = archive
+        .best()
+        .expect("archive contains at least one solution")
+        .clone();
+    (best, archive)
+}
+
+/// Reorders the items such that every item comes after all items which have to be placed before it,
+/// see [InstanceGeneric::precedence]. Otherwise, the relative order of `item_indices` is preserved.
+/// Items absent from `item_indices` (e.g. because they never fit) do not hold back any other item.
+pub fn order_by_precedence(item_indices: &[usize], precedence: &[(usize, usize)]) -> Vec<usize> {
+    let mut remaining = item_indices.to_vec();
+    let mut ordered = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        //first remaining item without any remaining predecessor
+        let i = remaining
+            .iter()
+            .position(|b| {
+                !precedence
+                    .iter()
+                    .any(|(a, pb)| pb == b && remaining.contains(a))
+            })
+            .expect("precedence constraints contain a cycle");
+        ordered.push(remaining.remove(i));
+    }
+    ordered
+}
+
+/// Places the item in the problem and reports the progress.
+fn place_and_report(
+    problem: &mut Problem,
+    instance: &Instance,
+    p_opt: PlacingOption,
+    n_worse_accepted: usize,
+    on_progress: &mut impl FnMut(LBFProgress),
+) -> LayoutIndex {
+    let (l_index, _) = problem.place_item(p_opt);
+    info!(
+        "[LBF] placing item {}/{} with id {} at [{}] in Layout {:?}",
+        problem.placed_item_qtys().sum::<usize>(),
+        instance.total_item_qty(),
+        p_opt.item_id,
+        p_opt.d_transf,
+        l_index
+    );
+    on_progress(LBFProgress {
+        n_items_placed: problem.placed_item_qtys().sum(),
+        n_items_total: instance.total_item_qty(),
+        usage: current_usage(problem),
+        n_worse_accepted,
+    });
+    l_index
+}
+
+/// Free space of a layout, along with the gap to keep around the rectangles placed in it.
+/// Items already present occupy their bounding box.
+fn layout_free_space(layout: &Layout) -> (MaxRects, fsize) {
+    let bbox = layout.bin.bbox();
+    let gap = fsize::min(bbox.width(), bbox.height()) * RECT_PACKING_GAP;
+    let mut free_space = MaxRects::new(bbox);
+    for pi in layout.placed_items().values() {
+        let pi_bbox = pi.shape.bbox();
+        free_space.occupy(&AARectangle::new(
+            pi_bbox.x_min - gap / 2.0,
+            pi_bbox.y_min - gap / 2.0,
+            pi_bbox.x_max + gap / 2.0,
+            pi_bbox.y_max + gap / 2.0,
+        ));
+    }
+    (free_space, gap)
+}
+
+/// Usage of all layouts in the problem, without requiring mutable access
+fn current_usage(problem: &Problem) -> fsize {
+    let (total_bin_area, total_used_area) = problem.layouts().iter().fold((0.0, 0.0), |acc, l| {
+        (acc.0 + l.bin.area, acc.1 + l.bin.area * l.usage())
+    });
+    total_used_area / total_bin_area
+}
+
+/// Key by which the bins eligible to be opened are ranked, lowest first (see [BinObjective]).
+/// Ties are broken by the area of the bin, smallest first.
+pub fn bin_opening_key(
+    bin: &Bin,
+    objective: BinObjective,
+    remaining_item_area: fsize,
+) -> (NotNan<fsize>, NotNan<fsize>) {
+    let estimated_cost = match objective {
+        BinObjective::CheapestBin => bin.value as fsize,
+        BinObjective::TotalCost => {
+            let n_bins_needed = fsize::max((remaining_item_area / bin.area).ceil(), 1.0);
+            bin.value as fsize * n_bins_needed
+        }
+    };
+    (
+        NotNan::new(estimated_cost).expect("estimated bin cost is NaN"),
+        NotNan::new(bin.area).expect("bin area is NaN"),
+    )
+}
+
+/// Total area of the items which still have to be placed.
+fn remaining_item_area(problem: &Problem, instance: &Instance) -> fsize {
+    problem
+        .missing_item_qtys()
+        .iter()
+        .enumerate()
+        .filter(|(_, qty)| **qty > 0)
+        .map(|(i, qty)| instance.item(i).shape.area() * *qty as fsize)
+        .sum()
+}
+
+/// Returns false if the item can never fit in a bin of the given dimensions, at any of its allowed rotations.
+/// Only bounding boxes are compared, so `true` does not guarantee a feasible placement exists.
+pub fn item_fits_bin(item: &Item, bin_width: fsize, bin_height: fsize) -> bool {
+    let shape = item.shape.as_ref();
+    if shape.area() > bin_width * bin_height {
+        return false;
+    }
+    let fits_at = |rotation: fsize| {
+        let bbox = shape
+            .transform_clone(&DTransformation::new(rotation, (0.0, 0.0)).compose())
+            .bbox();
+        bbox.width() <= bin_width && bbox.height() <= bin_height
+    };
+    match &item.allowed_rotation {
+        AllowedRotation::None => fits_at(0.0),
+        AllowedRotation::Discrete(rotations) => rotations.iter().any(|r| fits_at(*r)),
+        AllowedRotation::Continuous | AllowedRotation::Range { .. } => {
+            //the item has to fit through the narrowest side of the bin in at least one direction
+            min_width(shape) <= fsize::min(bin_width, bin_height)
+        }
+    }
+}
+
+/// Minimum width of the shape over all rotations, which is attained with an edge of its convex hull flush.
+fn min_width(shape: &SimplePolygon) -> fsize {
+    let ch = convex_hull_from_points(shape.points.clone());
+    (0..ch.len())
+        .map(|i| {
+            let (p1, p2) = (ch[i], ch[(i + 1) % ch.len()]);
+            let (Point(x1, y1), Point(x2, y2), length) = (p1, p2, p1.distance(p2));
+            ch.iter()
+                .map(|Point(x, y)| ((x2 - x1) * (y1 - y) - (x1 - x) * (y2 - y1)).abs() / length)
+                .fold(0.0, fsize::max)
+        })
+        .fold(fsize::INFINITY, fsize::min)
+}
+
+/// Slides a placed item down and then left, as far as possible without causing collisions.
+/// Returns the new transformation if the item moved (strictly) closer to the bottom-left corner of the bin.
+fn compact_item(
+    layout: &Layout,
+    item: &Item,
+    d_transf: &DTransformation,
+    p_item_entity: HazardEntity,
+) -> Option<DTransformation> {
+    //the item itself should not be considered as a hazard
+    let mut irrel_hazards = match item.hazard_filter.as_ref() {
+        None => vec![],
+        Some(hf) => hazard_filter::generate_irrelevant_hazards(hf, layout.cde().all_hazards()),
+    };
+    irrel_hazards.push(p_item_entity);
+
+    let bin_bbox = layout.bin.bbox();
+    let min_slide = item.shape.diameter() * COMPACTION_MIN_SLIDE;
+    let mut current = *d_transf;
+    let mut moved = false;
+
+    for direction in [(0.0, -1.0), (-1.0, 0.0)] {
+        let item_bbox = item.shape.transform_clone(&current.compose()).bbox();
+        let max_distance = match direction {
+            (0.0, _) => item_bbox.y_min - bin_bbox.y_min,
+            _ => item_bbox.x_min - bin_bbox.x_min,
+        };
+        let distance = max_slide(
+            layout,
+            item,
+            &current,
+            direction,
+            max_distance,
+            &irrel_hazards,
         );
-        solution
+        if distance > min_slide {
+            let (tx, ty) = current.translation();
+            current = DTransformation::new(
+                current.rotation(),
+                (tx + direction.0 * distance, ty + direction.1 * distance),
+            );
+            moved = true;
+        }
+    }
+
+    match moved {
+        true => Some(current),
+        false => None,
+    }
+}
+
+/// Returns the maximum distance an item can be slid from `d_transf` in `direction` (unit vector) without colliding,
+/// up to `max_distance`.
+/// The item advances in steps no larger than the thinnest feature of itself or any relevant hazard (see [thinnest_feature]),
+/// so it cannot tunnel through thin parts between two steps, unless this would take more than [MAX_SLIDE_STEPS] steps.
+/// The last step is refined by bisection.
+pub fn max_slide(
+    layout: &Layout,
+    item: &Item,
+    d_transf: &DTransformation,
+    direction: (fsize, fsize),
+    max_distance: fsize,
+    irrel_hazards: &[HazardEntity],
+) -> fsize {
+    let cde = layout.cde();
+    let mut buffer = {
+        let mut buffer = (*item.shape).clone();
+        buffer.surrogate = None;
+        buffer
+    };
+    let (tx, ty) = d_transf.translation();
+    let mut collides_at = |distance: fsize| {
+        let transf = DTransformation::new(
+            d_transf.rotation(),
+            (tx + direction.0 * distance, ty + direction.1 * distance),
+        )
+        .compose();
+        cde.item_collides(item, &transf, &mut buffer, irrel_hazards)
+    };
+
+    let step = cde
+        .all_hazards()
+        .filter(|h| h.active && !irrel_hazards.contains(&h.entity))
+        .map(|h| thinnest_feature(&h.shape))
+        .fold(thinnest_feature(&item.shape), fsize::min)
+        .max(max_distance / MAX_SLIDE_STEPS as fsize);
+    let mut feasible = 0.0;
+
+    while feasible < max_distance {
+        let next = fsize::min(feasible + step, max_distance);
+        if !collides_at(next) {
+            feasible = next;
+            continue;
+        }
+        //collision somewhere between feasible and next, bisect
+        let mut infeasible = next;
+        for _ in 0..SLIDE_BISECTION_STEPS {
+            let mid = (feasible + infeasible) / 2.0;
+            match collides_at(mid) {
+                true => infeasible = mid,
+                false => feasible = mid,
+            }
+        }
+        break;
+    }
+    feasible
+}
+
+/// Estimate of the width of the thinnest part of a shape, used to bound the step size in [max_slide].
+/// Thin features are either approximated by small poles or ended by short edges.
+fn thinnest_feature(shape: &SimplePolygon) -> fsize {
+    let smallest_pole = shape
+        .surrogate
+        .iter()
+        .flat_map(|s| s.poles.iter())
+        .map(|pole| 2.0 * pole.radius)
+        .fold(shape.poi.radius, fsize::min);
+    shape
+        .edge_iter()
+        .map(|e| e.diameter())
+        .fold(smallest_pole, fsize::min)
+}
+
+/// Moves a sampled transformation onto the position lattice of the item, if it has one (see [Item::snap_to_lattice]).
+fn snap_to_lattice(item: &Item, layout: &Layout, transform: Transformation) -> Transformation {
+    match item.position_lattice {
+        None => transform,
+        Some(_) => item
+            .snap_to_lattice(&transform.decompose(), &layout.bin)
+            .compose(),
     }
 }
 
@@ -148,22 +1017,77 @@ pub fn find_lbf_placement(
     config: &LBFConfig,
     rng: &mut impl Rng,
     sample_counter: &mut usize,
+    acceptance: &mut AcceptanceState,
 ) -> Option<PlacingOption> {
-    //search all existing layouts and template layouts with remaining stock
-    let existing_layouts = problem.layout_indices();
-    let template_layouts = problem.template_layout_indices_with_stock();
+    //search all existing layouts which are not full, new bins are opened by the caller (see BPProblem::ensure_open_bin_for)
+    let existing_layouts = problem
+        .layout_indices()
+        .filter(|l_idx| !problem.layout_is_full(l_idx));
+
+    let mut remaining_budget = config.max_samples_per_item.unwrap_or(usize::MAX);
 
-    //sequential search until a valid placement is found
-    for layout in existing_layouts.chain(template_layouts) {
+    //sequential search until a valid placement is found or the budget for the item runs out
+    for layout in existing_layouts {
+        if remaining_budget == 0 {
+            debug!("sample budget of item {} exhausted", item.id);
+            break;
+        }
         debug!("searching in layout {:?}", layout);
-        if let Some(placing_opt) = sample_layout(problem, layout, item, config, rng, sample_counter)
-        {
+        let budgeted_config = LBFConfig {
+            n_samples: config.n_samples.min(remaining_budget),
+            ..*config
+        };
+        let n_samples_before = *sample_counter;
+        if let Some(placing_opt) = sample_layout(
+            problem,
+            layout,
+            item,
+            &budgeted_config,
+            rng,
+            sample_counter,
+            acceptance,
+        ) {
             return Some(placing_opt);
         }
+        remaining_budget = remaining_budget.saturating_sub(*sample_counter - n_samples_before);
     }
     None
 }
 
+/// Cost of placing `item` with `shape` (already transformed) at `rotation` in `bin`, including the penalties of the config
+fn placing_cost(
+    shape: &SimplePolygon,
+    rotation: fsize,
+    item: &Item,
+    bin: &Bin,
+    config: &LBFConfig,
+) -> LBFPlacingCost {
+    let cost =
+        LBFPlacingCost::from_shape(shape).with_rotation_penalty(rotation, config.rotation_penalty);
+    match (item.base_quality, config.quality_penalty) {
+        (None | Some(0), _) | (_, 0.0) => cost,
+        (Some(base_quality), penalty) => {
+            let inferior_fraction = inferior_quality_fraction(shape, bin, base_quality);
+            cost.with_quality_penalty(base_quality, inferior_fraction, penalty)
+        }
+    }
+}
+
+/// Fraction of the area of `shape` lying in the inferior quality zones of `bin` which an item of `base_quality` is allowed in
+pub fn inferior_quality_fraction(shape: &SimplePolygon, bin: &Bin, base_quality: usize) -> fsize {
+    let bbox = shape.bbox();
+    let inferior_area: fsize = bin.quality_zones[base_quality..]
+        .iter()
+        .flatten()
+        .flat_map(|qz| qz.zones.iter().zip(qz.convex_parts.iter()))
+        .filter(|(zone, _)| zone.bbox().collides_with(&bbox))
+        .flat_map(|(_, parts)| parts.iter())
+        .map(|part| shape.intersection_area_with_convex(part))
+        .sum();
+    fsize::min(1.0, inferior_area / shape.area())
+}
+
+/// Searches for the cheapest valid placement of `item` in the layout, see [RotationStrategy] for how its rotation is chosen.
 pub fn sample_layout(
     problem: &Problem,
     layout_idx: LayoutIndex,
@@ -171,7 +1095,76 @@ pub fn sample_layout(
     config: &LBFConfig,
     rng: &mut impl Rng,
     sample_counter: &mut usize,
+    acceptance: &mut AcceptanceState,
 ) -> Option<PlacingOption> {
+    sample_layout_with_strategy(
+        problem,
+        layout_idx,
+        item,
+        config,
+        rng,
+        sample_counter,
+        acceptance,
+    )
+    .map(|(p_opt, _)| p_opt)
+}
+
+/// Same as [sample_layout], but also returns the cost of the placement
+fn sample_layout_with_strategy(
+    problem: &Problem,
+    layout_idx: LayoutIndex,
+    item: &Item,
+    config: &LBFConfig,
+    rng: &mut impl Rng,
+    sample_counter: &mut usize,
+    acceptance: &mut AcceptanceState,
+) -> Option<(PlacingOption, LBFPlacingCost)> {
+    match (config.rotation_strategy, &item.allowed_rotation) {
+        (RotationStrategy::Sweep, AllowedRotation::Discrete(rotations)) if rotations.len() > 1 => {
+            //every rotation is searched with the same random stream,
+            //so the sweep is never worse than searching any single one of the rotations with that stream
+            let seed = rng.gen::<u64>();
+            rotations
+                .iter()
+                .filter_map(|&rotation| {
+                    let fixed_item = Item {
+                        allowed_rotation: AllowedRotation::Discrete(vec![rotation]),
+                        ..item.clone()
+                    };
+                    sample_layout_with_cost(
+                        problem,
+                        layout_idx,
+                        &fixed_item,
+                        config,
+                        &mut LBFRng::seed_from_u64(seed),
+                        sample_counter,
+                        acceptance,
+                    )
+                })
+                .min_by_key(|(_, cost)| *cost)
+        }
+        _ => sample_layout_with_cost(
+            problem,
+            layout_idx,
+            item,
+            config,
+            rng,
+            sample_counter,
+            acceptance,
+        ),
+    }
+}
+
+/// Samples placements of `item` in the layout, returning the best valid one along with its cost
+fn sample_layout_with_cost(
+    problem: &Problem,
+    layout_idx: LayoutIndex,
+    item: &Item,
+    config: &LBFConfig,
+    rng: &mut impl Rng,
+    sample_counter: &mut usize,
+    acceptance: &mut AcceptanceState,
+) -> Option<(PlacingOption, LBFPlacingCost)> {
     let layout: &Layout = problem.get_layout(layout_idx);
     let cde = layout.cde();
     let irrel_hazards = match item.hazard_filter.as_ref() {
@@ -187,6 +1180,15 @@ pub fn sample_layout(
         buffer
     };
 
+    //circular items are checked exactly against their circle, which their surrogate does not necessarily lie within
+    let surrogate_collides = |transform: &Transformation| {
+        item.circle.is_none() && cde.surrogate_collides(surrogate, transform, &irrel_hazards)
+    };
+    let shape_collides = |t_shape: &SimplePolygon, transform: &Transformation| match &item.circle {
+        Some(circle) => cde.circle_collides(&circle.transform_clone(transform), &irrel_hazards),
+        None => cde.poly_collides(t_shape, &irrel_hazards),
+    };
+
     let mut best: Option<(PlacingOption, LBFPlacingCost)> = None;
 
     //calculate the number of uniform and local search samples
@@ -194,29 +1196,51 @@ pub fn sample_layout(
     let uni_sample_budget = config.n_samples - ls_sample_budget;
 
     //uniform sampling within the valid cells of the Hazard Proximity Grid, tracking the best valid insertion option
-    let mut hpg_sampler = HPGSampler::new(item, layout)?;
+    let mut hpg_sampler = HPGSampler::new(item, layout)?.with_bias(config.sampling_bias, layout);
+
+    //the rotation minimizing the width of the item serves as the initial guess, see [LBFConfig::min_footprint_guess]
+    let footprint_guess =
+        match config.min_footprint_guess && item.allowed_rotation != AllowedRotation::None {
+            true => Some(item.min_footprint_rotation(PackingAxis::X)),
+            false => None,
+        };
 
     for i in 0..uni_sample_budget {
-        let transform = hpg_sampler.sample(rng);
-        if !cde.surrogate_collides(surrogate, &transform, &irrel_hazards) {
+        let sample = match (i, footprint_guess) {
+            (0, Some(rotation)) => hpg_sampler.sample_with_rotation(rng, rotation),
+            _ => hpg_sampler.sample(rng),
+        };
+        let transform = snap_to_lattice(item, layout, sample);
+        if !surrogate_collides(&transform) {
             //if no collision is detected on the surrogate, apply the transformation
             buffer.transform_from(&item.shape, &transform);
-            let cost = LBFPlacingCost::from_shape(&buffer);
+            let cost = placing_cost(
+                &buffer,
+                transform.decompose().rotation(),
+                item,
+                &layout.bin,
+                config,
+            );
 
             //only validate the sample if it possibly can replace the current best
             let worth_testing = match (best.as_ref(), &cost) {
-                (Some((_, best_cost)), cost) => {
-                    cost.partial_cmp(best_cost).unwrap() == Ordering::Less
-                }
+                (Some((_, best_cost)), cost) => acceptance.considers(*cost, *best_cost, rng),
                 (None, _) => true,
             };
 
-            if worth_testing && !cde.poly_collides(&buffer, &irrel_hazards) {
-                //sample is valid and improves on the current best
+            if worth_testing && !shape_collides(&buffer, &transform) {
+                //sample is valid and is accepted as the new best
+                if best
+                    .as_ref()
+                    .is_some_and(|(_, best_cost)| cost >= *best_cost)
+                {
+                    acceptance.n_worse_accepted += 1;
+                }
                 let p_opt = PlacingOption {
                     layout_idx,
                     item_id: item.id,
                     d_transf: transform.decompose(),
+                    subtract: false,
                 };
                 hpg_sampler.tighten(cost);
                 debug!(
@@ -243,21 +1267,25 @@ pub fn sample_layout(
     let mut ls_sampler = LSSampler::from_defaults(item, &best_opt.d_transf, &layout.bin.bbox());
 
     for i in 0..ls_sample_budget {
-        let d_transf = ls_sampler.sample(rng);
+        let d_transf = item.snap_to_lattice(&ls_sampler.sample(rng), &layout.bin);
         let transf = d_transf.compose();
-        if !cde.surrogate_collides(surrogate, &transf, &irrel_hazards) {
+        if !surrogate_collides(&transf) {
             buffer.transform_from(&item.shape, &transf);
-            let cost = LBFPlacingCost::from_shape(&buffer);
+            let cost = placing_cost(&buffer, d_transf.rotation(), item, &layout.bin, config);
 
             //only validate the sample if it possibly can replace the current best
-            let worth_testing = cost < *best_cost;
+            let worth_testing = acceptance.considers(cost, *best_cost, rng);
 
-            if worth_testing && !cde.poly_collides(&buffer, &irrel_hazards) {
-                //sample is valid and improves on the current best
+            if worth_testing && !shape_collides(&buffer, &transf) {
+                //sample is valid and is accepted as the new best
+                if cost >= *best_cost {
+                    acceptance.n_worse_accepted += 1;
+                }
                 let p_opt = PlacingOption {
                     layout_idx,
                     item_id: item.id,
                     d_transf,
+                    subtract: false,
                 };
                 ls_sampler.shift_mean(&p_opt.d_transf);
                 debug!("[LS: {i}/{ls_sample_budget}] better: {}", &p_opt.d_transf);
@@ -270,5 +1298,5 @@ pub fn sample_layout(
 
     *sample_counter += ls_sampler.n_samples;
 
-    best.map(|(p_opt, _)| p_opt)
+    best
 }
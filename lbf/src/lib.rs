@@ -2,10 +2,15 @@ use std::time::Instant;
 
 use once_cell::sync::Lazy;
 
+pub mod dry_run;
+pub mod ffd;
 pub mod io;
 pub mod lbf_config;
 pub mod lbf_cost;
 pub mod lbf_optimizer;
+pub mod rect_packer;
+pub mod rng;
 pub mod samplers;
+pub mod solve_report;
 
 pub static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
@@ -1,22 +1,26 @@
 use std::fs;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, IsTerminal};
 use std::path::Path;
 
 use clap::Parser as ClapParser;
-use log::{error, warn};
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{error, info, warn};
 use mimalloc::MiMalloc;
-use rand::prelude::SmallRng;
 use rand::SeedableRng;
 
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
 use jagua_rs::io::parser;
 use jagua_rs::io::parser::Parser;
 use jagua_rs::util::polygon_simplification::PolySimplConfig;
+use lbf::dry_run::DryRunReport;
 use lbf::io::cli::Cli;
+use lbf::io::gcode::GcodeOptions;
 use lbf::io::json_output::JsonOutput;
 use lbf::io::layout_to_svg::s_layout_to_svg;
 use lbf::lbf_config::LBFConfig;
-use lbf::lbf_optimizer::LBFOptimizer;
+use lbf::lbf_optimizer::{LBFOptimizer, LBFProgress};
+use lbf::rng::LBFRng;
 use lbf::{io, EPOCH};
 
 #[global_allocator]
@@ -51,24 +55,69 @@ fn main() {
 
     let json_instance = io::read_json_instance(args.input_file.as_path());
     let poly_simpl_config = match config.poly_simpl_tolerance {
-        Some(tolerance) => PolySimplConfig::Enabled { tolerance },
+        Some(tolerance) => PolySimplConfig::Enabled {
+            tolerance,
+            direction: config.poly_simpl_direction,
+        },
         None => PolySimplConfig::Disabled,
     };
 
     let parser = Parser::new(poly_simpl_config, config.cde_config, true);
     let instance = parser.parse(&json_instance);
 
+    if args.dry_run {
+        println!("{}", DryRunReport::new(&instance));
+        return;
+    }
+
     let rng = match config.prng_seed {
-        Some(seed) => SmallRng::seed_from_u64(seed),
-        None => SmallRng::from_entropy(),
+        Some(seed) => LBFRng::seed_from_u64(seed),
+        None => LBFRng::from_os_entropy(),
     };
 
     let mut optimizer = LBFOptimizer::new(instance.clone(), config, rng);
-    let solution = optimizer.solve();
+    let solution = match !args.quiet && std::io::stderr().is_terminal() {
+        true => {
+            let progress_bar = ProgressBar::new(instance.total_item_qty() as u64).with_style(
+                ProgressStyle::with_template(
+                    "[{elapsed_precise}] {wide_bar} {pos}/{len} items placed, usage: {msg}",
+                )
+                .unwrap(),
+            );
+            let solution = optimizer.solve_with_progress(|p: LBFProgress| {
+                progress_bar.set_position(p.n_items_placed as u64);
+                progress_bar.set_message(format!("{:.3}%", p.usage * 100.0));
+            });
+            progress_bar.finish_and_clear();
+            solution
+        }
+        false => {
+            //fall back to logging progress every 10% of the items
+            let mut next_report = 0.1;
+            optimizer.solve_with_progress(|p: LBFProgress| {
+                let fraction = p.n_items_placed as f64 / p.n_items_total as f64;
+                if fraction >= next_report {
+                    info!(
+                        "[LBF] progress: {}/{} items placed, usage: {:.3}%",
+                        p.n_items_placed,
+                        p.n_items_total,
+                        p.usage * 100.0
+                    );
+                    next_report = (fraction * 10.0).floor() / 10.0 + 0.1;
+                }
+            })
+        }
+    };
 
     let json_output = JsonOutput {
         instance: json_instance.clone(),
-        solution: parser::compose_json_solution(&solution, &instance, *EPOCH),
+        solution: parser::compose_json_solution(
+            &solution,
+            &instance,
+            *EPOCH,
+            config.output_grid,
+            false,
+        ),
         config,
     };
 
@@ -88,6 +137,18 @@ fn main() {
         .join(format!("sol_{}.json", input_file_stem));
     io::write_json_output(&json_output, Path::new(&solution_path));
 
+    if args.gcode {
+        let gcode_path = args
+            .solution_folder
+            .join(format!("sol_{}.gcode", input_file_stem));
+        io::write_gcode(
+            &solution,
+            &instance,
+            Path::new(&gcode_path),
+            &GcodeOptions::default(),
+        );
+    }
+
     for (i, s_layout) in solution.layout_snapshots.iter().enumerate() {
         let svg_path = args
             .solution_folder
@@ -0,0 +1,170 @@
+use itertools::Itertools;
+
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::item::Item;
+use jagua_rs::fsize;
+use jagua_rs::geometry::d_transformation::DTransformation;
+use jagua_rs::geometry::geo_enums::AllowedRotation;
+use jagua_rs::geometry::geo_traits::{Shape, Transformable};
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+use jagua_rs::util::fpa::FPA;
+
+const FRAC_PI_2: fsize = std::f64::consts::FRAC_PI_2 as fsize;
+
+/// Gap (as a fraction of the smallest dimension of the container) kept around every rectangle.
+/// Touching edges are considered collisions by the collision detection engine, so rectangles cannot be packed flush.
+pub const RECT_PACKING_GAP: fsize = 1e-4;
+
+/// Heuristics to select the free rectangle in which to place a new rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxRectsHeuristic {
+    /// Minimizes the shortest leftover side of the free rectangle, well suited for bins of fixed size
+    BestShortSideFit,
+    /// Minimizes the right edge of the placed rectangle, then its bottom edge, well suited for strips
+    BottomLeft,
+}
+
+/// Free space of a rectangular container, represented as the set of all maximal free rectangles.
+/// See Jylänki, J. (2010). A thousand ways to pack the bin - a practical approach to two-dimensional rectangle bin packing.
+#[derive(Debug, Clone)]
+pub struct MaxRects {
+    free_rects: Vec<AARectangle>,
+}
+
+impl MaxRects {
+    pub fn new(container: AARectangle) -> Self {
+        Self {
+            free_rects: vec![container],
+        }
+    }
+
+    pub fn free_rects(&self) -> &[AARectangle] {
+        &self.free_rects
+    }
+
+    /// Searches a free rectangle that can hold a `width` x `height` rectangle.
+    /// Returns the rectangle positioned in the bottom-left corner of the best free rectangle, along with its score (lower is better).
+    pub fn find_position(
+        &self,
+        width: fsize,
+        height: fsize,
+        heuristic: MaxRectsHeuristic,
+    ) -> Option<(AARectangle, (fsize, fsize))> {
+        self.free_rects
+            .iter()
+            .filter(|f| width <= f.width() && height <= f.height())
+            .map(|f| {
+                let score = match heuristic {
+                    MaxRectsHeuristic::BestShortSideFit => {
+                        let (dw, dh) = (f.width() - width, f.height() - height);
+                        (fsize::min(dw, dh), fsize::max(dw, dh))
+                    }
+                    MaxRectsHeuristic::BottomLeft => (f.x_min + width, f.y_min),
+                };
+                let rect = AARectangle::new(f.x_min, f.y_min, f.x_min + width, f.y_min + height);
+                (rect, score)
+            })
+            .min_by(|(_, s1), (_, s2)| s1.partial_cmp(s2).unwrap())
+    }
+
+    /// Marks `rect` as occupied, splitting all free rectangles it overlaps with into maximal free rectangles.
+    pub fn occupy(&mut self, rect: &AARectangle) {
+        self.free_rects = self
+            .free_rects
+            .iter()
+            .flat_map(|f| f.subtract(rect))
+            .collect_vec();
+
+        AARectangle::remove_enclosed(&mut self.free_rects);
+    }
+}
+
+/// Returns the width and height of the shape if it is an axis-aligned rectangle.
+pub fn rect_dimensions(shape: &SimplePolygon) -> Option<(fsize, fsize)> {
+    shape.as_rect().map(|r| (r.width(), r.height()))
+}
+
+/// Returns the allowed rotations which keep an axis-aligned rectangle axis-aligned.
+/// Only one rotation is returned per distinct footprint (regular and rotated by 90°).
+pub fn axis_aligned_rotations(allowed_rotation: &AllowedRotation) -> Vec<fsize> {
+    let candidates = match allowed_rotation {
+        AllowedRotation::None => vec![0.0],
+        AllowedRotation::Continuous => vec![0.0, FRAC_PI_2],
+        AllowedRotation::Discrete(rotations) => rotations
+            .iter()
+            .cloned()
+            .filter(|r| FPA(*r / FRAC_PI_2) == FPA((*r / FRAC_PI_2).round()))
+            .collect_vec(),
+        AllowedRotation::Range { min, max } => {
+            let (k_min, k_max) = ((min / FRAC_PI_2).ceil(), (max / FRAC_PI_2).floor());
+            (k_min as i64..=k_max as i64)
+                .map(|k| k as fsize * FRAC_PI_2)
+                .collect_vec()
+        }
+    };
+    candidates
+        .into_iter()
+        .unique_by(|r| (*r / FRAC_PI_2).round() as i64 % 2 != 0)
+        .collect_vec()
+}
+
+/// Whether the item is an axis-aligned rectangle which can be placed axis-aligned
+pub fn is_rect_item(item: &Item) -> bool {
+    rect_dimensions(&item.shape).is_some()
+        && !axis_aligned_rotations(&item.allowed_rotation).is_empty()
+}
+
+/// Whether the instance only consists of axis-aligned rectangular items and rectangular containers without holes or quality zones,
+/// in which case it can be solved with [MaxRects].
+/// Items restricted to a position lattice cannot be placed by [MaxRects].
+pub fn is_rect_instance(instance: &Instance) -> bool {
+    let rect_bins = match instance {
+        Instance::BP(bpi) => bpi.bins.iter().all(|(bin, _)| {
+            rect_dimensions(&bin.outer).is_some()
+                && bin.holes.is_empty()
+                && bin
+                    .quality_zones
+                    .iter()
+                    .flatten()
+                    .all(|qz| qz.zones.is_empty())
+        }),
+        Instance::SP(_) => true,
+    };
+    rect_bins
+        && instance
+            .items()
+            .iter()
+            .all(|(item, _)| is_rect_item(item) && item.position_lattice.is_none())
+}
+
+/// Finds the best position for a rectangular item in the free space, over all of its axis-aligned rotations.
+/// Returns the transformation to apply to the item and the occupied footprint (including the gap).
+pub fn find_rect_placement(
+    free_space: &MaxRects,
+    item: &Item,
+    gap: fsize,
+    heuristic: MaxRectsHeuristic,
+) -> Option<(DTransformation, AARectangle, (fsize, fsize))> {
+    axis_aligned_rotations(&item.allowed_rotation)
+        .into_iter()
+        .filter_map(|rotation| {
+            let rotated_bbox = item
+                .shape
+                .transform_clone(&DTransformation::new(rotation, (0.0, 0.0)).compose())
+                .bbox();
+            let (width, height) = (rotated_bbox.width() + gap, rotated_bbox.height() + gap);
+            free_space
+                .find_position(width, height, heuristic)
+                .map(|(footprint, score)| {
+                    let translation = (
+                        footprint.x_min + gap / 2.0 - rotated_bbox.x_min,
+                        footprint.y_min + gap / 2.0 - rotated_bbox.y_min,
+                    );
+                    let d_transf = DTransformation::new(rotation, translation);
+                    (d_transf, footprint, score)
+                })
+        })
+        .min_by(|(_, _, s1), (_, _, s2)| s1.partial_cmp(s2).unwrap())
+}
@@ -0,0 +1,62 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use rand::rngs::OsRng;
+use rand::{Error, RngCore, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+
+/// Pseudo-random number generator used by the optimizer and its samplers.
+/// Unlike `SmallRng`, whose algorithm depends on the platform and version of `rand`,
+/// a given seed produces the same sequence of numbers on every target.
+#[derive(Debug, Clone)]
+pub struct LBFRng(Pcg64Mcg);
+
+impl RngCore for LBFRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl SeedableRng for LBFRng {
+    type Seed = <Pcg64Mcg as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        LBFRng(Pcg64Mcg::from_seed(seed))
+    }
+}
+
+impl LBFRng {
+    /// Seeds the generator from the entropy source of the operating system.
+    /// Unlike [SeedableRng::from_entropy], this does not panic when no entropy is available (e.g. in some sandboxed environments),
+    /// see [LBFRng::from_entropy_source].
+    pub fn from_os_entropy() -> Self {
+        Self::from_entropy_source(OsRng)
+    }
+
+    /// Seeds the generator from `source`. If `source` fails to provide a seed,
+    /// a warning is logged and the generator is seeded with the current system time instead (or 0 if that is unavailable too).
+    pub fn from_entropy_source(source: impl RngCore) -> Self {
+        match Self::from_rng(source) {
+            Ok(rng) => rng,
+            Err(err) => {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64);
+                warn!("[RNG] no entropy available ({err}), falling back to seed {seed}");
+                Self::seed_from_u64(seed)
+            }
+        }
+    }
+}
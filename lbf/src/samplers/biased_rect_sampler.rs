@@ -0,0 +1,43 @@
+use rand::distributions::{Distribution, Uniform};
+use rand::Rng;
+
+use jagua_rs::entities::item::Item;
+use jagua_rs::fsize;
+use jagua_rs::geometry::d_transformation::DTransformation;
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+
+use crate::samplers::rotation_distr::UniformRotDistr;
+
+/// Samples a `DTransformation` over a given `AARectangle`, biased toward its bottom-left corner, and a `UniformRotDistr`.
+/// Both coordinates are drawn as `min + (max - min) * u^(1 + bias)`, with `u` uniform in [0, 1).
+/// A bias of 0 samples uniformly, higher values concentrate the samples near the bottom and left edges.
+pub struct BiasedSampler {
+    pub bbox: AARectangle,
+    pub bias: fsize,
+    pub uniform_unit: Uniform<fsize>,
+    pub uniform_r: UniformRotDistr,
+}
+
+impl BiasedSampler {
+    pub fn new(bbox: AARectangle, item: &Item, bias: fsize) -> Self {
+        assert!(bias >= 0.0, "bias must be non-negative");
+        Self {
+            bbox,
+            bias,
+            uniform_unit: Uniform::new(0.0, 1.0),
+            uniform_r: UniformRotDistr::from_item(item),
+        }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> DTransformation {
+        let r_sample = self.uniform_r.sample(rng);
+        let x_sample = self.bbox.x_min + self.bbox.width() * self.biased_unit(rng);
+        let y_sample = self.bbox.y_min + self.bbox.height() * self.biased_unit(rng);
+
+        DTransformation::new(r_sample, (x_sample, y_sample))
+    }
+
+    fn biased_unit(&self, rng: &mut impl Rng) -> fsize {
+        self.uniform_unit.sample(rng).powf(1.0 + self.bias)
+    }
+}
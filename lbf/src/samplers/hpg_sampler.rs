@@ -6,11 +6,13 @@ use rand::Rng;
 use jagua_rs::entities::item::Item;
 use jagua_rs::entities::layout::Layout;
 use jagua_rs::fsize;
+use jagua_rs::geometry::d_transformation::DTransformation;
 use jagua_rs::geometry::geo_traits::Shape;
 use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
 use jagua_rs::geometry::transformation::Transformation;
 
 use crate::lbf_cost::LBFPlacingCost;
+use crate::samplers::biased_rect_sampler::BiasedSampler;
 use crate::samplers::uniform_rect_sampler::UniformAARectSampler;
 
 /// Creates `Transformation` samples for a given item.
@@ -19,6 +21,8 @@ use crate::samplers::uniform_rect_sampler::UniformAARectSampler;
 pub struct HPGSampler<'a> {
     pub item: &'a Item,
     pub cell_samplers: Vec<UniformAARectSampler>,
+    /// If set, samples are drawn from the entire bin, biased toward the bottom-left, instead of from the eligible cells
+    pub biased_sampler: Option<BiasedSampler>,
     pub cost_bound: LBFPlacingCost,
     pub pretransform: Transformation,
     pub coverage_area: fsize,
@@ -66,6 +70,7 @@ impl<'a> HPGSampler<'a> {
                 Some(HPGSampler {
                     item,
                     cell_samplers,
+                    biased_sampler: None,
                     cost_bound,
                     pretransform,
                     coverage_area,
@@ -76,20 +81,47 @@ impl<'a> HPGSampler<'a> {
         }
     }
 
+    /// Draws the samples from a [BiasedSampler] over the bin's bounding box with the given bias, if it is positive.
+    /// Trades the filtering of the eligible cells for samples concentrated near the bottom-left.
+    pub fn with_bias(mut self, bias: fsize, layout: &Layout) -> Self {
+        self.biased_sampler = match bias > 0.0 {
+            true => Some(BiasedSampler::new(layout.bin.bbox(), self.item, bias)),
+            false => None,
+        };
+        self
+    }
+
     /// Samples a `Transformation`
     pub fn sample(&mut self, rng: &mut impl Rng) -> Transformation {
-        self.n_samples += 1;
+        let sample = self.sample_decomposed(rng);
 
-        //sample one of the eligible cells
-        let cell_sampler = self.cell_samplers.choose(rng).expect("no active samplers");
+        //combine the pretransform with the sampled transformation
+        self.pretransform.clone().transform_from_decomposed(&sample)
+    }
 
-        //from that cell, sample a transformation
-        let sample = cell_sampler.sample(rng);
+    /// Samples a `Transformation` like [HPGSampler::sample], but with the given `rotation` instead of a sampled one
+    pub fn sample_with_rotation(&mut self, rng: &mut impl Rng, rotation: fsize) -> Transformation {
+        let sample = self.sample_decomposed(rng);
+        let sample = DTransformation::new(rotation, sample.translation());
 
-        //combine the pretransform with the sampled transformation
         self.pretransform.clone().transform_from_decomposed(&sample)
     }
 
+    fn sample_decomposed(&mut self, rng: &mut impl Rng) -> DTransformation {
+        self.n_samples += 1;
+
+        match &self.biased_sampler {
+            Some(biased_sampler) => biased_sampler.sample(rng),
+            None => {
+                //sample one of the eligible cells
+                let cell_sampler = self.cell_samplers.choose(rng).expect("no active samplers");
+
+                //from that cell, sample a transformation
+                cell_sampler.sample(rng)
+            }
+        }
+    }
+
     /// Removes all cells that cannot possibly generate a `Transformation` which would be better than the current best solution.
     /// LBF specific
     pub fn tighten(&mut self, best: LBFPlacingCost) {
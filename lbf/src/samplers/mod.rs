@@ -1,3 +1,4 @@
+pub mod biased_rect_sampler;
 pub mod hpg_sampler;
 pub mod ls_sampler;
 pub mod rotation_distr;
@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use rand::distributions::Uniform;
 use rand::prelude::Distribution;
 use rand::seq::SliceRandom;
@@ -22,18 +23,39 @@ pub enum UniformRotDistr {
 
 /// Samples a rotation from a normal distribution over a given range or a discrete set of rotations.
 /// In case of discrete rotations the mean is always returned.
+/// In case of a bounded range, samples are clamped into [min, max].
 pub enum NormalRotDistr {
     Range(Normal<fsize>),
+    BoundedRange(Normal<fsize>, (fsize, fsize)),
     Discrete(fsize),
     None,
 }
 
 impl UniformRotDistr {
+    /// Rotations which result in the same shape, due to the item's rotational symmetry, are only sampled once.
     pub fn from_item(item: &Item) -> Self {
+        let symmetry_angle = 2.0 * PI / item.rotational_symmetry as fsize;
         match &item.allowed_rotation {
             AllowedRotation::None => UniformRotDistr::None,
-            AllowedRotation::Continuous => UniformRotDistr::Range(Uniform::new(0.0, 2.0 * PI)),
-            AllowedRotation::Discrete(a_o) => UniformRotDistr::Discrete(a_o.clone()),
+            AllowedRotation::Continuous => {
+                UniformRotDistr::Range(Uniform::new(0.0, symmetry_angle))
+            }
+            AllowedRotation::Discrete(a_o) => {
+                //keep the first rotation of every group of rotations which are equivalent modulo the symmetry angle
+                let distinct = a_o
+                    .iter()
+                    .cloned()
+                    .unique_by(|r| {
+                        let steps = (r.rem_euclid(symmetry_angle) / symmetry_angle * 1e4).round();
+                        (steps as i64) % 10_000
+                    })
+                    .collect_vec();
+                UniformRotDistr::Discrete(distinct)
+            }
+            AllowedRotation::Range { min, max } => {
+                let max = fsize::min(*max, min + symmetry_angle);
+                UniformRotDistr::Range(Uniform::new_inclusive(*min, max))
+            }
         }
     }
 
@@ -54,6 +76,10 @@ impl NormalRotDistr {
                 NormalRotDistr::Range(Normal::new(r_ref, stddev).unwrap())
             }
             AllowedRotation::Discrete(_) => NormalRotDistr::Discrete(r_ref),
+            AllowedRotation::Range { min, max } => NormalRotDistr::BoundedRange(
+                Normal::new(r_ref.clamp(*min, *max), stddev).unwrap(),
+                (*min, *max),
+            ),
         }
     }
 
@@ -62,13 +88,16 @@ impl NormalRotDistr {
             NormalRotDistr::Range(n) => {
                 *n = Normal::new(mean, n.std_dev()).unwrap();
             }
+            NormalRotDistr::BoundedRange(n, (min, max)) => {
+                *n = Normal::new(mean.clamp(*min, *max), n.std_dev()).unwrap();
+            }
             NormalRotDistr::Discrete(_) | NormalRotDistr::None => {}
         }
     }
 
     pub fn set_stddev(&mut self, stddev: fsize) {
         match self {
-            NormalRotDistr::Range(n) => {
+            NormalRotDistr::Range(n) | NormalRotDistr::BoundedRange(n, _) => {
                 *n = Normal::new(n.mean(), stddev).unwrap();
             }
             NormalRotDistr::Discrete(_) | NormalRotDistr::None => {}
@@ -79,6 +108,7 @@ impl NormalRotDistr {
         match self {
             NormalRotDistr::None => 0.0,
             NormalRotDistr::Range(n) => n.sample(rng),
+            NormalRotDistr::BoundedRange(n, (min, max)) => n.sample(rng).clamp(*min, *max),
             NormalRotDistr::Discrete(r) => *r,
         }
     }
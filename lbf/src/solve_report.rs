@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use itertools::Itertools;
+
+/// Number of items listed by the [Display] implementation of [SolveReport]
+const N_ITEMS_DISPLAYED: usize = 5;
+
+/// Sampling effort spent on every item during [LBFOptimizer::solve](crate::lbf_optimizer::LBFOptimizer::solve),
+/// to find out which items are hard to place (e.g. awkward concave parts).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SolveReport {
+    /// Effort spent per item, indexed by item id
+    pub items: Vec<ItemSolveStats>,
+}
+
+/// Sampling effort spent on a single item, see [SolveReport]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ItemSolveStats {
+    pub item_id: usize,
+    /// Number of times a placement was searched for a copy of the item
+    pub n_attempts: usize,
+    /// Number of copies of the item which were placed
+    pub n_placed: usize,
+    /// Number of samples spent searching placements for the item
+    pub n_samples: usize,
+    /// Time spent searching placements for the item
+    pub sampling_time: Duration,
+    /// Number of attempts which ran out of the sample budget in the open layouts,
+    /// see [LBFConfig::max_samples_per_item](crate::lbf_config::LBFConfig::max_samples_per_item)
+    pub n_budget_exhausted: usize,
+}
+
+impl ItemSolveStats {
+    /// Average time spent per attempt, zero if the item was never attempted
+    pub fn time_per_attempt(&self) -> Duration {
+        match self.n_attempts {
+            0 => Duration::ZERO,
+            n => self.sampling_time / n as u32,
+        }
+    }
+}
+
+impl SolveReport {
+    pub fn new(n_items: usize) -> Self {
+        let items = (0..n_items)
+            .map(|item_id| ItemSolveStats {
+                item_id,
+                ..ItemSolveStats::default()
+            })
+            .collect();
+        Self { items }
+    }
+
+    /// Registers an attempt to place a copy of item `item_id`
+    pub fn record_attempt(
+        &mut self,
+        item_id: usize,
+        n_samples: usize,
+        sampling_time: Duration,
+        placed: bool,
+        budget_exhausted: bool,
+    ) {
+        let stats = &mut self.items[item_id];
+        stats.n_attempts += 1;
+        stats.n_placed += placed as usize;
+        stats.n_samples += n_samples;
+        stats.sampling_time += sampling_time;
+        stats.n_budget_exhausted += budget_exhausted as usize;
+    }
+
+    pub fn total_sampling_time(&self) -> Duration {
+        self.items.iter().map(|i| i.sampling_time).sum()
+    }
+
+    pub fn total_samples(&self) -> usize {
+        self.items.iter().map(|i| i.n_samples).sum()
+    }
+
+    /// Ids of the items whose time per attempt exceeds `factor` times the average time per attempt over all items,
+    /// sorted from most to least time consuming.
+    pub fn disproportionate_items(&self, factor: f64) -> Vec<usize> {
+        let n_attempts = self.items.iter().map(|i| i.n_attempts).sum::<usize>();
+        if n_attempts == 0 {
+            return vec![];
+        }
+        let mean_time = self.total_sampling_time().as_secs_f64() / n_attempts as f64;
+        self.items
+            .iter()
+            .filter(|i| i.time_per_attempt().as_secs_f64() > factor * mean_time)
+            .sorted_by_key(|i| std::cmp::Reverse(i.time_per_attempt()))
+            .map(|i| i.item_id)
+            .collect()
+    }
+}
+
+impl Display for SolveReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "sampling time: {:.3}ms ({} samples)",
+            self.total_sampling_time().as_secs_f64() * 1000.0,
+            self.total_samples()
+        )?;
+        let slowest = self
+            .items
+            .iter()
+            .filter(|i| i.n_attempts > 0)
+            .sorted_by_key(|i| std::cmp::Reverse(i.sampling_time))
+            .take(N_ITEMS_DISPLAYED)
+            .collect_vec();
+        for stats in slowest {
+            writeln!(
+                f,
+                "item {}: {:.3}ms, {} samples, {}/{} placed, budget exhausted {} times",
+                stats.item_id,
+                stats.sampling_time.as_secs_f64() * 1000.0,
+                stats.n_samples,
+                stats.n_placed,
+                stats.n_attempts,
+                stats.n_budget_exhausted
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,22 +1,115 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
     use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Instant;
 
+    use itertools::Itertools;
     use rand::prelude::IteratorRandom;
-    use rand::prelude::SmallRng;
-    use rand::{Rng, SeedableRng};
+    use rand::{Rng, RngCore, SeedableRng};
     use test_case::test_case;
 
-    use jagua_rs::entities::problems::problem_generic::LayoutIndex;
+    use jagua_rs::collision_detection::cd_engine::CDEngine;
+    use jagua_rs::collision_detection::hazard::{Hazard, HazardEntity};
+    use jagua_rs::collision_detection::hazard_filter::{
+        BinHazardFilter, HazardFilter, QZHazardFilter,
+    };
+    use jagua_rs::collision_detection::hpg::hpg_cell::HPGCell;
+    use jagua_rs::entities::bin::Bin;
+    use jagua_rs::entities::instances::bin_packing::BPInstance;
+    use jagua_rs::entities::instances::instance::Instance;
+    use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+    use jagua_rs::entities::instances::strip_packing::PackingAxis;
+    use jagua_rs::entities::item::Item;
+    use jagua_rs::entities::layout::{Layout, LayoutSnapshot};
+    use jagua_rs::entities::pareto_archive::{ParetoArchive, SolutionObjective};
+    use jagua_rs::entities::placed_item::PlacedItem;
+    use jagua_rs::entities::placing_option::PlacingOption;
+    use jagua_rs::entities::problems::bin_packing::BPProblem;
+    use jagua_rs::entities::problems::problem::Problem;
     use jagua_rs::entities::problems::problem_generic::ProblemGeneric;
-    use jagua_rs::io::parser::Parser;
-    use jagua_rs::util::polygon_simplification::PolySimplConfig;
+    use jagua_rs::entities::problems::problem_generic::{LayoutIndex, STRIP_LAYOUT_IDX};
+    use jagua_rs::entities::problems::strip_packing::SPProblem;
+    use jagua_rs::entities::quality_zone::InferiorQualityZone;
+    use jagua_rs::entities::solution::{Solution, SolutionMergeError};
+    use jagua_rs::geometry;
+    use jagua_rs::geometry::d_transformation::DTransformation;
+    use jagua_rs::geometry::fail_fast::sp_surrogate::{SPSurrogate, SPSurrogateBuilder};
+    use jagua_rs::geometry::geo_enums::{AllowedRotation, GeoPosition};
+    use jagua_rs::geometry::geo_traits::{CollidesWith, DistanceFrom, Shape, Transformable};
+    use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+    use jagua_rs::geometry::primitives::circle::Circle;
+    use jagua_rs::geometry::primitives::ellipse::Ellipse;
+    use jagua_rs::geometry::primitives::point::Point;
+    use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+    use jagua_rs::geometry::transformation::{MatrixError, Transformation};
+    use jagua_rs::io::json_instance::{
+        JsonAnchor, JsonBin, JsonInstance, JsonItem, JsonPoly, JsonQualityZone, JsonShape,
+        JsonSimplePoly, JsonStrip, RotationUnit,
+    };
+    use jagua_rs::io::json_solution::{
+        JsonContainer, JsonLayout, JsonLayoutStats, JsonPlacedItem, JsonSolution,
+        JsonTransformation,
+    };
+    use jagua_rs::io::parser;
+    use jagua_rs::io::parser::{ParseError, ParseWarning, Parser, ShapeSource, WindingPolicy};
+    use jagua_rs::util::assertions;
+    use jagua_rs::util::config::{CDEConfig, CdePreset, GeoTolerance, SPSurrogateConfig};
+    use jagua_rs::util::polygon_simplification;
+    use jagua_rs::util::polygon_simplification::{
+        PolySimplConfig, PolySimplDirection, PolySimplMode,
+    };
+    use jagua_rs::{fsize, PI};
+    use lbf::dry_run::DryRunReport;
     use lbf::io;
-    use lbf::lbf_config::LBFConfig;
-    use lbf::lbf_optimizer::LBFOptimizer;
+    use lbf::io::gcode;
+    use lbf::io::gcode::GcodeOptions;
+    use lbf::io::layout_to_svg::layout_to_svg;
+    use lbf::io::svg_util::{SvgDrawOptions, YAxis};
+    use lbf::lbf_config::{Acceptance, BinObjective, LBFConfig, RotationStrategy, Solver};
+    use lbf::lbf_cost::LBFPlacingCost;
+    use lbf::lbf_optimizer;
+    use lbf::lbf_optimizer::{AcceptanceState, LBFOptimizer};
+    use lbf::rect_packer;
+    use lbf::rng::LBFRng;
+    use lbf::samplers::biased_rect_sampler::BiasedSampler;
+    use lbf::samplers::hpg_sampler::HPGSampler;
+    use lbf::samplers::ls_sampler::LSSampler;
+    use lbf::samplers::rotation_distr::UniformRotDistr;
+    use lbf::samplers::uniform_rect_sampler::UniformAARectSampler;
+    use lbf::solve_report::SolveReport;
 
     const N_ITEMS_TO_REMOVE: usize = 5;
 
+    /// Reads and parses an instance from `path`, without polygon simplification
+    fn parse_instance(path: &str, cde_config: CDEConfig) -> Instance {
+        parse_json_instance(&io::read_json_instance(Path::new(path)), cde_config)
+    }
+
+    /// Parses `json_instance` without polygon simplification
+    fn parse_json_instance(json_instance: &JsonInstance, cde_config: CDEConfig) -> Instance {
+        Parser::new(PolySimplConfig::Disabled, cde_config, true).parse(json_instance)
+    }
+
+    /// Item without any of the optional properties
+    fn json_item(shape: JsonShape, demand: u64) -> JsonItem {
+        JsonItem {
+            demand,
+            allowed_orientations: None,
+            allowed_orientation_range: None,
+            shape,
+            value: None,
+            base_quality: None,
+            position_lattice: None,
+            anchor: None,
+            min_edge_contact: None,
+            meta: None,
+        }
+    }
+
     #[test_case("../assets/swim.json"; "swim")]
     #[test_case("../assets/shirts.json"; "shirts")]
     #[test_case("../assets/trousers.json"; "trousers")]
@@ -35,16 +128,19 @@ mod tests {
         config.n_samples = 100;
         let json_instance = io::read_json_instance(&instance);
         let poly_simpl_config = match config.poly_simpl_tolerance {
-            Some(tolerance) => PolySimplConfig::Enabled { tolerance },
+            Some(tolerance) => PolySimplConfig::Enabled {
+                tolerance,
+                direction: config.poly_simpl_direction,
+            },
             None => PolySimplConfig::Disabled,
         };
 
         let parser = Parser::new(poly_simpl_config, config.cde_config, true);
         let instance = parser.parse(&json_instance);
 
-        let mut optimizer = LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0));
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
 
-        let mut rng = SmallRng::seed_from_u64(0);
+        let mut rng = LBFRng::seed_from_u64(0);
 
         // a first optimization run
         optimizer.solve();
@@ -76,4 +172,5462 @@ mod tests {
             optimizer.solve();
         }
     }
+
+    #[test_case("../assets/swim.json"; "swim")]
+    #[test_case("../assets/baldacci1.json"; "baldacci1")]
+    fn test_compaction(instance_path: &str) {
+        let instance = Path::new(instance_path);
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let json_instance = io::read_json_instance(instance);
+        let poly_simpl_config = match config.poly_simpl_tolerance {
+            Some(tolerance) => PolySimplConfig::Enabled {
+                tolerance,
+                direction: config.poly_simpl_direction,
+            },
+            None => PolySimplConfig::Disabled,
+        };
+
+        let parser = Parser::new(poly_simpl_config, config.cde_config, true);
+        let instance = parser.parse(&json_instance);
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+
+        let extents = |optimizer: &LBFOptimizer| -> Vec<(fsize, fsize)> {
+            optimizer
+                .problem
+                .layouts()
+                .iter()
+                .map(|l| {
+                    l.placed_items()
+                        .values()
+                        .fold((fsize::MIN, fsize::MIN), |(x, y), pi| {
+                            let bbox = pi.shape.bbox();
+                            (fsize::max(x, bbox.x_max), fsize::max(y, bbox.y_max))
+                        })
+                })
+                .collect()
+        };
+
+        let before = extents(&optimizer);
+        optimizer.compact();
+        let after = extents(&optimizer);
+
+        assert_eq!(before.len(), after.len());
+        for ((x_before, y_before), (x_after, y_after)) in before.iter().zip(after.iter()) {
+            assert!(x_after <= x_before && y_after <= y_before);
+        }
+        for layout in optimizer.problem.layouts() {
+            assert!(assertions::layout_is_collision_free(layout));
+        }
+    }
+
+    #[test]
+    fn test_max_items_per_layout() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let Instance::BP(instance) = parse_instance("../assets/baldacci1.json", config.cde_config)
+        else {
+            panic!("expected a bin packing instance");
+        };
+
+        let mut bp_problem = BPProblem::new(instance.clone());
+        bp_problem.max_items_per_layout = Some(3);
+        let mut problem = Problem::BP(bp_problem);
+        let mut rng = LBFRng::seed_from_u64(0);
+        let mut sample_counter = 0;
+        let mut acceptance = AcceptanceState::new(config.acceptance);
+
+        //the smallest item, so it is guaranteed to fit in an existing layout multiple times
+        let item = instance
+            .items
+            .iter()
+            .map(|(item, _)| item)
+            .min_by(|a, b| a.shape.area().partial_cmp(&b.shape.area()).unwrap())
+            .unwrap();
+
+        //bins are only opened on demand
+        if let Problem::BP(bp) = &mut problem {
+            bp.ensure_open_bin_for(item.id).unwrap();
+        }
+        for _ in 0..3 {
+            let p_opt = lbf_optimizer::find_lbf_placement(
+                &problem,
+                item,
+                &config,
+                &mut rng,
+                &mut sample_counter,
+                &mut acceptance,
+            )
+            .unwrap();
+            let (l_idx, _) = problem.place_item(p_opt);
+            assert_eq!(l_idx, LayoutIndex::Real(0));
+        }
+        assert!(problem.layout_is_full(LayoutIndex::Real(0)));
+
+        //the 4th item would still fit in the first layout...
+        let mut unrestricted = problem.clone();
+        if let Problem::BP(bp) = &mut unrestricted {
+            bp.max_items_per_layout = None;
+        }
+        let fitting = lbf_optimizer::sample_layout(
+            &unrestricted,
+            LayoutIndex::Real(0),
+            item,
+            &config,
+            &mut rng,
+            &mut sample_counter,
+            &mut acceptance,
+        )
+        .unwrap();
+        //...but placing it there is rejected...
+        assert!(problem.try_place_item(fitting).is_none());
+        assert_eq!(problem.layouts()[0].placed_items().len(), 3);
+
+        //...so it has to open a new one
+        assert!(lbf_optimizer::find_lbf_placement(
+            &problem,
+            item,
+            &config,
+            &mut rng,
+            &mut sample_counter,
+            &mut acceptance,
+        )
+        .is_none());
+        if let Problem::BP(bp) = &mut problem {
+            bp.ensure_open_bin_for(item.id).unwrap();
+        }
+        let p_opt = lbf_optimizer::find_lbf_placement(
+            &problem,
+            item,
+            &config,
+            &mut rng,
+            &mut sample_counter,
+            &mut acceptance,
+        )
+        .unwrap();
+        let (l_idx, _) = problem.place_item(p_opt);
+        assert_eq!(l_idx, LayoutIndex::Real(1));
+        assert_eq!(problem.layouts().len(), 2);
+
+        //without room for a single item, no bin has usable stock
+        assert!(problem
+            .template_layout_indices_with_stock()
+            .next()
+            .is_some());
+        if let Problem::BP(bp) = &mut problem {
+            bp.max_items_per_layout = Some(0);
+        }
+        assert_eq!(problem.template_layout_indices_with_stock().count(), 0);
+    }
+
+    #[test]
+    fn test_perimeter() {
+        let square = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(1.0, 0.0),
+            Point(1.0, 1.0),
+            Point(0.0, 1.0),
+        ]);
+        assert!((square.perimeter() - 4.0).abs() < 1e-6);
+        assert!((AARectangle::new(0.0, 0.0, 1.0, 1.0).perimeter() - 4.0).abs() < 1e-6);
+
+        let circle = Circle::new(Point(0.0, 0.0), 1.0);
+        assert!((circle.perimeter() - 2.0 * std::f64::consts::PI as fsize).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rotation_range() {
+        let (min, max) = (-0.26, 0.26);
+        let shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(2.0, 0.0),
+            Point(2.0, 1.0),
+            Point(0.0, 1.0),
+        ]);
+        let item = Item::new(
+            0,
+            shape,
+            AllowedRotation::Range { min, max },
+            None,
+            0,
+            Transformation::empty(),
+            LBFConfig::default().cde_config.item_surrogate_config,
+        );
+        let mut rng = LBFRng::seed_from_u64(0);
+
+        let uniform = UniformRotDistr::from_item(&item);
+        let bbox = AARectangle::new(0.0, 0.0, 10.0, 10.0);
+        //reference rotation at the edge of the range, so the normal distribution regularly falls outside of it
+        let mut ls_sampler =
+            LSSampler::from_defaults(&item, &DTransformation::new(max, (5.0, 5.0)), &bbox);
+        ls_sampler.set_stddev(1.0, 1.0);
+
+        for _ in 0..1000 {
+            let r = uniform.sample(&mut rng);
+            assert!(min <= r && r <= max, "uniform rotation {r} out of range");
+            let r = ls_sampler.sample(&mut rng).rotation();
+            assert!(
+                min <= r && r <= max,
+                "local search rotation {r} out of range"
+            );
+        }
+
+        //a range in the input is parsed into radians, conflicting or inverted ranges are rejected
+        let json_instance = |allowed_orientations, allowed_orientation_range| JsonInstance {
+            name: "rotation_range".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations,
+                allowed_orientation_range,
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 2.0,
+                        height: 1.0,
+                    },
+                    1,
+                )
+            }],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 10.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let instance = parser
+            .parse_with_warnings(&json_instance(None, Some([-15.0, 15.0])))
+            .unwrap()
+            .0;
+        let AllowedRotation::Range { min, max } = instance.item(0).allowed_rotation else {
+            panic!("expected a rotation range");
+        };
+        assert!((min + 15.0_f64.to_radians() as fsize).abs() < 1e-6);
+        assert!((max - 15.0_f64.to_radians() as fsize).abs() < 1e-6);
+        assert_eq!(
+            parser
+                .parse_with_warnings(&json_instance(Some(vec![0.0, 90.0]), Some([-15.0, 15.0])))
+                .err(),
+            Some(ParseError::ConflictingOrientations { item_id: 0 })
+        );
+        assert_eq!(
+            parser
+                .parse_with_warnings(&json_instance(None, Some([15.0, -15.0])))
+                .err(),
+            Some(ParseError::InvalidOrientationRange { item_id: 0 })
+        );
+    }
+
+    #[test]
+    fn test_sub_instance() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+
+        //first pass
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let layout = &optimizer.problem.layouts()[0];
+
+        //second pass: nest extra copies of the smallest item into the gaps of the first pass
+        let mut small_item = instance
+            .items()
+            .iter()
+            .map(|(item, _)| item)
+            .min_by(|a, b| a.shape.area().partial_cmp(&b.shape.area()).unwrap())
+            .unwrap()
+            .clone();
+        small_item.id = 0;
+        let sub_instance = layout.to_sub_instance(vec![(small_item, 10)]);
+        assert_eq!(
+            sub_instance.bins[0].0.holes.len(),
+            layout.bin.holes.len() + layout.placed_items().len()
+        );
+
+        let mut sub_optimizer =
+            LBFOptimizer::new(Instance::BP(sub_instance), config, LBFRng::seed_from_u64(0));
+        let solution = sub_optimizer.solve();
+
+        assert!(solution.n_items_placed() > 0);
+        for layout in sub_optimizer.problem.layouts() {
+            assert!(assertions::layout_is_collision_free(layout));
+        }
+    }
+
+    #[test]
+    fn test_point_helpers() {
+        let a = Point(0.0, 0.0);
+        let b = Point(3.0, 4.0);
+        assert!((a.distance(b) - 5.0).abs() < 1e-6);
+        assert!((a.sq_distance(b) - 25.0).abs() < 1e-6);
+        assert_eq!(a.midpoint(b), Point(1.5, 2.0));
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+    }
+
+    #[test]
+    fn test_svg_round_coordinates() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+
+        let options = SvgDrawOptions {
+            surrogate: true,
+            round_coordinates: Some(2),
+            ..SvgDrawOptions::default()
+        };
+        let layout = &optimizer.problem.layouts()[0];
+        let svg = layout_to_svg(layout, &instance, options).to_string();
+
+        let mut n_coords = 0;
+        for attr in ["d=\"", "transform=\"", "viewBox=\"", "cx=\"", "cy=\""] {
+            for value in svg.split(attr).skip(1) {
+                let value = value.split('"').next().unwrap();
+                let numbers = value
+                    .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == 'e'))
+                    .filter(|n| n.chars().any(|c| c.is_ascii_digit()));
+                for number in numbers {
+                    let n_fractional_digits = number.split('.').nth(1).map_or(0, |f| f.len());
+                    assert!(!number.contains('e'), "{number} in exponential notation");
+                    assert!(
+                        n_fractional_digits <= 2,
+                        "{number} has more than 2 decimals"
+                    );
+                    n_coords += 1;
+                }
+            }
+        }
+        assert!(n_coords > 0);
+    }
+
+    #[test]
+    fn test_oversized_item_is_skipped() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let strip_height = json_instance.strip.as_ref().unwrap().height;
+
+        //add an item which cannot fit in the strip, at any rotation
+        let mut oversized_json_instance = json_instance.clone();
+        oversized_json_instance.items.push(json_item(
+            JsonShape::Rectangle {
+                width: 2.0 * strip_height,
+                height: 2.0 * strip_height,
+            },
+            1,
+        ));
+        let oversized_item_id = oversized_json_instance.items.len() - 1;
+        //both runs start from the same strip width, which is otherwise derived from the total item area
+        let strip_width =
+            parse_json_instance(&oversized_json_instance, config.cde_config).item_area() * 2.0
+                / strip_height;
+        let solve = |json_instance| {
+            let instance = parse_json_instance(json_instance, config.cde_config);
+            let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+            if let Problem::SP(sp_problem) = &mut optimizer.problem {
+                sp_problem.modify_strip_in_back(strip_width);
+            }
+            let solution = optimizer.solve();
+            (optimizer, solution)
+        };
+
+        let (optimizer, solution) = solve(&oversized_json_instance);
+        let oversized_item = &optimizer.instance.items()[oversized_item_id].0;
+        assert!(!optimizer.item_fits_instance(oversized_item));
+        assert_eq!(solution.placed_item_qtys[oversized_item_id], 0);
+
+        //the oversized item should not have been sampled at all
+        let (reference_optimizer, reference_solution) = solve(&json_instance);
+        assert_eq!(optimizer.sample_counter, reference_optimizer.sample_counter);
+        assert_eq!(
+            solution.n_items_placed(),
+            reference_solution.n_items_placed()
+        );
+    }
+
+    #[test]
+    fn test_nearest_item() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let layout = &optimizer.problem.layouts()[0];
+
+        for (pik, pi) in layout.placed_items().iter() {
+            //the pole of inaccessibility always lies inside the item
+            let (nearest_pik, distance) = layout.nearest_item(pi.shape.poi.center).unwrap();
+            assert_eq!(nearest_pik, pik);
+            assert_eq!(distance, 0.0);
+        }
+
+        let bbox = layout.bin.bbox();
+        let (_, distance) = layout
+            .nearest_item(Point(bbox.x_max + 1000.0, bbox.y_max + 1000.0))
+            .unwrap();
+        assert!(distance > 0.0);
+
+        //the quadtree search agrees with an exhaustive one
+        let mut rng = LBFRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let p = Point(
+                rng.gen_range(bbox.x_min - 10.0..bbox.x_max + 10.0),
+                rng.gen_range(bbox.y_min - 10.0..bbox.y_max + 10.0),
+            );
+            let exhaustive = layout
+                .placed_items()
+                .iter()
+                .map(|(k, pi)| (k, pi.shape.distance(&p)))
+                .min_by(|(k1, d1), (k2, d2)| d1.total_cmp(d2).then(k1.cmp(k2)));
+            assert_eq!(layout.nearest_item(p), exhaustive);
+        }
+    }
+
+    #[test]
+    fn test_svg_margin() {
+        let margin = 10.0;
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let layout = &optimizer.problem.layouts()[0];
+
+        let options = SvgDrawOptions {
+            margin: Some(margin),
+            ..SvgDrawOptions::default()
+        };
+        let svg = layout_to_svg(layout, &instance, options).to_string();
+
+        let margin_path = svg
+            .split("<path")
+            .find(|p| p.contains("id=\"bin_margin\""))
+            .expect("margin not drawn");
+        let data = margin_path.split("d=\"").nth(1).unwrap();
+        let coords = data
+            .split('"')
+            .next()
+            .unwrap()
+            .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<fsize>().unwrap())
+            .collect_vec();
+
+        //bounding box of the bin in absolute coordinates
+        let bin = parser::pretransform_bin(&layout.bin, &layout.bin.pretransform.clone().inverse());
+        let bbox = bin.bbox();
+        let expected = [
+            (bbox.x_min + margin, bbox.y_min + margin),
+            (bbox.x_max - margin, bbox.y_min + margin),
+            (bbox.x_max - margin, bbox.y_max - margin),
+            (bbox.x_min + margin, bbox.y_max - margin),
+        ];
+        assert_eq!(coords.len(), 2 * expected.len());
+        for (i, (x, y)) in expected.iter().enumerate() {
+            assert!((coords[2 * i] - x).abs() < 1e-3);
+            assert!((coords[2 * i + 1] - y).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_solution_merge() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let json_instance = io::read_json_instance(Path::new("../assets/baldacci1.json"));
+
+        //two sub-problems, each demanding a single copy of a different item
+        let solve_sub_problem = |item_id: usize| {
+            let mut sub_json_instance = json_instance.clone();
+            for (i, json_item) in sub_json_instance.items.iter_mut().enumerate() {
+                json_item.demand = if i == item_id { 1 } else { 0 };
+            }
+            let instance = parse_json_instance(&sub_json_instance, config.cde_config);
+            let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+            optimizer.solve()
+        };
+        let solutions = [solve_sub_problem(0), solve_sub_problem(1)];
+        assert!(solutions.iter().all(|s| s.layout_snapshots.len() == 1));
+
+        let merged = Solution::merge(&solutions).unwrap();
+        assert_eq!(merged.layout_snapshots.len(), 2);
+        assert_eq!(merged.n_items_placed(), 2);
+        assert_eq!(merged.placed_item_qtys[0], 1);
+        assert_eq!(merged.placed_item_qtys[1], 1);
+        assert_ne!(merged.layout_snapshots[0].id, merged.layout_snapshots[1].id);
+        let expected_usage = solutions.iter().map(|s| s.usage).sum::<fsize>() / 2.0;
+        assert!((merged.usage - expected_usage).abs() < 1e-6);
+
+        //merging a solution with itself is not allowed
+        assert_eq!(
+            Solution::merge(&[solutions[0].clone(), solutions[0].clone()]).unwrap_err(),
+            SolutionMergeError::OverlappingItem(0)
+        );
+    }
+
+    #[test]
+    fn test_hazard_filter_combinators() {
+        let entities = [
+            HazardEntity::PlacedItem {
+                id: 0,
+                dt: DTransformation::empty(),
+            },
+            HazardEntity::BinExterior,
+            HazardEntity::BinHole { id: 0 },
+            HazardEntity::InferiorQualityZone { quality: 0, id: 0 },
+            HazardEntity::InferiorQualityZone { quality: 2, id: 1 },
+        ];
+        let (bin_filter, qz_filter) = (BinHazardFilter, QZHazardFilter(1));
+
+        let and_filter = (&bin_filter).and(&qz_filter);
+        let or_filter = (&bin_filter).or(&qz_filter);
+        let not_filter = (&bin_filter).not();
+        for entity in &entities {
+            let (a, b) = (
+                bin_filter.is_irrelevant(entity),
+                qz_filter.is_irrelevant(entity),
+            );
+            assert_eq!(and_filter.is_irrelevant(entity), a && b);
+            assert_eq!(or_filter.is_irrelevant(entity), a || b);
+            assert_eq!(not_filter.is_irrelevant(entity), !a);
+        }
+        //only the inferior quality zone above the cutoff is deemed irrelevant by both
+        assert_eq!(
+            entities
+                .iter()
+                .filter(|e| and_filter.is_irrelevant(e))
+                .collect_vec(),
+            vec![&entities[4]]
+        );
+    }
+
+    #[test]
+    fn test_progress_callback() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        let mut n_callbacks = 0;
+        let solution = optimizer.solve_with_progress(|p| {
+            n_callbacks += 1;
+            assert_eq!(p.n_items_placed, n_callbacks);
+        });
+
+        assert_eq!(n_callbacks, solution.n_items_placed());
+    }
+
+    #[test]
+    fn test_convex_distance_from_border() {
+        let hexagon = SimplePolygon::new(
+            (0..6)
+                .map(|i| {
+                    let angle = i as fsize * std::f64::consts::FRAC_PI_3 as fsize;
+                    Point(10.0 * angle.cos(), 10.0 * angle.sin())
+                })
+                .collect(),
+        );
+        //clockwise input with a collinear point, still convex
+        let rect = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(0.0, 4.0),
+            Point(6.0, 4.0),
+            Point(6.0, 0.0),
+            Point(3.0, 0.0),
+        ]);
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(6.0, 0.0),
+            Point(6.0, 2.0),
+            Point(2.0, 2.0),
+            Point(2.0, 6.0),
+            Point(0.0, 6.0),
+        ]);
+        assert!(hexagon.is_convex && rect.is_convex);
+        assert!(!l_shape.is_convex);
+
+        let mut rng = LBFRng::seed_from_u64(0);
+        for convex in [hexagon, rect] {
+            let general = SimplePolygon {
+                is_convex: false,
+                ..convex.clone()
+            };
+            for _ in 0..1000 {
+                let p = Point(rng.gen_range(-15.0..15.0), rng.gen_range(-15.0..15.0));
+                let (c_pos, c_dist) = convex.distance_from_border(&p);
+                let (g_pos, g_dist) = general.distance_from_border(&p);
+                assert!((c_dist - g_dist).abs() < 1e-4 * convex.diameter());
+                if g_dist > 1e-4 * convex.diameter() {
+                    assert_eq!(c_pos, g_pos);
+                }
+            }
+            assert_eq!(
+                convex.distance_from_border(&convex.poi.center).0,
+                GeoPosition::Interior
+            );
+        }
+    }
+
+    #[test]
+    fn test_proximity_heatmap() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let layout = &optimizer.problem.layouts()[0];
+
+        let path = std::env::temp_dir().join("jagua_rs_test_proximity_heatmap.png");
+        io::write_proximity_heatmap(layout.cde(), &layout.bin, (120, 40), &path);
+
+        let heatmap = image::open(&path).unwrap().to_rgb8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(heatmap.dimensions(), (120, 40));
+        assert!(heatmap.pixels().any(|p| p.0 == [0, 0, 0]));
+        assert!(heatmap.pixels().unique().count() > 2);
+    }
+
+    #[test]
+    fn test_transformation_matrix_round_trip() {
+        let t = Transformation::from_rotation(0.7)
+            .translate((3.5, -2.0))
+            .rotate(-1.3);
+        let m = t.as_matrix();
+        let round_trip = Transformation::from_matrix(m).unwrap();
+        assert_eq!(round_trip.matrix(), t.matrix());
+        assert_eq!(round_trip.as_matrix(), m);
+
+        let scaled = [[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 1.0]];
+        let sheared = [[1.0, 0.5, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let mirrored = [[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let projective = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.1, 0.0, 1.0]];
+        let nan = [[f64::NAN, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        for (m, err) in [
+            (scaled, MatrixError::NotRigid),
+            (sheared, MatrixError::NotRigid),
+            (mirrored, MatrixError::NotRigid),
+            (projective, MatrixError::NotAffine),
+            (nan, MatrixError::NonFinite),
+        ] {
+            assert_eq!(Transformation::from_matrix(m).err(), Some(err));
+        }
+    }
+
+    #[test]
+    fn test_rect_packing() {
+        let mut rng = LBFRng::seed_from_u64(0);
+        let json_instance = JsonInstance {
+            name: "rectangles".to_string(),
+            items: (0..20)
+                .map(|_| {
+                    let demand = rng.gen_range(1..4);
+                    JsonItem {
+                        allowed_orientations: Some(vec![0.0, 90.0]),
+                        ..json_item(
+                            JsonShape::Rectangle {
+                                width: rng.gen_range(5.0..40.0),
+                                height: rng.gen_range(5.0..40.0),
+                            },
+                            demand,
+                        )
+                    }
+                })
+                .collect(),
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let general_config = LBFConfig::default();
+        let parser = Parser::new(PolySimplConfig::Disabled, general_config.cde_config, true);
+        let instance = parser.parse(&json_instance);
+        assert!(rect_packer::is_rect_instance(&instance));
+
+        let solve = |config: LBFConfig| {
+            let mut optimizer =
+                LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+            let solution = optimizer.solve();
+            assert!(assertions::problem_matches_solution(
+                &optimizer.problem,
+                &solution
+            ));
+            assert_eq!(solution.n_items_placed(), instance.total_item_qty());
+            assert!(assertions::layout_is_collision_free(
+                &optimizer.problem.layouts()[0]
+            ));
+            (solution.usage, optimizer.sample_counter)
+        };
+        let (general_usage, general_samples) = solve(general_config);
+        let (rect_usage, rect_samples) = solve(LBFConfig {
+            rect_packing: true,
+            ..general_config
+        });
+        assert!(rect_usage >= general_usage);
+        //wall-clock time is unreliable in (debug-asserted) tests, the number of samples is a deterministic proxy for the work done
+        assert!(general_samples > 0);
+        assert_eq!(rect_samples, 0);
+    }
+
+    #[test]
+    fn test_cutout() {
+        let cde_config = LBFConfig::default().cde_config;
+        let square = |size: fsize| {
+            json_item(
+                JsonShape::Rectangle {
+                    width: size,
+                    height: size,
+                },
+                1,
+            )
+        };
+        let json_instance = JsonInstance {
+            name: "cutout".to_string(),
+            items: vec![square(50.0), square(20.0), square(5.0)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let Instance::SP(instance) = parse_json_instance(&json_instance, cde_config) else {
+            panic!("expected a strip packing instance")
+        };
+        let mut problem = SPProblem::new(instance.clone(), 100.0, cde_config);
+
+        //placing option for an item with its center at `center`
+        let p_opt = |item_id: usize, center: Point, subtract: bool| {
+            let Point(cx, cy) = instance.item(item_id).shape.bbox().centroid();
+            PlacingOption {
+                layout_idx: STRIP_LAYOUT_IDX,
+                item_id,
+                d_transf: DTransformation::new(0.0, (center.0 - cx, center.1 - cy)),
+                subtract,
+            }
+        };
+        problem.place_item(p_opt(0, Point(50.0, 50.0), false));
+        let usage_before = problem.usage();
+        problem.place_item(p_opt(1, Point(50.0, 50.0), true));
+        assert!(problem.usage() < usage_before);
+
+        //the cutout region is free again, the rest of the part is not
+        let probe = |Point(x, y): Point| {
+            SimplePolygon::new(vec![
+                Point(x - 2.5, y - 2.5),
+                Point(x + 2.5, y - 2.5),
+                Point(x + 2.5, y + 2.5),
+                Point(x - 2.5, y + 2.5),
+            ])
+        };
+        let cde = problem.layout.cde();
+        assert!(!cde.poly_collides(&probe(Point(50.0, 50.0)), &[]));
+        assert!(cde.poly_collides(&probe(Point(30.0, 30.0)), &[]));
+
+        problem.place_item(p_opt(2, Point(50.0, 50.0), false));
+        assert!(assertions::layout_is_collision_free(&problem.layout));
+
+        //all items are placed back after resizing the strip
+        problem.modify_strip_in_back(90.0);
+        assert_eq!(problem.layout.placed_items().len(), 3);
+        assert!(assertions::layout_is_collision_free(&problem.layout));
+
+        //removing the inner item and the cutout restores the full area of the part
+        for item_id in [2, 1] {
+            let (pik, _) = problem
+                .layout
+                .placed_items()
+                .iter()
+                .find(|(_, pi)| pi.item_id == item_id)
+                .unwrap();
+            problem.remove_item(STRIP_LAYOUT_IDX, pik, true);
+        }
+        assert_eq!(problem.usage(), 50.0 * 50.0 / (90.0 * 100.0));
+        let cde = problem.layout.cde();
+        assert!(cde.poly_collides(&probe(Point(50.0, 50.0)), &[]));
+    }
+
+    #[test]
+    fn test_compaction_with_cutout() {
+        let config = LBFConfig::default();
+        let square = |size: fsize| {
+            json_item(
+                JsonShape::Rectangle {
+                    width: size,
+                    height: size,
+                },
+                1,
+            )
+        };
+        let json_instance = JsonInstance {
+            name: "compaction_cutout".to_string(),
+            items: vec![square(50.0), square(20.0), square(5.0)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let Instance::SP(sp_instance) = &instance else {
+            panic!("expected a strip packing instance")
+        };
+        let mut problem = SPProblem::new(sp_instance.clone(), 100.0, config.cde_config);
+        let p_opt = |item_id: usize, center: Point, subtract: bool| {
+            let Point(cx, cy) = instance.item(item_id).shape.bbox().centroid();
+            PlacingOption {
+                layout_idx: STRIP_LAYOUT_IDX,
+                item_id,
+                d_transf: DTransformation::new(0.0, (center.0 - cx, center.1 - cy)),
+                subtract,
+            }
+        };
+        //the part has room to slide towards the origin, but has a region cut out of it
+        problem.place_item(p_opt(0, Point(60.0, 60.0), false));
+        problem.place_item(p_opt(1, Point(60.0, 60.0), true));
+        problem.place_item(p_opt(2, Point(95.0, 95.0), false));
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.problem = Problem::SP(problem);
+        let d_transf_of = |optimizer: &LBFOptimizer, item_id: usize| {
+            optimizer.problem.layouts()[0]
+                .placed_items()
+                .values()
+                .find(|pi| pi.item_id == item_id)
+                .map(|pi| pi.d_transf)
+                .unwrap()
+        };
+        let (part_before, cutout_before) = (d_transf_of(&optimizer, 0), d_transf_of(&optimizer, 1));
+        optimizer.compact();
+
+        //the part and its cutout stay in place, the other item can still slide
+        let layout = &optimizer.problem.layouts()[0];
+        assert_eq!(layout.placed_items().len(), 3);
+        assert_eq!(layout.cde().cutouts().len(), 1);
+        assert_eq!(d_transf_of(&optimizer, 0), part_before);
+        assert_eq!(d_transf_of(&optimizer, 1), cutout_before);
+        assert!(layout
+            .placed_items()
+            .values()
+            .any(|pi| pi.item_id == 1 && pi.subtract));
+        assert_ne!(
+            d_transf_of(&optimizer, 2),
+            DTransformation::new(0.0, (95.0, 95.0))
+        );
+        assert!(assertions::layout_is_collision_free(layout));
+    }
+
+    #[test]
+    fn test_rng_reproducibility() {
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let instance = parser.parse(&json_instance);
+        let item = instance.item(0);
+
+        let sampler = UniformAARectSampler::new(AARectangle::new(0.0, 0.0, 100.0, 50.0), item);
+        let mut rng = LBFRng::seed_from_u64(0);
+        let samples = (0..10)
+            .map(|_| {
+                let dt = sampler.sample(&mut rng);
+                (dt.rotation(), dt.translation())
+            })
+            .collect_vec();
+        //the same seed has to yield exactly the same samples on any platform
+        const PI: fsize = std::f64::consts::PI as fsize;
+        let expected = [
+            (0.0, (84.091606, 1.8657625)),
+            (0.0, (47.008358, 27.779734)),
+            (PI, (40.404488, 38.164394)),
+            (PI, (46.68411, 38.001778)),
+            (0.0, (90.758026, 1.7983735)),
+            (0.0, (59.224045, 28.165823)),
+            (0.0, (91.57312, 21.92123)),
+            (0.0, (78.483604, 33.366386)),
+            (PI, (33.659615, 12.214434)),
+            (PI, (92.20117, 3.932482)),
+        ];
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn test_used_bounds() {
+        let cde_config = LBFConfig::default().cde_config;
+        let json_instance = JsonInstance {
+            name: "used_bounds".to_string(),
+            items: vec![json_item(
+                JsonShape::Rectangle {
+                    width: 4.0,
+                    height: 4.0,
+                },
+                2,
+            )],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 10.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let Instance::SP(instance) = parse_json_instance(&json_instance, cde_config) else {
+            panic!("expected a strip packing instance")
+        };
+        let mut problem = SPProblem::new(instance.clone(), 20.0, cde_config);
+
+        //place the items at x in [0, 4] and [6, 10]
+        let item_bbox = instance.item(0).shape.bbox();
+        for x_min in [0.0, 6.0] {
+            problem.place_item(PlacingOption {
+                layout_idx: STRIP_LAYOUT_IDX,
+                item_id: 0,
+                d_transf: DTransformation::new(
+                    0.0,
+                    (x_min - item_bbox.x_min, 3.0 - item_bbox.y_min),
+                ),
+                subtract: false,
+            });
+        }
+        let solution = problem.create_solution(None);
+
+        let bounds = solution.used_bounds().remove(0).unwrap();
+        assert_eq!(bounds.width(), 10.0);
+        assert_eq!(bounds.height(), 4.0);
+        //the problem itself is left untouched
+        assert_eq!(problem.strip_width(), 20.0);
+    }
+
+    #[test]
+    fn test_parse_warnings() {
+        let item = |points: Vec<(fsize, fsize)>| {
+            json_item(JsonShape::SimplePolygon(JsonSimplePoly(points)), 1)
+        };
+        let square = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let cw_square = square.iter().rev().cloned().collect_vec();
+        let json_instance = JsonInstance {
+            name: "parse_warnings".to_string(),
+            items: vec![item(square), item(cw_square)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let (instance, warnings) = parser.parse_with_warnings(&json_instance).unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::FlippedWinding {
+                source: ShapeSource::Item(1)
+            }]
+        );
+        assert_eq!(warnings[0].level(), log::Level::Warn);
+        let simplified = ParseWarning::SimplifiedPolygon {
+            source: ShapeSource::Item(1),
+            n_points_before: 8,
+            n_points_after: 4,
+        };
+        assert_eq!(simplified.level(), log::Level::Debug);
+        //the shapes are identical after fixing the winding
+        assert_eq!(instance.item(0).shape.points, instance.item(1).shape.points);
+    }
+
+    #[test]
+    fn test_maximal_empty_rects() {
+        let cde_config = LBFConfig::default().cde_config;
+        let square = |size: fsize| JsonShape::Rectangle {
+            width: size,
+            height: size,
+        };
+        let json_instance = JsonInstance {
+            name: "maximal_empty_rects".to_string(),
+            items: vec![json_item(square(4.0), 1)],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: square(10.0),
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(PolySimplConfig::Disabled, cde_config, false);
+        let Instance::BP(instance) = parser.parse(&json_instance) else {
+            panic!("expected a bin packing instance")
+        };
+
+        //an empty bin is a single empty rectangle
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        assert_eq!(
+            layout.maximal_empty_rects(),
+            vec![AARectangle::new(0.0, 0.0, 10.0, 10.0)]
+        );
+
+        //a centered item leaves four (overlapping) rectangles along the borders
+        layout.place_item(instance.item(0), DTransformation::new(0.0, (3.0, 3.0)));
+        let empty_rects = layout.maximal_empty_rects();
+        let expected = [
+            AARectangle::new(0.0, 0.0, 3.0, 10.0),
+            AARectangle::new(7.0, 0.0, 10.0, 10.0),
+            AARectangle::new(0.0, 0.0, 10.0, 3.0),
+            AARectangle::new(0.0, 7.0, 10.0, 10.0),
+        ];
+        assert_eq!(empty_rects.len(), expected.len());
+        assert!(expected.iter().all(|r| empty_rects.contains(r)));
+    }
+
+    #[test]
+    fn test_rotation_penalty() {
+        let json_instance = JsonInstance {
+            name: "rotation_penalty".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0, 90.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 4.0,
+                    },
+                    3,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 20.0,
+                    height: 20.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+
+        //returns the rotation of all placed items
+        let solve = |rotation_penalty: fsize| {
+            let config = LBFConfig {
+                n_samples: 1000,
+                rotation_penalty,
+                ..LBFConfig::default()
+            };
+            let instance = parse_json_instance(&json_instance, config.cde_config);
+            let mut optimizer =
+                LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+            let solution = optimizer.solve();
+            assert_eq!(solution.n_items_placed(), 3);
+            optimizer.problem.layouts()[0]
+                .placed_items()
+                .values()
+                .map(|pi| pi.d_transf.rotation())
+                .collect_vec()
+        };
+
+        //standing upright, the items are further to the left, which is preferred without a penalty
+        assert!(solve(0.0).iter().any(|r| r.abs() > 1e-3));
+        //with a high penalty, all items keep their nominal rotation since it fits as well
+        assert!(solve(1000.0).iter().all(|r| r.abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_bin_inset() {
+        let config = LBFConfig {
+            n_samples: 1000,
+            ..LBFConfig::default()
+        };
+        let inset = 5.0;
+        //L-shaped bin, so the inset has to follow a concave contour
+        let l_shape = vec![
+            (0.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 40.0),
+            (40.0, 40.0),
+            (40.0, 100.0),
+            (0.0, 100.0),
+        ];
+        let json_instance = JsonInstance {
+            name: "bin_inset".to_string(),
+            items: vec![json_item(
+                JsonShape::Rectangle {
+                    width: 12.0,
+                    height: 7.0,
+                },
+                30,
+            )],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: Some(1),
+                shape: JsonShape::SimplePolygon(JsonSimplePoly(l_shape)),
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let Instance::BP(mut instance) = parse_json_instance(&json_instance, config.cde_config)
+        else {
+            panic!("expected a bin packing instance")
+        };
+        instance.bins[0].0 = instance.bins[0].0.clone().with_inset(inset);
+
+        let mut optimizer = LBFOptimizer::new(instance.into(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert!(solution.n_items_placed() > 0);
+
+        let layout = &optimizer.problem.layouts()[0];
+        let bin_outer = &layout.bin.outer;
+        for pi in layout.placed_items().values() {
+            //no point of the item lies closer than `inset` to the contour of the bin
+            for p in pi.shape.points.iter() {
+                let (position, distance) = bin_outer.distance_from_border(p);
+                assert_eq!(position, GeoPosition::Interior);
+                assert!(distance >= inset, "{distance} < {inset}");
+            }
+            //and no vertex of the bin lies closer than `inset` to the item
+            for p in bin_outer.points.iter() {
+                assert!(pi.shape.distance_from_border(p).1 >= inset);
+            }
+        }
+
+        //fitting a strip keeps the inset on both sides
+        let json_instance = JsonInstance {
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 40.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            ..json_instance
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        if let Problem::SP(sp_problem) = &mut optimizer.problem {
+            sp_problem.set_strip_inset(inset);
+        }
+        optimizer.solve();
+        let layout = &optimizer.problem.layouts()[0];
+        let strip_bbox = layout.bin.bbox();
+        let items_bbox = layout
+            .placed_items()
+            .values()
+            .map(|pi| pi.shape.bbox())
+            .reduce(|a, b| AARectangle::bounding_rectangle(&a, &b))
+            .unwrap();
+        assert!(items_bbox.x_min - strip_bbox.x_min >= inset);
+        assert!(strip_bbox.x_max - items_bbox.x_max >= inset);
+        assert!(items_bbox.y_min - strip_bbox.y_min >= inset);
+        assert!(strip_bbox.y_max - items_bbox.y_max >= inset);
+    }
+
+    #[test]
+    fn test_self_intersecting_polygon() {
+        let to_points =
+            |coords: &[(fsize, fsize)]| coords.iter().map(|&c| Point::from(c)).collect_vec();
+        let figure_eight = [(0.0, 0.0), (10.0, 10.0), (10.0, 0.0), (0.0, 10.0)];
+        //a vertex touching a non-adjacent edge
+        let touching = [
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (6.0, 10.0),
+            (5.0, 0.0),
+            (4.0, 10.0),
+            (0.0, 10.0),
+        ];
+        let l_shape = [
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 4.0),
+            (4.0, 4.0),
+            (4.0, 10.0),
+            (0.0, 10.0),
+        ];
+        assert!(!SimplePolygon::calculate_simplicity(&to_points(
+            &figure_eight
+        )));
+        assert!(!SimplePolygon::calculate_simplicity(&to_points(&touching)));
+        assert!(SimplePolygon::new(to_points(&l_shape)).is_simple());
+
+        let json_instance = JsonInstance {
+            name: "self_intersecting".to_string(),
+            items: vec![json_item(
+                JsonShape::SimplePolygon(JsonSimplePoly(figure_eight.to_vec())),
+                1,
+            )],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        assert_eq!(
+            parser.parse_with_warnings(&json_instance).err(),
+            Some(ParseError::SelfIntersectingPolygon {
+                source: ShapeSource::Item(0)
+            })
+        );
+    }
+
+    #[test]
+    fn test_bins_opened_lazily() {
+        let bin = |size: fsize| JsonBin {
+            cost: 1,
+            stock: Some(100),
+            shape: JsonShape::Rectangle {
+                width: size,
+                height: size,
+            },
+            zones: vec![],
+        };
+        let json_instance = JsonInstance {
+            name: "lazy_bins".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    3,
+                )
+            }],
+            //the value of a bin is its area, the last one is too small for the items
+            bins: Some(vec![bin(200.0), bin(100.0), bin(5.0)]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 1000,
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+
+        assert_eq!(solution.n_items_placed(), 3);
+        //a single bin is opened: the cheapest one large enough to hold the items
+        assert_eq!(solution.layout_snapshots.len(), 1);
+        assert_eq!(solution.layout_snapshots[0].bin.id, 1);
+        assert_eq!(solution.bin_qtys, vec![100, 99, 100]);
+    }
+
+    #[test]
+    fn test_rect_polygon_conversion() {
+        let rect = AARectangle::new(-3.0, 1.5, 7.0, 4.0);
+        let poly = SimplePolygon::from(&rect);
+        assert_eq!(poly.as_rect(), Some(rect.clone()));
+
+        //starting from a different corner and in the opposite direction
+        let poly = SimplePolygon::new(vec![
+            Point(7.0, 4.0),
+            Point(7.0, 1.5),
+            Point(-3.0, 1.5),
+            Point(-3.0, 4.0),
+        ]);
+        assert_eq!(poly.as_rect(), Some(rect.clone()));
+
+        //rotated rectangles, trapeziums and polygons with more vertices are not axis-aligned rectangles
+        let rotated = poly.transform_clone(&Transformation::from_rotation(0.1));
+        assert_eq!(rotated.as_rect(), None);
+        let trapezium = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(4.0, 0.0),
+            Point(3.0, 2.0),
+            Point(0.0, 2.0),
+        ]);
+        assert_eq!(trapezium.as_rect(), None);
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(2.0, 0.0),
+            Point(2.0, 1.0),
+            Point(1.0, 1.0),
+            Point(1.0, 2.0),
+            Point(0.0, 2.0),
+        ]);
+        assert_eq!(l_shape.as_rect(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "collides with")]
+    fn test_verify_placements() {
+        let json_instance = JsonInstance {
+            name: "verify_placements".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    3,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let mut config = LBFConfig::default();
+        config.cde_config.verify_placements = true;
+        //the bin and the item are both centered around the origin
+        let Instance::BP(instance) = parse_json_instance(&json_instance, config.cde_config) else {
+            panic!("expected a bin packing instance");
+        };
+        let mut problem = BPProblem::new(instance);
+
+        let p_opt = |layout_idx, x| PlacingOption {
+            layout_idx,
+            item_id: 0,
+            d_transf: DTransformation::new(0.0, (x, 0.0)),
+            subtract: false,
+        };
+        let (l_idx, _) = problem.place_item(p_opt(LayoutIndex::Template(0), 0.0));
+        //a collision-free placement passes the verification
+        problem.place_item(p_opt(l_idx, 20.0));
+        //an overlapping one does not
+        problem.place_item(p_opt(l_idx, 5.0));
+    }
+
+    #[test]
+    fn test_biased_sampler() {
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let instance = parser.parse(&json_instance);
+        let bbox = AARectangle::new(0.0, 0.0, 100.0, 50.0);
+        let mut rng = LBFRng::seed_from_u64(0);
+
+        let mean_translation = |bias: fsize, rng: &mut LBFRng| {
+            let sampler = BiasedSampler::new(bbox.clone(), instance.item(0), bias);
+            let n_samples = 10_000;
+            let (sum_x, sum_y) = (0..n_samples)
+                .map(|_| sampler.sample(rng).translation())
+                .inspect(|(x, y)| assert!((0.0..100.0).contains(x) && (0.0..50.0).contains(y)))
+                .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+            (sum_x / n_samples as fsize, sum_y / n_samples as fsize)
+        };
+
+        //without bias, the samples are spread uniformly
+        let (mean_x, mean_y) = mean_translation(0.0, &mut rng);
+        assert!((mean_x - 50.0).abs() < 2.0 && (mean_y - 25.0).abs() < 1.0);
+
+        //with a bias, they concentrate toward the bottom-left
+        for bias in [0.5, 2.0] {
+            let (mean_x, mean_y) = mean_translation(bias, &mut rng);
+            assert!(mean_x < 50.0 && mean_y < 25.0);
+        }
+        let (_, mean_y_weak) = mean_translation(0.5, &mut rng);
+        let (_, mean_y_strong) = mean_translation(2.0, &mut rng);
+        assert!(mean_y_strong < mean_y_weak);
+
+        //the optimizer still finds valid placements when sampling with a bias
+        let config = LBFConfig {
+            n_samples: 1000,
+            sampling_bias: 2.0,
+            ..LBFConfig::default()
+        };
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), instance.total_item_qty());
+        assert!(assertions::layout_is_collision_free(
+            &optimizer.problem.layouts()[0]
+        ));
+    }
+
+    #[test]
+    fn test_replay() {
+        let json_instance = JsonInstance {
+            name: "replay".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    3,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let Instance::BP(instance) = parse_json_instance(&json_instance, config.cde_config) else {
+            panic!("expected a bin packing instance");
+        };
+        let problem = Problem::BP(BPProblem::new(instance));
+
+        let p_opts = [
+            (LayoutIndex::Template(0), 0.0),
+            (LayoutIndex::Real(0), 20.0),
+            (LayoutIndex::Real(0), -20.0),
+        ]
+        .map(|(layout_idx, x)| PlacingOption {
+            layout_idx,
+            item_id: 0,
+            d_transf: DTransformation::new(0.0, (x, 0.0)),
+            subtract: false,
+        });
+
+        let snapshots = problem.replay(&p_opts);
+        assert_eq!(snapshots.len(), 3);
+        for (i, snapshot) in snapshots.iter().enumerate() {
+            assert_eq!(snapshot.placed_items.len(), i + 1);
+            assert!(assertions::layout_is_collision_free(
+                &Layout::from_snapshot(snapshot)
+            ));
+        }
+        assert!(snapshots[0].usage < snapshots[1].usage && snapshots[1].usage < snapshots[2].usage);
+        //the problem itself is not modified
+        assert!(problem.layouts().is_empty());
+    }
+
+    #[test]
+    fn test_geo_tolerance_scale_invariance() {
+        let create_instance = |scale: fsize| {
+            let item = |demand, shape| JsonItem {
+                allowed_orientations: Some(vec![0.0, 90.0]),
+                ..json_item(shape, demand)
+            };
+            JsonInstance {
+                name: "scaled".to_string(),
+                items: vec![
+                    item(
+                        2,
+                        JsonShape::Rectangle {
+                            width: 4.0 * scale,
+                            height: 2.0 * scale,
+                        },
+                    ),
+                    item(
+                        2,
+                        JsonShape::Rectangle {
+                            width: 3.0 * scale,
+                            height: 3.0 * scale,
+                        },
+                    ),
+                    item(
+                        3,
+                        JsonShape::SimplePolygon(JsonSimplePoly(vec![
+                            (0.0, 0.0),
+                            (5.0 * scale, 0.0),
+                            (0.0, 2.5 * scale),
+                        ])),
+                    ),
+                ],
+                bins: None,
+                strip: Some(JsonStrip {
+                    height: 6.0 * scale,
+                    axis: PackingAxis::X,
+                }),
+                precedence: vec![],
+                rotation_unit: RotationUnit::Deg,
+            }
+        };
+
+        //returns the fitted strip width and the placements of all items
+        let solve = |scale: fsize| {
+            let mut config = LBFConfig {
+                n_samples: 500,
+                ..LBFConfig::default()
+            };
+            config.cde_config.geo_tolerance = GeoTolerance {
+                abs: 1e-4 * scale,
+                ..GeoTolerance::default()
+            };
+            let instance = parse_json_instance(&create_instance(scale), config.cde_config);
+            let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+            optimizer.solve();
+            let Problem::SP(sp_problem) = &mut optimizer.problem else {
+                panic!("expected a strip packing problem");
+            };
+            sp_problem.fit_strip();
+            let placements = sp_problem
+                .layout
+                .placed_items()
+                .values()
+                .map(|pi| {
+                    let (tx, ty) = pi.d_transf.translation();
+                    (pi.item_id, pi.d_transf.rotation(), tx / scale, ty / scale)
+                })
+                .collect_vec();
+            (sp_problem.strip_width() / scale, placements)
+        };
+
+        let (width, placements) = solve(1.0);
+        let (scaled_width, scaled_placements) = solve(1000.0);
+
+        assert_eq!(placements.len(), 7);
+        assert!((width - scaled_width).abs() < 1e-3);
+        assert_eq!(placements.len(), scaled_placements.len());
+        for (p, s_p) in placements.iter().zip(scaled_placements.iter()) {
+            assert_eq!((p.0, p.1), (s_p.0, s_p.1));
+            assert!((p.2 - s_p.2).abs() < 1e-3 && (p.3 - s_p.3).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_demand_report() {
+        let json_instance = JsonInstance {
+            name: "demand_report".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    5,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: Some(3),
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let Instance::BP(instance) = parser.parse(&json_instance) else {
+            panic!("expected a bin packing instance");
+        };
+        let mut problem = BPProblem::new(instance);
+
+        let report = problem.demand_report();
+        assert_eq!(report.items[0].remaining, 5);
+        assert_eq!(report.bin_stock, vec![3]);
+
+        let (l_idx, _) = problem.place_item(PlacingOption {
+            layout_idx: LayoutIndex::Template(0),
+            item_id: 0,
+            d_transf: DTransformation::new(0.0, (0.0, 0.0)),
+            subtract: false,
+        });
+        problem.place_item(PlacingOption {
+            layout_idx: l_idx,
+            item_id: 0,
+            d_transf: DTransformation::new(0.0, (20.0, 0.0)),
+            subtract: false,
+        });
+
+        let report = problem.demand_report();
+        assert_eq!(report.items[0].placed, 2);
+        assert_eq!(report.items[0].remaining, 3);
+        assert_eq!((report.total_placed(), report.total_remaining()), (2, 3));
+        assert_eq!(report.bin_stock, vec![2]);
+    }
+
+    #[test]
+    fn test_convex_decomposition() {
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(2.0, 0.0),
+            Point(2.0, 1.0),
+            Point(1.0, 1.0),
+            Point(1.0, 2.0),
+            Point(0.0, 2.0),
+        ]);
+        let parts = l_shape.convex_decomposition();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|p| p.is_convex));
+        let area = parts.iter().map(|p| p.area()).sum::<fsize>();
+        assert!((area - l_shape.area()).abs() < 1e-6);
+
+        //a convex polygon remains in one piece
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(square.convex_decomposition().len(), 1);
+
+        //the parts of more complex shapes cover the original area exactly
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let instance = parser.parse(&json_instance);
+        for (item, _) in instance.items() {
+            let parts = item.shape.convex_decomposition();
+            assert!(parts.iter().all(|p| p.is_convex));
+            let area = parts.iter().map(|p| p.area()).sum::<fsize>();
+            assert!((area - item.shape.area()).abs() < item.shape.area() * 1e-4);
+            assert!(item.shape.is_convex || parts.len() > 1);
+        }
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let json_instance = JsonInstance {
+            name: "dry_run".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    3,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: Some(5),
+                shape: JsonShape::Rectangle {
+                    width: 15.0,
+                    height: 15.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let report = DryRunReport::new(&parser.parse(&json_instance));
+        assert!(report.is_feasible());
+        assert!(report.unfit_item_ids.is_empty());
+        //300 units of item area need at least two bins of 225
+        assert_eq!(report.min_bins, Some(2));
+        assert!((report.max_usage.unwrap() - 300.0 / 450.0).abs() < 1e-6);
+
+        //one large bin covers the items, but three small ones can hold them without any waste
+        let mixed_bins = |width: fsize, stock: u64| JsonBin {
+            cost: 1,
+            stock: Some(stock),
+            shape: JsonShape::Rectangle {
+                width,
+                height: width,
+            },
+            zones: vec![],
+        };
+        let mixed_json_instance = JsonInstance {
+            bins: Some(vec![mixed_bins(10.0, 3), mixed_bins(30.0, 1)]),
+            ..json_instance.clone()
+        };
+        let report = DryRunReport::new(&parser.parse(&mixed_json_instance));
+        assert_eq!(report.min_bins, Some(1));
+        assert_eq!(report.max_usage, Some(1.0));
+
+        //the cli reports without optimizing
+        let dir = std::env::temp_dir().join("lbf_test_dry_run");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input_file = dir.join("dry_run.json");
+        std::fs::write(&input_file, serde_json::to_string(&json_instance).unwrap()).unwrap();
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_lbf"))
+            .args(["--dry-run", "-l", "off", "-i"])
+            .arg(&input_file)
+            .arg("-s")
+            .arg(dir.join("solutions"))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("minimum number of bins: 2"));
+        assert!(!dir.join("solutions").exists());
+    }
+
+    #[test]
+    fn test_rotational_symmetry() {
+        const PI: fsize = std::f64::consts::PI as fsize;
+        let regular_polygon = |n: usize| {
+            SimplePolygon::new(
+                (0..n)
+                    .map(|i| {
+                        let angle = 2.0 * PI * i as fsize / n as fsize;
+                        Point(10.0 * angle.cos() + 3.0, 10.0 * angle.sin() - 1.0)
+                    })
+                    .collect(),
+            )
+        };
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 5.0, 5.0));
+        let rectangle = SimplePolygon::from(AARectangle::new(0.0, 0.0, 5.0, 2.0));
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(2.0, 0.0),
+            Point(2.0, 1.0),
+            Point(1.0, 1.0),
+            Point(1.0, 2.0),
+            Point(0.0, 2.0),
+        ]);
+        assert_eq!(square.rotational_symmetry(), 4);
+        assert_eq!(rectangle.rotational_symmetry(), 2);
+        assert_eq!(regular_polygon(3).rotational_symmetry(), 3);
+        assert_eq!(regular_polygon(6).rotational_symmetry(), 6);
+        assert_eq!(l_shape.rotational_symmetry(), 1);
+
+        //a square rotated by 90° is the same square, so only one of both is sampled
+        let quarter_turns = vec![0.0, PI / 2.0, PI, 3.0 * PI / 2.0];
+        let item = |shape| {
+            Item::new(
+                0,
+                shape,
+                AllowedRotation::Discrete(quarter_turns.clone()),
+                None,
+                0,
+                Transformation::empty(),
+                LBFConfig::default().cde_config.item_surrogate_config,
+            )
+        };
+        let mut rng = LBFRng::seed_from_u64(0);
+        let square_distr = UniformRotDistr::from_item(&item(square));
+        let samples = (0..100)
+            .map(|_| square_distr.sample(&mut rng))
+            .collect_vec();
+        assert!(samples.iter().all(|r| *r == 0.0));
+
+        //a rectangle still needs both orientations, but not their 180° counterparts
+        let rectangle_distr = UniformRotDistr::from_item(&item(rectangle));
+        let samples = (0..100)
+            .map(|_| rectangle_distr.sample(&mut rng))
+            .unique_by(|r| (r * 1000.0) as i64)
+            .sorted_by(|a, b| a.partial_cmp(b).unwrap())
+            .collect_vec();
+        assert_eq!(samples, vec![0.0, PI / 2.0]);
+    }
+
+    #[test]
+    fn test_precedence() {
+        //without precedence, the large item (1) would be placed first
+        let json_instance = JsonInstance {
+            name: "precedence".to_string(),
+            items: vec![
+                JsonItem {
+                    allowed_orientations: Some(vec![0.0]),
+                    ..json_item(
+                        JsonShape::Rectangle {
+                            width: 2.0,
+                            height: 2.0,
+                        },
+                        3,
+                    )
+                },
+                JsonItem {
+                    allowed_orientations: Some(vec![0.0]),
+                    ..json_item(
+                        JsonShape::Rectangle {
+                            width: 10.0,
+                            height: 10.0,
+                        },
+                        2,
+                    )
+                },
+            ],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 20.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![(0, 1)],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 500,
+            compact: true,
+            ..LBFConfig::default()
+        };
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let instance = parser.parse(&json_instance);
+        assert_eq!(instance.precedence(), &[(0, 1)]);
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 5);
+        assert_eq!(solution.placement_order, vec![0, 0, 0, 1, 1]);
+        assert!(solution.precedence_violations(&instance).is_empty());
+
+        //placing a copy of item 1 before the last copy of item 0 violates the constraint
+        let violating = Solution {
+            placement_order: vec![0, 0, 1, 0, 1],
+            ..solution.clone()
+        };
+        assert_eq!(violating.precedence_violations(&instance), vec![(0, 1)]);
+
+        //constraints on unknown items or forming a cycle are rejected by the parser
+        let unknown = JsonInstance {
+            precedence: vec![(0, 2)],
+            ..json_instance.clone()
+        };
+        assert_eq!(
+            parser.parse_with_warnings(&unknown).err(),
+            Some(ParseError::PrecedenceUnknownItem { item_id: 2 })
+        );
+        let cyclic = JsonInstance {
+            precedence: vec![(0, 1), (1, 0)],
+            ..json_instance
+        };
+        assert!(matches!(
+            parser.parse_with_warnings(&cyclic),
+            Err(ParseError::PrecedenceCycle { .. })
+        ));
+
+        assert_eq!(
+            lbf_optimizer::order_by_precedence(&[2, 1, 0], &[(0, 1)]),
+            vec![2, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_rect_from_points() {
+        let points = [Point(1.0, 4.0), Point(-2.0, 3.0), Point(5.0, -1.0)];
+        let rect = AARectangle::from_points(&points);
+        assert_eq!(rect, AARectangle::new(-2.0, -1.0, 5.0, 4.0));
+        assert!(points.iter().all(|p| rect.collides_with(p)));
+
+        //a single point yields a rectangle without area, located at the point
+        let rect = AARectangle::from_points(&[Point(3.0, 2.0)]);
+        assert_eq!(
+            (rect.x_min, rect.y_min, rect.x_max, rect.y_max),
+            (3.0, 2.0, 3.0, 2.0)
+        );
+        assert_eq!(rect.area(), 0.0);
+
+        //growing to include points, in any order, results in the same rectangle
+        let mut grown = AARectangle::from_points(&[Point(3.0, 2.0)]);
+        grown.grow_to_include(Point(1.0, 7.0));
+        assert_eq!(
+            grown,
+            AARectangle::from_points(&[Point(3.0, 2.0), Point(1.0, 7.0)])
+        );
+        grown.grow_to_include(Point(6.0, -2.0));
+        grown.grow_to_include(Point(2.0, 3.0)); //already inside, no effect
+        assert_eq!(grown, AARectangle::new(1.0, -2.0, 6.0, 7.0));
+
+        //the bounding box of a polygon is the rectangle around its points
+        let shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(4.0, 1.0),
+            Point(3.0, 5.0),
+            Point(-1.0, 2.0),
+        ]);
+        assert_eq!(AARectangle::from_points(&shape.points), shape.bbox());
+    }
+
+    #[test]
+    fn test_parallel_pole_collisions() {
+        let config = LBFConfig {
+            n_samples: 200,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let Problem::SP(solved_problem) = optimizer.problem else {
+            panic!("expected a strip packing problem")
+        };
+        let Instance::SP(sp_instance) = instance else {
+            panic!("expected a strip packing instance")
+        };
+
+        //half of the solved layout, once checked serially and once with every surrogate of at least one pole in parallel
+        let p_opts = solved_problem
+            .layout
+            .placed_items()
+            .values()
+            .map(|pi| PlacingOption {
+                layout_idx: STRIP_LAYOUT_IDX,
+                item_id: pi.item_id,
+                d_transf: pi.d_transf,
+                subtract: false,
+            })
+            .collect_vec();
+        let create_problem = |parallel_pole_threshold| {
+            let mut cde_config = config.cde_config;
+            cde_config.parallel_pole_threshold = parallel_pole_threshold;
+            let mut problem = SPProblem::new(
+                sp_instance.clone(),
+                solved_problem.strip_width(),
+                cde_config,
+            );
+            for p_opt in &p_opts[..p_opts.len() / 2] {
+                problem.place_item(*p_opt);
+            }
+            problem.flush_changes();
+            problem
+        };
+        let serial_problem = create_problem(None);
+        let parallel_problem = create_problem(Some(1));
+
+        let mut rng = LBFRng::seed_from_u64(0);
+        for item_id in [0, 3] {
+            let item = sp_instance.item(item_id);
+            let surrogate = SPSurrogate::new(
+                &item.shape,
+                SPSurrogateConfig {
+                    pole_coverage_goal: 1.0,
+                    max_poles: 100,
+                    n_ff_poles: 100,
+                    n_ff_piers: 0,
+                },
+            );
+            let mut sampler = HPGSampler::new(item, &serial_problem.layout).unwrap();
+            let results = (0..1000)
+                .map(|_| {
+                    let transf = sampler.sample(&mut rng);
+                    let serial =
+                        serial_problem
+                            .layout
+                            .cde()
+                            .surrogate_collides(&surrogate, &transf, &[]);
+                    let parallel =
+                        parallel_problem
+                            .layout
+                            .cde()
+                            .surrogate_collides(&surrogate, &transf, &[]);
+                    assert_eq!(serial, parallel, "item {item_id} at {:?}", transf);
+                    serial
+                })
+                .collect_vec();
+            //both outcomes should occur, for the comparison to be meaningful
+            assert!(results.contains(&true) && results.contains(&false));
+        }
+    }
+
+    #[test]
+    fn test_position_lattice() {
+        let item = |width: fsize, height: fsize, position_lattice| JsonItem {
+            allowed_orientations: Some(vec![0.0, 90.0]),
+            position_lattice,
+            ..json_item(
+                JsonShape::SimplePolygon(JsonSimplePoly(vec![
+                    (1.0, 1.0),
+                    (1.0 + width, 1.0),
+                    (1.0 + width, 1.0 + height),
+                    (1.0, 1.0 + height),
+                ])),
+                6,
+            )
+        };
+        let json_instance = JsonInstance {
+            name: "lattice".to_string(),
+            items: vec![item(7.0, 3.0, Some((2.5, 4.0))), item(4.0, 4.0, None)],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: Some(10),
+                shape: JsonShape::Rectangle {
+                    width: 40.0,
+                    height: 30.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 1000,
+            compact: true,
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        assert_eq!(instance.item(0).position_lattice, Some((2.5, 4.0)));
+        assert_eq!(instance.item(1).position_lattice, None);
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 12);
+
+        //the final positions, in the coordinates of the input file, lie on the lattice
+        let json_solution =
+            parser::compose_json_solution(&solution, &instance, Instant::now(), None, false);
+        let on_lattice = |value: fsize, step: fsize| {
+            let n_steps = value / step;
+            (n_steps - n_steps.round()).abs() < 1e-3
+        };
+        let placed_items = json_solution
+            .layouts
+            .iter()
+            .flat_map(|l| l.placed_items.iter())
+            .collect_vec();
+        let lattice_items = placed_items.iter().filter(|pi| pi.index == 0).collect_vec();
+        assert_eq!(lattice_items.len(), 6);
+        for pi in lattice_items {
+            let (x, y) = pi.transformation.translation;
+            assert!(on_lattice(x, 2.5) && on_lattice(y, 4.0), "({x}, {y})");
+        }
+        //unconstrained items are placed anywhere
+        assert!(placed_items
+            .iter()
+            .filter(|pi| pi.index == 1)
+            .any(|pi| !on_lattice(pi.transformation.translation.0, 2.5)));
+    }
+
+    #[test]
+    fn test_shape_area() {
+        let ccw_points = vec![
+            Point(0.0, 0.0),
+            Point(4.0, 0.0),
+            Point(4.0, 3.0),
+            Point(1.0, 3.0),
+            Point(1.0, 1.0),
+            Point(0.0, 1.0),
+        ];
+        let cw_points = ccw_points.iter().rev().cloned().collect_vec();
+
+        //the shoelace formula is signed, but the area of a polygon is always positive
+        assert_eq!(SimplePolygon::calculate_area(&ccw_points), 10.0);
+        assert_eq!(SimplePolygon::calculate_area(&cw_points), -10.0);
+        let ccw = SimplePolygon::new(ccw_points);
+        let cw = SimplePolygon::new(cw_points);
+        assert_eq!(ccw.area(), 10.0);
+        assert_eq!(cw.area(), ccw.area());
+
+        let circle = Circle::new(Point(1.0, 2.0), 3.0);
+        assert_eq!(circle.area(), 9.0 * jagua_rs::PI);
+        let rect = AARectangle::new(-1.0, 2.0, 3.0, 4.5);
+        assert_eq!(rect.area(), 10.0);
+        assert_eq!(SimplePolygon::from(&rect).area(), rect.area());
+    }
+
+    #[test]
+    fn test_simulated_annealing() {
+        let solve = |acceptance: Acceptance| {
+            let config = LBFConfig {
+                n_samples: 500,
+                acceptance,
+                ..LBFConfig::default()
+            };
+            let instance = parse_instance("../assets/swim.json", config.cde_config);
+            let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+            let mut n_worse_accepted = vec![];
+            let solution =
+                optimizer.solve_with_progress(|p| n_worse_accepted.push(p.n_worse_accepted));
+            (solution, n_worse_accepted, optimizer.acceptance)
+        };
+
+        //greedy never accepts a worse placement
+        let (greedy, n_worse_accepted, _) = solve(Acceptance::Greedy);
+        assert!(n_worse_accepted.iter().all(|&n| n == 0));
+
+        //with a nonzero temperature, worse placements are occasionally accepted
+        let (annealed, n_worse_accepted, state) = solve(Acceptance::SimulatedAnnealing {
+            t0: 100.0,
+            cooling: 0.95,
+        });
+        assert!(*n_worse_accepted.last().unwrap() > 0);
+        assert!(n_worse_accepted.iter().tuple_windows().all(|(a, b)| a <= b));
+        assert_eq!(state.n_worse_accepted, *n_worse_accepted.last().unwrap());
+        //the temperature cooled down after every placed item
+        let expected_temperature = 100.0 * (0.95 as fsize).powi(annealed.n_items_placed() as i32);
+        assert!((state.temperature - expected_temperature).abs() < 1e-3);
+        assert_eq!(annealed.n_items_placed(), greedy.n_items_placed());
+    }
+
+    #[test]
+    fn test_esicup_round_trip() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<nesting>
+  <problem>
+    <boards>
+      <piece id="board0" quantity="1">
+        <component idPolygon="polygon0" type="0" xOffset="0" yOffset="0"/>
+      </piece>
+    </boards>
+    <lot>
+      <piece id="piece0" quantity="3">
+        <orientation>
+          <enumeration angle="0"/>
+          <enumeration angle="90"/>
+        </orientation>
+        <component idPolygon="polygon1" type="0" xOffset="0" yOffset="0"/>
+      </piece>
+      <piece id="piece1" quantity="2">
+        <component idPolygon="polygon2" type="0" xOffset="5" yOffset="0"/>
+      </piece>
+    </lot>
+  </problem>
+  <polygons>
+    <polygon id="polygon0" nVertices="4">
+      <lines>
+        <segment n="1" x0="0" x1="100" y0="0" y1="0"/>
+        <segment n="2" x0="100" x1="100" y0="0" y1="20"/>
+        <segment n="3" x0="100" x1="0" y0="20" y1="20"/>
+        <segment n="4" x0="0" x1="0" y0="20" y1="0"/>
+      </lines>
+    </polygon>
+    <polygon id="polygon1" nVertices="4">
+      <lines>
+        <segment n="1" x0="0" x1="10" y0="0" y1="0"/>
+        <segment n="2" x0="10" x1="10" y0="0" y1="5"/>
+        <segment n="3" x0="10" x1="0" y0="5" y1="5"/>
+        <segment n="4" x0="0" x1="0" y0="5" y1="0"/>
+      </lines>
+    </polygon>
+    <polygon id="polygon2" nVertices="3">
+      <lines>
+        <segment n="1" x0="0" x1="8" y0="0" y1="0"/>
+        <segment n="2" x0="8" x1="4" y0="0" y1="6"/>
+        <segment n="3" x0="4" x1="0" y0="6" y1="0"/>
+      </lines>
+    </polygon>
+  </polygons>
+</nesting>"#;
+        let dir = std::env::temp_dir();
+        let instance_path = dir.join("jagua_rs_test_esicup_instance.xml");
+        let output_path = dir.join("jagua_rs_test_esicup_output.xml");
+        std::fs::write(&instance_path, xml).unwrap();
+
+        let json_instance = io::read_esicup_instance(&instance_path);
+        assert_eq!(json_instance.name, "jagua_rs_test_esicup_instance");
+        assert_eq!(json_instance.strip.as_ref().map(|s| s.height), Some(20.0));
+        assert_eq!(
+            json_instance.items[0].allowed_orientations,
+            Some(vec![0.0, 90.0])
+        );
+        assert!(json_instance.items[1].allowed_orientations.is_none());
+        let JsonShape::SimplePolygon(triangle) = &json_instance.items[1].shape else {
+            panic!("expected a simple polygon");
+        };
+        assert_eq!(triangle.0, vec![(5.0, 0.0), (13.0, 0.0), (9.0, 6.0)]);
+
+        let config = LBFConfig::default();
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 5);
+
+        io::write_esicup_output(&solution, &instance, &output_path);
+        let output = std::fs::read_to_string(&output_path).unwrap();
+        assert_eq!(output.matches("<placement ").count(), 5);
+
+        //the exported instance is read back with the same pieces, quantities and strip
+        let round_trip = io::read_esicup_instance(&output_path);
+        assert_eq!(round_trip.strip.as_ref().map(|s| s.height), Some(20.0));
+        assert_eq!(
+            round_trip.items.iter().map(|i| i.demand).collect_vec(),
+            json_instance.items.iter().map(|i| i.demand).collect_vec()
+        );
+        assert_eq!(
+            round_trip.items[0].allowed_orientations,
+            Some(vec![0.0, 90.0])
+        );
+        let round_trip_instance = parse_json_instance(&round_trip, config.cde_config);
+        for (item, qty) in instance.items() {
+            let (rt_item, rt_qty) = &round_trip_instance.items()[item.id];
+            assert_eq!(qty, rt_qty);
+            assert!((item.shape.area() - rt_item.shape.area()).abs() < 1e-3);
+        }
+
+        std::fs::remove_file(instance_path).unwrap();
+        std::fs::remove_file(output_path).unwrap();
+    }
+
+    #[test]
+    fn test_bin_lower_bound() {
+        let instance_with = |item_width: fsize, demand: u64| {
+            let json_instance = JsonInstance {
+                name: "lower_bound".to_string(),
+                items: vec![json_item(
+                    JsonShape::Rectangle {
+                        width: item_width,
+                        height: 10.0,
+                    },
+                    demand,
+                )],
+                bins: Some(vec![JsonBin {
+                    cost: 1,
+                    stock: None,
+                    shape: JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    zones: vec![],
+                }]),
+                strip: None,
+                precedence: vec![],
+                rotation_unit: RotationUnit::Deg,
+            };
+            let parser = Parser::new(
+                PolySimplConfig::Disabled,
+                LBFConfig::default().cde_config,
+                true,
+            );
+            let Instance::BP(instance) = parser.parse(&json_instance) else {
+                panic!("expected a bin packing instance");
+            };
+            instance
+        };
+
+        //3 items of area 60 in bins of area 100
+        let instance = instance_with(6.0, 3);
+        assert_eq!(instance.area_lower_bound(), 2);
+        //no two of them fit in the same bin, which the 1D relaxation detects
+        assert_eq!(instance.lower_bound(), 3);
+
+        //5 items of area 40, the area bound is tight
+        let instance = instance_with(4.0, 5);
+        assert_eq!(instance.area_lower_bound(), 2);
+        assert_eq!(instance.lower_bound(), 2);
+
+        //exactly filling the bins does not round up
+        let instance = instance_with(5.0, 4);
+        assert_eq!(instance.lower_bound(), 2);
+    }
+
+    #[test]
+    fn test_zero_demand_item() {
+        let item = |demand: u64| JsonItem {
+            allowed_orientations: Some(vec![0.0]),
+            ..json_item(
+                JsonShape::Rectangle {
+                    width: 10.0,
+                    height: 10.0,
+                },
+                demand,
+            )
+        };
+        let json_instance = JsonInstance {
+            name: "zero_demand".to_string(),
+            items: vec![item(2), item(0), item(1)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 20.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let (instance, warnings) = parser.parse_with_warnings(&json_instance).unwrap();
+        assert_eq!(warnings, vec![ParseWarning::ZeroDemandItem { item_id: 1 }]);
+        //the other items keep their ids
+        assert_eq!(instance.items().len(), 3);
+        assert_eq!(instance.item(2).id, 2);
+
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert!(solution.is_complete(&instance));
+        assert_eq!(solution.n_items_placed(), 3);
+        assert_eq!(solution.target_item_qtys[1], 0);
+        assert_eq!(solution.placed_item_qtys[1], 0);
+        assert!(!solution.placement_order.contains(&1));
+        assert!(solution
+            .missing_item_qtys(&instance)
+            .iter()
+            .all(|&qty| qty == 0));
+    }
+
+    #[test]
+    fn test_bounding_circle_broad_phase() {
+        let json_instance = JsonInstance {
+            name: "bounding_circle".to_string(),
+            items: vec![json_item(
+                JsonShape::SimplePolygon(JsonSimplePoly(vec![
+                    (0.0, 0.0),
+                    (10.0, 0.0),
+                    (10.0, 4.0),
+                    (4.0, 4.0),
+                    (4.0, 10.0),
+                    (0.0, 10.0),
+                ])),
+                2,
+            )],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 20.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let problem_with = |broad_phase: bool| {
+            let mut config = LBFConfig::default();
+            config.cde_config.bounding_circle_broad_phase = broad_phase;
+            let Instance::SP(instance) = parse_json_instance(&json_instance, config.cde_config)
+            else {
+                panic!("expected a strip packing instance");
+            };
+            let mut problem = SPProblem::new(instance, 100.0, config.cde_config);
+            problem.place_item(PlacingOption {
+                layout_idx: STRIP_LAYOUT_IDX,
+                item_id: 0,
+                d_transf: DTransformation::new(0.0, (10.0, 10.0)),
+                subtract: false,
+            });
+            problem
+        };
+
+        let problem = problem_with(true);
+        let cde = problem.layout.cde();
+        let surrogate = problem.instance.item(0).shape.surrogate();
+        //far away from the placed item, rejected by the bounding circle alone
+        let far = DTransformation::new(0.0, (80.0, 10.0)).compose();
+        assert!(!cde.bounding_circle_collides(surrogate, &far, &[]));
+        assert!(!cde.surrogate_collides(surrogate, &far, &[]));
+        //on top of the placed item
+        let near = DTransformation::new(0.0, (11.0, 10.0)).compose();
+        assert!(cde.bounding_circle_collides(surrogate, &near, &[]));
+        assert!(cde.surrogate_collides(surrogate, &near, &[]));
+
+        //the broad phase does not change the outcome of any query
+        let reference = problem_with(false);
+        let sampler =
+            UniformAARectSampler::new(problem.layout.bin.bbox(), problem.instance.item(0));
+        let mut rng = LBFRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let transf = sampler.sample(&mut rng).compose();
+            assert_eq!(
+                cde.surrogate_collides(surrogate, &transf, &[]),
+                reference
+                    .layout
+                    .cde()
+                    .surrogate_collides(surrogate, &transf, &[])
+            );
+        }
+    }
+
+    #[test]
+    fn test_item_meta() {
+        let json = r#"{
+            "Name": "meta",
+            "Items": [
+                {
+                    "Demand": 2,
+                    "AllowedOrientations": [0.0],
+                    "Shape": {"Type": "Rectangle", "Data": {"Width": 10.0, "Height": 5.0}},
+                    "Meta": {"PartNumber": "PN-1234-A", "Description": "bracket"}
+                },
+                {
+                    "Demand": 1,
+                    "Shape": {"Type": "Rectangle", "Data": {"Width": 4.0, "Height": 4.0}}
+                }
+            ],
+            "Strip": {"Height": 10.0}
+        }"#;
+        let json_instance: JsonInstance = serde_json::from_str(json).unwrap();
+        let config = LBFConfig::default();
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 3);
+
+        let json_solution =
+            parser::compose_json_solution(&solution, &instance, Instant::now(), None, false);
+        let placed_items = json_solution
+            .layouts
+            .iter()
+            .flat_map(|l| l.placed_items.iter())
+            .collect_vec();
+        for pi in placed_items {
+            match pi.index {
+                0 => {
+                    let meta = pi.meta.as_ref().unwrap();
+                    assert_eq!(meta["PartNumber"], "PN-1234-A");
+                    assert_eq!(meta["Description"], "bracket");
+                }
+                _ => assert!(pi.meta.is_none()),
+            }
+        }
+        //the metadata is echoed in the written output, items without metadata do not get the field
+        let output = serde_json::to_string(&json_solution).unwrap();
+        assert_eq!(output.matches("\"PartNumber\":\"PN-1234-A\"").count(), 2);
+        assert_eq!(output.matches("\"Meta\"").count(), 2);
+    }
+
+    #[test]
+    fn test_layout_snapshot_hash() {
+        let json_instance = JsonInstance {
+            name: "snapshot_hash".to_string(),
+            items: vec![
+                json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    2,
+                ),
+                json_item(
+                    JsonShape::Rectangle {
+                        width: 20.0,
+                        height: 5.0,
+                    },
+                    1,
+                ),
+            ],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let Instance::BP(instance) = parse_json_instance(&json_instance, config.cde_config) else {
+            panic!("expected a bin packing instance");
+        };
+        let bin = &instance.bins[0].0;
+        let placements = [
+            (0, DTransformation::new(0.0, (-30.0, -30.0))),
+            (0, DTransformation::new(PI / 2.0, (0.0, 0.0))),
+            (1, DTransformation::new(0.0, (20.0, 30.0))),
+        ];
+        let snapshot_of = |order: &[usize], id: usize| {
+            let mut layout = Layout::new(id, bin.clone());
+            for &i in order {
+                let (item_id, d_transf) = placements[i];
+                layout.place_item(instance.item(item_id), d_transf);
+            }
+            layout.create_snapshot()
+        };
+        let hash = |snapshot: &LayoutSnapshot| {
+            let mut hasher = DefaultHasher::new();
+            snapshot.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        //the same placements in a different order (and in a different layout) are equal
+        let a = snapshot_of(&[0, 1, 2], 0);
+        let b = snapshot_of(&[2, 0, 1], 1);
+        assert!(a == b);
+        assert_eq!(hash(&a), hash(&b));
+
+        //a full turn or a deviation within the tolerance does not matter
+        let mut layout = Layout::new(2, bin.clone());
+        layout.place_item(
+            instance.item(1),
+            DTransformation::new(2.0 * PI, (20.0, 30.0)),
+        );
+        layout.place_item(instance.item(0), DTransformation::new(PI / 2.0, (0.0, 0.0)));
+        layout.place_item(
+            instance.item(0),
+            DTransformation::new(0.0, (-30.0, -30.0 + 1e-6)),
+        );
+        let c = layout.create_snapshot();
+        assert!(a == c);
+        assert_eq!(hash(&a), hash(&c));
+
+        //a different set of placements is not
+        let d = snapshot_of(&[0, 1], 3);
+        assert!(a != d);
+        let mut set = HashSet::new();
+        set.extend([a, b, c, d]);
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_svg_explode_gap() {
+        let json_instance = JsonInstance {
+            name: "explode_gap".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    2,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, false);
+        let instance = parser.parse(&json_instance);
+        let Instance::BP(bp_instance) = &instance else {
+            panic!("expected a bin packing instance");
+        };
+        //two items touching along the edge x = 10
+        let mut layout = Layout::new(0, bp_instance.bins[0].0.clone());
+        for x in [0.0, 10.0] {
+            layout.place_item(instance.item(0), DTransformation::new(0.0, (x, 0.0)));
+        }
+
+        let render = |explode_gap: Option<fsize>| {
+            let options = SvgDrawOptions {
+                explode_gap,
+                ..SvgDrawOptions::default()
+            };
+            layout_to_svg(&layout, &instance, options).to_string()
+        };
+        //x coordinates of the (untransformed) outline of the item, as defined in the svg
+        let item_xs = |svg: &str| {
+            let data = svg.split("id=\"item_0\"").nth(1).unwrap();
+            let data = data
+                .split("d=\"")
+                .nth(1)
+                .unwrap()
+                .split('"')
+                .next()
+                .unwrap();
+            data.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+                .filter(|n| !n.is_empty())
+                .map(|n| n.parse::<fsize>().unwrap())
+                .step_by(2)
+                .collect_vec()
+        };
+        let transforms = |svg: &str| {
+            svg.split("transform=\"")
+                .skip(1)
+                .map(|t| t.split('"').next().unwrap().to_string())
+                .collect_vec()
+        };
+
+        let plain = render(None);
+        let exploded = render(Some(1.0));
+        //without a gap, the outlines share the edge at x = 10 once translated
+        let xs = item_xs(&plain);
+        assert!(xs.contains(&0.0) && xs.contains(&10.0));
+        //with a gap, every vertex lies strictly inside the original outline, so the drawn polygons are disjoint
+        let xs = item_xs(&exploded);
+        assert!(xs.iter().all(|&x| x > 0.1 && x < 9.9));
+        //the placements themselves are unaffected
+        assert_eq!(transforms(&plain), transforms(&exploded));
+    }
+
+    #[test]
+    fn test_bin_cost_objective() {
+        let bin = |size: fsize, cost: u64| JsonBin {
+            cost,
+            stock: None,
+            shape: JsonShape::Rectangle {
+                width: size,
+                height: size,
+            },
+            zones: vec![],
+        };
+        let solve = |small_bin_cost: u64, bin_objective: BinObjective| {
+            let json_instance = JsonInstance {
+                name: "bin_cost".to_string(),
+                items: vec![JsonItem {
+                    allowed_orientations: Some(vec![0.0]),
+                    ..json_item(
+                        JsonShape::Rectangle {
+                            width: 9.0,
+                            height: 9.0,
+                        },
+                        4,
+                    )
+                }],
+                //a large bin holding all items, and a small bin holding a single one
+                bins: Some(vec![bin(20.0, 10), bin(10.0, small_bin_cost)]),
+                strip: None,
+                precedence: vec![],
+                rotation_unit: RotationUnit::Deg,
+            };
+            let config = LBFConfig {
+                bin_objective,
+                ..LBFConfig::default()
+            };
+            let instance = parse_json_instance(&json_instance, config.cde_config);
+            let mut optimizer =
+                LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+            let solution = optimizer.solve();
+            assert!(solution.is_complete(&instance));
+            solution.total_cost()
+        };
+
+        //the cheapest bin is opened every time, even though one large bin costs less in total
+        assert_eq!(solve(3, BinObjective::CheapestBin), 4 * 3);
+        assert_eq!(solve(3, BinObjective::TotalCost), 10);
+        //unless the small bins are cheaper in total as well
+        assert_eq!(solve(2, BinObjective::TotalCost), 4 * 2);
+    }
+
+    #[test]
+    fn test_hpg_incremental_deregister() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+
+        let Problem::SP(problem) = &mut optimizer.problem else {
+            panic!("expected a strip packing problem");
+        };
+        //remove every other item, updating the hazard proximity grid immediately,
+        //after every removal each cell has the proximity it would get from the remaining hazards
+        let keys = problem.layout.placed_items().keys().sorted().collect_vec();
+        for (i, pik) in keys.into_iter().enumerate() {
+            if i % 2 == 0 {
+                problem.remove_item(STRIP_LAYOUT_IDX, pik, true);
+                let cde = problem.layout.cde();
+                assert!(assertions::hpg_matches_full_rebuild(
+                    cde.haz_prox_grid().unwrap(),
+                    cde.dynamic_hazards().iter()
+                ));
+            }
+        }
+
+        //rebuild the same layout from scratch
+        let mut rebuilt = Layout::new(1, problem.layout.bin.clone());
+        for pi in problem.layout.placed_items().values() {
+            rebuilt.place_item(problem.instance.item(pi.item_id), pi.d_transf);
+        }
+
+        let incremental = problem.layout.cde().haz_prox_grid().unwrap();
+        let full = rebuilt.cde().haz_prox_grid().unwrap();
+        assert_eq!(incremental.grid.cells.len(), full.grid.cells.len());
+        for (a, b) in incremental.grid.cells.iter().zip(full.grid.cells.iter()) {
+            match (a, b) {
+                (Some(a), Some(b)) => assert!(
+                    (a.uni_prox.0 - b.uni_prox.0).abs() < 1e-3,
+                    "{:?} != {:?}",
+                    a.uni_prox,
+                    b.uni_prox
+                ),
+                (None, None) => {}
+                _ => panic!("grids have a different structure"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_instance_item_iter() {
+        let config = LBFConfig::default();
+        let instance = parse_instance("../assets/baldacci1.json", config.cde_config);
+
+        assert_eq!(instance.item_iter().count(), instance.items().len());
+        for (id, item) in instance.item_iter().enumerate() {
+            assert_eq!(item.id, id);
+            let surrogate = item.surrogate();
+            assert!(!surrogate.poles.is_empty());
+            assert!(surrogate.poles.len() <= config.cde_config.item_surrogate_config.max_poles);
+            assert!(item.shape.area() > 0.0);
+            assert!(
+                !matches!(&item.allowed_rotation, AllowedRotation::Discrete(r) if r.is_empty())
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "rotation which is not allowed")]
+    fn test_disallowed_rotation() {
+        let json_instance = JsonInstance {
+            name: "disallowed_rotation".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0, 90.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 20.0,
+                        height: 10.0,
+                    },
+                    3,
+                )
+            }],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        //rotations are checked even if placements are not verified for collisions
+        let config = LBFConfig::default();
+        assert!(!config.cde_config.verify_placements);
+        let Instance::SP(instance) = parse_json_instance(&json_instance, config.cde_config) else {
+            panic!("expected a strip packing instance");
+        };
+        let item = instance.item(0);
+        assert!(item.is_rotation_allowed(0.0));
+        assert!(item.is_rotation_allowed(PI / 2.0));
+        assert!(item.is_rotation_allowed(PI / 2.0 - 2.0 * PI));
+        assert!(!item.is_rotation_allowed(PI / 4.0));
+        assert!(!item.is_rotation_allowed(PI));
+
+        let range = AllowedRotation::Range {
+            min: -PI / 4.0,
+            max: PI / 4.0,
+        };
+        assert!(range.allows(0.0));
+        assert!(range.allows(2.0 * PI - 0.1));
+        assert!(!range.allows(PI / 2.0));
+
+        let mut problem = SPProblem::new(instance, 200.0, config.cde_config);
+        let p_opt = |rotation, x| PlacingOption {
+            layout_idx: STRIP_LAYOUT_IDX,
+            item_id: 0,
+            d_transf: DTransformation::new(rotation, (x, 50.0)),
+            subtract: false,
+        };
+        problem.place_item(p_opt(0.0, 20.0));
+        assert!(problem.try_place_item(p_opt(PI / 2.0, 60.0)).is_some());
+        //45° is not one of the allowed orientations, the fallible variant rejects it without placing the item
+        assert!(problem.try_place_item(p_opt(PI / 4.0, 120.0)).is_none());
+        assert_eq!(problem.layout.placed_items().len(), 2);
+        problem.place_item(p_opt(PI / 4.0, 120.0));
+    }
+
+    #[test]
+    fn test_free_space_to_instance() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let layout = &optimizer.problem.layouts()[0];
+
+        let free_instance = io::free_space_to_instance(layout, &json_instance);
+        assert!(free_instance.strip.is_none());
+        assert_eq!(free_instance.items.len(), json_instance.items.len());
+
+        //survives a round trip through JSON
+        let free_instance: JsonInstance =
+            serde_json::from_str(&serde_json::to_string(&free_instance).unwrap()).unwrap();
+        let Instance::BP(free_instance) = parse_json_instance(&free_instance, config.cde_config)
+        else {
+            panic!("expected a bin packing instance");
+        };
+        assert_eq!(free_instance.bins.len(), 1);
+        let free_bin = &free_instance.bins[0].0;
+        assert_eq!(
+            free_bin.holes.len(),
+            layout.bin.holes.len() + layout.placed_items().len()
+        );
+
+        let free_area = layout.bin.area * (1.0 - layout.usage());
+        assert!((free_bin.area - free_area).abs() < 1e-3 * layout.bin.area);
+    }
+
+    #[test]
+    fn test_output_grid() {
+        const GRID: fsize = 0.01;
+        let config = LBFConfig {
+            n_samples: 100,
+            output_grid: Some(GRID),
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+
+        let json_solution = parser::compose_json_solution(
+            &solution,
+            &instance,
+            Instant::now(),
+            config.output_grid,
+            false,
+        );
+        //up to the precision of the floating point representation
+        let is_multiple = |v: fsize| {
+            let (v, grid) = (v as f64, GRID as f64);
+            (v - (v / grid).round() * grid).abs() <= 1e-6 * v.abs().max(1.0)
+        };
+        for json_layout in json_solution.layouts.iter() {
+            assert_eq!(
+                json_layout.placed_items.len(),
+                solution.layout_snapshots[0].placed_items.len()
+            );
+            for json_pi in json_layout.placed_items.iter() {
+                let (x, y) = json_pi.transformation.translation;
+                assert!(is_multiple(x) && is_multiple(y), "({x}, {y})");
+            }
+        }
+
+        //the snapped placements are still free of collisions
+        let snapped_solution =
+            parser::build_solution_from_json(&instance, &json_solution.layouts, config.cde_config);
+        for sl in snapped_solution.layout_snapshots.iter() {
+            assert!(assertions::layout_is_collision_free(
+                &Layout::from_snapshot(sl)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_svg_animation() {
+        let json_instance = JsonInstance {
+            name: "svg_animation".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    4,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let Instance::BP(bp_instance) = &instance else {
+            panic!("expected a bin packing instance");
+        };
+        let problem = Problem::BP(BPProblem::new(bp_instance.clone()));
+
+        let p_opts = [0.0, 20.0, -20.0, 40.0].map(|x| PlacingOption {
+            layout_idx: match x == 0.0 {
+                true => LayoutIndex::Template(0),
+                false => LayoutIndex::Real(0),
+            },
+            item_id: 0,
+            d_transf: DTransformation::new(0.0, (x, 0.0)),
+            subtract: false,
+        });
+        let frames = problem.replay(&p_opts);
+
+        let animation = io::layout_to_svg::svg_animation(
+            &frames,
+            &instance,
+            SvgDrawOptions::default(),
+            io::SVG_FRAME_DURATION,
+        )
+        .to_string();
+
+        //every frame is a nested svg, containing the items placed so far, referring to its own definitions
+        let frame_contents = animation.split("<svg").skip(2).collect_vec();
+        assert_eq!(frame_contents.len(), p_opts.len());
+        let item_counts = frame_contents
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                assert!(frame.contains(&format!("id=\"frame_{i}_item_0\"")));
+                frame
+                    .matches(&format!("xlink:href=\"#frame_{i}_item_"))
+                    .count()
+            })
+            .collect_vec();
+        assert_eq!(item_counts, vec![1, 2, 3, 4]);
+        assert_eq!(animation.matches("<animate").count(), p_opts.len());
+
+        //no id is used twice across the frames
+        let ids = animation
+            .split(" id=\"")
+            .skip(1)
+            .map(|id| &id[..id.find('"').unwrap()])
+            .collect_vec();
+        assert_eq!(ids.iter().unique().count(), ids.len());
+    }
+
+    #[test]
+    fn test_max_samples_per_item() {
+        let config = LBFConfig {
+            n_samples: 1000,
+            ls_frac: 0.0,
+            ..LBFConfig::default()
+        };
+        let Instance::BP(instance) = parse_instance("../assets/baldacci1.json", config.cde_config)
+        else {
+            panic!("expected a bin packing instance");
+        };
+        let mut bp_problem = BPProblem::new(instance.clone());
+        let item = instance.item(0);
+        bp_problem.ensure_open_bin_for(item.id).unwrap();
+        let problem = Problem::BP(bp_problem);
+
+        let placement_cost = |max_samples: usize| {
+            let config = LBFConfig {
+                max_samples_per_item: Some(max_samples),
+                ..config
+            };
+            let mut rng = LBFRng::seed_from_u64(0);
+            let mut sample_counter = 0;
+            let mut acceptance = AcceptanceState::new(config.acceptance);
+            let p_opt = lbf_optimizer::find_lbf_placement(
+                &problem,
+                item,
+                &config,
+                &mut rng,
+                &mut sample_counter,
+                &mut acceptance,
+            )
+            .unwrap();
+            //the budget is respected
+            assert!(sample_counter <= max_samples);
+            let shape = item.shape.transform_clone(&p_opt.d_transf.compose());
+            LBFPlacingCost::from_shape(&shape)
+        };
+
+        //a larger budget can only improve the placement
+        let costs = [10, 100, 1000].map(placement_cost);
+        assert!(costs.windows(2).all(|w| w[1] <= w[0]), "{costs:?}");
+    }
+
+    #[test]
+    fn test_packing_axis_y() {
+        //three 20x10 rectangles in a strip with a fixed width of 25 can only be stacked on top of each other
+        let json_instance = JsonInstance {
+            name: "packing_axis_y".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 20.0,
+                        height: 10.0,
+                    },
+                    3,
+                )
+            }],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 25.0,
+                axis: PackingAxis::Y,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 500,
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 3);
+
+        let json_solution =
+            parser::compose_json_solution(&solution, &instance, Instant::now(), None, false);
+        let JsonContainer::Strip { width, height } = json_solution.layouts[0].container else {
+            panic!("expected a strip");
+        };
+        assert_eq!(width, 25.0);
+        assert!((height - 30.0).abs() < 0.1, "height: {height}");
+
+        //the placements in the coordinates of the instance are unrotated and stacked within the strip
+        let abs_bboxes = json_solution.layouts[0]
+            .placed_items
+            .iter()
+            .map(|json_pi| {
+                let transf = &json_pi.transformation;
+                assert!(AllowedRotation::Discrete(vec![0.0]).allows(transf.rotation));
+                let dt = DTransformation::new(transf.rotation, transf.translation);
+                SimplePolygon::from(AARectangle::new(0.0, 0.0, 20.0, 10.0))
+                    .transform_clone(&Transformation::from_dt(&dt))
+                    .bbox()
+            })
+            .collect_vec();
+        let strip = AARectangle::new(0.0, 0.0, width, height);
+        for (i, bbox) in abs_bboxes.iter().enumerate() {
+            assert!(bbox.x_min >= -0.01 && bbox.x_max <= strip.x_max + 0.01);
+            assert!(bbox.y_min >= -0.01 && bbox.y_max <= strip.y_max + 0.01);
+            for other in abs_bboxes[i + 1..].iter() {
+                let overlap =
+                    fsize::min(bbox.y_max, other.y_max) - fsize::max(bbox.y_min, other.y_min);
+                assert!(overlap < 0.01, "{bbox:?} overlaps {other:?}");
+            }
+        }
+
+        //the solution can be rebuilt from its rotated back placements
+        let rebuilt =
+            parser::build_solution_from_json(&instance, &json_solution.layouts, config.cde_config);
+        assert_eq!(rebuilt.n_items_placed(), 3);
+        assert!(assertions::layout_is_collision_free(
+            &Layout::from_snapshot(&rebuilt.layout_snapshots[0])
+        ));
+    }
+
+    #[test]
+    fn test_bbox_fill_ratio() {
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(square.bbox_area(), 100.0);
+        assert_eq!(square.bbox_fill_ratio(), 1.0);
+
+        //a thin sliver along the diagonal covers only a small part of its bounding box
+        let sliver = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(10.0, 9.9),
+            Point(10.0, 10.0),
+            Point(0.0, 0.1),
+        ]);
+        assert!((sliver.bbox_area() - 100.0).abs() < 1e-3);
+        assert!(
+            sliver.bbox_fill_ratio() < 0.02,
+            "{}",
+            sliver.bbox_fill_ratio()
+        );
+
+        let circle = Circle::new(Point(1.0, 1.0), 1.0);
+        assert!((circle.bbox_fill_ratio() - PI / 4.0).abs() < 1e-5);
+    }
+
+    #[test_case("../assets/swim.json"; "swim")]
+    #[test_case("../assets/baldacci1.json"; "baldacci1")]
+    fn test_remove_item_undo(instance_path: &str) {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let json_instance = io::read_json_instance(Path::new(instance_path));
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+
+        let problem = &mut optimizer.problem;
+        let placements = |problem: &Problem| {
+            problem
+                .layouts()
+                .iter()
+                .map(|l| {
+                    l.placed_items()
+                        .values()
+                        .map(|pi| (pi.item_id, pi.d_transf))
+                        .sorted_by_key(|(id, dt)| (*id, format!("{dt:?}")))
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+        let original = placements(problem);
+        let original_usage = problem.usage();
+
+        //empty the last layout, which closes it in bin packing problems, and undo the removals in reverse order
+        let layout_index = LayoutIndex::Real(problem.layouts().len() - 1);
+        let piks = problem
+            .get_layout(layout_index)
+            .placed_items()
+            .keys()
+            .collect_vec();
+        let removed = piks
+            .into_iter()
+            .map(|pik| problem.remove_item(layout_index, pik, true))
+            .collect_vec();
+        for p_opt in removed.into_iter().rev() {
+            problem.place_item(p_opt);
+        }
+
+        assert_eq!(placements(problem), original);
+        assert!((problem.usage() - original_usage).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_outward_only_simplification() {
+        //a jagged star, which can be simplified a lot both inward and outward
+        let points = (0..40)
+            .map(|i| {
+                let angle = 2.0 * PI * i as fsize / 40.0;
+                let radius = if i % 2 == 0 { 10.0 } else { 9.0 };
+                (radius * angle.cos(), radius * angle.sin())
+            })
+            .collect_vec();
+        let json_instance = JsonInstance {
+            name: "outward_only".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(JsonShape::SimplePolygon(JsonSimplePoly(points.clone())), 1)
+            }],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let original = SimplePolygon::new(points.iter().map(|&p| Point::from(p)).collect());
+        let simplify = |direction: PolySimplDirection| {
+            let simpl_config = PolySimplConfig::Enabled {
+                tolerance: 0.05,
+                direction,
+            };
+            let parser = Parser::new(simpl_config, LBFConfig::default().cde_config, true);
+            let instance = parser.parse(&json_instance);
+            //undo the centering of the item to compare with the original
+            let item = instance.item(0);
+            item.shape
+                .transform_clone(&item.pretransform.clone().inverse())
+        };
+
+        let outward = simplify(PolySimplDirection::OutwardOnly);
+        assert!(outward.number_of_points() < original.number_of_points());
+        assert!(outward.area() >= original.area());
+        //all points of the original part lie within the simplified one
+        for p in original.points.iter() {
+            match outward.distance_from_border(p) {
+                (GeoPosition::Interior, _) => {}
+                (GeoPosition::Exterior, dist) => assert!(dist < 1e-3, "{p:?} outside by {dist}"),
+            }
+        }
+        assert!(polygon_simplification::simplification_is_conservative(
+            &original,
+            &outward,
+            PolySimplMode::Inflate
+        ));
+
+        //an inward simplification is not conservative for an item
+        let deflated =
+            polygon_simplification::simplify_shape(&original, PolySimplMode::Deflate, 0.05);
+        assert!(deflated.area() < original.area());
+        assert!(!polygon_simplification::simplification_is_conservative(
+            &original,
+            &deflated,
+            PolySimplMode::Inflate
+        ));
+
+        let inward = simplify(PolySimplDirection::InwardAllowed);
+        assert!(inward.number_of_points() <= outward.number_of_points());
+
+        //all points of the inner rectangle lie within the notched square, but its edges cross the notch
+        let notched = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(10.0, 0.0),
+            Point(10.0, 10.0),
+            Point(7.1, 10.0),
+            Point(7.1, 2.0),
+            Point(6.9, 2.0),
+            Point(6.9, 10.0),
+            Point(0.0, 10.0),
+        ]);
+        let across_notch = SimplePolygon::new(vec![
+            Point(1.0, 3.0),
+            Point(9.0, 3.0),
+            Point(9.0, 4.0),
+            Point(1.0, 4.0),
+        ]);
+        let beside_notch = SimplePolygon::new(vec![
+            Point(1.0, 3.0),
+            Point(6.9, 3.0),
+            Point(6.9, 4.0),
+            Point(1.0, 4.0),
+        ]);
+        assert!(!polygon_simplification::simplification_is_conservative(
+            &notched,
+            &across_notch,
+            PolySimplMode::Deflate
+        ));
+        assert!(polygon_simplification::simplification_is_conservative(
+            &notched,
+            &beside_notch,
+            PolySimplMode::Deflate
+        ));
+    }
+
+    #[test]
+    fn test_svg_y_axis() {
+        let json_instance = JsonInstance {
+            name: "svg_y_axis".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    1,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 40.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        let layout = &solution.layout_snapshots[0];
+
+        let view_box = |options: SvgDrawOptions| {
+            let doc = io::layout_to_svg::s_layout_to_svg(layout, &instance, options);
+            let view_box = doc.get_attributes()["viewBox"].to_string();
+            let values = view_box
+                .split([' ', ','])
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse::<fsize>().unwrap())
+                .collect_vec();
+            (doc.to_string(), values)
+        };
+        let (down_svg, down) = view_box(SvgDrawOptions::default());
+        let (up_svg, up) = view_box(SvgDrawOptions {
+            y_axis: YAxis::Up,
+            ..SvgDrawOptions::default()
+        });
+
+        //the default frame draws the coordinates as they are
+        assert!(!down_svg.contains("id=\"frame\""));
+        //pointing the y-axis up mirrors the drawing around the x-axis: y -> -y
+        assert!(up_svg.contains("matrix(1 0 0 -1 0 0)"));
+        assert_eq!(up[0], down[0]);
+        assert!((up[1] + (down[1] + down[3])).abs() < 1e-3);
+        assert_eq!((up[2], up[3]), (down[2], down[3]));
+
+        //moving the origin translates the view box along with the drawing
+        let (shifted_svg, shifted) = view_box(SvgDrawOptions {
+            origin: Point(10.0, 5.0),
+            ..SvgDrawOptions::default()
+        });
+        assert!(shifted_svg.contains("translate(-10 -5)"));
+        assert!((shifted[0] - (down[0] - 10.0)).abs() < 1e-3);
+        assert!((shifted[1] - (down[1] - 5.0)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_nesting_candidates() {
+        let item = |demand: u64, points: Vec<(fsize, fsize)>| JsonItem {
+            allowed_orientations: Some(vec![0.0, 90.0]),
+            ..json_item(JsonShape::SimplePolygon(JsonSimplePoly(points)), demand)
+        };
+        let square = |size: fsize| vec![(0.0, 0.0), (size, 0.0), (size, size), (0.0, size)];
+        let json_instance = JsonInstance {
+            name: "nesting_candidates".to_string(),
+            items: vec![
+                //C-shape with a 20x10 pocket
+                item(
+                    1,
+                    vec![
+                        (0.0, 0.0),
+                        (30.0, 0.0),
+                        (30.0, 10.0),
+                        (10.0, 10.0),
+                        (10.0, 20.0),
+                        (30.0, 20.0),
+                        (30.0, 30.0),
+                        (0.0, 30.0),
+                    ],
+                ),
+                //fits in the mouth of the C
+                item(1, square(8.0)),
+                //too large for the pocket
+                item(1, square(15.0)),
+            ],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        let instance = parser.parse(&json_instance);
+
+        //convex items have no concavities, and a single copy of the C cannot be nested in itself
+        assert_eq!(instance.nesting_candidates(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_layouts_to_svg() {
+        //every item needs its own bin
+        let json_instance = JsonInstance {
+            name: "layouts_to_svg".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                ..json_item(
+                    JsonShape::Rectangle {
+                        width: 15.0,
+                        height: 15.0,
+                    },
+                    3,
+                )
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 20.0,
+                    height: 20.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.layout_snapshots.len(), 3);
+
+        let sheet =
+            io::layout_to_svg::layouts_to_svg(&solution, &instance, SvgDrawOptions::default(), 2);
+        let dims = |attr: &str| {
+            attr.split([' ', ','])
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse::<fsize>().unwrap())
+                .collect_vec()
+        };
+        let sheet_dims = dims(&sheet.get_attributes()["viewBox"].to_string());
+        let svg = sheet.to_string();
+
+        //a 2x2 grid, of which the first three cells hold a layout
+        let cells = svg.split("id=\"cell_").skip(1).collect_vec();
+        assert_eq!(cells.len(), 3);
+        let (cell_width, cell_height) = (sheet_dims[2] / 2.0, sheet_dims[3] / 2.0);
+        let positions = cells
+            .iter()
+            .map(|cell| {
+                let layout = cell.split("id=\"layout_").nth(1).unwrap();
+                let attr = |name: &str| {
+                    let value = layout.split(&format!(" {name}=\"")).nth(1).unwrap();
+                    value[..value.find('"').unwrap()].parse::<fsize>().unwrap()
+                };
+                (
+                    (attr("x") / cell_width).round() as usize,
+                    (attr("y") / cell_height).floor() as usize,
+                )
+            })
+            .collect_vec();
+        assert_eq!(positions, vec![(0, 0), (1, 0), (0, 1)]);
+        assert!(cells.iter().all(|cell| cell.contains("usage: ")));
+
+        //every layout refers to its own definitions and no id is used twice across the layouts
+        for (i, cell) in cells.iter().enumerate() {
+            assert!(cell.contains(&format!("id=\"layout_{i}_item_0\"")));
+            assert_eq!(cell.matches("xlink:href=\"#").count(), 1);
+            assert_eq!(
+                cell.matches(&format!("xlink:href=\"#layout_{i}_item_"))
+                    .count(),
+                1
+            );
+        }
+        let ids = svg
+            .split(" id=\"")
+            .skip(1)
+            .map(|id| &id[..id.find('"').unwrap()])
+            .collect_vec();
+        assert_eq!(ids.iter().unique().count(), ids.len());
+    }
+
+    #[test]
+    fn test_min_footprint_rotation() {
+        let rect = |width: fsize, height: fsize, allowed_rotation: AllowedRotation| {
+            Item::new(
+                0,
+                SimplePolygon::from(AARectangle::new(0.0, 0.0, width, height)),
+                allowed_rotation,
+                None,
+                0,
+                Transformation::empty(),
+                LBFConfig::default().cde_config.item_surrogate_config,
+            )
+        };
+        let extents = |item: &Item, rotation: fsize| {
+            let bbox = item
+                .shape
+                .transform_clone(&Transformation::from_rotation(rotation))
+                .bbox();
+            (bbox.width(), bbox.height())
+        };
+
+        //a long rectangle lies flat to minimize its height, and stands up to minimize its width
+        let standing = rect(5.0, 40.0, AllowedRotation::Continuous);
+        let rotation = standing.min_footprint_rotation(PackingAxis::Y);
+        assert!((extents(&standing, rotation).1 - 5.0).abs() < 1e-3);
+        let rotation = standing.min_footprint_rotation(PackingAxis::X);
+        assert!((extents(&standing, rotation).0 - 5.0).abs() < 1e-3);
+
+        //the best allowed rotation is chosen among discrete ones and within ranges
+        let discrete = rect(
+            5.0,
+            40.0,
+            AllowedRotation::Discrete(vec![0.0, PI / 4.0, PI / 2.0]),
+        );
+        assert_eq!(discrete.min_footprint_rotation(PackingAxis::Y), PI / 2.0);
+        let range = rect(
+            5.0,
+            40.0,
+            AllowedRotation::Range {
+                min: 0.0,
+                max: PI / 6.0,
+            },
+        );
+        assert_eq!(range.min_footprint_rotation(PackingAxis::Y), PI / 6.0);
+        let fixed = rect(5.0, 40.0, AllowedRotation::None);
+        assert_eq!(fixed.min_footprint_rotation(PackingAxis::Y), 0.0);
+
+        //used as initial guess, the long rectangles are placed flat in a low strip
+        let json_instance = JsonInstance {
+            name: "min_footprint".to_string(),
+            items: vec![json_item(
+                JsonShape::Rectangle {
+                    width: 5.0,
+                    height: 40.0,
+                },
+                3,
+            )],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 12.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 500,
+            min_footprint_guess: true,
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 3);
+    }
+
+    #[test]
+    fn test_cde_presets() {
+        let parse = |cde_config: CDEConfig| {
+            let Instance::BP(instance) = parse_instance("../assets/baldacci1.json", cde_config)
+            else {
+                panic!("expected a bin packing instance");
+            };
+            instance
+        };
+        let fast = parse(CDEConfig::preset(CdePreset::Fast));
+        let accurate = parse(CDEConfig::preset(CdePreset::Accurate));
+
+        let n_poles = |instance: &BPInstance| {
+            instance
+                .items
+                .iter()
+                .map(|(item, _)| item.shape.surrogate().poles.len())
+                .sum::<usize>()
+        };
+        assert!(n_poles(&accurate) > n_poles(&fast));
+        assert!(
+            accurate.bins[0].0.base_cde.quadtree().level > fast.bins[0].0.base_cde.quadtree().level
+        );
+
+        //presets can be referenced by name, explicit configurations are still accepted
+        let cde_config_of = |cde_config: &str| {
+            let mut config = serde_json::to_value(LBFConfig::default()).unwrap();
+            config["cde_config"] = serde_json::from_str(cde_config).unwrap();
+            serde_json::from_value::<LBFConfig>(config).map(|c| c.cde_config)
+        };
+        let accurate = cde_config_of("\"Accurate\"").unwrap();
+        assert_eq!(accurate, CDEConfig::preset(CdePreset::Accurate));
+        let explicit = serde_json::to_string(&CDEConfig::preset(CdePreset::Fast)).unwrap();
+        assert_eq!(
+            cde_config_of(&explicit).unwrap(),
+            CDEConfig::preset(CdePreset::Fast)
+        );
+        assert!(cde_config_of("\"Sloppy\"").is_err());
+        //missing fields are taken from the default preset
+        let partial = cde_config_of("{\"verify_placements\": true}").unwrap();
+        assert_eq!(
+            partial,
+            CDEConfig {
+                verify_placements: true,
+                ..CDEConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_mtv() {
+        let square = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(1.0, 0.0),
+            Point(1.0, 1.0),
+            Point(0.0, 1.0),
+        ]);
+        let a_t = Transformation::empty();
+
+        let b_t = Transformation::from_translation((0.7, 0.0));
+        let (depth, (dx, dy)) = geometry::mtv(&square, &a_t, &square, &b_t).unwrap();
+        assert!((depth - 0.3).abs() < 1e-5);
+        assert!((dx.abs() - 1.0).abs() < 1e-5 && dy.abs() < 1e-5);
+
+        //moving b along the MTV resolves the overlap
+        let b_t =
+            Transformation::from_translation((0.7 + dx * (depth + 1e-3), dy * (depth + 1e-3)));
+        assert_eq!(geometry::mtv(&square, &a_t, &square, &b_t), None);
+
+        let b_t = Transformation::from_translation((0.0, 1.5));
+        assert_eq!(geometry::mtv(&square, &a_t, &square, &b_t), None);
+    }
+
+    #[test]
+    fn test_soft_bin_boundary() {
+        let rect = AARectangle::new(0.0, 0.0, 10.0, 10.0);
+        let hard_cde = CDEConfig::preset(CdePreset::Balanced);
+        let soft_cde = CDEConfig {
+            soft_bin_boundary: true,
+            ..hard_cde
+        };
+        let item = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(4.0, 0.0),
+            Point(4.0, 4.0),
+            Point(0.0, 4.0),
+        ]);
+        let half_outside = Transformation::from_translation((8.0, 3.0));
+        let inside = Transformation::from_translation((3.0, 3.0));
+        let t_item = item.transform_clone(&half_outside);
+
+        let hard_bin = Bin::from_strip(rect.clone(), hard_cde);
+        assert!(hard_bin.base_cde.poly_collides(&t_item, &[]));
+
+        let soft_bin = Bin::from_strip(rect, soft_cde);
+        let cde = &soft_bin.base_cde;
+        assert!(!cde.poly_collides(&t_item, &[]));
+        assert!((cde.boundary_violation(&item, &half_outside) - 8.0).abs() < 1e-4);
+        assert_eq!(cde.boundary_violation(&item, &inside), 0.0);
+
+        //rotated by 45 degrees around the corner of the bin: a quarter of the diamond lies inside
+        let corner = Transformation::from_translation((-2.0, -2.0))
+            .rotate(PI / 4.0)
+            .translate((10.0, 10.0));
+        assert!((cde.boundary_violation(&item, &corner) - 12.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_strip_growth() {
+        let rect_item = |demand: u64, width: fsize, height: fsize| JsonItem {
+            allowed_orientations: Some(vec![0.0]),
+            ..json_item(JsonShape::Rectangle { width, height }, demand)
+        };
+        let json_instance = JsonInstance {
+            name: "strip_growth".to_string(),
+            items: vec![rect_item(1, 30.0, 5.0), rect_item(2, 4.0, 4.0)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 10.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 500,
+            strip_growth_step: Some(5.0),
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+
+        //the wide item does not fit in the initial strip, growing makes room for it in steps of 5
+        let Instance::SP(sp_instance) = &instance else {
+            panic!("expected a strip packing instance");
+        };
+        let mut sp_problem = SPProblem::new(sp_instance.clone(), 5.0, config.cde_config);
+        assert_eq!(sp_problem.grow_to_fit(0, 5.0), 30.0);
+        assert_eq!(sp_problem.grow_to_fit(0, 5.0), 35.0);
+
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 3);
+        let Problem::SP(sp_problem) = &optimizer.problem else {
+            panic!("expected a strip packing problem");
+        };
+        assert!(sp_problem.strip_width() >= 30.0);
+        assert!(sp_problem.strip_width() < 40.0);
+    }
+
+    #[test]
+    fn test_rotation_unit() {
+        let parse_rotations = |unit: &str, orientations: &str| {
+            let json = format!(
+                r#"{{
+                    "Name": "rotation_unit",
+                    {unit}
+                    "Items": [{{
+                        "Demand": 1,
+                        "AllowedOrientations": {orientations},
+                        "Shape": {{"Type": "Rectangle", "Data": {{"Width": 10.0, "Height": 5.0}}}}
+                    }}],
+                    "Strip": {{"Height": 10.0}}
+                }}"#
+            );
+            let json_instance: JsonInstance = serde_json::from_str(&json).unwrap();
+            let parser = Parser::new(
+                PolySimplConfig::Disabled,
+                LBFConfig::default().cde_config,
+                true,
+            );
+            let instance = parser.parse(&json_instance);
+            match &instance.item(0).allowed_rotation {
+                AllowedRotation::Discrete(rotations) => rotations.clone(),
+                _ => panic!("expected discrete rotations"),
+            }
+        };
+        let half_pi = PI / 2.0;
+
+        let rotations = parse_rotations(r#""rotation_unit": "deg","#, "[0.0, 90.0]");
+        assert!((rotations[1] - half_pi).abs() < 1e-6);
+        let rotations = parse_rotations(r#""RotationUnit": "rad","#, "[0.0, 1.5707964]");
+        assert!((rotations[1] - half_pi).abs() < 1e-6);
+        //degrees are assumed when no unit is given, as before
+        let rotations = parse_rotations("", "[0.0, 90.0]");
+        assert!((rotations[1] - half_pi).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sample_interior() {
+        //L-shape: a 2x2 square without its top-right quadrant
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(2.0, 0.0),
+            Point(2.0, 1.0),
+            Point(1.0, 1.0),
+            Point(1.0, 2.0),
+            Point(0.0, 2.0),
+        ]);
+        let mut rng = LBFRng::seed_from_u64(0);
+        let n_samples = 10_000;
+        let samples = (0..n_samples)
+            .map(|_| l_shape.sample_interior(&mut rng))
+            .collect_vec();
+        assert!(samples.iter().all(|p| l_shape.collides_with(p)));
+
+        //two thirds of the area lies left of x = 1
+        let frac_left = samples.iter().filter(|p| p.0 < 1.0).count() as fsize / n_samples as fsize;
+        assert!((frac_left - 2.0 / 3.0).abs() < 0.02, "{frac_left}");
+    }
+
+    #[test]
+    fn test_triangulate() {
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(2.0, 0.0),
+            Point(2.0, 1.0),
+            Point(1.0, 1.0),
+            Point(1.0, 2.0),
+            Point(0.0, 2.0),
+        ]);
+        let triangles = l_shape
+            .triangulate()
+            .into_iter()
+            .map(|t| SimplePolygon::new(t.to_vec()))
+            .collect_vec();
+        assert_eq!(triangles.len(), l_shape.number_of_points() - 2);
+
+        let total_area = triangles.iter().map(|t| t.area()).sum::<fsize>();
+        assert!((total_area - l_shape.area()).abs() < 1e-6);
+        for (t1, t2) in triangles.iter().tuple_combinations() {
+            assert!(t1.intersection_area_with_convex(t2) < 1e-6);
+        }
+        //every triangle lies within the polygon
+        for t in &triangles {
+            assert!((l_shape.intersection_area_with_convex(t) - t.area()).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_gcode() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/swim.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+
+        let options = GcodeOptions {
+            feed_rate: 500.0,
+            ..GcodeOptions::default()
+        };
+        let gcode = gcode::compose_gcode(&solution, &instance, &options);
+        let lines = gcode.lines().collect_vec();
+        assert_eq!(lines[..2], ["G21", "G90"]);
+        assert_eq!(lines.last(), Some(&"M2"));
+
+        //one rapid move per placed item, one cutting move per vertex
+        let n_vertices = solution
+            .layout_snapshots
+            .iter()
+            .flat_map(|l| l.placed_items.values())
+            .map(|pi| pi.shape.number_of_points())
+            .sum::<usize>();
+        let count = |prefix: &str| lines.iter().filter(|l| l.starts_with(prefix)).count();
+        assert_eq!(count("G0 "), solution.n_items_placed());
+        assert_eq!(count("G1 "), n_vertices);
+        assert_eq!(
+            lines.iter().filter(|l| l.ends_with(" F500")).count(),
+            solution.n_items_placed()
+        );
+
+        //simplified items are traced along their contour in the input file
+        let simpl_config = PolySimplConfig::Enabled {
+            tolerance: 0.01,
+            direction: PolySimplDirection::OutwardOnly,
+        };
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let instance = Parser::new(simpl_config, config.cde_config, true).parse(&json_instance);
+        assert!(instance
+            .items()
+            .iter()
+            .any(|(item, _)| item.original_shape.is_some()));
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        let gcode = gcode::compose_gcode(&solution, &instance, &options);
+        let n_original_vertices = solution
+            .layout_snapshots
+            .iter()
+            .flat_map(|l| l.placed_items.values())
+            .map(|pi| match &instance.item(pi.item_id).original_shape {
+                Some(original_shape) => original_shape.number_of_points(),
+                None => pi.shape.number_of_points(),
+            })
+            .sum::<usize>();
+        let n_cutting_moves = gcode.lines().filter(|l| l.starts_with("G1 ")).count();
+        assert_eq!(n_cutting_moves, n_original_vertices);
+    }
+
+    #[test]
+    fn test_layout_is_collision_free() {
+        let cde_config = LBFConfig::default().cde_config;
+        let bin = Bin::from_strip(AARectangle::new(0.0, 0.0, 10.0, 10.0), cde_config);
+        let square = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(4.0, 0.0),
+            Point(4.0, 4.0),
+            Point(0.0, 4.0),
+        ]);
+        let item = Item::new(
+            0,
+            square,
+            AllowedRotation::None,
+            None,
+            0,
+            Transformation::empty(),
+            cde_config.item_surrogate_config,
+        );
+        let at = |x: fsize, y: fsize| DTransformation::new(0.0, (x, y));
+
+        let mut layout = Layout::new(0, bin);
+        layout.place_item(&item, at(1.0, 1.0));
+        layout.place_item(&item, at(5.5, 1.0));
+        assert!(layout.is_collision_free());
+
+        //overlapping items
+        let mut overlapping = layout.clone();
+        overlapping.place_item(&item, at(3.0, 3.0));
+        assert!(!overlapping.is_collision_free());
+
+        //an item poking outside the bin
+        let mut outside = layout.clone();
+        outside.place_item(&item, at(8.0, 5.5));
+        assert!(!outside.is_collision_free());
+
+        //an overlapping item which bypassed the collision detection engine is caught as well
+        let mut bypassed = layout.clone();
+        bypassed
+            .placed_items
+            .insert(PlacedItem::new(&item, at(2.0, 2.0)));
+        assert!(!bypassed.is_collision_free());
+    }
+
+    #[test]
+    fn test_pareto_archive() {
+        let rect_item = |demand: u64, width: fsize, height: fsize| JsonItem {
+            allowed_orientations: Some(vec![0.0]),
+            ..json_item(JsonShape::Rectangle { width, height }, demand)
+        };
+        let json_instance = JsonInstance {
+            name: "pareto".to_string(),
+            items: vec![rect_item(1, 20.0, 1.0), rect_item(2, 5.0, 5.0)],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 25.0,
+                    height: 25.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let Instance::BP(bp_instance) = &instance else {
+            panic!("expected a bin packing instance");
+        };
+
+        //every item in a bin of its own, bins and items are centered around the origin
+        let solution_with_bins = |item_ids: &[usize]| {
+            let mut problem = BPProblem::new(bp_instance.clone());
+            for &item_id in item_ids {
+                problem.place_item(PlacingOption {
+                    layout_idx: LayoutIndex::Template(0),
+                    item_id,
+                    d_transf: DTransformation::empty(),
+                    subtract: false,
+                });
+            }
+            problem.create_solution(None)
+        };
+        //fewer bins, but longer cuts
+        let thin = solution_with_bins(&[0]);
+        //shorter cuts, but more bins
+        let squares = solution_with_bins(&[1, 1]);
+        //dominated by the first
+        let mixed = solution_with_bins(&[0, 1]);
+        assert!((thin.cut_length() - 42.0).abs() < 1e-4);
+        assert!((squares.cut_length() - 40.0).abs() < 1e-4);
+
+        let mut archive =
+            ParetoArchive::new(vec![SolutionObjective::NBins, SolutionObjective::CutLength]);
+        assert!(archive.insert(thin.clone()));
+        assert!(archive.insert(squares.clone()));
+        assert!(!archive.insert(mixed));
+        assert!(!archive.insert(thin.clone()));
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.best().unwrap().layout_snapshots.len(), 1);
+
+        //a solution dominating both replaces them
+        let mut problem = BPProblem::new(bp_instance.clone());
+        problem.place_item(PlacingOption {
+            layout_idx: LayoutIndex::Template(0),
+            item_id: 1,
+            d_transf: DTransformation::empty(),
+            subtract: false,
+        });
+        assert!(archive.insert(problem.create_solution(None)));
+        assert_eq!(archive.len(), 1);
+
+        let (best, archive) = lbf_optimizer::solve_pareto(
+            &instance,
+            LBFConfig {
+                n_samples: 100,
+                ..config
+            },
+            &[0, 1],
+            vec![SolutionObjective::NBins, SolutionObjective::CutLength],
+        );
+        assert!(!archive.is_empty());
+        assert!(archive
+            .solutions()
+            .all(|s| s.layout_snapshots.len() >= best.layout_snapshots.len()));
+    }
+
+    #[test]
+    fn test_rng_entropy_fallback() {
+        //entropy source which is never available
+        struct NoEntropy;
+        impl RngCore for NoEntropy {
+            fn next_u32(&mut self) -> u32 {
+                unimplemented!()
+            }
+            fn next_u64(&mut self) -> u64 {
+                unimplemented!()
+            }
+            fn fill_bytes(&mut self, _dest: &mut [u8]) {
+                unimplemented!()
+            }
+            fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> Result<(), rand::Error> {
+                Err(rand::Error::new(std::io::Error::other("no entropy")))
+            }
+        }
+
+        let mut rng = LBFRng::from_entropy_source(NoEntropy);
+        let samples = (0..100).map(|_| rng.gen_range(0..1000)).collect_vec();
+        assert!(samples.iter().unique().count() > 1);
+
+        //an available source is used as is
+        let mut source = LBFRng::seed_from_u64(0);
+        let mut expected = LBFRng::from_rng(source.clone()).unwrap();
+        let mut rng = LBFRng::from_entropy_source(&mut source);
+        assert_eq!(rng.next_u64(), expected.next_u64());
+    }
+
+    #[test]
+    fn test_item_anchor() {
+        let item = |points: Vec<(fsize, fsize)>, anchor| JsonItem {
+            anchor,
+            ..json_item(JsonShape::SimplePolygon(JsonSimplePoly(points)), 1)
+        };
+        let l_shape = vec![
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 4.0),
+            (0.0, 4.0),
+        ];
+        let rect = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)];
+        let json_instance = JsonInstance {
+            name: "anchor".to_string(),
+            items: vec![
+                item(l_shape.clone(), Some(JsonAnchor::Centroid)),
+                item(rect, Some(JsonAnchor::Vertex(2))),
+            ],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 20.0,
+                    height: 20.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig::default();
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let instance = parser.parse(&json_instance);
+
+        let placed = |index, rotation, translation| JsonPlacedItem {
+            index,
+            transformation: JsonTransformation {
+                rotation,
+                translation,
+            },
+            subtract: false,
+            meta: None,
+            collision_shape: None,
+            original_shape: None,
+        };
+        let json_layouts = vec![JsonLayout {
+            container: JsonContainer::Bin { index: 0 },
+            placed_items: vec![
+                placed(0, PI / 2.0, (5.0, 5.0)),
+                placed(1, 0.0, (15.0, 15.0)),
+            ],
+            statistics: JsonLayoutStats { usage: 0.0 },
+            snapshot: None,
+        }];
+        let solution =
+            parser::build_solution_from_json(&instance, &json_layouts, config.cde_config);
+        let layout = &solution.layout_snapshots[0];
+        let abs_shape = |item_id: usize| {
+            let pi = layout
+                .placed_items
+                .values()
+                .find(|pi| pi.item_id == item_id)
+                .unwrap();
+            pi.shape
+                .transform_clone(&layout.bin.pretransform.clone().inverse())
+        };
+
+        //the centroid of the L-shape ends up at the translation, regardless of the rotation
+        let centroid = abs_shape(0).centroid();
+        assert!(centroid.distance(Point(5.0, 5.0)) < 1e-4, "{centroid:?}");
+        //the top-right corner of the rectangle ends up at the translation
+        let bbox = abs_shape(1).bbox();
+        assert!((bbox.x_max - 15.0).abs() < 1e-4 && (bbox.y_max - 15.0).abs() < 1e-4);
+
+        //the composed solution refers to the anchors as well
+        let json_solution =
+            parser::compose_json_solution(&solution, &instance, Instant::now(), None, false);
+        let (x, y) = json_solution.layouts[0].placed_items[0]
+            .transformation
+            .translation;
+        assert!((x - 5.0).abs() < 1e-4 && (y - 5.0).abs() < 1e-4);
+
+        let invalid = JsonInstance {
+            items: vec![item(l_shape, Some(JsonAnchor::Vertex(6)))],
+            ..json_instance
+        };
+        assert_eq!(
+            parser.parse_with_warnings(&invalid).err(),
+            Some(ParseError::AnchorVertexOutOfRange {
+                item_id: 0,
+                vertex: 6
+            })
+        );
+
+        //the centroid of the input contour is the anchor, not the one of its simplification
+        let sawtooth = [(0.0, 0.0), (20.0, 0.0), (20.0, 10.0), (10.0, 10.0)]
+            .into_iter()
+            .chain((0..10).flat_map(|i| {
+                let x = 10.0 - i as fsize;
+                [(x - 0.5, 9.0), (x - 1.0, 10.0)]
+            }))
+            .collect_vec();
+        let sawtooth_instance = JsonInstance {
+            items: vec![item(sawtooth, Some(JsonAnchor::Centroid))],
+            ..invalid
+        };
+        let simpl_config = PolySimplConfig::Enabled {
+            tolerance: 0.05,
+            direction: PolySimplDirection::OutwardOnly,
+        };
+        let instance =
+            Parser::new(simpl_config, config.cde_config, false).parse(&sawtooth_instance);
+        let item = instance.item(0);
+        let original_shape = item.original_shape.as_ref().unwrap();
+        assert!(original_shape.centroid().distance(Point(0.0, 0.0)) < 1e-4);
+        assert!(item.shape.centroid().distance(Point(0.0, 0.0)) > 1e-2);
+    }
+
+    #[test]
+    fn test_circle_collision() {
+        let config = CDEConfig::preset(CdePreset::Balanced);
+        let [mut square, mut bin] = [
+            AARectangle::new(2.0, 2.0, 4.0, 4.0),
+            AARectangle::new(0.0, 0.0, 10.0, 10.0),
+        ]
+        .map(SimplePolygon::from);
+        square.generate_surrogate(config.item_surrogate_config);
+        bin.generate_surrogate(config.item_surrogate_config);
+        let mut cde = CDEngine::new(
+            bin.bbox().inflate_to_square(),
+            vec![Hazard::new(HazardEntity::BinExterior, Arc::new(bin))],
+            config,
+        );
+        cde.register_hazard(Hazard::new(
+            HazardEntity::PlacedItem {
+                id: 0,
+                dt: DTransformation::empty(),
+            },
+            Arc::new(square.clone()),
+        ));
+
+        //tangent to the top edge of the square
+        let tangent = Circle::new(Point(3.0, 5.0), 1.0);
+        let distance = square
+            .edge_iter()
+            .map(|e| tangent.distance(&e))
+            .fold(fsize::INFINITY, fsize::min);
+        assert!(distance.abs() < 1e-6);
+        assert!(square.collides_with(&tangent));
+        assert!(cde.circle_collides(&tangent, &[]));
+
+        let clear = Circle::new(Point(3.0, 5.01), 1.0);
+        assert!(!square.collides_with(&clear));
+        assert!(!cde.circle_collides(&clear, &[]));
+
+        //fully inside the square, no edge within reach
+        assert!(cde.circle_collides(&Circle::new(Point(3.0, 3.0), 0.5), &[]));
+        //poking out of the bin
+        assert!(cde.circle_collides(&Circle::new(Point(9.8, 5.0), 0.5), &[]));
+        assert!(!cde.circle_collides(&Circle::new(Point(9.0, 5.0), 0.5), &[]));
+    }
+
+    #[test]
+    fn test_circle_items() {
+        let circle_item = |radius: fsize, demand: u64| JsonItem {
+            allowed_orientations: Some(vec![0.0]),
+            ..json_item(JsonShape::Circle { radius }, demand)
+        };
+        //the bin is just wide enough for the circle, but not for the polygon circumscribing it
+        let json_instance = JsonInstance {
+            name: "circle_items".to_string(),
+            items: vec![circle_item(5.0, 1)],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 10.01,
+                    height: 10.01,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let cde_config = CDEConfig {
+            verify_placements: true,
+            ..CDEConfig::default()
+        };
+        let Instance::BP(instance) = parse_json_instance(&json_instance, cde_config) else {
+            panic!("expected a bin packing instance");
+        };
+        let item = instance.item(0);
+        assert!(item.circle.is_some());
+        assert!(item.shape.bbox().width() > 10.01);
+
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        let d_transf = DTransformation::new(0.0, (0.0, 0.0));
+        let mut buffer = item.shape.as_ref().clone();
+        assert!(layout.cde().surrogate_or_poly_collides(
+            &item.shape,
+            &d_transf.compose(),
+            &mut buffer,
+            &[]
+        ));
+        //the check dispatched on the item's shape uses the exact circle
+        assert!(!layout
+            .cde()
+            .item_collides(item, &d_transf.compose(), &mut buffer, &[]));
+        let shifted = DTransformation::new(0.0, (0.01, 0.0)).compose();
+        assert!(layout.cde().item_collides(item, &shifted, &mut buffer, &[]));
+
+        //placements of circular items are verified and validated against the circle as well
+        layout.place_item(item, d_transf);
+        assert!(layout.is_collision_free());
+
+        //the optimizer samples circular items through the same check
+        let json_instance = JsonInstance {
+            items: vec![circle_item(1.0, 12)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 6.0,
+                axis: PackingAxis::X,
+            }),
+            ..json_instance
+        };
+        let config = LBFConfig {
+            n_samples: 500,
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        assert_eq!(solution.n_items_placed(), 12);
+        let layout = &optimizer.problem.layouts()[0];
+        assert!(assertions::layout_is_collision_free(layout));
+        let circles = layout
+            .placed_items()
+            .values()
+            .map(|pi| pi.circle.clone().unwrap())
+            .collect_vec();
+        for (a, b) in circles.iter().tuple_combinations() {
+            assert!(a.center.distance(b.center) >= 2.0 - 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_simplification() {
+        let max_deviation = 0.05;
+        let target_vertices = 12;
+        //high detail: a gear-like shape with 60 small teeth
+        let detailed = SimplePolygon::new(
+            (0..120)
+                .map(|i| {
+                    let angle = i as fsize * 2.0 * PI / 120.0;
+                    let r = if i % 2 == 0 { 10.0 } else { 9.8 };
+                    Point(r * angle.cos(), r * angle.sin())
+                })
+                .collect(),
+        );
+        //blocky: an L-shape
+        let blocky = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(10.0, 0.0),
+            Point(10.0, 4.0),
+            Point(4.0, 4.0),
+            Point(4.0, 10.0),
+            Point(0.0, 10.0),
+        ]);
+
+        let [detailed_simpl, blocky_simpl] = [&detailed, &blocky].map(|s| {
+            polygon_simplification::simplify_shape_adaptive(
+                s,
+                PolySimplMode::Inflate,
+                max_deviation,
+                target_vertices,
+            )
+        });
+        assert!(detailed_simpl.number_of_points() < detailed.number_of_points());
+        assert_eq!(blocky_simpl.number_of_points(), blocky.number_of_points());
+        assert!(detailed_simpl.number_of_points() > blocky_simpl.number_of_points());
+        for (original, simplified) in [(&detailed, &detailed_simpl), (&blocky, &blocky_simpl)] {
+            assert!(polygon_simplification::simplification_is_conservative(
+                original,
+                simplified,
+                PolySimplMode::Inflate
+            ));
+            assert!(simplified.area() <= original.area() * (1.0 + max_deviation) + 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_item_at() {
+        let cde_config = LBFConfig::default().cde_config;
+        let square = |size: fsize| {
+            json_item(
+                JsonShape::Rectangle {
+                    width: size,
+                    height: size,
+                },
+                1,
+            )
+        };
+        let json_instance = JsonInstance {
+            name: "item_at".to_string(),
+            items: vec![square(50.0), square(20.0), square(5.0)],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let Instance::SP(instance) = parse_json_instance(&json_instance, cde_config) else {
+            panic!("expected a strip packing instance")
+        };
+        let mut problem = SPProblem::new(instance.clone(), 100.0, cde_config);
+        //places an item with its center at (50, 50)
+        let mut place = |item_id: usize, subtract: bool| {
+            let Point(cx, cy) = instance.item(item_id).shape.bbox().centroid();
+            problem.place_item(PlacingOption {
+                layout_idx: STRIP_LAYOUT_IDX,
+                item_id,
+                d_transf: DTransformation::new(0.0, (50.0 - cx, 50.0 - cy)),
+                subtract,
+            });
+        };
+        place(0, false);
+        place(1, true);
+        place(2, false);
+
+        let layout = &problem.layout;
+        let item_at = |p: Point| layout.item_at(p).map(|k| layout.placed_items()[k].item_id);
+        assert_eq!(item_at(Point(30.0, 30.0)), Some(0));
+        //inside the cutout: the item placed in it, or nothing
+        assert_eq!(item_at(Point(50.0, 50.0)), Some(2));
+        assert_eq!(item_at(Point(45.0, 45.0)), None);
+        //empty space
+        assert_eq!(item_at(Point(90.0, 90.0)), None);
+
+        //the quadtree search agrees with an exhaustive one
+        let cutouts = layout.cde().cutouts();
+        for (x, y) in (0..50).cartesian_product(0..50) {
+            let p = Point(x as fsize * 2.0 + 0.5, y as fsize * 2.0 + 0.5);
+            let exhaustive = layout
+                .placed_items()
+                .iter()
+                .filter(|(_, pi)| !pi.subtract && pi.shape.collides_with(&p))
+                .filter(|(_, pi)| {
+                    !cutouts
+                        .iter()
+                        .any(|c| c.parent == HazardEntity::from(*pi) && c.shape.collides_with(&p))
+                })
+                .map(|(k, _)| k)
+                .min();
+            assert_eq!(layout.item_at(p), exhaustive);
+        }
+    }
+
+    #[test]
+    fn test_touching_allowed() {
+        let square = |x: fsize, y: fsize| {
+            let mut square = SimplePolygon::from(AARectangle::new(x, y, x + 10.0, y + 10.0));
+            square.generate_surrogate(CDEConfig::preset(CdePreset::Balanced).item_surrogate_config);
+            square
+        };
+        let collides = |touching_allowed: bool, other: &SimplePolygon| {
+            let config = CDEConfig {
+                touching_allowed,
+                ..CDEConfig::preset(CdePreset::Balanced)
+            };
+            let mut bin = SimplePolygon::from(AARectangle::new(-20.0, -20.0, 40.0, 40.0));
+            bin.generate_surrogate(config.item_surrogate_config);
+            let mut cde = CDEngine::new(
+                bin.bbox().inflate_to_square(),
+                vec![Hazard::new(HazardEntity::BinExterior, Arc::new(bin))],
+                config,
+            );
+            cde.register_hazard(Hazard::new(
+                HazardEntity::PlacedItem {
+                    id: 0,
+                    dt: DTransformation::empty(),
+                },
+                Arc::new(square(0.0, 0.0)),
+            ));
+            let mut buffer = other.clone();
+            cde.surrogate_or_poly_collides(other, &Transformation::empty(), &mut buffer, &[])
+        };
+
+        //sharing an edge
+        let neighbour = square(10.0, 0.0);
+        assert!(collides(false, &neighbour));
+        assert!(!collides(true, &neighbour));
+        //sharing only a corner
+        let diagonal = square(10.0, 10.0);
+        assert!(collides(false, &diagonal));
+        assert!(!collides(true, &diagonal));
+        //overlapping
+        let overlapping = square(9.5, 0.0);
+        assert!(collides(false, &overlapping));
+        assert!(collides(true, &overlapping));
+    }
+
+    #[test]
+    fn test_width_history() {
+        let cde_config = LBFConfig::default().cde_config;
+        let json_instance = JsonInstance {
+            name: "width_history".to_string(),
+            items: vec![json_item(
+                JsonShape::Rectangle {
+                    width: 50.0,
+                    height: 50.0,
+                },
+                1,
+            )],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let Instance::SP(instance) = parse_json_instance(&json_instance, cde_config) else {
+            panic!("expected a strip packing instance")
+        };
+        let mut problem = SPProblem::new(instance.clone(), 100.0, cde_config);
+        let Point(cx, cy) = instance.item(0).shape.bbox().centroid();
+        problem.place_item(PlacingOption {
+            layout_idx: STRIP_LAYOUT_IDX,
+            item_id: 0,
+            d_transf: DTransformation::new(0.0, (30.0 - cx, 50.0 - cy)),
+            subtract: false,
+        });
+
+        //not recorded by default
+        problem.modify_strip_in_back(110.0);
+        assert_eq!(problem.width_history(), None);
+
+        problem.record_width_history(true);
+        for width in [120.0, 90.0, 40.0] {
+            problem.modify_strip_in_back(width);
+        }
+        let history = problem.width_history().unwrap();
+        assert_eq!(history.len(), 3);
+        let feasible = history.iter().map(|&(_, f)| f).collect_vec();
+        assert_eq!(feasible, [true, true, false]);
+        assert!((history[2].0 - 40.0).abs() < 1e-3);
+
+        problem.record_width_history(false);
+        assert_eq!(problem.width_history(), None);
+    }
+
+    #[test]
+    fn test_verbose_json_solution() {
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/albano.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+
+        let compact =
+            parser::compose_json_solution(&solution, &instance, Instant::now(), None, false);
+        assert!(compact.layouts.iter().all(|l| l.snapshot.is_none()));
+
+        let verbose =
+            parser::compose_json_solution(&solution, &instance, Instant::now(), None, true);
+        let json = serde_json::to_string(&verbose).unwrap();
+        let verbose: JsonSolution = serde_json::from_str(&json).unwrap();
+        for (json_layout, sl) in verbose.layouts.iter().zip(&solution.layout_snapshots) {
+            let snapshot = json_layout.snapshot.as_ref().unwrap();
+            assert!(json.contains(&format!("\"Id\":{}", sl.id)));
+            assert_eq!(snapshot.id, sl.id);
+            assert_eq!(snapshot.bin_id, sl.bin.id);
+            assert_eq!(snapshot.placed_items.len(), sl.placed_items.len());
+        }
+
+        //the verbose output can still be used as a warm start
+        let rebuilt =
+            parser::build_solution_from_json(&instance, &verbose.layouts, config.cde_config);
+        assert_eq!(rebuilt.n_items_placed(), solution.n_items_placed());
+    }
+
+    #[test]
+    fn test_quality_penalty() {
+        let rect = |width: fsize, height: fsize| JsonShape::Rectangle { width, height };
+        let json_instance = JsonInstance {
+            name: "quality_penalty".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0]),
+                base_quality: Some(3),
+                ..json_item(rect(4.0, 4.0), 1)
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: rect(20.0, 10.0),
+                //the cheap (left) half of the bin is of inferior, but sufficient, quality
+                zones: vec![JsonQualityZone {
+                    quality: 5,
+                    shape: rect(10.0, 10.0),
+                }],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let solve = |quality_penalty: fsize| {
+            let config = LBFConfig {
+                n_samples: 1000,
+                quality_penalty,
+                ..LBFConfig::default()
+            };
+            let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, false);
+            let instance = parser.parse(&json_instance);
+            let mut optimizer =
+                LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+            let solution = optimizer.solve();
+            assert_eq!(solution.n_items_placed(), 1);
+            let layout = &solution.layout_snapshots[0];
+            let placed_item = layout.placed_items.values().next().unwrap();
+            lbf_optimizer::inferior_quality_fraction(&placed_item.shape, &layout.bin, 3)
+        };
+        //without penalty, the item ends up in the inferior zone, with it, in the regular part of the bin
+        assert!(solve(0.0) > 0.5);
+        assert!(solve(1000.0) < 1e-3);
+
+        //a non-convex zone is decomposed once, into convex parts covering the same area
+        let l_zone = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(10.0, 0.0),
+            Point(10.0, 5.0),
+            Point(5.0, 5.0),
+            Point(5.0, 10.0),
+            Point(0.0, 10.0),
+        ]);
+        let quality_zone = InferiorQualityZone::new(5, vec![l_zone]);
+        assert_eq!(quality_zone.convex_parts.len(), 1);
+        assert!(quality_zone.convex_parts[0].len() > 1);
+        let parts_area: fsize = quality_zone.convex_parts[0].iter().map(|p| p.area()).sum();
+        assert!((parts_area - 75.0).abs() < 1e-3);
+        let bin = Bin::new(
+            0,
+            SimplePolygon::from(AARectangle::new(0.0, 0.0, 20.0, 10.0)),
+            1,
+            Transformation::empty(),
+            vec![],
+            vec![quality_zone],
+            LBFConfig::default().cde_config,
+        );
+        //a square over the notch of the L only overlaps the zone for three quarters
+        let square = SimplePolygon::from(AARectangle::new(3.0, 3.0, 7.0, 7.0));
+        let fraction = lbf_optimizer::inferior_quality_fraction(&square, &bin, 3);
+        assert!((fraction - 0.75).abs() < 1e-3, "{fraction}");
+    }
+
+    #[test]
+    fn test_surrogate_builder() {
+        let l_shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(10.0, 0.0),
+            Point(10.0, 3.0),
+            Point(3.0, 3.0),
+            Point(3.0, 10.0),
+            Point(0.0, 10.0),
+        ]);
+        let surrogate = SPSurrogateBuilder::new()
+            .with_pole_coverage_goal(0.95)
+            .with_max_poles(6)
+            .with_n_ff_poles(3)
+            .build(&l_shape);
+        assert!(surrogate.poles.len() > 1 && surrogate.poles.len() <= 6);
+        assert_eq!(
+            surrogate.ff_poles().len(),
+            usize::min(3, surrogate.poles.len())
+        );
+        //the first pole is the pole of inaccessibility
+        assert_eq!(surrogate.poles[0], l_shape.poi);
+        let bc = &surrogate.poles_bounding_circle;
+        for pole in &surrogate.poles {
+            assert!(pole.center.distance(bc.center) + pole.radius <= bc.radius + 1e-3);
+        }
+
+        //a single pole
+        let surrogate = SPSurrogateBuilder::new().with_max_poles(1).build(&l_shape);
+        assert_eq!(surrogate.poles.len(), 1);
+    }
+
+    #[test]
+    fn test_rotation_sweep() {
+        let json_instance = JsonInstance {
+            name: "rotation_sweep".to_string(),
+            items: vec![JsonItem {
+                allowed_orientations: Some(vec![0.0, 90.0, 180.0, 270.0]),
+                ..json_item(
+                    JsonShape::SimplePolygon(JsonSimplePoly(vec![
+                        (0.0, 0.0),
+                        (10.0, 0.0),
+                        (10.0, 3.0),
+                        (3.0, 3.0),
+                        (3.0, 7.0),
+                        (0.0, 7.0),
+                    ])),
+                    8,
+                )
+            }],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 20.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 200,
+            ..LBFConfig::default()
+        };
+        let sweep_config = LBFConfig {
+            rotation_strategy: RotationStrategy::Sweep,
+            ..config
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+        let Problem::SP(problem) = &mut optimizer.problem else {
+            panic!("expected a strip packing problem");
+        };
+        //open up some room in the layout
+        let keys = problem.layout.placed_items().keys().sorted().collect_vec();
+        for pik in keys.into_iter().step_by(3) {
+            problem.remove_item(STRIP_LAYOUT_IDX, pik, true);
+        }
+        let problem = &optimizer.problem;
+        let item = instance.item(0);
+
+        let cost = |item: &Item, config: &LBFConfig, rng: &mut LBFRng| {
+            let mut acceptance = AcceptanceState::new(Acceptance::Greedy);
+            lbf_optimizer::sample_layout(
+                problem,
+                STRIP_LAYOUT_IDX,
+                item,
+                config,
+                rng,
+                &mut 0,
+                &mut acceptance,
+            )
+            .map(|p_opt| {
+                LBFPlacingCost::from_shape(&item.shape.transform_clone(&p_opt.d_transf.compose()))
+            })
+        };
+        for seed in 0..5 {
+            let sweep_cost = cost(item, &sweep_config, &mut LBFRng::seed_from_u64(seed)).unwrap();
+            //the sweep searches every rotation with the same random stream
+            let stream_seed = LBFRng::seed_from_u64(seed).gen::<u64>();
+            let AllowedRotation::Discrete(rotations) = &item.allowed_rotation else {
+                panic!("expected discrete rotations")
+            };
+            assert_eq!(rotations.len(), 4);
+            for &rotation in rotations {
+                let fixed_item = Item {
+                    allowed_rotation: AllowedRotation::Discrete(vec![rotation]),
+                    ..item.clone()
+                };
+                let single_cost = cost(
+                    &fixed_item,
+                    &config,
+                    &mut LBFRng::seed_from_u64(stream_seed),
+                );
+                assert!(single_cost.map_or(true, |c| sweep_cost <= c));
+            }
+        }
+    }
+
+    #[test]
+    fn test_instance_fingerprint() {
+        let json_instance = io::read_json_instance(Path::new("../assets/albano.json"));
+        let config = LBFConfig::default();
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+
+        let instance = parser.parse(&json_instance);
+        let fingerprint = instance.fingerprint();
+        assert_eq!(fingerprint, parser.parse(&json_instance).fingerprint());
+
+        //moving a single vertex changes the fingerprint
+        let mut changed_json_instance = json_instance.clone();
+        let JsonShape::SimplePolygon(JsonSimplePoly(points)) =
+            &mut changed_json_instance.items[0].shape
+        else {
+            panic!("expected a simple polygon")
+        };
+        points[1].0 += 1.0;
+        let changed_instance = parser.parse(&changed_json_instance);
+        assert_ne!(fingerprint, changed_instance.fingerprint());
+        assert_ne!(
+            parser.input_fingerprint(&json_instance),
+            parser.input_fingerprint(&changed_json_instance)
+        );
+
+        //the second parse restores the surrogates from the cache, resulting in the same instance
+        let cache_dir = std::env::temp_dir().join(format!(
+            "jagua_rs_cache_{}",
+            parser.input_fingerprint(&json_instance)
+        ));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let first = parser.parse_cached(&json_instance, &cache_dir);
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+        let second = parser.parse_cached(&json_instance, &cache_dir);
+        assert_eq!(first.fingerprint(), fingerprint);
+        assert_eq!(second.fingerprint(), fingerprint);
+        for ((a, _), (b, _)) in instance.items().iter().zip(second.items()) {
+            assert_eq!(a.surrogate().poles, b.surrogate().poles);
+            assert_eq!(a.surrogate().piers, b.surrogate().piers);
+        }
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_edge_contact() {
+        let cde_config = LBFConfig::default().cde_config;
+        let square = |size: fsize| JsonShape::Rectangle {
+            width: size,
+            height: size,
+        };
+        let json_instance = JsonInstance {
+            name: "edge_contact".to_string(),
+            items: vec![JsonItem {
+                min_edge_contact: Some(25.0),
+                ..json_item(square(10.0), 3)
+            }],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: square(30.0),
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(PolySimplConfig::Disabled, cde_config, false);
+        let Instance::BP(instance) = parser.parse(&json_instance) else {
+            panic!("expected a bin packing instance")
+        };
+        assert_eq!(instance.item(0).min_edge_contact, Some(25.0));
+
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        let corner = layout.place_item(instance.item(0), DTransformation::new(0.0, (0.0, 0.0)));
+        let next = layout.place_item(instance.item(0), DTransformation::new(0.0, (10.0, 0.0)));
+        let apart = layout.place_item(instance.item(0), DTransformation::new(0.0, (15.0, 15.0)));
+
+        //two squares sharing a full edge
+        assert!((layout.edge_contact(corner, next) - 10.0).abs() < 1e-3);
+        assert!((layout.edge_contact(next, corner) - 10.0).abs() < 1e-3);
+        //only touching at a vertex, or not at all
+        assert!((layout.edge_contact(next, apart) - 0.0).abs() < 1e-3);
+        assert!((layout.edge_contact(corner, apart) - 0.0).abs() < 1e-3);
+
+        //the item in the corner also touches the bottom and left side of the bin
+        assert!((layout.bin_edge_contact(corner) - 20.0).abs() < 1e-3);
+        assert!((layout.total_edge_contact(corner) - 30.0).abs() < 1e-3);
+        assert!((layout.total_edge_contact(next) - 20.0).abs() < 1e-3);
+        assert!((layout.total_edge_contact(apart) - 0.0).abs() < 1e-3);
+
+        //only the item in the corner achieves the minimum contact
+        let violations = layout.edge_contact_violations(&instance);
+        assert_eq!(
+            violations.into_iter().sorted().collect_vec(),
+            vec![next, apart]
+        );
+    }
+
+    #[test]
+    fn test_hpg_cell_tie_break() {
+        //two placed item hazards, each with a single pole exactly 4 away from the centroid of the cell
+        let hazard_at = |id: usize, x: fsize| {
+            let mut shape = SimplePolygon::from(AARectangle::new(x - 1.0, -1.0, x + 1.0, 1.0));
+            shape.generate_surrogate(SPSurrogateConfig {
+                pole_coverage_goal: 0.0,
+                max_poles: 1,
+                n_ff_poles: 1,
+                n_ff_piers: 0,
+            });
+            let pole = Circle::new(Point(x, 0.0), 1.0);
+            let surrogate = shape.surrogate.as_mut().unwrap();
+            surrogate.poles = vec![pole.clone()];
+            surrogate.poles_bounding_circle = pole;
+            let entity = HazardEntity::PlacedItem {
+                id,
+                dt: DTransformation::new(0.0, (x, 0.0)),
+            };
+            Hazard::new(entity, Arc::new(shape))
+        };
+        let (left, right) = (hazard_at(7, -5.0), hazard_at(3, 5.0));
+        let bbox = AARectangle::new(-0.5, -0.5, 0.5, 0.5);
+
+        let closest = |order: [&Hazard; 2], per_pole: bool| {
+            let mut cell = HPGCell::new(bbox.clone(), &[]);
+            for hazard in order {
+                match per_pole {
+                    false => cell.register_hazard(hazard),
+                    true => cell.register_hazard_pole(hazard, &hazard.shape.surrogate().poles[0]),
+                };
+            }
+            cell.uni_prox
+        };
+        for per_pole in [false, true] {
+            let (prox_lr, entity_lr) = closest([&left, &right], per_pole);
+            let (prox_rl, entity_rl) = closest([&right, &left], per_pole);
+            assert_eq!(prox_lr, 4.0);
+            assert_eq!(prox_rl, 4.0);
+            //the smallest entity wins, regardless of the registration order
+            assert_eq!(entity_lr, right.entity);
+            assert_eq!(entity_rl, right.entity);
+        }
+        //registering all hazards at once agrees
+        let mut cell = HPGCell::new(bbox.clone(), &[]);
+        cell.register_hazards([&left, &right].into_iter());
+        assert_eq!(cell.uni_prox.1, right.entity);
+    }
+
+    #[test]
+    fn test_rotated_bounds() {
+        //a unit square rotated by 45° around its center is bounded by a square with side √2
+        let square = AARectangle::new(-0.5, -0.5, 0.5, 0.5);
+        let bounds = square.rotated_bounds(PI / 4.0);
+        let sqrt_2 = (2.0 as fsize).sqrt();
+        assert!((bounds.width() - sqrt_2).abs() < 1e-5);
+        assert!((bounds.height() - sqrt_2).abs() < 1e-5);
+        assert!(bounds.centroid().0.abs() < 1e-5 && bounds.centroid().1.abs() < 1e-5);
+
+        //the bounds contain the rotated shape, and are tight for rectangles
+        let rect = AARectangle::new(1.0, 2.0, 4.0, 3.0);
+        for angle in [0.0, 0.3, PI / 2.0, 2.5, PI, 5.0] {
+            let rotated = SimplePolygon::from(rect.clone())
+                .transform_clone(&Transformation::from_rotation(angle));
+            let expected = rotated.bbox();
+            let bounds = rect.rotated_bounds(angle);
+            for (a, b) in [
+                (bounds.x_min, expected.x_min),
+                (bounds.y_min, expected.y_min),
+                (bounds.x_max, expected.x_max),
+                (bounds.y_max, expected.y_max),
+            ] {
+                assert!((a - b).abs() < 1e-4, "{bounds:?} != {expected:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_stored_solutions() {
+        let rect_item = |demand: u64, width: fsize, height: fsize| JsonItem {
+            allowed_orientations: Some(vec![0.0]),
+            ..json_item(JsonShape::Rectangle { width, height }, demand)
+        };
+        let json_instance = JsonInstance {
+            name: "max_stored_solutions".to_string(),
+            items: vec![rect_item(1, 20.0, 1.0), rect_item(2, 5.0, 5.0)],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 25.0,
+                    height: 25.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 100,
+            ..LBFConfig::default()
+        };
+        let instance = parse_json_instance(&json_instance, config.cde_config);
+        let Instance::BP(bp_instance) = &instance else {
+            panic!("expected a bin packing instance");
+        };
+        let solution_with_bins = |item_ids: &[usize]| {
+            let mut problem = BPProblem::new(bp_instance.clone());
+            for &item_id in item_ids {
+                problem.place_item(PlacingOption {
+                    layout_idx: LayoutIndex::Template(0),
+                    item_id,
+                    d_transf: DTransformation::empty(),
+                    subtract: false,
+                });
+            }
+            problem.create_solution(None)
+        };
+        //neither dominates the other, but the first uses fewer bins
+        let thin = solution_with_bins(&[0]);
+        let squares = solution_with_bins(&[1, 1]);
+        let objectives = vec![SolutionObjective::NBins, SolutionObjective::CutLength];
+
+        //only the best solution is kept, regardless of the order in which they are found
+        let mut archive = ParetoArchive::new(objectives.clone()).with_max_solutions(Some(1));
+        assert!(archive.insert(thin.clone()));
+        assert!(!archive.insert(squares.clone()));
+        assert_eq!(archive.len(), 1);
+        assert_eq!(archive.best().unwrap().layout_snapshots.len(), 1);
+
+        let mut archive = ParetoArchive::new(objectives.clone()).with_max_solutions(Some(1));
+        assert!(archive.insert(squares));
+        assert!(archive.insert(thin));
+        assert_eq!(archive.len(), 1);
+        assert_eq!(archive.best().unwrap().layout_snapshots.len(), 1);
+
+        //solving with multiple seeds retains the same best solution as without a cap
+        let seeds = [0, 1, 2, 3];
+        let (uncapped_best, _) =
+            lbf_optimizer::solve_pareto(&instance, config, &seeds, objectives.clone());
+        let (capped_best, capped_archive) = lbf_optimizer::solve_pareto(
+            &instance,
+            LBFConfig {
+                max_stored_solutions: Some(1),
+                ..config
+            },
+            &seeds,
+            objectives.clone(),
+        );
+        assert_eq!(capped_archive.len(), 1);
+        for objective in objectives {
+            assert_eq!(
+                objective.evaluate(&capped_best),
+                objective.evaluate(&uncapped_best)
+            );
+        }
+    }
+
+    #[test]
+    fn test_best_placement_for() {
+        let config = LBFConfig {
+            n_samples: 1000,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/albano.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+
+        //the fitted strip is nearly full: an extra copy of any item either fits without overlap or is rejected
+        let n_placed = optimizer.problem.placed_item_qtys().sum::<usize>();
+        for item_id in 0..instance.items().len() {
+            if let Some(p_opt) = optimizer.best_placement_for(item_id) {
+                let mut problem = optimizer.problem.clone();
+                problem.place_item(p_opt);
+                assert!(problem.get_layout(p_opt.layout_idx).is_collision_free());
+            }
+        }
+        //the search does not modify the problem
+        assert_eq!(
+            optimizer.problem.placed_item_qtys().sum::<usize>(),
+            n_placed
+        );
+
+        //with every other item removed from the solution, there is room again for the smallest removed one
+        let Problem::SP(problem) = &mut optimizer.problem else {
+            panic!("expected a strip packing problem");
+        };
+        let keys = problem.layout.placed_items().keys().sorted().collect_vec();
+        let removed_item_ids = keys
+            .into_iter()
+            .step_by(2)
+            .map(|pik| problem.remove_item(STRIP_LAYOUT_IDX, pik, true).item_id)
+            .collect_vec();
+        let item_id = removed_item_ids
+            .into_iter()
+            .min_by(|a, b| {
+                let area = |id: usize| instance.item(id).shape.area();
+                area(*a).total_cmp(&area(*b))
+            })
+            .unwrap();
+        let p_opt = optimizer.best_placement_for(item_id).unwrap();
+        assert_eq!(p_opt.item_id, item_id);
+        optimizer.problem.place_item(p_opt);
+        assert!(optimizer
+            .problem
+            .get_layout(STRIP_LAYOUT_IDX)
+            .is_collision_free());
+    }
+
+    #[test]
+    fn test_winding_policy() {
+        let square = |x: fsize, size: fsize| {
+            JsonSimplePoly(vec![
+                (x, x),
+                (x + size, x),
+                (x + size, x + size),
+                (x, x + size),
+            ])
+        };
+        let reversed = |JsonSimplePoly(points): JsonSimplePoly| {
+            JsonSimplePoly(points.into_iter().rev().collect_vec())
+        };
+        let instance_with_hole = |hole: JsonSimplePoly| JsonInstance {
+            name: "winding_policy".to_string(),
+            items: vec![json_item(
+                JsonShape::Rectangle {
+                    width: 2.0,
+                    height: 2.0,
+                },
+                1,
+            )],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Polygon(JsonPoly {
+                    outer: square(0.0, 10.0),
+                    inner: vec![hole],
+                }),
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let hole_source = ShapeSource::BinHole {
+            bin_id: 0,
+            hole_idx: 0,
+        };
+        let lenient = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            false,
+        );
+        let strict = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            false,
+        )
+        .with_winding_policy(WindingPolicy::Strict);
+
+        //a hole wound opposite to the (counterclockwise) outline is accepted by both
+        let opposite = instance_with_hole(reversed(square(4.0, 2.0)));
+        for parser in [&lenient, &strict] {
+            let (_, warnings) = parser.parse_with_warnings(&opposite).unwrap();
+            assert!(!warnings.contains(&ParseWarning::HoleWindingNotOpposite {
+                source: hole_source
+            }));
+        }
+
+        //a hole wound in the same direction is rejected in strict mode
+        let same = instance_with_hole(square(4.0, 2.0));
+        assert_eq!(
+            strict.parse_with_warnings(&same).err(),
+            Some(ParseError::HoleWindingNotOpposite {
+                source: hole_source
+            })
+        );
+
+        //lenient mode keeps it as a hole, with a warning
+        let (instance, warnings) = lenient.parse_with_warnings(&same).unwrap();
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::HoleWindingNotOpposite {
+                source: hole_source
+            }]
+        );
+        let Instance::BP(instance) = instance else {
+            panic!("expected a bin packing instance")
+        };
+        let bin = &instance.bins[0].0;
+        assert_eq!(bin.holes.len(), 1);
+        assert!((bin.holes[0].area() - 4.0).abs() < 1e-6);
+        assert!((bin.area - 96.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_second_moment() {
+        let cde_config = LBFConfig::default().cde_config;
+        let json_instance = JsonInstance {
+            name: "second_moment".to_string(),
+            items: vec![json_item(
+                JsonShape::Rectangle {
+                    width: 20.0,
+                    height: 10.0,
+                },
+                1,
+            )],
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: None,
+                shape: JsonShape::Rectangle {
+                    width: 30.0,
+                    height: 30.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let parser = Parser::new(PolySimplConfig::Disabled, cde_config, false);
+        let Instance::BP(instance) = parser.parse(&json_instance) else {
+            panic!("expected a bin packing instance")
+        };
+
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        let key = layout.place_item(instance.item(0), DTransformation::new(0.0, (5.0, 5.0)));
+        let shape = &layout.placed_items()[key].shape;
+        let centroid = shape.centroid();
+        let (b, h) = (20.0, 10.0);
+
+        //about the centroid, the analytic moments of a rectangle
+        let (i_x, i_y) = shape.second_moments_about(centroid);
+        assert!((i_x - b * h * h * h / 12.0).abs() < 1e-2);
+        assert!((i_y - h * b * b * b / 12.0).abs() < 1e-2);
+        let polar = layout.second_moment_about(centroid);
+        assert!((polar - (i_x + i_y)).abs() < 1e-2);
+
+        //about another point, shifted by the parallel axis theorem
+        let p = Point(centroid.0 + 3.0, centroid.1 - 4.0);
+        let shifted = polar + b * h * 25.0;
+        assert!((layout.second_moment_about(p) - shifted).abs() / shifted < 1e-5);
+
+        //an empty layout has no inertia
+        layout.remove_item(key, true);
+        assert_eq!(layout.second_moment_about(p), 0.0);
+    }
+
+    #[test]
+    fn test_lock_layout() {
+        let config = LBFConfig {
+            n_samples: 100,
+            compact: true,
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/baldacci1.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, LBFRng::seed_from_u64(0));
+        optimizer.solve();
+
+        let Problem::BP(bp_problem) = &mut optimizer.problem else {
+            panic!("expected a bin packing problem");
+        };
+        assert!(bp_problem.layouts.len() > 1);
+        bp_problem.lock_layout(LayoutIndex::Real(0));
+        assert!(bp_problem.layout_is_locked(LayoutIndex::Real(0)));
+        assert!(bp_problem.layout_is_full(LayoutIndex::Real(0)));
+        let locked_id = bp_problem.layouts[0].id();
+        let contents = |layout: &Layout| {
+            layout
+                .placed_items()
+                .values()
+                .map(|pi| (pi.item_id, pi.d_transf))
+                .sorted()
+                .collect_vec()
+        };
+        let locked_contents = contents(&bp_problem.layouts[0]);
+
+        //clear all other layouts, closing them
+        while bp_problem.layouts.len() > 1 {
+            let l_idx = LayoutIndex::Real(bp_problem.layouts.len() - 1);
+            let pik = bp_problem
+                .layouts
+                .last()
+                .unwrap()
+                .placed_items()
+                .keys()
+                .next();
+            let pik = pik.unwrap();
+            bp_problem.remove_item(l_idx, pik, true);
+        }
+        bp_problem.remove_empty_layouts();
+        assert_eq!(bp_problem.layouts.len(), 1);
+
+        //re-solve, the unplaced items end up in new layouts
+        let solution = optimizer.solve();
+        assert!(solution.layout_snapshots.len() > 1);
+        let locked_layout = optimizer
+            .problem
+            .layouts()
+            .iter()
+            .find(|l| l.id() == locked_id)
+            .unwrap();
+        assert_eq!(contents(locked_layout), locked_contents);
+        assert!(optimizer.problem.layout_is_locked(LayoutIndex::Real(0)));
+    }
+
+    #[test]
+    fn test_circular_bin() {
+        let cde_config = LBFConfig::default().cde_config;
+        let circle = Circle::new(Point(10.0, 10.0), 10.0);
+        let bin = Bin::from_circle(0, circle.clone(), 1, cde_config);
+        //the circle is the exact contour, the polygon circumscribes it within the tolerance
+        let max_deviation = cde_config.geo_tolerance.margin(20.0);
+        assert_eq!(bin.outer_ellipse, Some(Ellipse::from(circle.clone())));
+        assert!(bin.outer.points.iter().all(|p| {
+            let d = p.distance(circle.center);
+            d >= 10.0 - 1e-4 && d <= 10.0 + max_deviation
+        }));
+        assert_eq!(bin.area, circle.area());
+
+        let layout = Layout::new(0, bin);
+        //a square centered in the circle, with its corners at distance `half_diagonal` from the center
+        let square_at = |half_diagonal: fsize| {
+            let Point(c_x, c_y) = circle.center;
+            let offset = half_diagonal / (2.0 as fsize).sqrt();
+            SimplePolygon::from(AARectangle::new(
+                c_x - offset,
+                c_y - offset,
+                c_x + offset,
+                c_y + offset,
+            ))
+        };
+        //corners just inside the circle
+        assert!(!layout.cde().poly_collides(&square_at(9.9999), &[]));
+        //corners just outside the circle, but inside the circumscribed polygon
+        let poking = square_at(10.0001);
+        assert!(poking
+            .points
+            .iter()
+            .all(|p| layout.bin.outer.collides_with(p)));
+        assert!(layout.cde().poly_collides(&poking, &[]));
+        let mut detected = vec![];
+        layout
+            .cde()
+            .collect_poly_collisions(&poking, &[], &mut detected);
+        assert_eq!(detected, vec![HazardEntity::BinExterior]);
+        //unless the bin exterior is irrelevant
+        assert!(!layout
+            .cde()
+            .poly_collides(&poking, &[HazardEntity::BinExterior]));
+        //circles are checked against the circle exactly as well
+        let inner = |radius: fsize, dx: fsize| {
+            Circle::new(Point(circle.center.0 + dx, circle.center.1), radius)
+        };
+        assert!(!layout.cde().circle_collides(&inner(4.999, 5.0), &[]));
+        assert!(layout.cde().circle_collides(&inner(5.001, 5.0), &[]));
+
+        //an ellipse with a large aspect ratio
+        let ellipse = Ellipse::new(Point(20.0, 5.0), 20.0, 5.0);
+        assert_eq!(ellipse.distance_from_border(&ellipse.center).1, 5.0);
+        assert!((ellipse.distance(&Point(45.0, 5.0)) - 5.0).abs() < 1e-4);
+        assert!((ellipse.distance(&Point(20.0, 13.0)) - 3.0).abs() < 1e-4);
+        let bin = Bin::from_ellipse(1, ellipse.clone(), 1, cde_config);
+        let max_deviation = cde_config.geo_tolerance.margin(ellipse.diameter());
+        assert!(bin
+            .outer
+            .points
+            .iter()
+            .all(|p| ellipse.distance(p) <= max_deviation));
+        let layout = Layout::new(1, bin);
+        let square = |x_min: fsize, y_min: fsize, size: fsize| {
+            SimplePolygon::from(AARectangle::new(x_min, y_min, x_min + size, y_min + size))
+        };
+        assert!(!layout.cde().poly_collides(&square(17.0, 2.0, 6.0), &[]));
+        assert!(layout.cde().poly_collides(&square(1.0, 1.0, 6.0), &[]));
+        //a circle touching the ellipse at the end of its minor axis
+        assert!(!layout
+            .cde()
+            .circle_collides(&Circle::new(Point(20.0, 7.0), 2.999), &[]));
+        assert!(layout
+            .cde()
+            .circle_collides(&Circle::new(Point(20.0, 7.0), 3.001), &[]));
+    }
+
+    #[test]
+    fn test_solution_original_shapes() {
+        //a jagged star which gets simplified, and a rectangle which does not
+        let points = (0..40)
+            .map(|i| {
+                let angle = 2.0 * PI * i as fsize / 40.0;
+                let radius = if i % 2 == 0 { 10.0 } else { 9.0 };
+                (radius * angle.cos(), radius * angle.sin())
+            })
+            .collect_vec();
+        let item = |shape: JsonShape| JsonItem {
+            allowed_orientations: Some(vec![0.0, 90.0]),
+            ..json_item(shape, 2)
+        };
+        let json_instance = JsonInstance {
+            name: "original_shapes".to_string(),
+            items: vec![
+                item(JsonShape::SimplePolygon(JsonSimplePoly(points.clone()))),
+                item(JsonShape::Rectangle {
+                    width: 10.0,
+                    height: 5.0,
+                }),
+            ],
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 30.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            n_samples: 200,
+            ..LBFConfig::default()
+        };
+        let simpl_config = PolySimplConfig::Enabled {
+            tolerance: 0.05,
+            direction: PolySimplDirection::OutwardOnly,
+        };
+        let parser = Parser::new(simpl_config, config.cde_config, true);
+        let instance = parser.parse(&json_instance);
+        assert!(instance.item(0).original_shape.is_some());
+        assert!(instance.item(1).original_shape.is_none());
+
+        let epoch = Instant::now();
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let solution = optimizer.solve();
+        let json_solution = parser::compose_json_solution(&solution, &instance, epoch, None, false);
+
+        let placed_items = json_solution.layouts[0].placed_items.iter().collect_vec();
+        assert_eq!(placed_items.len(), 4);
+        for json_pi in placed_items {
+            match json_pi.index {
+                0 => {
+                    let original = json_pi.original_shape.as_ref().unwrap();
+                    let collision_shape = json_pi.collision_shape.as_ref().unwrap();
+                    assert!(collision_shape.0.len() < original.0.len());
+                    //the original contour is the input shape, transformed by the reported placement
+                    let transf = DTransformation::new(
+                        json_pi.transformation.rotation,
+                        json_pi.transformation.translation,
+                    )
+                    .compose();
+                    let expected = SimplePolygon::new(points.iter().map(|&p| p.into()).collect())
+                        .transform_clone(&transf);
+                    assert_eq!(original.0.len(), expected.points.len());
+                    for (&(x, y), p) in original.0.iter().zip(expected.points.iter()) {
+                        assert!((x - p.0).abs() < 1e-3 && (y - p.1).abs() < 1e-3);
+                    }
+                    //the outward simplified collision shape encloses the original contour
+                    let collision_shape =
+                        SimplePolygon::new(collision_shape.0.iter().map(|&p| p.into()).collect());
+                    assert!(collision_shape.area() >= expected.area());
+                }
+                _ => {
+                    assert!(json_pi.original_shape.is_none());
+                    assert!(json_pi.collision_shape.is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_first_fit_decreasing() {
+        let mut rng = LBFRng::seed_from_u64(0);
+        let items = (0..20)
+            .map(|_| {
+                let demand = rng.gen_range(1..4);
+                JsonItem {
+                    allowed_orientations: Some(vec![0.0, 90.0]),
+                    ..json_item(
+                        JsonShape::Rectangle {
+                            width: rng.gen_range(5.0..40.0),
+                            height: rng.gen_range(5.0..40.0),
+                        },
+                        demand,
+                    )
+                }
+            })
+            .collect_vec();
+        let bp_json_instance = JsonInstance {
+            name: "ffd_bins".to_string(),
+            items: items.clone(),
+            bins: Some(vec![JsonBin {
+                cost: 1,
+                stock: Some(20),
+                shape: JsonShape::Rectangle {
+                    width: 100.0,
+                    height: 100.0,
+                },
+                zones: vec![],
+            }]),
+            strip: None,
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let sp_json_instance = JsonInstance {
+            name: "ffd_strip".to_string(),
+            items,
+            bins: None,
+            strip: Some(JsonStrip {
+                height: 100.0,
+                axis: PackingAxis::X,
+            }),
+            precedence: vec![],
+            rotation_unit: RotationUnit::Deg,
+        };
+        let config = LBFConfig {
+            solver: Solver::FirstFitDecreasing,
+            ..LBFConfig::default()
+        };
+
+        for json_instance in [bp_json_instance, sp_json_instance] {
+            let instance = parse_json_instance(&json_instance, config.cde_config);
+            let mut optimizer =
+                LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+            let solution = optimizer.solve();
+            assert!(assertions::problem_matches_solution(
+                &optimizer.problem,
+                &solution
+            ));
+            assert_eq!(solution.n_items_placed(), instance.total_item_qty());
+            //no sampling involved
+            assert_eq!(optimizer.sample_counter, 0);
+            for layout in optimizer.problem.layouts() {
+                assert!(assertions::layout_is_collision_free(layout));
+                let bin_bbox = layout.bin.bbox();
+                assert!(layout.placed_items().values().all(|pi| {
+                    let bbox = pi.shape.bbox();
+                    bbox.x_min >= bin_bbox.x_min
+                        && bbox.y_min >= bin_bbox.y_min
+                        && bbox.x_max <= bin_bbox.x_max
+                        && bbox.y_max <= bin_bbox.y_max
+                }));
+                //bounding boxes never overlap
+                let bboxes = layout
+                    .placed_items()
+                    .values()
+                    .map(|pi| pi.shape.bbox())
+                    .collect_vec();
+                for (a, b) in bboxes.iter().tuple_combinations() {
+                    assert!(
+                        a.x_max <= b.x_min
+                            || b.x_max <= a.x_min
+                            || a.y_max <= b.y_min
+                            || b.y_max <= a.y_min
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_report() {
+        let config = LBFConfig {
+            n_samples: 200,
+            max_samples_per_item: Some(100),
+            ..LBFConfig::default()
+        };
+        let instance = parse_instance("../assets/albano.json", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance.clone(), config, LBFRng::seed_from_u64(0));
+        let start = Instant::now();
+        let solution = optimizer.solve();
+        let solve_time = start.elapsed();
+
+        let report = &optimizer.report;
+        assert_eq!(report.items.len(), instance.items().len());
+        assert!(report.total_sampling_time() <= solve_time);
+        //sampling every item once more in the final layout, timed here, costs about as much per sample
+        let mut rng = LBFRng::seed_from_u64(0);
+        let mut acceptance = AcceptanceState::new(config.acceptance);
+        let mut n_samples = 0;
+        let start = Instant::now();
+        for (item, _) in instance.items() {
+            lbf_optimizer::sample_layout(
+                &optimizer.problem,
+                LayoutIndex::Real(0),
+                item,
+                &config,
+                &mut rng,
+                &mut n_samples,
+                &mut acceptance,
+            );
+        }
+        let measured_per_sample = start.elapsed().as_secs_f64() / n_samples as f64;
+        let reported_per_sample =
+            report.total_sampling_time().as_secs_f64() / report.total_samples() as f64;
+        //generous bounds, the timings are noisy and the layouts sampled during the solve differ
+        assert!((0.1..20.0).contains(&(reported_per_sample / measured_per_sample)));
+        assert_eq!(report.total_samples(), optimizer.sample_counter);
+        let n_placed = report.items.iter().map(|i| i.n_placed).sum::<usize>();
+        assert_eq!(n_placed, solution.n_items_placed());
+        assert!(report.items.iter().all(|i| i.n_placed <= i.n_attempts));
+        assert!(report
+            .items
+            .iter()
+            .all(|i| i.n_budget_exhausted <= i.n_attempts));
+
+        //an item which takes far longer than the others stands out
+        let mut report = SolveReport::new(3);
+        let ms = std::time::Duration::from_millis;
+        report.record_attempt(0, 10, ms(1), true, false);
+        report.record_attempt(1, 10, ms(1), true, false);
+        report.record_attempt(2, 100, ms(10), true, true);
+        assert_eq!(report.disproportionate_items(2.0), vec![2]);
+        assert_eq!(report.disproportionate_items(5.0), Vec::<usize>::new());
+        assert_eq!(report.items[2].n_budget_exhausted, 1);
+    }
 }